@@ -1,7 +1,9 @@
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use anyhow::Result;
-use forge_app::{AppConfig, InitAuth, User};
+use forge_app::{AppConfig, InitAuth, LayerRules, User};
+use forge_fs::FileLock;
 use forge_stream::MpscStream;
 
 use crate::*;
@@ -62,6 +64,10 @@ pub trait API: Sync + Send {
     /// Returns the conversation with the given ID
     async fn conversation(&self, conversation_id: &ConversationId) -> Result<Option<Conversation>>;
 
+    /// Returns the rules of every layer in the order of precedence, with the
+    /// file they are read from
+    async fn rules(&self) -> Vec<(RulesLayer, Option<LayerRules>)>;
+
     /// Compacts the context of the main agent for the given conversation and
     /// persists it. Returns metrics about the compaction (original vs.
     /// compacted tokens and messages).
@@ -70,6 +76,24 @@ pub trait API: Sync + Send {
         conversation_id: &ConversationId,
     ) -> Result<CompactionResult>;
 
+    /// Reads the files in a single multi-file read and adds their content to
+    /// the context of the conversation. Returns the paths that were read.
+    async fn read_files(
+        &self,
+        conversation_id: &ConversationId,
+        paths: Vec<String>,
+    ) -> Result<Vec<String>>;
+
+    /// Runs a built-in tool directly, without the model, within the
+    /// conversation. The arguments are validated against the schema of the
+    /// tool before it's run, and tools disabled for the conversation are
+    /// rejected.
+    async fn run_tool(
+        &self,
+        conversation_id: &ConversationId,
+        call: ToolCallFull,
+    ) -> Result<ToolOutput>;
+
     /// Executes a shell command using the shell tool infrastructure
     async fn execute_shell_command(
         &self,
@@ -80,6 +104,15 @@ pub trait API: Sync + Send {
     /// Executes the shell command on present stdio.
     async fn execute_shell_command_raw(&self, command: &str) -> Result<std::process::ExitStatus>;
 
+    /// Waits until files matching the glob pattern change, debouncing bursts
+    /// of changes, and returns the changed paths
+    async fn wait_for_changes(&self, pattern: &str, debounce: Duration) -> Result<Vec<PathBuf>>;
+
+    /// Locks the conversation against use by other processes until the
+    /// returned lock is dropped. The lock names the process that already has
+    /// the conversation open, if any.
+    async fn lock_conversation(&self, id: &ConversationId) -> Result<FileLock>;
+
     /// Reads and merges MCP configurations from all available configuration
     /// files This combines both user-level and local configurations with
     /// local taking precedence
@@ -97,5 +130,6 @@ pub trait API: Sync + Send {
     async fn logout(&self) -> anyhow::Result<()>;
     async fn provider(&self) -> anyhow::Result<Provider>;
     async fn app_config(&self) -> anyhow::Result<AppConfig>;
+    async fn write_app_config(&self, config: &AppConfig) -> anyhow::Result<()>;
     async fn user_info(&self) -> anyhow::Result<Option<User>>;
 }
@@ -1,15 +1,17 @@
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use forge_app::{
     AppConfig, AppConfigService, AuthService, ConversationService, EnvironmentService,
-    FileDiscoveryService, ForgeApp, InitAuth, McpConfigManager, ProviderRegistry, ProviderService,
-    Services, User, Walker, WorkflowService,
+    FileDiscoveryService, ForgeApp, InitAuth, LayerRules, McpConfigManager, ProviderRegistry,
+    ProviderService, Services, User, Walker, WorkflowService,
 };
 use forge_domain::*;
-use forge_infra::ForgeInfra;
-use forge_services::{CommandInfra, ForgeServices};
+use forge_fs::FileLock;
+use forge_infra::{ForgeInfra, ForgeMcpToolServer};
+use forge_services::{CommandInfra, FileLockInfra, FileWatcherInfra, ForgeServices};
 use forge_stream::MpscStream;
 
 use crate::API;
@@ -26,15 +28,28 @@ impl<A, F> ForgeAPI<A, F> {
 }
 
 impl ForgeAPI<ForgeServices<ForgeInfra>, ForgeInfra> {
-    pub fn init(restricted: bool, cwd: PathBuf) -> Self {
-        let infra = Arc::new(ForgeInfra::new(restricted, cwd));
+    pub fn init(
+        restricted: bool,
+        shell: Option<String>,
+        cwd: PathBuf,
+        remote: Option<RemoteTarget>,
+    ) -> Self {
+        let infra = Arc::new(ForgeInfra::new(restricted, shell, cwd, remote));
         let app = Arc::new(ForgeServices::new(infra.clone()));
         ForgeAPI::new(app, infra)
     }
+
+    /// Serves the built-in tools as an MCP server over stdio, running them in
+    /// the workspace until the client disconnects
+    pub async fn serve_mcp(restricted: bool, shell: Option<String>, cwd: PathBuf) -> Result<()> {
+        let infra = Arc::new(ForgeInfra::new(restricted, shell, cwd, None).quiet());
+        let services = Arc::new(ForgeServices::new(infra));
+        ForgeMcpToolServer::new(services).serve_stdio().await
+    }
 }
 
 #[async_trait::async_trait]
-impl<A: Services, F: CommandInfra> API for ForgeAPI<A, F> {
+impl<A: Services, F: CommandInfra + FileWatcherInfra + FileLockInfra> API for ForgeAPI<A, F> {
     async fn discover(&self) -> Result<Vec<File>> {
         let environment = self.services.get_environment();
         let config = Walker::unlimited().cwd(environment.cwd);
@@ -73,6 +88,10 @@ impl<A: Services, F: CommandInfra> API for ForgeAPI<A, F> {
         self.services.upsert(conversation).await
     }
 
+    async fn rules(&self) -> Vec<(RulesLayer, Option<LayerRules>)> {
+        ForgeApp::new(self.services.clone()).rules().await
+    }
+
     async fn compact_conversation(
         &self,
         conversation_id: &ConversationId,
@@ -81,6 +100,24 @@ impl<A: Services, F: CommandInfra> API for ForgeAPI<A, F> {
         forge_app.compact_conversation(conversation_id).await
     }
 
+    async fn read_files(
+        &self,
+        conversation_id: &ConversationId,
+        paths: Vec<String>,
+    ) -> anyhow::Result<Vec<String>> {
+        let forge_app = ForgeApp::new(self.services.clone());
+        forge_app.read_files(conversation_id, paths).await
+    }
+
+    async fn run_tool(
+        &self,
+        conversation_id: &ConversationId,
+        call: ToolCallFull,
+    ) -> anyhow::Result<ToolOutput> {
+        let forge_app = ForgeApp::new(self.services.clone());
+        forge_app.run_tool(conversation_id, call).await
+    }
+
     fn environment(&self) -> Environment {
         self.services.get_environment().clone()
     }
@@ -142,6 +179,17 @@ impl<A: Services, F: CommandInfra> API for ForgeAPI<A, F> {
         self.infra.execute_command_raw(command, cwd).await
     }
 
+    async fn wait_for_changes(&self, pattern: &str, debounce: Duration) -> Result<Vec<PathBuf>> {
+        self.infra.wait_for_changes(pattern, debounce).await
+    }
+
+    async fn lock_conversation(&self, id: &ConversationId) -> Result<FileLock> {
+        let environment = self.services.get_environment();
+        self.infra
+            .lock_file(&environment.conversation_lock_path(id))
+            .await
+    }
+
     async fn init_login(&self) -> Result<InitAuth> {
         let forge_app = ForgeApp::new(self.services.clone());
         forge_app.init_auth().await
@@ -164,6 +212,9 @@ impl<A: Services, F: CommandInfra> API for ForgeAPI<A, F> {
     async fn app_config(&self) -> anyhow::Result<AppConfig> {
         self.services.read_app_config().await
     }
+    async fn write_app_config(&self, config: &AppConfig) -> anyhow::Result<()> {
+        self.services.write_app_config(config).await
+    }
 
     async fn user_info(&self) -> Result<Option<User>> {
         let provider = self.provider().await?;
@@ -174,3 +225,186 @@ impl<A: Services, F: CommandInfra> API for ForgeAPI<A, F> {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn fixture_api(cwd: &Path) -> ForgeAPI<ForgeServices<ForgeInfra>, ForgeInfra> {
+        let infra = Arc::new(ForgeInfra::new(false, None, cwd.to_path_buf(), None).quiet());
+        ForgeAPI::new(Arc::new(ForgeServices::new(infra.clone())), infra)
+    }
+
+    async fn fixture_conversation(
+        api: &ForgeAPI<ForgeServices<ForgeInfra>, ForgeInfra>,
+    ) -> ConversationId {
+        api.init_conversation(Workflow::default()).await.unwrap().id
+    }
+
+    #[tokio::test]
+    async fn test_run_tool_reads_the_file() {
+        let workspace = tempfile::tempdir().unwrap();
+        let path = workspace.path().join("notes.md");
+        std::fs::write(&path, "run without the model\n").unwrap();
+        let fixture = fixture_api(workspace.path());
+        let conversation_id = fixture_conversation(&fixture).await;
+
+        let actual = fixture
+            .run_tool(
+                &conversation_id,
+                ToolCallFull::new(ToolsDiscriminants::ForgeToolFsRead.name())
+                    .arguments(json!({ "path": path.display().to_string() })),
+            )
+            .await
+            .unwrap();
+
+        assert!(!actual.is_error);
+        assert!(actual.as_str().unwrap().contains("run without the model"));
+    }
+
+    #[tokio::test]
+    async fn test_run_tool_rejects_arguments_outside_the_schema() {
+        let workspace = tempfile::tempdir().unwrap();
+        let fixture = fixture_api(workspace.path());
+        let conversation_id = fixture_conversation(&fixture).await;
+
+        let actual = fixture
+            .run_tool(
+                &conversation_id,
+                ToolCallFull::new(ToolsDiscriminants::ForgeToolFsRead.name())
+                    .arguments(json!({ "file": "notes.md" })),
+            )
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            actual.downcast_ref::<forge_app::Error>(),
+            Some(forge_app::Error::CallArgument(_))
+        ));
+    }
+
+    /// Runs git in the directory, failing the test when it fails
+    fn git(cwd: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args([
+                "-c",
+                "user.name=Forge",
+                "-c",
+                "user.email=forge@example.com",
+            ])
+            .args(args)
+            .current_dir(cwd)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[tokio::test]
+    async fn test_read_at_revision_returns_the_committed_content() {
+        let workspace = tempfile::tempdir().unwrap();
+        let path = workspace.path().join("notes.md");
+        git(workspace.path(), &["init", "--quiet"]);
+        std::fs::write(&path, "committed\n").unwrap();
+        git(workspace.path(), &["add", "notes.md"]);
+        git(workspace.path(), &["commit", "--quiet", "-m", "Add notes"]);
+        std::fs::write(&path, "edited in the working tree\n").unwrap();
+        let fixture = fixture_api(workspace.path());
+        let conversation_id = fixture_conversation(&fixture).await;
+
+        let actual = fixture
+            .run_tool(
+                &conversation_id,
+                ToolCallFull::new(ToolsDiscriminants::ForgeToolFsRead.name())
+                    .arguments(json!({ "path": path.display().to_string(), "revision": "HEAD" })),
+            )
+            .await
+            .unwrap();
+
+        let text = actual.as_str().unwrap();
+        assert!(text.contains("committed"));
+        assert!(!text.contains("edited in the working tree"));
+        assert!(text.contains(r#"revision="HEAD""#));
+    }
+
+    #[tokio::test]
+    async fn test_read_at_unknown_revision_fails() {
+        let workspace = tempfile::tempdir().unwrap();
+        let path = workspace.path().join("notes.md");
+        git(workspace.path(), &["init", "--quiet"]);
+        std::fs::write(&path, "committed\n").unwrap();
+        git(workspace.path(), &["add", "notes.md"]);
+        git(workspace.path(), &["commit", "--quiet", "-m", "Add notes"]);
+        let fixture = fixture_api(workspace.path());
+        let conversation_id = fixture_conversation(&fixture).await;
+
+        let actual = fixture
+            .run_tool(
+                &conversation_id,
+                ToolCallFull::new(ToolsDiscriminants::ForgeToolFsRead.name()).arguments(
+                    json!({ "path": path.display().to_string(), "revision": "no-such-branch" }),
+                ),
+            )
+            .await
+            .unwrap_err()
+            .to_string();
+
+        assert!(actual.contains("at revision no-such-branch"));
+    }
+
+    #[tokio::test]
+    async fn test_run_tool_saves_the_scratchpad_to_the_conversation() {
+        let workspace = tempfile::tempdir().unwrap();
+        let fixture = fixture_api(workspace.path());
+        let conversation_id = fixture_conversation(&fixture).await;
+
+        fixture
+            .run_tool(
+                &conversation_id,
+                ToolCallFull::new(ToolsDiscriminants::ForgeToolScratchpadWrite.name())
+                    .arguments(json!({ "content": "Keep the public API unchanged" })),
+            )
+            .await
+            .unwrap();
+
+        let actual = fixture
+            .conversation(&conversation_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .scratchpad;
+        let expected = "Keep the public API unchanged";
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_run_tool_rejects_a_tool_disabled_for_the_conversation() {
+        let workspace = tempfile::tempdir().unwrap();
+        let fixture = fixture_api(workspace.path());
+        let mut conversation = fixture
+            .init_conversation(Workflow::default())
+            .await
+            .unwrap();
+        conversation.disable_tool(ToolsDiscriminants::ForgeToolFsRead.name());
+        fixture
+            .upsert_conversation(conversation.clone())
+            .await
+            .unwrap();
+
+        let actual = fixture
+            .run_tool(
+                &conversation.id,
+                ToolCallFull::new(ToolsDiscriminants::ForgeToolFsRead.name()).arguments(
+                    json!({ "path": workspace.path().join("notes.md").display().to_string() }),
+                ),
+            )
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            actual.downcast_ref::<forge_app::Error>(),
+            Some(forge_app::Error::Disabled(_))
+        ));
+    }
+}
@@ -3,5 +3,5 @@ mod forge_api;
 
 pub use api::*;
 pub use forge_api::*;
-pub use forge_app::{AppConfig, LoginInfo};
+pub use forge_app::{AppConfig, LayerRules, LoginInfo, Profile, ProfileProvider};
 pub use forge_domain::*;
@@ -1,16 +1,33 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use forge_domain::{
     Agent, ChatCompletionMessage, Context, Conversation, ModelId, ResultStream, ToolCallContext,
     ToolCallFull, ToolResult,
 };
+use tokio::sync::OwnedSemaphorePermit;
 
 use crate::tool_registry::ToolRegistry;
+use crate::truncation::create_temp_file;
 use crate::{
-    AppConfigService, ConversationService, ProviderRegistry, ProviderService, Services,
-    TemplateService,
+    AppConfigService, ConversationService, FollowUpService, ProviderRegistry, ProviderService,
+    Services, TemplateService,
 };
 
+/// Option offered to the user to let a plan-first agent start making changes
+const APPROVE_PLAN: &str = "Approve the plan";
+
+/// Decision of the user on the plan presented by the agent
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlanReview {
+    Approved,
+    /// The plan was approved, but the user chose not to run the pending tool
+    /// calls at these positions
+    Skipped(Vec<usize>),
+    /// The plan was rejected, along with what the user wants changed
+    Rejected(String),
+}
+
 /// Agent service trait that provides core chat and tool call functionality.
 /// This trait abstracts the essential operations needed by the Orchestrator.
 #[async_trait::async_trait]
@@ -39,6 +56,18 @@ pub trait AgentService: Send + Sync + 'static {
 
     /// Synchronize the on-going conversation
     async fn update(&self, conversation: Conversation) -> anyhow::Result<()>;
+
+    /// Writes content kept out of the context to the offload directory, so
+    /// that it can still be read back, and returns its path
+    async fn offload(&self, prefix: &str, content: &str) -> anyhow::Result<PathBuf>;
+
+    /// Asks the user to approve the plan before the agent makes any changes.
+    /// When several tool calls are pending, the user can pick some to skip.
+    async fn review_plan(&self, pending: &[ToolCallFull]) -> anyhow::Result<PlanReview>;
+
+    /// Waits until a provider request may be sent, the request being counted
+    /// until the returned permit is dropped
+    async fn acquire_request_permit(&self) -> anyhow::Result<Option<OwnedSemaphorePermit>>;
 }
 
 /// Blanket implementation of AgentService for any type that implements Services
@@ -75,4 +104,97 @@ impl<T: Services> AgentService for T {
     async fn update(&self, conversation: Conversation) -> anyhow::Result<()> {
         self.upsert(conversation).await
     }
+
+    async fn offload(&self, prefix: &str, content: &str) -> anyhow::Result<PathBuf> {
+        create_temp_file(self, prefix, ".txt", content).await
+    }
+
+    async fn review_plan(&self, pending: &[ToolCallFull]) -> anyhow::Result<PlanReview> {
+        let choice = self
+            .follow_up(
+                "The agent is about to make changes, do you approve its plan?".to_string(),
+                vec![APPROVE_PLAN.to_string(), "Reject with feedback".to_string()],
+                None,
+            )
+            .await?;
+        if choice.is_some_and(|choice| choice.ends_with(APPROVE_PLAN)) {
+            if pending.len() < 2 {
+                return Ok(PlanReview::Approved);
+            }
+
+            let labels = pending
+                .iter()
+                .enumerate()
+                .map(call_label)
+                .collect::<Vec<_>>();
+            let skipped = self
+                .select_positions(
+                    "Select the tool calls to skip, or none to run them all".to_string(),
+                    labels,
+                )
+                .await?
+                .unwrap_or_default();
+            return Ok(if skipped.is_empty() {
+                PlanReview::Approved
+            } else {
+                PlanReview::Skipped(skipped)
+            });
+        }
+
+        let feedback = self
+            .follow_up("What should change in the plan?".to_string(), vec![], None)
+            .await?;
+        Ok(PlanReview::Rejected(feedback.unwrap_or_default()))
+    }
+
+    async fn acquire_request_permit(&self) -> anyhow::Result<Option<OwnedSemaphorePermit>> {
+        self.request_limit().acquire().await
+    }
+}
+
+/// Describes a pending tool call by its position, name and the path or
+/// command it acts on
+fn call_label((index, call): (usize, &ToolCallFull)) -> String {
+    let target = ["path", "command"]
+        .iter()
+        .find_map(|key| call.arguments.get(key).and_then(|value| value.as_str()));
+    match target {
+        Some(target) => format!("#{} {} {target}", index + 1, call.name),
+        None => format!("#{} {}", index + 1, call.name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use forge_domain::ToolName;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture_pending() -> Vec<ToolCallFull> {
+        vec![
+            ToolCallFull::new(ToolName::new("forge_tool_fs_read"))
+                .arguments(serde_json::json!({"path": "src/lib.rs"})),
+            ToolCallFull::new(ToolName::new("forge_tool_fs_read_glob"))
+                .arguments(serde_json::json!({"path": "src/*.rs"})),
+            ToolCallFull::new(ToolName::new("forge_tool_process_shell"))
+                .arguments(serde_json::json!({"command": "cargo test"})),
+        ]
+    }
+
+    #[test]
+    fn test_call_label_lists_position_name_and_target() {
+        let actual = fixture_pending()
+            .iter()
+            .enumerate()
+            .map(call_label)
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            "#1 forge_tool_fs_read src/lib.rs".to_string(),
+            "#2 forge_tool_fs_read_glob src/*.rs".to_string(),
+            "#3 forge_tool_process_shell cargo test".to_string(),
+        ];
+        assert_eq!(actual, expected);
+    }
 }
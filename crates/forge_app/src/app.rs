@@ -5,11 +5,16 @@ use anyhow::{Context, Result};
 use chrono::Local;
 use forge_domain::*;
 use forge_stream::MpscStream;
+use strum::IntoEnumIterator;
 
 use crate::authenticator::Authenticator;
+use crate::operation::{Operation, TempContentFiles};
 use crate::orch::Orchestrator;
+use crate::project_rules::{LayerRules, find_layer_rules, read_project_rules};
+use crate::read_glob::read_files;
 use crate::services::TemplateService;
 use crate::tool_registry::ToolRegistry;
+use crate::tool_server::ToolServer;
 use crate::{
     AppConfigService, AttachmentService, ConversationService, EnvironmentService,
     FileDiscoveryService, InitAuth, ProviderRegistry, ProviderService, Services, Walker,
@@ -64,18 +69,19 @@ impl<S: Services> ForgeApp<S> {
         let max_depth = workflow.max_walker_depth;
         let environment = services.get_environment();
 
+        // The raw output of a call from an earlier turn shouldn't pass for the last
+        // one. It's forge's own file, so it's removed without a snapshot to undo
+        if environment.debug_raw_output {
+            let _ = tokio::fs::remove_file(environment.raw_output_path()).await;
+        }
+
         let mut walker = Walker::conservative().cwd(environment.cwd.clone());
 
         if let Some(depth) = max_depth {
             walker = walker.max_depth(depth);
         };
 
-        let files = services
-            .collect_files(walker)
-            .await?
-            .into_iter()
-            .map(|f| f.path)
-            .collect::<Vec<_>>();
+        let files = services.collect_files(walker).await?;
 
         // Register templates using workflow path or environment fallback
         let template_path = workflow
@@ -92,8 +98,15 @@ impl<S: Services> ForgeApp<S> {
             chat.event = chat.event.attachments(attachments);
         }
 
+        // Read on every chat so that edits to the rules apply from the next message
+        let project_rules = read_project_rules(
+            services.as_ref(),
+            RulesLayer::iter().map(|layer| layer.paths(&environment)),
+        )
+        .await;
+
         // Create the orchestrator with all necessary dependencies
-        let orch = Orchestrator::new(
+        let mut orch = Orchestrator::new(
             services.clone(),
             environment.clone(),
             conversation,
@@ -102,6 +115,9 @@ impl<S: Services> ForgeApp<S> {
         .tool_definitions(tool_definitions)
         .models(models)
         .files(files);
+        if let Some(project_rules) = project_rules {
+            orch = orch.project_rules(project_rules);
+        }
 
         // Create and return the stream
         let stream = MpscStream::spawn(
@@ -131,6 +147,18 @@ impl<S: Services> ForgeApp<S> {
         Ok(stream)
     }
 
+    /// Returns the rules of every layer in the order of precedence, read from
+    /// the same files the prompt gets them from
+    pub async fn rules(&self) -> Vec<(RulesLayer, Option<LayerRules>)> {
+        let environment = self.services.get_environment();
+        let mut layers = Vec::new();
+        for layer in RulesLayer::iter() {
+            let rules = find_layer_rules(self.services.as_ref(), layer.paths(&environment)).await;
+            layers.push((layer, rules));
+        }
+        layers
+    }
+
     /// Compacts the context of the main agent for the given conversation and
     /// persists it. Returns metrics about the compaction (original vs.
     /// compacted tokens and messages).
@@ -192,6 +220,95 @@ impl<S: Services> ForgeApp<S> {
         ))
     }
 
+    /// Reads the files in a single multi-file read and adds their content to
+    /// the context of the conversation. Returns the paths that were read,
+    /// binary and oversized files being left out.
+    pub async fn read_files(
+        &self,
+        conversation_id: &ConversationId,
+        paths: Vec<String>,
+    ) -> Result<Vec<String>> {
+        let mut conversation = self
+            .services
+            .find(conversation_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Conversation not found: {}", conversation_id))?;
+        let env = self.services.get_environment();
+        let model = conversation
+            .agents
+            .first()
+            .and_then(|agent| agent.model.clone());
+
+        let max_files = paths.len();
+        let output = read_files(
+            self.services.as_ref(),
+            paths,
+            max_files,
+            env.max_file_size as usize,
+        )
+        .await;
+        let read = output
+            .files
+            .iter()
+            .map(|file| file.path.clone())
+            .collect::<Vec<_>>();
+
+        let input = FSReadGlob { path: env.cwd.display().to_string(), ..Default::default() };
+        let operation = Operation::FsReadGlob { input, output };
+        let content = operation.into_tool_output(
+            ToolsDiscriminants::ForgeToolFsReadGlob.name(),
+            TempContentFiles::default(),
+            &env,
+        );
+        let content = content.as_str().unwrap_or_default().to_string();
+        conversation.context = Some(
+            conversation
+                .context
+                .take()
+                .unwrap_or_default()
+                .add_message(ContextMessage::user(content, model)),
+        );
+        self.services.upsert(conversation).await?;
+
+        Ok(read)
+    }
+
+    /// Runs a built-in tool directly, without the model, with the task list
+    /// and scratchpad of the conversation. The changes the tool makes to them
+    /// are saved back to the conversation.
+    pub async fn run_tool(
+        &self,
+        conversation_id: &ConversationId,
+        call: ToolCallFull,
+    ) -> Result<ToolOutput> {
+        let mut conversation = self
+            .services
+            .find(conversation_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Conversation not found: {}", conversation_id))?;
+        if conversation.is_tool_disabled(&call.name) {
+            return Err(crate::error::Error::Disabled(call.name).into());
+        }
+
+        let mut context = ToolCallContext::new(conversation.tasks.clone())
+            .changed_files(conversation.changed_files.clone())
+            .reported_changed_files(conversation.reported_changed_files.clone())
+            .scratchpad(conversation.scratchpad.clone())
+            .tool_post_processors(conversation.tool_post_processors.clone())
+            .formatters(conversation.formatters.clone());
+        let output = ToolServer::new(self.services.clone())
+            .call_in(call, &mut context)
+            .await?;
+
+        conversation.tasks = context.tasks;
+        conversation.changed_files = context.changed_files;
+        conversation.reported_changed_files = context.reported_changed_files;
+        conversation.scratchpad = context.scratchpad;
+        self.services.upsert(conversation).await?;
+
+        Ok(output)
+    }
+
     pub async fn list_tools(&self) -> Result<Vec<ToolDefinition>> {
         self.tool_registry.list().await
     }
@@ -1,4 +1,7 @@
+use std::collections::BTreeMap;
+
 use derive_more::From;
+use forge_domain::{ModelId, Provider, ProviderUrl};
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize)]
@@ -13,6 +16,95 @@ pub struct InitAuth {
 #[serde(rename_all = "camelCase")]
 pub struct AppConfig {
     pub key_info: Option<LoginInfo>,
+    /// Skips offering to save the conversation before it's discarded
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skip_save_prompt: bool,
+    /// Named profiles the user can switch between
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+    /// Name of the profile in use, which takes precedence over the login and
+    /// the environment variables when resolving the provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+}
+
+impl AppConfig {
+    /// Adds the profile, replacing any profile with the same name
+    pub fn add_profile(&mut self, name: impl Into<String>, profile: Profile) {
+        self.profiles.insert(name.into(), profile);
+    }
+
+    /// Makes the profile the active one, failing if it doesn't exist
+    pub fn use_profile(&mut self, name: &str) -> anyhow::Result<&Profile> {
+        let profile = self
+            .profiles
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("Profile '{name}' not found"))?;
+        self.active_profile = Some(name.to_string());
+        Ok(profile)
+    }
+
+    /// Returns the profile in use, if any
+    pub fn profile(&self) -> Option<&Profile> {
+        self.active_profile
+            .as_ref()
+            .and_then(|name| self.profiles.get(name))
+    }
+}
+
+/// Provider, key, base URL and default model used together, e.g. for a work
+/// account or for going through a proxy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub provider: ProfileProvider,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub api_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<ModelId>,
+}
+
+impl Profile {
+    /// Resolves the provider the profile points to
+    pub fn to_provider(&self) -> Provider {
+        let key = self.api_key.as_str();
+        let mut provider = match self.provider {
+            ProfileProvider::Forge => Provider::forge(key),
+            ProfileProvider::OpenRouter => Provider::open_router(key),
+            ProfileProvider::Requesty => Provider::requesty(key),
+            ProfileProvider::Xai => Provider::xai(key),
+            ProfileProvider::OpenAI => Provider::openai(key),
+            ProfileProvider::Anthropic => Provider::anthropic(key),
+            ProfileProvider::Gemini => Provider::gemini(key),
+            ProfileProvider::Ollama => Provider::ollama(),
+        };
+        if let Some(url) = self.base_url.clone() {
+            provider.url(match self.provider {
+                ProfileProvider::Anthropic => ProviderUrl::Anthropic(url),
+                ProfileProvider::Gemini => ProviderUrl::Gemini(url),
+                ProfileProvider::Ollama => ProviderUrl::Ollama(url),
+                _ => ProviderUrl::OpenAI(url),
+            });
+        }
+        provider
+    }
+}
+
+/// Providers a profile can point to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileProvider {
+    Forge,
+    OpenRouter,
+    Requesty,
+    Xai,
+    OpenAI,
+    Anthropic,
+    Gemini,
+    /// A local Ollama server, which doesn't take an API key
+    Ollama,
 }
 
 #[derive(Clone, Serialize, Deserialize, From)]
@@ -28,3 +120,89 @@ pub struct LoginInfo {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub auth_provider_id: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture_config() -> AppConfig {
+        let mut config = AppConfig::default();
+        config.add_profile(
+            "work",
+            Profile {
+                provider: ProfileProvider::OpenAI,
+                api_key: "work-key".to_string(),
+                base_url: Some("https://proxy.example.com/v1".to_string()),
+                model: Some(ModelId::new("gpt-4.1")),
+            },
+        );
+        config.add_profile(
+            "personal",
+            Profile {
+                provider: ProfileProvider::Anthropic,
+                api_key: "personal-key".to_string(),
+                base_url: None,
+                model: None,
+            },
+        );
+        config
+    }
+
+    #[test]
+    fn test_add_profiles_lists_them_by_name() {
+        let fixture = fixture_config();
+
+        let actual = fixture.profiles.keys().cloned().collect::<Vec<_>>();
+
+        let expected = vec!["personal".to_string(), "work".to_string()];
+        assert_eq!(actual, expected);
+        assert_eq!(fixture.profile(), None);
+    }
+
+    #[test]
+    fn test_use_profile_changes_resolved_provider() {
+        let mut fixture = fixture_config();
+
+        fixture.use_profile("work").unwrap();
+        let work = fixture.profile().map(Profile::to_provider);
+        fixture.use_profile("personal").unwrap();
+        let personal = fixture.profile().map(Profile::to_provider);
+
+        let mut expected_work = Provider::openai("work-key");
+        expected_work.url(ProviderUrl::OpenAI(
+            "https://proxy.example.com/v1".to_string(),
+        ));
+        assert_eq!(work, Some(expected_work));
+        assert_eq!(personal, Some(Provider::anthropic("personal-key")));
+        assert_eq!(fixture.active_profile, Some("personal".to_string()));
+    }
+
+    #[test]
+    fn test_ollama_profile_resolves_without_key() {
+        let fixture = Profile {
+            provider: ProfileProvider::Ollama,
+            api_key: String::new(),
+            base_url: Some("http://gpu-box:11434".to_string()),
+            model: None,
+        };
+
+        let actual = fixture.to_provider();
+
+        let mut expected = Provider::ollama();
+        expected.url(ProviderUrl::Ollama("http://gpu-box:11434".to_string()));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_use_unknown_profile_keeps_active_one() {
+        let mut fixture = fixture_config();
+        fixture.use_profile("work").unwrap();
+
+        let actual = fixture.use_profile("staging").is_err();
+
+        assert!(actual);
+        assert_eq!(fixture.active_profile, Some("work".to_string()));
+    }
+}
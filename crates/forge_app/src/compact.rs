@@ -2,10 +2,11 @@ use std::sync::Arc;
 
 use forge_domain::{
     Agent, ChatCompletionMessage, ChatCompletionMessageFull, Compact, CompactionStrategy, Context,
-    ContextMessage, ResultStreamExt, extract_tag_content,
+    ContextMessage, OFFLOAD_PREFIX, ResultStreamExt, ToolOutput, ToolResult, extract_tag_content,
 };
+use forge_template::Element;
 use futures::Stream;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::agent::AgentService;
 
@@ -54,6 +55,73 @@ impl<S: AgentService> Compactor<S> {
         }
     }
 
+    /// Replaces a tool output estimated above the configured threshold with a
+    /// summary generated by the compaction model. Failed and pinned results
+    /// are kept as is, and so is the content truncation saved to disk. The
+    /// original output is offloaded first so it can be read back in full, and
+    /// kept as is when either step fails.
+    pub async fn summarize_tool_output(&self, compact: &Compact, result: &mut ToolResult) {
+        let Some(threshold) = compact.tool_output_threshold else {
+            return;
+        };
+        let message = ContextMessage::Tool(result.clone());
+        let token_count = message.token_count_approx();
+        if result.pinned || result.is_error() || token_count <= threshold {
+            return;
+        }
+
+        let original = result
+            .output
+            .values
+            .iter()
+            .filter_map(|value| value.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let path = match self
+            .services
+            .offload(&format!("{OFFLOAD_PREFIX}summarized_"), &original)
+            .await
+        {
+            Ok(path) => path,
+            Err(error) => {
+                warn!(
+                    tool_name = %result.name,
+                    error = ?error,
+                    "Failed to offload the tool output, keeping it as is"
+                );
+                return;
+            }
+        };
+        let summary = match self
+            .generate_summary_for_sequence(compact, &[message])
+            .await
+        {
+            Ok(summary) => summary,
+            Err(error) => {
+                warn!(
+                    tool_name = %result.name,
+                    error = ?error,
+                    "Failed to summarize the tool output, keeping it as is"
+                );
+                return;
+            }
+        };
+
+        info!(
+            tool_name = %result.name,
+            token_count,
+            summary = %summary,
+            "Summarized oversized tool output"
+        );
+
+        result.output = ToolOutput::text(
+            Element::new("tool_output_summary")
+                .attr("original_tokens", token_count)
+                .attr("original_path", path.display())
+                .cdata(summary),
+        );
+    }
+
     /// Compress a single identified sequence of assistant messages.
     async fn compress_single_sequence(
         &self,
@@ -127,6 +195,9 @@ impl<S: AgentService> Compactor<S> {
             context = context.max_tokens(max_token);
         }
 
+        // Counts against the same limit as the requests of the agents, until the
+        // summary is streamed
+        let _permit = self.services.acquire_request_permit().await?;
         let response = self.services.chat_agent(&compact.model, context).await?;
 
         self.collect_completion_stream_content(compact, response)
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use forge_domain::{ToolCallArgumentError, ToolName};
 
 #[derive(thiserror::Error, Debug)]
@@ -19,6 +21,26 @@ pub enum Error {
         supported_tools: String,
     },
 
+    #[error("Tool '{0}' was disabled by the user for this session, continue without it")]
+    Disabled(ToolName),
+
+    #[error(
+        "Working directory '{}' is outside the sandbox root '{}', run commands within the sandbox root instead",
+        cwd.display(),
+        root.display()
+    )]
+    OutsideSandbox { cwd: PathBuf, root: PathBuf },
+
+    #[error(
+        "Tool '{0}' was called without an explanation, call it again with an explanation of why it's used and how it contributes to the goal"
+    )]
+    MissingExplanation(ToolName),
+
+    #[error(
+        "The content for '{0}' starts every line with the line numbers of a numbered read, call the tool again with the content without them"
+    )]
+    NumberedContent(String),
+
     #[error("Empty tool response")]
     EmptyToolResponse,
 
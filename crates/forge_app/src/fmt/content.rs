@@ -8,6 +8,23 @@ pub enum ContentFormat {
     Markdown(String),
 }
 
+impl ContentFormat {
+    /// Shows the home directory as `~` when home redaction is enabled
+    pub fn redact_home(self, env: &Environment) -> Self {
+        match self {
+            ContentFormat::Title(title) => ContentFormat::Title(TitleFormat {
+                title: env.redact_home_dir(&title.title),
+                sub_title: title
+                    .sub_title
+                    .map(|sub_title| env.redact_home_dir(&sub_title)),
+                ..title
+            }),
+            ContentFormat::PlainText(text) => ContentFormat::PlainText(env.redact_home_dir(&text)),
+            ContentFormat::Markdown(text) => ContentFormat::Markdown(env.redact_home_dir(&text)),
+        }
+    }
+}
+
 impl From<ContentFormat> for ChatResponse {
     fn from(value: ContentFormat) -> Self {
         match value {
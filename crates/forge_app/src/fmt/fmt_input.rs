@@ -30,6 +30,9 @@ impl FormatContent for Tools {
                         (None, None) => {}
                     }
                 };
+                if let Some(revision) = &input.revision {
+                    subtitle.push_str(&format!(" [At {revision}]"));
+                }
                 TitleFormat::debug("Read").sub_title(subtitle).into()
             }
             Tools::ForgeToolFsCreate(input) => {
@@ -73,7 +76,8 @@ impl FormatContent for Tools {
                     .into()
             }
             Tools::ForgeToolNetFetch(input) => {
-                TitleFormat::debug("GET").sub_title(&input.url).into()
+                let method = input.method.as_deref().unwrap_or("GET").to_uppercase();
+                TitleFormat::debug(method).sub_title(&input.url).into()
             }
             Tools::ForgeToolFollowup(input) => TitleFormat::debug("Follow-up")
                 .sub_title(&input.question)
@@ -92,6 +96,32 @@ impl FormatContent for Tools {
             }
             Tools::ForgeToolTaskListList(_) => TitleFormat::debug("Task Read".to_string()).into(),
             Tools::ForgeToolTaskListClear(_) => TitleFormat::debug("Task Clear".to_string()).into(),
+            Tools::ForgeToolScratchpadWrite(_) => {
+                TitleFormat::debug("Scratchpad Write".to_string()).into()
+            }
+            Tools::ForgeToolScratchpadRead(_) => {
+                TitleFormat::debug("Scratchpad Read".to_string()).into()
+            }
+            Tools::ForgeToolRepoTree(_) => TitleFormat::debug("Repo Tree").into(),
+            Tools::ForgeToolFsReadGlob(input) => TitleFormat::debug("Read")
+                .sub_title(format!(
+                    "{} [{}]",
+                    display_path_for(&input.path),
+                    input.pattern
+                ))
+                .into(),
+            Tools::ForgeToolSystemInfo(_) => TitleFormat::debug("System Info").into(),
+            Tools::ForgeToolFormatCode(input) => {
+                let display_path = display_path_for(&input.path);
+                TitleFormat::debug("Format").sub_title(display_path).into()
+            }
+            Tools::ForgeToolFsList(input) => {
+                let mut subtitle = display_path_for(&input.path);
+                if input.recursive.unwrap_or_default() {
+                    subtitle.push_str(" [Recursive]");
+                }
+                TitleFormat::debug("List").sub_title(subtitle).into()
+            }
         };
 
         Some(output)
@@ -122,6 +152,7 @@ mod tests {
     fn fixture_environment() -> Environment {
         Environment {
             os: "linux".to_string(),
+            arch: "x86_64".to_string(),
             pid: 12345,
             cwd: PathBuf::from("/home/user/project"),
             home: Some(PathBuf::from("/home/user")),
@@ -133,16 +164,29 @@ mod tests {
                 backoff_factor: 2,
                 max_retry_attempts: 3,
                 retry_status_codes: vec![429, 500, 502, 503, 504],
+                content_filter_codes: vec![],
                 max_delay: None,
             },
             max_search_lines: 25,
             fetch_truncation_limit: 55,
+            fetch_cache_ttl: 0,
             max_read_size: 10,
             stdout_max_prefix_length: 10,
             stdout_max_suffix_length: 10,
+            tool_limits: Default::default(),
             http: Default::default(),
             max_file_size: 0,
             forge_api_url: Url::parse("http://forgecode.dev/api").unwrap(),
+            shell_env: Default::default(),
+            truncation_template: None,
+            otlp_endpoint: None,
+            debug_raw_output: false,
+            sandbox_root: None,
+            max_concurrent_requests: None,
+            redact_home: false,
+            diff_ignore_whitespace: false,
+            diff_ignore_trailing_newline: false,
+            offload_dir: None,
         }
     }
 
@@ -152,6 +196,8 @@ mod tests {
             path: "/home/user/project/src/main.rs".to_string(),
             start_line: None,
             end_line: None,
+            revision: None,
+            with_line_numbers: None,
             explanation: None,
         });
         let env = fixture_environment();
@@ -170,6 +216,8 @@ mod tests {
             path: "/home/user/project/src/main.rs".to_string(),
             start_line: Some(10),
             end_line: Some(20),
+            revision: None,
+            with_line_numbers: None,
             explanation: None,
         });
         let env = fixture_environment();
@@ -224,6 +272,7 @@ mod tests {
             command: "ls -la".to_string(),
             cwd: PathBuf::from("/home/user/project"),
             keep_ansi: false,
+            parse_results: false,
             explanation: None,
         });
         let env = fixture_environment();
@@ -242,6 +291,8 @@ mod tests {
             path: "/home/user/project/src/main.rs".to_string(),
             start_line: None,
             end_line: None,
+            revision: None,
+            with_line_numbers: None,
             explanation: None,
         });
         let env = fixture_environment();
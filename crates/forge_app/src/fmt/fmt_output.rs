@@ -1,9 +1,9 @@
-use forge_display::{DiffFormat, GrepFormat};
+use forge_display::GrepFormat;
 use forge_domain::Environment;
 
 use crate::fmt::content::{ContentFormat, FormatContent};
 use crate::operation::Operation;
-use crate::utils::format_match;
+use crate::utils::{format_diff, format_match};
 
 impl FormatContent for Operation {
     fn to_content(&self, env: &Environment) -> Option<ContentFormat> {
@@ -24,15 +24,17 @@ impl FormatContent for Operation {
                 )
             }),
             Operation::FsPatch { input: _, output } => Some(ContentFormat::PlainText(
-                DiffFormat::format(&output.before, &output.after)
+                format_diff(&output.before, &output.after, env)
                     .diff()
                     .to_string(),
             )),
             Operation::FsUndo { input: _, output: _ } => None,
             Operation::NetFetch { input: _, output: _ } => None,
-            Operation::Shell { output: _ } => None,
+            Operation::Shell { .. } => None,
             Operation::FollowUp { output: _ } => None,
-            Operation::AttemptCompletion => None,
+            Operation::AttemptCompletion { task_report, .. } => task_report
+                .as_ref()
+                .map(|report| ContentFormat::Markdown(report.to_markdown())),
             Operation::TaskListAppend { _input: _, before, after }
             | Operation::TaskListAppendMultiple { _input: _, before, after }
             | Operation::TaskListUpdate { _input: _, before, after }
@@ -40,6 +42,19 @@ impl FormatContent for Operation {
             | Operation::TaskListClear { _input: _, before, after } => Some(
                 ContentFormat::Markdown(crate::fmt::fmt_task::to_markdown(before, after)),
             ),
+            Operation::ScratchpadWrite { .. } => None,
+            Operation::ScratchpadRead { .. } => None,
+            Operation::RepoTree { .. } => None,
+            Operation::FsReadGlob { .. } => None,
+            Operation::SystemInfo { .. } => None,
+            Operation::FsList { .. } => None,
+            Operation::FormatCode { input: _, output } => output.is_changed().then(|| {
+                ContentFormat::PlainText(
+                    format_diff(&output.before, &output.after, env)
+                        .diff()
+                        .to_string(),
+                )
+            }),
         }
     }
 }
@@ -92,6 +107,7 @@ mod tests {
     fn fixture_environment() -> Environment {
         Environment {
             os: "linux".to_string(),
+            arch: "x86_64".to_string(),
             pid: 12345,
             cwd: PathBuf::from("/home/user/project"),
             home: Some(PathBuf::from("/home/user")),
@@ -103,16 +119,29 @@ mod tests {
                 backoff_factor: 2,
                 max_retry_attempts: 3,
                 retry_status_codes: vec![429, 500, 502, 503, 504],
+                content_filter_codes: vec![],
                 max_delay: None,
             },
             max_search_lines: 25,
             fetch_truncation_limit: 55,
+            fetch_cache_ttl: 0,
             max_read_size: 10,
             stdout_max_prefix_length: 10,
             stdout_max_suffix_length: 10,
+            tool_limits: Default::default(),
             http: Default::default(),
             max_file_size: 0,
             forge_api_url: Url::parse("http://forgecode.dev/api").unwrap(),
+            shell_env: Default::default(),
+            truncation_template: None,
+            otlp_endpoint: None,
+            debug_raw_output: false,
+            sandbox_root: None,
+            max_concurrent_requests: None,
+            redact_home: false,
+            diff_ignore_whitespace: false,
+            diff_ignore_trailing_newline: false,
+            offload_dir: None,
         }
     }
 
@@ -123,6 +152,8 @@ mod tests {
                 path: "/home/user/test.txt".to_string(),
                 start_line: None,
                 end_line: None,
+                revision: None,
+                with_line_numbers: None,
                 explanation: Some("Test explanation".to_string()),
             },
             output: ReadOutput {
@@ -147,6 +178,8 @@ mod tests {
                 path: "/home/user/test.txt".to_string(),
                 start_line: Some(2),
                 end_line: Some(4),
+                revision: None,
+                with_line_numbers: None,
                 explanation: Some("Test explanation".to_string()),
             },
             output: ReadOutput {
@@ -256,6 +289,7 @@ mod tests {
                 path: "/home/user/project".to_string(),
                 regex: Some("Hello".to_string()),
                 file_pattern: None,
+                order: None,
                 max_search_lines: None,
                 start_index: None,
                 explanation: Some("Search for Hello".to_string()),
@@ -299,6 +333,7 @@ mod tests {
                 path: "/home/user/project".to_string(),
                 regex: Some("nonexistent".to_string()),
                 file_pattern: None,
+                order: None,
                 max_search_lines: None,
                 start_index: None,
                 explanation: Some("Search for nonexistent".to_string()),
@@ -327,6 +362,7 @@ mod tests {
                 path: "/home/user/project".to_string(),
                 regex: Some("search".to_string()),
                 file_pattern: None,
+                order: None,
                 max_search_lines: None,
                 start_index: None,
                 explanation: Some("Search test".to_string()),
@@ -416,6 +452,10 @@ mod tests {
             input: forge_domain::NetFetch {
                 url: "https://example.com".to_string(),
                 raw: Some(false),
+                no_cache: None,
+                method: None,
+                headers: None,
+                body: None,
                 explanation: Some("Fetch example website".to_string()),
             },
             output: HttpResponse {
@@ -423,6 +463,7 @@ mod tests {
                 code: 200,
                 context: ResponseContext::Parsed,
                 content_type: "text/html".to_string(),
+                cached: false,
             },
         };
         let env = fixture_environment();
@@ -439,6 +480,10 @@ mod tests {
             input: forge_domain::NetFetch {
                 url: "https://example.com/notfound".to_string(),
                 raw: Some(true),
+                no_cache: None,
+                method: None,
+                headers: None,
+                body: None,
                 explanation: Some("Fetch non-existent page".to_string()),
             },
             output: HttpResponse {
@@ -446,6 +491,7 @@ mod tests {
                 code: 404,
                 context: ResponseContext::Raw,
                 content_type: "text/plain".to_string(),
+                cached: false,
             },
         };
         let env = fixture_environment();
@@ -468,6 +514,7 @@ mod tests {
                 },
                 shell: "/bin/bash".to_string(),
             },
+            test_results: None,
         };
         let env = fixture_environment();
 
@@ -489,6 +536,7 @@ mod tests {
                 },
                 shell: "/bin/bash".to_string(),
             },
+            test_results: None,
         };
         let env = fixture_environment();
 
@@ -510,6 +558,7 @@ mod tests {
                 },
                 shell: "/bin/bash".to_string(),
             },
+            test_results: None,
         };
         let env = fixture_environment();
 
@@ -544,7 +593,11 @@ mod tests {
 
     #[test]
     fn test_attempt_completion() {
-        let fixture = Operation::AttemptCompletion;
+        let fixture = Operation::AttemptCompletion {
+            feedback: forge_domain::DEFAULT_COMPLETION_FEEDBACK.to_string(),
+            changed_files: Default::default(),
+            task_report: None,
+        };
         let env = fixture_environment();
 
         let actual = fixture.to_content(&env);
@@ -0,0 +1,265 @@
+use std::path::Path;
+
+use anyhow::bail;
+
+use crate::services::{FsCreateService, FsReadService, ShellService};
+
+/// A file run through its formatter
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormattedFile {
+    pub formatter: String,
+    pub before: String,
+    pub after: String,
+}
+
+impl FormattedFile {
+    pub fn is_changed(&self) -> bool {
+        self.before != self.after
+    }
+}
+
+/// Pipes the file through the formatter and writes the formatted content back
+/// with a snapshot, so that the formatting can be undone. The file is only
+/// written once the formatter succeeded and changed it, a failing formatter
+/// leaves it as it was. The output of the formatter is written as it was
+/// printed, ANSI codes included
+pub async fn format_file<S: ShellService + FsReadService + FsCreateService>(
+    services: &S,
+    path: &Path,
+    formatter: &str,
+) -> anyhow::Result<FormattedFile> {
+    if !path.is_absolute() {
+        bail!("Path must be absolute: {}", path.display());
+    }
+    let cwd = path.parent().unwrap_or(path).to_path_buf();
+    let before = services.read_all(path.display().to_string()).await?;
+    let output = services
+        .pipe(formatter.to_string(), cwd, before.clone(), true)
+        .await?
+        .output;
+    if output.exit_code != Some(0) {
+        let status = output
+            .exit_code
+            .map_or("was terminated".to_string(), |code| {
+                format!("exited with {code}")
+            });
+        bail!(
+            "Formatter `{formatter}` {status}, the file was left unchanged: {}",
+            output.stderr.trim()
+        );
+    }
+    // Formatters that rewrite the file in place print nothing, writing their
+    // output would empty the file
+    if output.stdout.is_empty() {
+        bail!(
+            "Formatter `{formatter}` printed nothing, it has to write the formatted file to stdout"
+        );
+    }
+
+    // Already formatted, there's nothing to write or to undo
+    if output.stdout == before {
+        return Ok(FormattedFile {
+            formatter: formatter.to_string(),
+            before,
+            after: output.stdout,
+        });
+    }
+
+    let created = services
+        .create(
+            path.display().to_string(),
+            output.stdout.clone(),
+            true,
+            true,
+        )
+        .await?;
+    Ok(FormattedFile {
+        formatter: formatter.to_string(),
+        before: created.before.unwrap_or_default(),
+        after: output.stdout,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    use console::strip_ansi_codes;
+    use forge_domain::CommandOutput;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{FsCreateOutput, ReadOutput, ShellOutput};
+
+    const PATH: &str = "/project/src/main.rs";
+
+    /// Holds a single file. The `indent` formatter indents every line of it,
+    /// the `highlight` formatter wraps it in ANSI codes, the `cat` formatter
+    /// leaves it as it is and the `broken` formatter fails
+    struct MockFormatter {
+        content: Mutex<String>,
+        snapshots: Mutex<Vec<String>>,
+    }
+
+    impl MockFormatter {
+        fn new(content: &str) -> Self {
+            Self {
+                content: Mutex::new(content.to_string()),
+                snapshots: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn content(&self) -> String {
+            self.content.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ShellService for MockFormatter {
+        async fn execute(
+            &self,
+            command: String,
+            cwd: PathBuf,
+            keep_ansi: bool,
+        ) -> anyhow::Result<ShellOutput> {
+            self.pipe(command, cwd, String::new(), keep_ansi).await
+        }
+
+        async fn pipe(
+            &self,
+            command: String,
+            _cwd: PathBuf,
+            input: String,
+            keep_ansi: bool,
+        ) -> anyhow::Result<ShellOutput> {
+            let (stdout, stderr, exit_code) = match command.as_str() {
+                "highlight" => (format!("\x1b[1m{input}\x1b[0m"), String::new(), 0),
+                "indent" => {
+                    let indented = input
+                        .lines()
+                        .map(|line| format!("    {line}\n"))
+                        .collect::<String>();
+                    (indented, String::new(), 0)
+                }
+                "cat" => (input, String::new(), 0),
+                _ => (String::new(), "error: expected `;`".to_string(), 1),
+            };
+            let stdout = if keep_ansi {
+                stdout
+            } else {
+                strip_ansi_codes(&stdout).into_owned()
+            };
+            Ok(ShellOutput {
+                output: CommandOutput { command, stdout, stderr, exit_code: Some(exit_code) },
+                shell: "/bin/bash".to_string(),
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FsReadService for MockFormatter {
+        async fn read(
+            &self,
+            _path: String,
+            _start_line: Option<u64>,
+            _end_line: Option<u64>,
+        ) -> anyhow::Result<ReadOutput> {
+            unimplemented!()
+        }
+
+        async fn read_all(&self, _path: String) -> anyhow::Result<String> {
+            Ok(self.content())
+        }
+
+        async fn read_revision(
+            &self,
+            _path: String,
+            _revision: String,
+            _start_line: Option<u64>,
+            _end_line: Option<u64>,
+        ) -> anyhow::Result<ReadOutput> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FsCreateService for MockFormatter {
+        async fn create(
+            &self,
+            _path: String,
+            content: String,
+            _overwrite: bool,
+            capture_snapshot: bool,
+        ) -> anyhow::Result<FsCreateOutput> {
+            let before = std::mem::replace(&mut *self.content.lock().unwrap(), content);
+            if capture_snapshot {
+                self.snapshots.lock().unwrap().push(before.clone());
+            }
+            Ok(FsCreateOutput { path: PATH.to_string(), before: Some(before), warning: None })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_format_file_writes_the_formatted_content_with_a_snapshot() {
+        let fixture = MockFormatter::new("fn main() {}\n");
+
+        let actual = format_file(&fixture, Path::new(PATH), "indent")
+            .await
+            .unwrap();
+
+        let expected = FormattedFile {
+            formatter: "indent".to_string(),
+            before: "fn main() {}\n".to_string(),
+            after: "    fn main() {}\n".to_string(),
+        };
+        assert_eq!(actual, expected);
+        assert_eq!(fixture.content(), "    fn main() {}\n");
+        assert_eq!(*fixture.snapshots.lock().unwrap(), vec!["fn main() {}\n"]);
+    }
+
+    #[tokio::test]
+    async fn test_format_file_skips_the_write_when_the_file_is_already_formatted() {
+        let fixture = MockFormatter::new("fn main() {}\n");
+
+        let actual = format_file(&fixture, Path::new(PATH), "cat").await.unwrap();
+
+        let expected = FormattedFile {
+            formatter: "cat".to_string(),
+            before: "fn main() {}\n".to_string(),
+            after: "fn main() {}\n".to_string(),
+        };
+        assert_eq!(actual, expected);
+        assert!(!actual.is_changed());
+        assert!(fixture.snapshots.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_format_file_leaves_the_file_unchanged_when_the_formatter_fails() {
+        let fixture = MockFormatter::new("fn main() {\n");
+
+        let actual = format_file(&fixture, Path::new(PATH), "broken")
+            .await
+            .unwrap_err()
+            .to_string();
+
+        let expected =
+            "Formatter `broken` exited with 1, the file was left unchanged: error: expected `;`";
+        assert_eq!(actual, expected);
+        assert_eq!(fixture.content(), "fn main() {\n");
+        assert!(fixture.snapshots.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_format_file_writes_the_formatter_output_as_printed() {
+        let fixture = MockFormatter::new("fn main() {}\n");
+
+        let actual = format_file(&fixture, Path::new(PATH), "highlight")
+            .await
+            .unwrap();
+
+        let expected = "\x1b[1mfn main() {}\n\x1b[0m";
+        assert_eq!(actual.after, expected);
+        assert_eq!(fixture.content(), expected);
+    }
+}
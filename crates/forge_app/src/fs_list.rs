@@ -0,0 +1,102 @@
+use forge_domain::File;
+
+/// Entries of a directory found by the walker, ordered by path
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DirListing {
+    /// Paths relative to the listed directory, directories end with `/`
+    pub entries: Vec<File>,
+}
+
+impl DirListing {
+    /// Builds the listing from the files found by the walker, leaving out the
+    /// listed directory itself
+    pub fn new(files: impl IntoIterator<Item = File>) -> Self {
+        let mut entries = files
+            .into_iter()
+            .filter(|file| file.path != "/" && !file.path.is_empty())
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        Self { entries }
+    }
+
+    /// Renders one line per entry with its type, its size and its path. Only
+    /// files have a size, and rendering stops after `max_entries` lines
+    pub fn render(&self, max_entries: usize) -> Vec<String> {
+        self.entries
+            .iter()
+            .take(max_entries)
+            .map(|entry| {
+                if entry.is_dir {
+                    format!("dir\t-\t{}", entry.path)
+                } else {
+                    format!("file\t{}\t{}", entry.size, entry.path)
+                }
+            })
+            .collect()
+    }
+
+    /// Directory holding the first entry left out when rendering at most
+    /// `max_entries` lines, relative to the listed directory
+    pub fn first_hidden_dir(&self, max_entries: usize) -> Option<&str> {
+        let hidden = self.entries.get(max_entries)?;
+        let path = hidden.path.trim_end_matches('/');
+        Some(path.rsplit_once('/').map_or("", |(dir, _)| dir))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture_listing() -> DirListing {
+        DirListing::new(
+            [
+                ("/", true, 4096),
+                ("src/main.rs", false, 120),
+                ("Cargo.toml", false, 312),
+                ("src/", true, 4096),
+                ("src/tools/", true, 4096),
+                ("src/tools/list.rs", false, 2048),
+            ]
+            .map(|(path, is_dir, size)| File {
+                path: path.to_string(),
+                is_dir,
+                size,
+                modified: None,
+            }),
+        )
+    }
+
+    #[test]
+    fn test_render_lists_entries_by_path_without_the_root() {
+        let fixture = fixture_listing();
+
+        let actual = fixture.render(10);
+
+        let expected = vec![
+            "file\t312\tCargo.toml",
+            "dir\t-\tsrc/",
+            "file\t120\tsrc/main.rs",
+            "dir\t-\tsrc/tools/",
+            "file\t2048\tsrc/tools/list.rs",
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_first_hidden_dir_points_at_the_rest_of_the_listing() {
+        let fixture = fixture_listing();
+
+        let actual = (
+            fixture.render(4).len(),
+            fixture.first_hidden_dir(4),
+            fixture.first_hidden_dir(3),
+            fixture.first_hidden_dir(5),
+        );
+
+        let expected = (4, Some("src/tools"), Some("src"), None);
+        assert_eq!(actual, expected);
+    }
+}
@@ -6,13 +6,24 @@ mod authenticator;
 mod compact;
 mod error;
 pub mod fmt;
+mod format_code;
+mod fs_list;
 mod mcp_executor;
 mod operation;
 mod orch;
+mod project_rules;
+mod read_glob;
+mod repo_tree;
+mod request_limit;
 mod retry;
+mod search_order;
 mod services;
+mod system_info;
+mod test_results;
+mod text_coalescer;
 mod tool_executor;
 mod tool_registry;
+mod tool_server;
 mod truncation;
 mod user;
 mod utils;
@@ -21,7 +32,11 @@ mod walker;
 pub use app::*;
 pub use app_config::*;
 pub use error::*;
+pub use project_rules::LayerRules;
+pub use request_limit::RequestLimit;
 pub use services::*;
+pub use system_info::{Toolchain, ToolchainCache};
+pub use tool_server::*;
 pub use user::*;
 pub use walker::*;
 pub mod domain {
@@ -3,18 +3,26 @@ use std::path::{Path, PathBuf};
 
 use console::strip_ansi_codes;
 use derive_setters::Setters;
-use forge_display::DiffFormat;
 use forge_domain::{
-    Environment, FSPatch, FSRead, FSRemove, FSSearch, FSUndo, FSWrite, NetFetch, TaskList,
-    TaskListAppend, TaskListAppendMultiple, TaskListClear, TaskListList, TaskListUpdate, ToolName,
+    ChangedFiles, Environment, FSListInput, FSPatch, FSRead, FSReadGlob, FSRemove, FSSearch,
+    FSUndo, FSWrite, FormatCode, NetFetch, OFFLOAD_PREFIX, RepoTree, ScratchpadRead,
+    ScratchpadWrite, Task, TaskList, TaskListAppend, TaskListAppendMultiple, TaskListClear,
+    TaskListList, TaskListUpdate, TaskReport, ToolName, ToolsDiscriminants,
 };
 use forge_template::Element;
-
+use serde_json::json;
+
+use crate::format_code::FormattedFile;
+use crate::fs_list::DirListing;
+use crate::read_glob::{GlobReadOutput, MAX_WALKED_FILES};
+use crate::repo_tree::{DEFAULT_MAX_DEPTH, DEFAULT_MAX_ENTRIES, DirTree};
+use crate::system_info::SystemFacts;
+use crate::test_results::TestResults;
 use crate::truncation::{
-    StreamElement, create_temp_file, truncate_fetch_content, truncate_search_output,
-    truncate_shell_output,
+    StreamElement, TruncationNote, create_temp_file, truncate_fetch_content,
+    truncate_search_output, truncate_shell_output,
 };
-use crate::utils::format_display_path;
+use crate::utils::{format_diff, format_display_path};
 use crate::{
     Content, EnvironmentService, FsCreateOutput, FsCreateService, FsUndoOutput, HttpResponse,
     PatchOutput, ReadOutput, ResponseContext, SearchResult, ShellOutput,
@@ -69,11 +77,16 @@ pub enum Operation {
     },
     Shell {
         output: ShellOutput,
+        test_results: Option<TestResults>,
     },
     FollowUp {
         output: Option<String>,
     },
-    AttemptCompletion,
+    AttemptCompletion {
+        feedback: String,
+        changed_files: ChangedFiles,
+        task_report: Option<TaskReport>,
+    },
     TaskListAppend {
         _input: TaskListAppend,
         before: TaskList,
@@ -99,6 +112,33 @@ pub enum Operation {
         before: TaskList,
         after: TaskList,
     },
+    ScratchpadWrite {
+        _input: ScratchpadWrite,
+        content: String,
+    },
+    ScratchpadRead {
+        _input: ScratchpadRead,
+        content: String,
+    },
+    RepoTree {
+        input: RepoTree,
+        output: DirTree,
+    },
+    FsReadGlob {
+        input: FSReadGlob,
+        output: GlobReadOutput,
+    },
+    SystemInfo {
+        output: SystemFacts,
+    },
+    FormatCode {
+        input: FormatCode,
+        output: FormattedFile,
+    },
+    FsList {
+        input: FSListInput,
+        output: DirListing,
+    },
 }
 
 /// Helper function to create stdout or stderr elements with consistent
@@ -106,6 +146,7 @@ pub enum Operation {
 fn create_stream_element<T: StreamElement>(
     stream: &T,
     full_output_path: Option<&Path>,
+    env: &Environment,
 ) -> Option<Element> {
     if stream.head_content().is_empty() {
         return None;
@@ -134,11 +175,199 @@ fn create_stream_element<T: StreamElement>(
 
     if let Some(path) = full_output_path {
         elem = elem.attr("full_output", path.display());
+
+        // The hidden lines sit between the head and the tail
+        if let Some(tail_start) = stream.tail_start_line() {
+            let note = TruncationNote::new(
+                format!(
+                    "Showing lines 1-{} and {}-{} of {}",
+                    stream.head_end_line(),
+                    tail_start,
+                    stream.total_lines(),
+                    stream.total_lines()
+                ),
+                ToolsDiscriminants::ForgeToolFsRead,
+                json!({
+                    "path": path.display().to_string(),
+                    "start_line": stream.head_end_line() + 1,
+                    "end_line": tail_start - 1,
+                }),
+            );
+            elem = elem.append(note.to_element(env));
+        }
     }
 
     Some(elem)
 }
+
+/// Creates the element holding what was read from a file, with a note on how
+/// to read the rest when only part of it was returned
+fn create_file_content_element(
+    path: &str,
+    revision: Option<&str>,
+    with_line_numbers: bool,
+    output: &ReadOutput,
+    env: &Environment,
+) -> Element {
+    let Content::File(content) = &output.content;
+    let body = if with_line_numbers {
+        number_lines(content, output.start_line)
+    } else {
+        content.clone()
+    };
+    let mut elm = Element::new("file_content")
+        .attr("path", path)
+        .attr_if_some("revision", revision)
+        .attr(
+            "display_lines",
+            format!("{}-{}", output.start_line, output.end_line),
+        )
+        .attr("total_lines", content.lines().count())
+        .attr("max_lines", env.max_read_size)
+        .cdata(body);
+
+    if output.end_line < output.total_lines {
+        let mut arguments = json!({
+            "path": path,
+            "start_line": output.end_line + 1,
+            "end_line": min(output.end_line + env.max_read_size, output.total_lines),
+        });
+        if let Some(revision) = revision {
+            arguments["revision"] = json!(revision);
+        }
+        let note = TruncationNote::new(
+            format!(
+                "Showing lines {}-{} of {}",
+                output.start_line, output.end_line, output.total_lines
+            ),
+            ToolsDiscriminants::ForgeToolFsRead,
+            arguments,
+        );
+        elm = elm.append(note.to_element(env));
+    }
+
+    elm
+}
+
+/// Prefixes every line with its number in the file followed by a tab, the
+/// content starting at the given line
+fn number_lines(content: &str, start_line: u64) -> String {
+    let last_line = start_line + content.lines().count().saturating_sub(1) as u64;
+    let width = last_line.to_string().len();
+    let mut numbered = content
+        .lines()
+        .zip(start_line..)
+        .map(|(line, number)| format!("{number:>width$}\t{line}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if content.ends_with('\n') {
+        numbered.push('\n');
+    }
+    numbered
+}
+
+/// Creates the list of files under the given name
+fn create_file_list_element(name: &str, paths: &[String]) -> Element {
+    Element::new(name).append(paths.iter().map(|path| Element::new("file").text(path)))
+}
+
+/// Creates the structured summary of a test run
+fn create_test_results_element(results: &TestResults) -> Element {
+    Element::new("test_results")
+        .attr("passed", results.passed)
+        .attr("failed", results.failed)
+        .attr("ignored", results.ignored)
+        .append(results.failures.iter().map(|failure| {
+            Element::new("failure")
+                .attr("name", &failure.name)
+                .attr_if_some("location", failure.location.as_ref())
+                .cdata(&failure.message)
+        }))
+}
+
+/// Creates the manifest of files changed during the conversation, omitting
+/// empty groups
+fn create_changed_files_element(changed_files: &ChangedFiles) -> Element {
+    [
+        ("created", &changed_files.created),
+        ("modified", &changed_files.modified),
+        ("removed", &changed_files.removed),
+    ]
+    .into_iter()
+    .filter(|(_, paths)| !paths.is_empty())
+    .fold(Element::new("changed_files"), |elem, (name, paths)| {
+        elem.append(
+            Element::new(name).append(paths.iter().map(|path| Element::new("file").text(path))),
+        )
+    })
+}
+
+fn create_task_report_element(report: &TaskReport) -> Element {
+    let tasks = |name: &str, tasks: &[Task]| {
+        Element::new(name).append(tasks.iter().map(|task| {
+            Element::new("task")
+                .attr("id", task.id)
+                .cdata(task.task.as_str())
+        }))
+    };
+    Element::new("task_report")
+        .append(tasks("done", &report.done))
+        .append(tasks("pending", &report.pending))
+        .append(
+            Element::new("unplanned").append(
+                report
+                    .unplanned_files
+                    .iter()
+                    .map(|path| Element::new("file").text(path)),
+            ),
+        )
+}
+
 impl Operation {
+    /// Renders the values the operation produced as they were, before any
+    /// truncation or wrapping in elements. Meant for debugging how tool output
+    /// gets formatted.
+    /// The unformatted texts of the output, such as the output of a command
+    /// or the content of a page
+    pub fn raw_texts_mut(&mut self) -> Vec<&mut String> {
+        match self {
+            Operation::Shell { output, .. } => {
+                vec![&mut output.output.stdout, &mut output.output.stderr]
+            }
+            Operation::FsRead { output, .. } => match &mut output.content {
+                Content::File(content) => vec![content],
+            },
+            Operation::NetFetch { output, .. } => vec![&mut output.content],
+            _ => vec![],
+        }
+    }
+
+    pub fn to_raw_dump(&self) -> String {
+        match self {
+            Operation::Shell { output, .. } => {
+                let output = &output.output;
+                let exit_code = output
+                    .exit_code
+                    .map_or("none".to_string(), |code| code.to_string());
+                format!(
+                    "command: {}\nexit_code: {exit_code}\n--- stdout ---\n{}\n--- stderr ---\n{}",
+                    output.command, output.stdout, output.stderr
+                )
+            }
+            Operation::FsRead { input, output } => match &output.content {
+                Content::File(content) => format!(
+                    "path: {}\nlines: {}-{} of {}\n--- content ---\n{content}",
+                    input.path, output.start_line, output.end_line, output.total_lines
+                ),
+            },
+            Operation::NetFetch { input, output } => format!(
+                "url: {}\nstatus: {}\ncontent_type: {}\n--- content ---\n{}",
+                input.url, output.code, output.content_type, output.content
+            ),
+            operation => format!("{operation:#?}"),
+        }
+    }
+
     pub fn into_tool_output(
         self,
         tool_name: ToolName,
@@ -146,23 +375,18 @@ impl Operation {
         env: &Environment,
     ) -> forge_domain::ToolOutput {
         match self {
-            Operation::FsRead { input, output } => match &output.content {
-                Content::File(content) => {
-                    let elm = Element::new("file_content")
-                        .attr("path", input.path)
-                        .attr(
-                            "display_lines",
-                            format!("{}-{}", output.start_line, output.end_line),
-                        )
-                        .attr("total_lines", content.lines().count())
-                        .cdata(content);
-
-                    forge_domain::ToolOutput::text(elm)
-                }
-            },
+            Operation::FsRead { input, output } => {
+                forge_domain::ToolOutput::text(create_file_content_element(
+                    &input.path,
+                    input.revision.as_deref(),
+                    input.with_line_numbers.unwrap_or_default(),
+                    &output,
+                    env,
+                ))
+            }
             Operation::FsCreate { input, output } => {
                 let mut elm = if let Some(before) = output.before.as_ref() {
-                    let diff_result = DiffFormat::format(before, &input.content);
+                    let diff_result = format_diff(before, &input.content, env);
                     let diff = console::strip_ansi_codes(diff_result.diff()).to_string();
                     // Log file change stats
                     file_change_stats(FileOperationStats {
@@ -197,7 +421,7 @@ impl Operation {
             Operation::FsSearch { input, output } => match output {
                 Some(out) => {
                     let max_lines = min(
-                        env.max_search_lines,
+                        env.search_limit(),
                         input.max_search_lines.unwrap_or(i32::MAX) as u64,
                     );
                     let start_index = input.start_index.unwrap_or(1);
@@ -210,6 +434,35 @@ impl Operation {
                         search_dir,
                     );
 
+                    let note =
+                        (truncated_output.end_line < truncated_output.total_lines).then(|| {
+                            let mut arguments = json!({
+                                "path": &input.path,
+                                "start_index": truncated_output.end_line + 1,
+                            });
+                            for (key, value) in [
+                                ("regex", &input.regex),
+                                ("file_pattern", &input.file_pattern),
+                            ] {
+                                if let Some(value) = value {
+                                    arguments[key] = json!(value);
+                                }
+                            }
+                            if let Some(order) = &input.order {
+                                arguments["order"] = json!(order);
+                            }
+                            TruncationNote::new(
+                                format!(
+                                    "Showing results {}-{} of {}",
+                                    truncated_output.start_line,
+                                    truncated_output.end_line,
+                                    truncated_output.total_lines
+                                ),
+                                ToolsDiscriminants::ForgeToolFsSearch,
+                                arguments,
+                            )
+                        });
+
                     let mut elm = Element::new("search_results")
                         .attr("path", &input.path)
                         .attr("total_lines", truncated_output.total_lines)
@@ -225,6 +478,7 @@ impl Operation {
                     elm = elm.attr_if_some("file_pattern", input.file_pattern);
 
                     elm = elm.cdata(truncated_output.output.trim());
+                    elm = elm.append(note.map(|note| note.to_element(env)));
 
                     forge_domain::ToolOutput::text(elm)
                 }
@@ -237,7 +491,7 @@ impl Operation {
                 }
             },
             Operation::FsPatch { input, output } => {
-                let diff_result = DiffFormat::format(&output.before, &output.after);
+                let diff_result = format_diff(&output.before, &output.after, env);
                 let diff = console::strip_ansi_codes(diff_result.diff()).to_string();
                 let mut elm = Element::new("file_diff")
                     .attr("path", &input.path)
@@ -282,7 +536,7 @@ impl Operation {
                         forge_domain::ToolOutput::text(elm)
                     }
                     (Some(after), Some(before)) => {
-                        let diff = DiffFormat::format(before, after);
+                        let diff = format_diff(before, after, env);
                         file_change_stats(FileOperationStats {
                             path: input.path.clone(),
                             tool_name,
@@ -304,31 +558,40 @@ impl Operation {
                     ResponseContext::Parsed => "text/markdown".to_string(),
                     ResponseContext::Raw => output.content_type,
                 };
-                let truncated_content =
-                    truncate_fetch_content(&output.content, env.fetch_truncation_limit);
+                let fetch_limit = env.fetch_limit();
+                let truncated_content = truncate_fetch_content(&output.content, fetch_limit);
                 let mut elm = Element::new("http_response")
                     .attr("url", &input.url)
                     .attr("status_code", output.code)
                     .attr("start_char", 0)
-                    .attr(
-                        "end_char",
-                        env.fetch_truncation_limit.min(output.content.len()),
-                    )
+                    .attr("end_char", fetch_limit.min(output.content.len()))
                     .attr("total_chars", output.content.len())
-                    .attr("content_type", content_type);
+                    .attr("content_type", content_type)
+                    .attr_if_some("cached", output.cached.then_some(true));
 
+                // The saved file holds the whole content, continue from the line the
+                // returned part ends in
+                let start_line = truncated_content.content.lines().count().max(1);
                 elm = elm.append(Element::new("body").cdata(truncated_content.content));
                 if let Some(path) = content_files.stdout {
-                    elm = elm.append(Element::new("truncated").text(
+                    let note = TruncationNote::new(
                         format!(
-                            "Content is truncated to {} chars, remaining content can be read from path: {}",
-                            env.fetch_truncation_limit, path.display())
-                    ));
+                            "Showing characters 0-{} of {}",
+                            fetch_limit,
+                            output.content.len()
+                        ),
+                        ToolsDiscriminants::ForgeToolFsRead,
+                        json!({
+                            "path": path.display().to_string(),
+                            "start_line": start_line,
+                        }),
+                    );
+                    elm = elm.append(note.to_element(env));
                 }
 
                 forge_domain::ToolOutput::text(elm)
             }
-            Operation::Shell { output } => {
+            Operation::Shell { output, test_results } => {
                 let mut parent_elem = Element::new("shell_output")
                     .attr("command", &output.output.command)
                     .attr("shell", &output.shell);
@@ -337,25 +600,30 @@ impl Operation {
                     parent_elem = parent_elem.attr("exit_code", exit_code);
                 }
 
+                let (prefix_lines, suffix_lines) = env.shell_limits();
                 let truncated_output = truncate_shell_output(
                     &output.output.stdout,
                     &output.output.stderr,
-                    env.stdout_max_prefix_length,
-                    env.stdout_max_suffix_length,
+                    prefix_lines,
+                    suffix_lines,
                 );
 
                 let stdout_elem = create_stream_element(
                     &truncated_output.stdout,
                     content_files.stdout.as_deref(),
+                    env,
                 );
 
                 let stderr_elem = create_stream_element(
                     &truncated_output.stderr,
                     content_files.stderr.as_deref(),
+                    env,
                 );
 
                 parent_elem = parent_elem.append(stdout_elem);
                 parent_elem = parent_elem.append(stderr_elem);
+                parent_elem =
+                    parent_elem.append(test_results.as_ref().map(create_test_results_element));
 
                 forge_domain::ToolOutput::text(parent_elem)
             }
@@ -369,10 +637,24 @@ impl Operation {
                     forge_domain::ToolOutput::text(elm)
                 }
             },
-            Operation::AttemptCompletion => forge_domain::ToolOutput::text(
-                Element::new("success")
-                    .text("[Task was completed successfully. Now wait for user feedback]"),
-            ),
+            Operation::AttemptCompletion { feedback, changed_files, task_report } => {
+                let elm = Element::new("success");
+                let elm = if feedback.is_empty() {
+                    elm
+                } else {
+                    elm.text(feedback)
+                };
+                let elm = if changed_files.is_empty() {
+                    elm
+                } else {
+                    elm.append(create_changed_files_element(&changed_files))
+                };
+                let elm = match task_report {
+                    Some(report) => elm.append(create_task_report_element(&report)),
+                    None => elm,
+                };
+                forge_domain::ToolOutput::text(elm)
+            }
             Operation::TaskListAppend { _input: _, before: _, after }
             | Operation::TaskListAppendMultiple { _input: _, before: _, after }
             | Operation::TaskListUpdate { _input: _, before: _, after }
@@ -393,6 +675,135 @@ impl Operation {
                     }));
                 forge_domain::ToolOutput::text(elm)
             }
+            Operation::ScratchpadWrite { _input: _, content }
+            | Operation::ScratchpadRead { _input: _, content } => {
+                forge_domain::ToolOutput::text(Element::new("scratchpad").cdata(content))
+            }
+            Operation::RepoTree { input, output } => {
+                let max_depth = input
+                    .max_depth
+                    .map_or(DEFAULT_MAX_DEPTH, |depth| depth.max(1) as usize);
+                let max_entries = input
+                    .max_entries
+                    .map_or(DEFAULT_MAX_ENTRIES, |entries| entries.max(1) as usize);
+                let tree = output.render(max_depth, max_entries);
+
+                let mut elm = Element::new("repo_tree")
+                    .attr("path", env.cwd.display())
+                    .attr("max_depth", max_depth)
+                    .attr("total_files", output.file_count())
+                    .cdata(tree.lines.join("\n"));
+
+                if tree.hidden_entries > 0 {
+                    let total_entries = tree.lines.len() + tree.hidden_entries;
+                    let note = TruncationNote::new(
+                        format!("Showing {} of {total_entries} entries", tree.lines.len()),
+                        ToolsDiscriminants::ForgeToolRepoTree,
+                        json!({ "max_depth": max_depth, "max_entries": total_entries }),
+                    );
+                    elm = elm.append(note.to_element(env));
+                }
+
+                forge_domain::ToolOutput::text(elm)
+            }
+            Operation::FsList { input, output } => {
+                let max_entries = env.max_search_lines as usize;
+                let recursive = input.recursive.unwrap_or_default();
+                let lines = output.render(max_entries);
+
+                let mut elm = Element::new("directory_listing")
+                    .attr("path", &input.path)
+                    .attr("recursive", recursive)
+                    .attr("total_entries", output.entries.len())
+                    .attr("columns", "type size path")
+                    .cdata(lines.join("\n"));
+
+                if lines.len() < output.entries.len() {
+                    let shown = format!(
+                        "Showing {} of {} entries",
+                        lines.len(),
+                        output.entries.len()
+                    );
+                    // The rest is reached by listing the directory it starts in
+                    elm = match output.first_hidden_dir(max_entries) {
+                        Some(dir) if recursive && !dir.is_empty() => {
+                            let path = Path::new(&input.path).join(dir);
+                            let note = TruncationNote::new(
+                                shown,
+                                ToolsDiscriminants::ForgeToolFsList,
+                                json!({ "path": path.display().to_string(), "recursive": true }),
+                            );
+                            elm.append(note.to_element(env))
+                        }
+                        _ => elm.append(Element::new("truncated").text(shown)),
+                    };
+                }
+
+                forge_domain::ToolOutput::text(elm)
+            }
+            Operation::FsReadGlob { input, output } => {
+                let mut elm = Element::new("files")
+                    .attr("path", &input.path)
+                    .attr_if_some(
+                        "pattern",
+                        Some(&input.pattern).filter(|pattern| !pattern.is_empty()),
+                    )
+                    .attr("total_matches", output.total_matches())
+                    .append(output.files.iter().map(|file| {
+                        create_file_content_element(&file.path, None, false, &file.output, env)
+                    }));
+
+                if !output.skipped.is_empty() {
+                    elm = elm.append(create_file_list_element("skipped", &output.skipped));
+                }
+
+                if output.is_walk_truncated {
+                    elm = elm.append(Element::new("walk_truncated").text(format!(
+                        "The walk stopped after {MAX_WALKED_FILES} files, files past them weren't matched against the pattern. Use a narrower path to match them"
+                    )));
+                }
+
+                if let Some(next) = output.omitted.first() {
+                    let note = TruncationNote::new(
+                        format!(
+                            "Showing {} of {} matching files",
+                            output.files.len(),
+                            output.total_matches()
+                        ),
+                        ToolsDiscriminants::ForgeToolFsRead,
+                        json!({ "path": next }),
+                    );
+                    elm = elm
+                        .append(create_file_list_element("omitted", &output.omitted))
+                        .append(note.to_element(env));
+                }
+
+                forge_domain::ToolOutput::text(elm)
+            }
+            Operation::SystemInfo { output } => forge_domain::ToolOutput::text(output.to_element()),
+            Operation::FormatCode { input, output } => {
+                let mut elm = Element::new("file_formatted")
+                    .attr("path", &input.path)
+                    .attr("formatter", &output.formatter)
+                    .attr("total_lines", output.after.lines().count());
+
+                if output.is_changed() {
+                    let diff_result = format_diff(&output.before, &output.after, env);
+                    file_change_stats(FileOperationStats {
+                        path: input.path,
+                        tool_name,
+                        lines_added: diff_result.lines_added(),
+                        lines_removed: diff_result.lines_removed(),
+                    });
+                    elm = elm.append(
+                        Element::new("file_diff").cdata(strip_ansi_codes(diff_result.diff())),
+                    );
+                } else {
+                    elm = elm.attr("status", "no_changes");
+                }
+
+                forge_domain::ToolOutput::text(elm)
+            }
         }
     }
 
@@ -403,26 +814,30 @@ impl Operation {
         match self {
             Operation::NetFetch { input: _, output } => {
                 let original_length = output.content.len();
-                let is_truncated =
-                    original_length > services.get_environment().fetch_truncation_limit;
+                let is_truncated = original_length > services.get_environment().fetch_limit();
                 let mut files = TempContentFiles::default();
 
                 if is_truncated {
                     files = files.stdout(
-                        create_temp_file(services, "forge_fetch_", ".txt", &output.content).await?,
+                        create_temp_file(
+                            services,
+                            &format!("{OFFLOAD_PREFIX}fetch_"),
+                            ".txt",
+                            &output.content,
+                        )
+                        .await?,
                     );
                 }
 
                 Ok(files)
             }
-            Operation::Shell { output } => {
+            Operation::Shell { output, .. } => {
                 let env = services.get_environment();
                 let stdout_lines = output.output.stdout.lines().count();
                 let stderr_lines = output.output.stderr.lines().count();
-                let stdout_truncated =
-                    stdout_lines > env.stdout_max_prefix_length + env.stdout_max_suffix_length;
-                let stderr_truncated =
-                    stderr_lines > env.stdout_max_prefix_length + env.stdout_max_suffix_length;
+                let (prefix_lines, suffix_lines) = env.shell_limits();
+                let stdout_truncated = stdout_lines > prefix_lines + suffix_lines;
+                let stderr_truncated = stderr_lines > prefix_lines + suffix_lines;
 
                 let mut files = TempContentFiles::default();
 
@@ -430,7 +845,7 @@ impl Operation {
                     files = files.stdout(
                         create_temp_file(
                             services,
-                            "forge_shell_stdout_",
+                            &format!("{OFFLOAD_PREFIX}shell_stdout_"),
                             ".txt",
                             &output.output.stdout,
                         )
@@ -441,7 +856,7 @@ impl Operation {
                     files = files.stderr(
                         create_temp_file(
                             services,
-                            "forge_shell_stderr_",
+                            &format!("{OFFLOAD_PREFIX}shell_stderr_"),
                             ".txt",
                             &output.output.stderr,
                         )
@@ -461,15 +876,17 @@ mod tests {
     use std::fmt::Write;
     use std::path::PathBuf;
 
-    use forge_domain::{FSRead, ToolValue};
+    use forge_domain::{FSRead, ToolLimits, ToolValue};
     use url::Url;
 
     use super::*;
+    use crate::test_results::TestFailure;
     use crate::{Match, MatchResult};
 
     fn fixture_environment() -> Environment {
         Environment {
             os: "linux".to_string(),
+            arch: "x86_64".to_string(),
             pid: 12345,
             cwd: PathBuf::from("/home/user/project"),
             home: Some(PathBuf::from("/home/user")),
@@ -481,16 +898,29 @@ mod tests {
                 backoff_factor: 2,
                 max_retry_attempts: 3,
                 retry_status_codes: vec![429, 500, 502, 503, 504],
+                content_filter_codes: vec![],
                 max_delay: None,
             },
             max_search_lines: 25,
             fetch_truncation_limit: 55,
+            fetch_cache_ttl: 0,
             max_read_size: 10,
             stdout_max_prefix_length: 10,
             stdout_max_suffix_length: 10,
+            tool_limits: Default::default(),
             http: Default::default(),
             max_file_size: 256 << 10, // 256 KiB
             forge_api_url: Url::parse("http://forgecode.dev/api").unwrap(),
+            shell_env: Default::default(),
+            truncation_template: None,
+            otlp_endpoint: None,
+            debug_raw_output: false,
+            sandbox_root: None,
+            max_concurrent_requests: None,
+            redact_home: false,
+            diff_ignore_whitespace: false,
+            diff_ignore_trailing_newline: false,
+            offload_dir: None,
         }
     }
 
@@ -519,6 +949,8 @@ mod tests {
                 path: "/home/user/test.txt".to_string(),
                 start_line: None,
                 end_line: None,
+                revision: None,
+                with_line_numbers: None,
                 explanation: Some("Test explanation".to_string()),
             },
             output: ReadOutput {
@@ -547,6 +979,8 @@ mod tests {
                 path: "/home/user/test.txt".to_string(),
                 start_line: None,
                 end_line: None,
+                revision: None,
+                with_line_numbers: None,
                 explanation: Some("Test explanation".to_string()),
             },
             output: ReadOutput {
@@ -575,6 +1009,8 @@ mod tests {
                 path: "/home/user/test.txt".to_string(),
                 start_line: Some(2),
                 end_line: Some(3),
+                revision: None,
+                with_line_numbers: None,
                 explanation: Some("Test explanation".to_string()),
             },
             output: ReadOutput {
@@ -596,6 +1032,92 @@ mod tests {
         insta::assert_snapshot!(to_value(actual));
     }
 
+    #[test]
+    fn test_fs_read_with_line_numbers_over_a_range() {
+        let fixture = Operation::FsRead {
+            input: FSRead {
+                path: "/home/user/test.txt".to_string(),
+                start_line: Some(9),
+                end_line: Some(11),
+                revision: None,
+                with_line_numbers: Some(true),
+                explanation: None,
+            },
+            output: ReadOutput {
+                content: Content::File("fn main() {\n    run();\n}".to_string()),
+                start_line: 9,
+                end_line: 11,
+                total_lines: 11,
+            },
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(
+            ToolName::new("forge_tool_fs_read"),
+            TempContentFiles::default(),
+            &env,
+        );
+
+        let expected = [
+            "<file_content",
+            "  path=\"/home/user/test.txt\"",
+            "  display_lines=\"9-11\"",
+            "  total_lines=\"3\"",
+            "  max_lines=\"10\"",
+            "><![CDATA[ 9\tfn main() {",
+            "10\t    run();",
+            "11\t}]]>",
+            "</file_content>",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(to_value(actual), expected);
+    }
+
+    #[test]
+    fn test_fs_read_with_line_numbers_over_a_full_file() {
+        let fixture = Operation::FsRead {
+            input: FSRead {
+                path: "/home/user/test.txt".to_string(),
+                start_line: None,
+                end_line: None,
+                revision: None,
+                with_line_numbers: Some(true),
+                explanation: None,
+            },
+            output: ReadOutput {
+                content: Content::File("Hello, world!\nThis is a test file.\n".to_string()),
+                start_line: 1,
+                end_line: 2,
+                total_lines: 2,
+            },
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(
+            ToolName::new("forge_tool_fs_read"),
+            TempContentFiles::default(),
+            &env,
+        );
+
+        let expected = [
+            "<file_content",
+            "  path=\"/home/user/test.txt\"",
+            "  display_lines=\"1-2\"",
+            "  total_lines=\"2\"",
+            "  max_lines=\"10\"",
+            "><![CDATA[1\tHello, world!",
+            "2\tThis is a test file.",
+            "]]>",
+            "</file_content>",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(to_value(actual), expected);
+    }
+
     #[test]
     fn test_fs_read_with_truncation_path() {
         let fixture = Operation::FsRead {
@@ -603,6 +1125,8 @@ mod tests {
                 path: "/home/user/large_file.txt".to_string(),
                 start_line: None,
                 end_line: None,
+                revision: None,
+                with_line_numbers: None,
                 explanation: Some("Test explanation".to_string()),
             },
             output: ReadOutput {
@@ -623,6 +1147,37 @@ mod tests {
         insta::assert_snapshot!(to_value(actual));
     }
 
+    #[test]
+    fn test_fs_read_with_custom_truncation_template() {
+        let fixture = Operation::FsRead {
+            input: FSRead {
+                path: "/home/user/large_file.txt".to_string(),
+                start_line: None,
+                end_line: None,
+                revision: None,
+                with_line_numbers: None,
+                explanation: None,
+            },
+            output: ReadOutput {
+                content: Content::File("Truncated content".to_string()),
+                start_line: 1,
+                end_line: 100,
+                total_lines: 200,
+            },
+        };
+
+        let mut env = fixture_environment();
+        env.truncation_template = Some("{shown} (next: {tool} {arguments})".to_string());
+
+        let actual = fixture.into_tool_output(
+            ToolName::new("forge_tool_fs_read"),
+            TempContentFiles::default(),
+            &env,
+        );
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
     #[test]
     fn test_fs_create_basic() {
         let fixture = Operation::FsCreate {
@@ -688,6 +1243,7 @@ mod tests {
                 },
                 shell: "/bin/bash".to_string(),
             },
+            test_results: None,
         };
 
         let env = fixture_environment();
@@ -700,6 +1256,40 @@ mod tests {
         insta::assert_snapshot!(to_value(actual));
     }
 
+    #[test]
+    fn test_raw_dump_keeps_untruncated_shell_output() {
+        let stdout = (1..=25)
+            .map(|i| format!("stdout line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let fixture = Operation::Shell {
+            output: ShellOutput {
+                output: forge_domain::CommandOutput {
+                    command: "long_command".to_string(),
+                    stdout: stdout.clone(),
+                    stderr: "warning: unused variable".to_string(),
+                    exit_code: Some(0),
+                },
+                shell: "/bin/bash".to_string(),
+            },
+            test_results: None,
+        };
+
+        let actual = fixture.to_raw_dump();
+
+        let expected = format!(
+            "command: long_command\nexit_code: 0\n--- stdout ---\n{stdout}\n--- stderr ---\nwarning: unused variable"
+        );
+        assert_eq!(actual, expected);
+        // The formatted output leaves out the middle of the same result
+        let formatted = to_value(fixture.into_tool_output(
+            ToolName::new("forge_tool_process_shell"),
+            TempContentFiles::default().stdout(PathBuf::from("/tmp/stdout_content.txt")),
+            &fixture_environment(),
+        ));
+        assert!(!formatted.contains("stdout line 15"));
+    }
+
     #[test]
     fn test_shell_output_stdout_truncation_only() {
         // Create stdout with more lines than the truncation limit
@@ -719,6 +1309,7 @@ mod tests {
                 },
                 shell: "/bin/bash".to_string(),
             },
+            test_results: None,
         };
 
         let env = fixture_environment();
@@ -733,6 +1324,53 @@ mod tests {
         insta::assert_snapshot!(to_value(actual));
     }
 
+    #[test]
+    fn test_shell_output_note_reads_the_hidden_lines_of_the_offloaded_file() {
+        let stdout = (1..=25)
+            .map(|i| format!("stdout line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let fixture = Operation::Shell {
+            output: ShellOutput {
+                output: forge_domain::CommandOutput {
+                    command: "long_command".to_string(),
+                    stdout,
+                    stderr: "".to_string(),
+                    exit_code: Some(0),
+                },
+                shell: "/bin/bash".to_string(),
+            },
+            test_results: None,
+        };
+        let env = fixture_environment().offload_dir(PathBuf::from("/home/user/project/.forge"));
+        let path = env.offload_path().join("forge_shell_stdout_a1.txt");
+
+        let actual = to_value(fixture.into_tool_output(
+            ToolName::new("forge_tool_process_shell"),
+            TempContentFiles::default().stdout(path.clone()),
+            &env,
+        ));
+
+        let expected = r#"<truncated>Showing lines 1-10 and 16-25 of 25. To read more, call forge_tool_fs_read with the arguments {"end_line":15,"path":"/home/user/project/.forge/forge_shell_stdout_a1.txt","start_line":11}</truncated>"#;
+        assert!(actual.contains(expected), "{actual}");
+        assert!(env.is_offloaded(&path));
+    }
+
+    #[test]
+    fn test_offloaded_output_defaults_to_a_directory_owned_by_forge() {
+        let env = fixture_environment();
+        let sandboxed = fixture_environment().sandbox_root(PathBuf::from("/home/user/sandbox"));
+
+        let actual = [env.offload_path(), sandboxed.offload_path()];
+
+        let expected = [
+            PathBuf::from("/home/user/project/offload"),
+            PathBuf::from("/home/user/sandbox/.forge/offload"),
+        ];
+        assert_eq!(actual, expected);
+        assert!(!env.is_offloaded(&std::env::temp_dir().join("forge_shell_stdout_a1.txt")));
+    }
+
     #[test]
     fn test_shell_output_stderr_truncation_only() {
         // Create stderr with more lines than the truncation limit
@@ -752,6 +1390,7 @@ mod tests {
                 },
                 shell: "/bin/bash".to_string(),
             },
+            test_results: None,
         };
 
         let env = fixture_environment();
@@ -791,6 +1430,7 @@ mod tests {
                 },
                 shell: "/bin/bash".to_string(),
             },
+            test_results: None,
         };
 
         let env = fixture_environment();
@@ -825,6 +1465,7 @@ mod tests {
                 },
                 shell: "/bin/bash".to_string(),
             },
+            test_results: None,
         };
 
         let env = fixture_environment();
@@ -849,6 +1490,7 @@ mod tests {
                 },
                 shell: "/bin/bash".to_string(),
             },
+            test_results: None,
         };
 
         let env = fixture_environment();
@@ -873,6 +1515,7 @@ mod tests {
                 },
                 shell: "/bin/bash".to_string(),
             },
+            test_results: None,
         };
 
         let env = fixture_environment();
@@ -910,6 +1553,7 @@ mod tests {
                 },
                 shell: "/bin/bash".to_string(),
             },
+            test_results: None,
         };
 
         let env = fixture_environment();
@@ -925,6 +1569,84 @@ mod tests {
         insta::assert_snapshot!(to_value(actual));
     }
 
+    #[test]
+    fn test_shell_output_uses_per_tool_limits() {
+        let stdout = (1..=25)
+            .map(|i| format!("stdout line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let fixture = Operation::Shell {
+            output: ShellOutput {
+                output: forge_domain::CommandOutput {
+                    command: "long_command".to_string(),
+                    stdout,
+                    stderr: "".to_string(),
+                    exit_code: Some(0),
+                },
+                shell: "/bin/bash".to_string(),
+            },
+            test_results: None,
+        };
+        let mut env = fixture_environment();
+        env.tool_limits = ToolLimits::default()
+            .shell_prefix_lines(2_usize)
+            .shell_suffix_lines(2_usize);
+
+        let actual = to_value(fixture.into_tool_output(
+            ToolName::new("forge_tool_process_shell"),
+            TempContentFiles::default().stdout(PathBuf::from("/tmp/stdout_content.txt")),
+            &env,
+        ));
+
+        // The global limits would keep the first and last 10 lines
+        assert!(actual.contains("stdout line 2\n"));
+        assert!(!actual.contains("stdout line 3\n"));
+        assert!(actual.contains("stdout line 24"));
+        assert!(!actual.contains("stdout line 23"));
+    }
+
+    #[test]
+    fn test_fs_search_without_per_tool_limit_uses_global_limit() {
+        let fixture = || Operation::FsSearch {
+            input: forge_domain::FSSearch {
+                path: "/home/user/project".to_string(),
+                regex: Some("search".to_string()),
+                start_index: None,
+                max_search_lines: None,
+                file_pattern: None,
+                order: None,
+                explanation: None,
+            },
+            output: Some(SearchResult {
+                matches: (1..=50)
+                    .map(|i| Match {
+                        path: "/home/user/project/foo.txt".to_string(),
+                        result: Some(MatchResult::Found {
+                            line: format!("Match line {i}: Test"),
+                            line_number: i,
+                        }),
+                    })
+                    .collect(),
+            }),
+        };
+        let mut env = fixture_environment();
+        env.tool_limits = ToolLimits::default().shell_prefix_lines(2_usize);
+
+        let actual = to_value(fixture().into_tool_output(
+            ToolName::new("forge_tool_fs_search"),
+            TempContentFiles::default(),
+            &env,
+        ));
+
+        let expected = to_value(fixture().into_tool_output(
+            ToolName::new("forge_tool_fs_search"),
+            TempContentFiles::default(),
+            &fixture_environment(),
+        ));
+        assert_eq!(actual, expected);
+        assert!(actual.contains("Showing results 1-25 of 50"));
+    }
+
     #[test]
     fn test_fs_search_output() {
         // Create a large number of search matches to trigger truncation
@@ -947,6 +1669,7 @@ mod tests {
                 start_index: Some(6),
                 max_search_lines: Some(30), // This will be limited by env.max_search_lines (25)
                 file_pattern: Some("*.txt".to_string()),
+                order: None,
                 explanation: Some("Testing truncated search output".to_string()),
             },
             output: Some(SearchResult { matches }),
@@ -985,6 +1708,7 @@ mod tests {
                 start_index: Some(6),
                 max_search_lines: Some(30), // This will be limited by env.max_search_lines (25)
                 file_pattern: Some("*.txt".to_string()),
+                order: None,
                 explanation: Some("Testing truncated search output".to_string()),
             },
             output: Some(SearchResult { matches }),
@@ -1012,6 +1736,7 @@ mod tests {
                 start_index: None,
                 max_search_lines: None,
                 file_pattern: None,
+                order: None,
                 explanation: Some("Testing search with no matches".to_string()),
             },
             output: None,
@@ -1279,6 +2004,27 @@ mod tests {
         insta::assert_snapshot!(to_value(actual));
     }
 
+    #[test]
+    fn test_fs_remove_keeps_the_home_directory_for_the_model() {
+        let fixture = Operation::FsRemove {
+            input: forge_domain::FSRemove {
+                path: "/home/user/file_to_delete.txt".to_string(),
+                explanation: None,
+            },
+        };
+
+        let env = fixture_environment().redact_home(true);
+
+        let actual = to_value(fixture.into_tool_output(
+            ToolName::new("forge_tool_fs_remove"),
+            TempContentFiles::default(),
+            &env,
+        ));
+
+        let expected = "<file_removed\n  path=\"/home/user/file_to_delete.txt\"\n  status=\"completed\"\n>\n</file_removed>\n";
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_fs_search_with_results() {
         let fixture = Operation::FsSearch {
@@ -1288,6 +2034,7 @@ mod tests {
                 start_index: None,
                 max_search_lines: None,
                 file_pattern: Some("*.txt".to_string()),
+                order: None,
                 explanation: Some("Searching for Hello pattern".to_string()),
             },
             output: Some(SearchResult {
@@ -1330,6 +2077,7 @@ mod tests {
                 start_index: None,
                 max_search_lines: None,
                 file_pattern: None,
+                order: None,
                 explanation: Some("Searching for non-existent pattern".to_string()),
             },
             output: None,
@@ -1527,6 +2275,10 @@ mod tests {
             input: forge_domain::NetFetch {
                 url: "https://example.com".to_string(),
                 raw: Some(false),
+                no_cache: None,
+                method: None,
+                headers: None,
+                body: None,
                 explanation: Some("Fetching content from example website".to_string()),
             },
             output: HttpResponse {
@@ -1534,6 +2286,7 @@ mod tests {
                 code: 200,
                 context: ResponseContext::Raw,
                 content_type: "text/plain".to_string(),
+                cached: false,
             },
         };
 
@@ -1561,6 +2314,10 @@ mod tests {
             input: forge_domain::NetFetch {
                 url: "https://example.com/large-page".to_string(),
                 raw: Some(false),
+                no_cache: None,
+                method: None,
+                headers: None,
+                body: None,
                 explanation: Some("Fetching large content that will be truncated".to_string()),
             },
             output: HttpResponse {
@@ -1568,6 +2325,7 @@ mod tests {
                 code: 200,
                 context: ResponseContext::Parsed,
                 content_type: "text/html".to_string(),
+                cached: false,
             },
         };
 
@@ -1590,6 +2348,41 @@ mod tests {
         insta::assert_snapshot!(to_value(actual));
     }
 
+    #[test]
+    fn test_shell_with_test_results() {
+        let fixture = Operation::Shell {
+            output: ShellOutput {
+                output: forge_domain::CommandOutput {
+                    command: "cargo test".to_string(),
+                    stdout: "test result: FAILED. 1 passed; 1 failed; 0 ignored".to_string(),
+                    stderr: "".to_string(),
+                    exit_code: Some(101),
+                },
+                shell: "/bin/bash".to_string(),
+            },
+            test_results: Some(TestResults {
+                passed: 1,
+                failed: 1,
+                ignored: 0,
+                failures: vec![TestFailure {
+                    name: "tests::test_sub".to_string(),
+                    location: Some("src/lib.rs:17".to_string()),
+                    message: "assertion `left == right` failed".to_string(),
+                }],
+            }),
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(
+            ToolName::new("forge_tool_process_shell"),
+            TempContentFiles::default(),
+            &env,
+        );
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
     #[test]
     fn test_shell_success() {
         let fixture = Operation::Shell {
@@ -1602,6 +2395,7 @@ mod tests {
                 },
                 shell: "/bin/bash".to_string(),
             },
+            test_results: None,
         };
 
         let env = fixture_environment();
@@ -1617,7 +2411,75 @@ mod tests {
 
     #[test]
     fn test_attempt_completion() {
-        let fixture = Operation::AttemptCompletion;
+        let fixture = Operation::AttemptCompletion {
+            feedback: forge_domain::DEFAULT_COMPLETION_FEEDBACK.to_string(),
+            changed_files: ChangedFiles::default(),
+            task_report: None,
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(
+            ToolName::new("forge_tool_attempt_completion"),
+            TempContentFiles::default(),
+            &env,
+        );
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_attempt_completion_with_custom_feedback() {
+        let fixture = Operation::AttemptCompletion {
+            feedback: "[Done]".to_string(),
+            changed_files: ChangedFiles::default(),
+            task_report: None,
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(
+            ToolName::new("forge_tool_attempt_completion"),
+            TempContentFiles::default(),
+            &env,
+        );
+
+        let expected = "<success>[Done]</success>\n";
+        assert_eq!(to_value(actual), expected);
+    }
+
+    #[test]
+    fn test_attempt_completion_with_suppressed_feedback() {
+        let fixture = Operation::AttemptCompletion {
+            feedback: String::new(),
+            changed_files: ChangedFiles::default(),
+            task_report: None,
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(
+            ToolName::new("forge_tool_attempt_completion"),
+            TempContentFiles::default(),
+            &env,
+        );
+
+        let expected = "<success></success>\n";
+        assert_eq!(to_value(actual), expected);
+    }
+
+    #[test]
+    fn test_attempt_completion_with_changed_files() {
+        let mut changed_files = ChangedFiles::default();
+        changed_files.record_created("/home/user/project/src/parser.rs");
+        changed_files.record_modified("/home/user/project/src/lib.rs");
+        changed_files.record_modified("/home/user/project/src/parser.rs");
+        changed_files.record_removed("/home/user/project/src/old_parser.rs");
+        let fixture = Operation::AttemptCompletion {
+            feedback: "[Done]".to_string(),
+            changed_files,
+            task_report: None,
+        };
 
         let env = fixture_environment();
 
@@ -1627,9 +2489,282 @@ mod tests {
             &env,
         );
 
+        let expected = [
+            "<success>[Done]",
+            "<changed_files>",
+            "<created>",
+            "<file>/home/user/project/src/parser.rs</file>",
+            "</created>",
+            "<modified>",
+            "<file>/home/user/project/src/lib.rs</file>",
+            "</modified>",
+            "<removed>",
+            "<file>/home/user/project/src/old_parser.rs</file>",
+            "</removed>",
+            "</changed_files>",
+            "</success>",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(to_value(actual), expected);
+    }
+
+    #[test]
+    fn test_scratchpad_read() {
+        let fixture = Operation::ScratchpadRead {
+            _input: ScratchpadRead { explanation: None },
+            content: "## Plan\n- [ ] Add the lexer".to_string(),
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(
+            ToolName::new("forge_tool_scratchpad_read"),
+            TempContentFiles::default(),
+            &env,
+        );
+
+        let expected = "<scratchpad><![CDATA[## Plan\n- [ ] Add the lexer]]></scratchpad>\n";
+        assert_eq!(to_value(actual), expected);
+    }
+
+    #[test]
+    fn test_format_code_surfaces_the_formatter_diff() {
+        let fixture = Operation::FormatCode {
+            input: FormatCode {
+                path: "/home/user/project/src/main.rs".to_string(),
+                explanation: None,
+            },
+            output: FormattedFile {
+                formatter: "rustfmt --edition 2021".to_string(),
+                before: "fn main(){}".to_string(),
+                after: "fn main() {}".to_string(),
+            },
+        };
+
+        let env = fixture_environment();
+        let actual = fixture.into_tool_output(
+            ToolName::new("forge_tool_format_code"),
+            TempContentFiles::default(),
+            &env,
+        );
+
+        let expected = [
+            "<file_formatted",
+            "  path=\"/home/user/project/src/main.rs\"",
+            "  formatter=\"rustfmt --edition 2021\"",
+            "  total_lines=\"1\"",
+            ">",
+            "<file_diff><![CDATA[1        |-fn main(){}",
+            "    1    |+fn main() {}",
+            "]]></file_diff>",
+            "</file_formatted>",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(to_value(actual), expected);
+    }
+
+    #[test]
+    fn test_format_code_without_changes() {
+        let fixture = Operation::FormatCode {
+            input: FormatCode {
+                path: "/home/user/project/src/main.rs".to_string(),
+                explanation: None,
+            },
+            output: FormattedFile {
+                formatter: "rustfmt --edition 2021".to_string(),
+                before: "fn main() {}\n".to_string(),
+                after: "fn main() {}\n".to_string(),
+            },
+        };
+
+        let env = fixture_environment();
+        let actual = fixture.into_tool_output(
+            ToolName::new("forge_tool_format_code"),
+            TempContentFiles::default(),
+            &env,
+        );
+
+        let expected = [
+            "<file_formatted",
+            "  path=\"/home/user/project/src/main.rs\"",
+            "  formatter=\"rustfmt --edition 2021\"",
+            "  total_lines=\"1\"",
+            "  status=\"no_changes\"",
+            ">",
+            "</file_formatted>",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(to_value(actual), expected);
+    }
+
+    #[test]
+    fn test_system_info_reports_environment_and_toolchains() {
+        let env = fixture_environment();
+        let toolchains = vec![crate::system_info::Toolchain {
+            name: "rustc".to_string(),
+            version: "rustc 1.88.0".to_string(),
+        }];
+        let fixture = Operation::SystemInfo { output: SystemFacts::new(&env, toolchains) };
+
+        let actual = fixture.into_tool_output(
+            ToolName::new("forge_tool_system_info"),
+            TempContentFiles::default(),
+            &env,
+        );
+
+        let expected = [
+            "<environment",
+            "  os=\"linux\"",
+            "  arch=\"x86_64\"",
+            "  shell=\"/bin/bash\"",
+            "  cwd=\"/home/user/project\"",
+            ">",
+            "<toolchain",
+            "  name=\"rustc\"",
+            "  version=\"rustc 1.88.0\"",
+            ">",
+            "</toolchain>",
+            "</environment>",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(to_value(actual), expected);
+    }
+
+    #[test]
+    fn test_fs_list_over_max_entries() {
+        let fixture = Operation::FsList {
+            input: FSListInput {
+                path: "/home/user/project".to_string(),
+                recursive: Some(true),
+                explanation: None,
+            },
+            output: DirListing::new(
+                [
+                    ("Cargo.toml", false, 312),
+                    ("src/", true, 4096),
+                    ("src/lib.rs", false, 1024),
+                    ("src/main.rs", false, 120),
+                ]
+                .map(|(path, is_dir, size)| forge_domain::File {
+                    path: path.to_string(),
+                    is_dir,
+                    size,
+                    modified: None,
+                }),
+            ),
+        };
+
+        let mut env = fixture_environment();
+        env.max_search_lines = 3;
+
+        let actual = fixture.into_tool_output(
+            ToolName::new("forge_tool_fs_list"),
+            TempContentFiles::default(),
+            &env,
+        );
+
         insta::assert_snapshot!(to_value(actual));
     }
 
+    #[test]
+    fn test_repo_tree_with_hidden_entries() {
+        let fixture = Operation::RepoTree {
+            input: RepoTree { max_depth: Some(1), max_entries: Some(2), explanation: None },
+            output: DirTree::from_files(
+                [
+                    ("Cargo.toml", false),
+                    ("README.md", false),
+                    ("src/", true),
+                    ("src/lib.rs", false),
+                    ("src/main.rs", false),
+                ]
+                .map(|(path, is_dir)| forge_domain::File {
+                    path: path.to_string(),
+                    is_dir,
+                    size: 0,
+                    modified: None,
+                }),
+            ),
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(
+            ToolName::new("forge_tool_repo_tree"),
+            TempContentFiles::default(),
+            &env,
+        );
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_fs_read_glob_over_byte_cap() {
+        let fixture = Operation::FsReadGlob {
+            input: FSReadGlob {
+                path: "/home/user/project".to_string(),
+                pattern: "src/*.rs".to_string(),
+                max_files: None,
+                explanation: None,
+            },
+            output: GlobReadOutput {
+                files: vec![crate::read_glob::GlobFile {
+                    path: "/home/user/project/src/a.rs".to_string(),
+                    output: ReadOutput {
+                        content: Content::File("fn a() {}".to_string()),
+                        start_line: 1,
+                        end_line: 1,
+                        total_lines: 1,
+                    },
+                }],
+                skipped: vec!["/home/user/project/src/logo.rs".to_string()],
+                omitted: vec![
+                    "/home/user/project/src/b.rs".to_string(),
+                    "/home/user/project/src/c.rs".to_string(),
+                ],
+                is_walk_truncated: false,
+            },
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(
+            ToolName::new("forge_tool_fs_read_glob"),
+            TempContentFiles::default(),
+            &env,
+        );
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_fs_read_glob_over_a_truncated_walk() {
+        let fixture = Operation::FsReadGlob {
+            input: FSReadGlob {
+                path: "/".to_string(),
+                pattern: "**/Cargo.toml".to_string(),
+                max_files: None,
+                explanation: None,
+            },
+            output: GlobReadOutput { is_walk_truncated: true, ..Default::default() },
+        };
+
+        let env = fixture_environment();
+
+        let actual = to_value(fixture.into_tool_output(
+            ToolName::new("forge_tool_fs_read_glob"),
+            TempContentFiles::default(),
+            &env,
+        ));
+
+        let expected = "<walk_truncated>The walk stopped after 10000 files, files past them weren't matched against the pattern. Use a narrower path to match them</walk_truncated>";
+        assert!(actual.contains(expected), "{actual}");
+    }
+
     #[test]
     fn test_follow_up_with_question() {
         let fixture = Operation::FollowUp {
@@ -1,16 +1,25 @@
 use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use async_recursion::async_recursion;
 use derive_setters::Setters;
 use forge_domain::*;
 use forge_template::Element;
 use serde_json::Value;
-use tracing::{debug, info, warn};
+use tracing::{Instrument, Span, debug, field, info, info_span, warn};
 
-use crate::agent::AgentService;
+use crate::agent::{AgentService, PlanReview};
 use crate::compact::Compactor;
+use crate::text_coalescer::{PARTIAL_TEXT_INTERVAL, TextCoalescer};
+use crate::tool_executor::ensure_within_sandbox;
+
+/// Instruction added to every task while plan-first mode is on
+const PLAN_FIRST_INSTRUCTION: &str = "Before making any changes, lay out your plan with the task list tools and present it. Tools that change the workspace are blocked until the user approves the plan.";
+const EMPTY_RESPONSE_NUDGE: &str = "You returned an empty response. Please proceed with the task.";
+/// Capacity of the channel a concurrent tool call sends its output through
+const TOOL_OUTPUT_BUFFER: usize = 32;
 
 pub type ArcSender = Arc<tokio::sync::mpsc::Sender<anyhow::Result<ChatResponse>>>;
 
@@ -23,7 +32,10 @@ pub struct Orchestrator<S> {
     environment: Environment,
     tool_definitions: Vec<ToolDefinition>,
     models: Vec<Model>,
-    files: Vec<String>,
+    files: Vec<File>,
+    /// Rules read from the global, project and local rules files, added to
+    /// the agent's own
+    project_rules: Option<String>,
     current_time: chrono::DateTime<chrono::Local>,
 }
 
@@ -42,6 +54,7 @@ impl<S: AgentService> Orchestrator<S> {
             tool_definitions: Default::default(),
             models: Default::default(),
             files: Default::default(),
+            project_rules: Default::default(),
             current_time,
         }
     }
@@ -51,50 +64,205 @@ impl<S: AgentService> Orchestrator<S> {
         &self.conversation
     }
 
-    // Helper function to get all tool results from a vector of tool calls
+    // Helper function to get all tool results from a vector of tool calls.
+    // `cached_outputs` holds the results of read-only calls made since the
+    // last mutating call of the response.
     #[async_recursion]
     async fn execute_tool_calls(
         &self,
         agent: &Agent,
         tool_calls: &[ToolCallFull],
         tool_context: &mut ToolCallContext,
+        cached_outputs: &mut HashMap<(ToolName, String), ToolOutput>,
     ) -> anyhow::Result<Vec<(ToolCallFull, ToolResult)>> {
-        // Always process tool calls sequentially
+        let concurrent = agent.concurrent_tool_calls.unwrap_or(true);
         let mut tool_call_records = Vec::with_capacity(tool_calls.len());
+        let mut remaining = tool_calls;
+
+        while !remaining.is_empty() {
+            // Consecutive read-only calls run at once, any other call runs on its own so
+            // its side effects happen in the order they were asked for. A call identical
+            // to one in the batch waits for it, to reuse its result
+            let batch_len = if concurrent {
+                let mut keys = HashSet::new();
+                remaining
+                    .iter()
+                    .take_while(|tool_call| {
+                        Tools::is_read_only(tool_call) && keys.insert(Self::cache_key(tool_call))
+                    })
+                    .count()
+                    .max(1)
+            } else {
+                1
+            };
+            let (batch, rest) = remaining.split_at(batch_len);
+            remaining = rest;
 
-        for tool_call in tool_calls {
-            // Send the start notification
-            self.send(ChatResponse::ToolCallStart(tool_call.clone()))
-                .await?;
+            // Send the start notifications, the calls of a batch all start before the
+            // first one ends
+            for tool_call in batch {
+                self.send(ChatResponse::ToolCallStart(tool_call.clone()))
+                    .await?;
+            }
 
-            // Execute the tool
-            let tool_result = self
-                .services
-                .call(agent, tool_context, tool_call.clone())
-                .await;
+            let cached = batch
+                .iter()
+                .map(|tool_call| cached_outputs.get(&Self::cache_key(tool_call)).cloned())
+                .collect::<Vec<_>>();
+            let tool_results = if let [tool_call] = batch {
+                let tool_result = self
+                    .execute_tool_call(agent, tool_call, tool_context, cached[0].clone())
+                    .await;
+                vec![(tool_result, Vec::new())]
+            } else {
+                // Read-only tools leave the context untouched, each call gets a copy of it.
+                // What a call sends is held back until it's done, so that the output of
+                // concurrent calls doesn't interleave
+                futures::future::join_all(batch.iter().zip(cached.iter().cloned()).map(
+                    |(tool_call, cached)| {
+                        let (tx, mut rx) = tokio::sync::mpsc::channel(TOOL_OUTPUT_BUFFER);
+                        let mut context = tool_context.clone().sender(Some(Arc::new(tx)));
+                        let tool_result = async move {
+                            self.execute_tool_call(agent, tool_call, &mut context, cached)
+                                .await
+                        };
+                        let messages = async move {
+                            let mut messages = Vec::new();
+                            while let Some(message) = rx.recv().await {
+                                messages.push(message);
+                            }
+                            messages
+                        };
+                        async move { tokio::join!(tool_result, messages) }
+                    },
+                ))
+                .await
+            };
 
-            if tool_result.is_error() {
-                warn!(
-                    agent_id = %agent.id,
-                    name = %tool_call.name,
-                    arguments = %tool_call.arguments,
-                    output = ?tool_result.output,
-                    "Tool call failed",
-                );
-            }
+            for ((tool_call, (tool_result, messages)), cached) in
+                batch.iter().zip(tool_results).zip(cached)
+            {
+                if cached.is_none() {
+                    if !Tools::is_read_only(tool_call) {
+                        // Anything read before may have changed
+                        cached_outputs.clear();
+                    } else if !tool_result.is_error() {
+                        cached_outputs
+                            .insert(Self::cache_key(tool_call), tool_result.output.clone());
+                    }
+                }
 
-            // Send the end notification
-            self.send(ChatResponse::ToolCallEnd(tool_result.clone()))
-                .await?;
+                if tool_result.is_error() {
+                    warn!(
+                        agent_id = %agent.id,
+                        name = %tool_call.name,
+                        arguments = %tool_call.arguments,
+                        output = ?tool_result.output,
+                        "Tool call failed",
+                    );
+                }
+
+                // Send what the call sent while it ran, followed by its end notification
+                if let Some(sender) = &self.sender {
+                    for message in messages {
+                        sender.send(message).await?;
+                    }
+                }
+                self.send(ChatResponse::ToolCallEnd(tool_result.clone()))
+                    .await?;
 
-            // Ensure all tool calls and results are recorded
-            // Adding task completion records is critical for compaction to work correctly
-            tool_call_records.push((tool_call.clone(), tool_result));
+                // Ensure all tool calls and results are recorded
+                // Adding task completion records is critical for compaction to work correctly
+                tool_call_records.push((tool_call.clone(), tool_result));
+            }
         }
 
         Ok(tool_call_records)
     }
 
+    /// Runs the calls queued up from consecutive segments of a response and
+    /// records their results
+    async fn run_queued_calls(
+        &self,
+        agent: &Agent,
+        queued_calls: &mut Vec<ToolCallFull>,
+        tool_context: &mut ToolCallContext,
+        cached_outputs: &mut HashMap<(ToolName, String), ToolOutput>,
+        tool_call_records: &mut Vec<(ToolCallFull, ToolResult)>,
+    ) -> anyhow::Result<()> {
+        if queued_calls.is_empty() {
+            return Ok(());
+        }
+        let records = self
+            .execute_tool_calls(agent, queued_calls, tool_context, cached_outputs)
+            .await?;
+        tool_call_records.extend(records);
+        queued_calls.clear();
+        Ok(())
+    }
+
+    /// Runs the tool, or reuses the output of an earlier identical call
+    async fn execute_tool_call(
+        &self,
+        agent: &Agent,
+        tool_call: &ToolCallFull,
+        tool_context: &mut ToolCallContext,
+        cached: Option<ToolOutput>,
+    ) -> ToolResult {
+        if let Some(output) = cached {
+            debug!(name = %tool_call.name, "Reusing result of identical tool call");
+            return ToolResult::new(tool_call.name.clone())
+                .call_id(tool_call.call_id.clone())
+                .output(Ok(Self::reused_output(output)));
+        }
+
+        let span = info_span!(
+            "tool_call",
+            tool_name = %tool_call.name,
+            status = field::Empty,
+            "otel.status_code" = field::Empty,
+        );
+        let tool_result = self
+            .services
+            .call(agent, tool_context, tool_call.clone())
+            .instrument(span.clone())
+            .await;
+        if tool_result.is_error() {
+            span.record("status", "error");
+            span.record("otel.status_code", "ERROR");
+        } else {
+            span.record("status", "success");
+        }
+        tool_result
+    }
+
+    /// Identical calls share the key their outputs are cached under
+    fn cache_key(tool_call: &ToolCallFull) -> (ToolName, String) {
+        (tool_call.name.clone(), tool_call.arguments.to_string())
+    }
+
+    /// Marks the output of an earlier identical call as reused, so the model
+    /// knows the tool wasn't run again
+    fn reused_output(output: ToolOutput) -> ToolOutput {
+        ToolOutput::text(Element::new("note").text(
+            "This call is identical to an earlier one in this response, its result was reused",
+        ))
+        .combine(output)
+    }
+
+    /// Returns the violations of the agent's response format found in the
+    /// content of the response, which is what the format constrains. A
+    /// response without content, like one only calling tools, has nothing to
+    /// check
+    fn response_format_violations(agent: &Agent, content: &str) -> Vec<String> {
+        match agent.response_format.as_ref() {
+            Some(response_format) if !content.trim().is_empty() => {
+                response_format.validate(content)
+            }
+            _ => vec![],
+        }
+    }
+
     async fn send(&self, message: ChatResponse) -> anyhow::Result<()> {
         if let Some(sender) = &self.sender {
             sender.send(Ok(message)).await?
@@ -104,21 +272,8 @@ impl<S: AgentService> Orchestrator<S> {
 
     /// Get the allowed tools for an agent
     fn get_allowed_tools(&mut self, agent: &Agent) -> anyhow::Result<Vec<ToolDefinition>> {
-        let completion = ToolsDiscriminants::ForgeToolAttemptCompletion;
-        let mut tools = vec![];
-        if !self.tool_definitions.is_empty() {
-            let allowed = agent.tools.iter().flatten().collect::<HashSet<_>>();
-            tools.extend(
-                self.tool_definitions
-                    .iter()
-                    .filter(|tool| tool.name != completion.name())
-                    .filter(|tool| allowed.contains(&tool.name))
-                    .cloned(),
-            );
-        }
-
-        tools.push(completion.definition());
-
+        let mut tools = agent.allowed_tools(&self.tool_definitions);
+        tools.retain(|tool| !self.conversation.is_tool_disabled(&tool.name));
         Ok(tools)
     }
 
@@ -181,16 +336,47 @@ impl<S: AgentService> Orchestrator<S> {
         Ok(reasoning_supported)
     }
 
+    /// Returns the directory the agent works in, which has to be within the
+    /// sandbox root as well as the project root
+    fn agent_cwd(&self, agent: &Agent) -> anyhow::Result<PathBuf> {
+        let cwd = agent.cwd(&self.environment.cwd)?;
+        ensure_within_sandbox(&cwd, self.environment.sandbox_root.as_deref())?;
+        Ok(cwd)
+    }
+
+    /// Returns the files of the project within the working directory of the
+    /// agent, relative to it
+    fn agent_files(&self, cwd: &Path) -> Vec<File> {
+        let working_dir = cwd
+            .strip_prefix(&self.environment.cwd)
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_default();
+        if working_dir.is_empty() {
+            return self.files.clone();
+        }
+        let prefix = format!("{working_dir}/");
+        self.files
+            .iter()
+            .filter_map(|file| {
+                let path = file.path.strip_prefix(&prefix)?;
+                (!path.is_empty()).then(|| File { path: path.to_string(), ..file.clone() })
+            })
+            .collect()
+    }
+
     async fn set_system_prompt(
         &mut self,
         context: Context,
         agent: &Agent,
         variables: &HashMap<String, Value>,
     ) -> anyhow::Result<Context> {
-        Ok(if let Some(system_prompt) = &agent.system_prompt {
-            let env = self.environment.clone();
-            let mut files = self.files.clone();
-            files.sort();
+        let system_message = if let Some(system_prompt) = &agent.system_prompt {
+            let mut env = self.environment.clone();
+            env.cwd = self.agent_cwd(agent)?;
+            let files = self
+                .conversation
+                .file_ranking
+                .select(self.agent_files(&env.cwd));
 
             let current_time = self
                 .current_time
@@ -204,25 +390,57 @@ impl<S: AgentService> Orchestrator<S> {
                 false => Some(ToolUsagePrompt::from(&self.get_allowed_tools(agent)?).to_string()),
             };
 
-            let ctx = SystemContext {
+            let mut ctx = SystemContext {
                 current_time,
                 env: Some(env),
                 tool_information,
                 tool_supported,
                 files,
-                custom_rules: agent.custom_rules.as_ref().cloned().unwrap_or_default(),
+                custom_rules: agent
+                    .custom_rules
+                    .iter()
+                    .chain(&self.project_rules)
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join("\n\n"),
                 variables: variables.clone(),
                 supports_parallel_tool_calls,
+                tag_prefix: self.conversation.tag_prefix.clone(),
+                scratchpad: self.conversation.scratchpad.clone(),
+                sections: None,
             };
+            if let Some(sections) = agent.system_context_sections.clone() {
+                ctx = ctx.with_sections(sections);
+            }
 
-            let system_message = self
-                .services
-                .render(system_prompt.template.as_str(), &ctx)
-                .await?;
+            Some(
+                self.services
+                    .render(system_prompt.template.as_str(), &ctx)
+                    .await?,
+            )
+        } else {
+            None
+        };
 
-            context.set_first_system_message(system_message)
+        // The scratchpad is always part of the system message, so that compaction
+        // never summarizes it away, also when the template doesn't render it
+        let scratchpad = &self.conversation.scratchpad;
+        let is_rendered = system_message
+            .as_ref()
+            .is_some_and(|message| message.contains("<scratchpad>"));
+        let system_message = if scratchpad.is_empty() || is_rendered {
+            system_message
         } else {
-            context
+            let scratchpad = Element::new("scratchpad").cdata(scratchpad).to_string();
+            Some(match system_message {
+                Some(message) => format!("{message}\n\n{scratchpad}"),
+                None => scratchpad,
+            })
+        };
+
+        Ok(match system_message {
+            Some(system_message) => context.set_system_message(system_message),
+            None => context,
         })
     }
 
@@ -239,7 +457,13 @@ impl<S: AgentService> Orchestrator<S> {
 
         // Execute all agent initialization with the event
         for agent_id in &target_agents {
-            self.init_agent(agent_id, &event).await?;
+            let span = info_span!(
+                "turn",
+                agent_id = %agent_id,
+                conversation_id = %self.conversation.id,
+                model = field::Empty,
+            );
+            self.init_agent(agent_id, &event).instrument(span).await?;
         }
 
         Ok(())
@@ -251,18 +475,166 @@ impl<S: AgentService> Orchestrator<S> {
         context: Context,
         tool_supported: bool,
         reasoning_supported: bool,
+        reasoning_budget: Option<usize>,
     ) -> anyhow::Result<ChatCompletionMessageFull> {
+        // The agent can override whether the model supports tools
+        let model = self
+            .models
+            .iter()
+            .find(|model| &model.id == model_id)
+            .cloned()
+            .unwrap_or_else(|| Model::new(model_id.clone()))
+            .tools_supported(Some(tool_supported));
         let mut transformers = TransformToolCalls::new()
-            .when(|_| !tool_supported)
+            .when(when_model_lacks_tools(&model))
+            .pipe(DropImages.when(when_model_lacks_vision(&model)))
             .pipe(ImageHandling::new())
             .pipe(DropReasoningDetails.when(|_| !reasoning_supported))
             .pipe(ReasoningNormalizer.when(|_| reasoning_supported));
-        let response = self
-            .services
-            .chat_agent(model_id, transformers.transform(context))
-            .await?;
-        response.into_full(!tool_supported).await
+        let span = info_span!(
+            "provider_request",
+            model = %model_id,
+            prompt_tokens = field::Empty,
+            completion_tokens = field::Empty,
+            cached_tokens = field::Empty,
+        );
+        let response = async {
+            // Held until the whole response is streamed, tools run afterwards so
+            // sub-agents never wait on the permit of their parent
+            let _permit = self.services.acquire_request_permit().await?;
+            let stream = self
+                .services
+                .chat_agent(model_id, transformers.transform(context))
+                .await?;
+            let stream = self.with_reasoning_budget(stream, reasoning_budget);
+            // Calls written as XML are part of the text, it's only shown once complete
+            let stream = if tool_supported {
+                self.with_partial_text(stream)
+            } else {
+                stream
+            };
+            self.with_tool_call_progress(stream)
+                .into_full_with_tag_prefix(!tool_supported, &self.conversation.tag_prefix)
+                .await
+        }
+        .instrument(span.clone())
+        .await?;
+        span.record("prompt_tokens", *response.usage.prompt_tokens as u64);
+        span.record(
+            "completion_tokens",
+            *response.usage.completion_tokens as u64,
+        );
+        span.record("cached_tokens", *response.usage.cached_tokens as u64);
+        Ok(response)
     }
+    /// Reports how much of a tool call's arguments was streamed so far, so
+    /// that large calls show progress before they're complete. The messages
+    /// themselves pass through untouched.
+    fn with_tool_call_progress(
+        &self,
+        stream: BoxStream<ChatCompletionMessage, anyhow::Error>,
+    ) -> BoxStream<ChatCompletionMessage, anyhow::Error> {
+        let Some(sender) = self.sender.clone() else {
+            return stream;
+        };
+
+        let mut current: Option<(ToolName, usize)> = None;
+        Box::pin(futures::StreamExt::inspect(stream, move |message| {
+            let Ok(message) = message else {
+                return;
+            };
+            for part in message.tool_calls.iter().filter_map(ToolCall::as_partial) {
+                // Only the first part of a call carries its name
+                current = match (&part.name, current.take()) {
+                    (Some(name), _) => Some((name.clone(), part.arguments_part.len())),
+                    (None, Some((name, bytes))) => Some((name, bytes + part.arguments_part.len())),
+                    (None, None) => None,
+                };
+                if let Some((name, bytes)) = current.clone() {
+                    let _ = sender.try_send(Ok(ChatResponse::ToolCallProgress { name, bytes }));
+                }
+            }
+        }))
+    }
+
+    /// Sends the text of the response while it's streamed, gathered over a
+    /// short interval so that fast streams don't cause a render per delta. The
+    /// rest is sent once a tool call starts or the response finishes
+    fn with_partial_text(
+        &self,
+        stream: BoxStream<ChatCompletionMessage, anyhow::Error>,
+    ) -> BoxStream<ChatCompletionMessage, anyhow::Error> {
+        let Some(sender) = self.sender.clone() else {
+            return stream;
+        };
+
+        let mut coalescer = TextCoalescer::new(PARTIAL_TEXT_INTERVAL);
+        Box::pin(futures::StreamExt::then(stream, move |message| {
+            let mut texts = Vec::new();
+            if let Ok(message) = &message {
+                if let Some(content) = message.content.as_ref()
+                    && !content.as_str().is_empty()
+                {
+                    texts.extend(coalescer.push(content.as_str(), Instant::now()));
+                }
+                if !message.tool_calls.is_empty() || message.finish_reason.is_some() {
+                    texts.extend(coalescer.flush());
+                }
+            }
+            let sender = sender.clone();
+            async move {
+                // Waits for the UI rather than dropping text when it falls behind
+                for text in texts {
+                    let _ = sender
+                        .send(Ok(ChatResponse::Text {
+                            text,
+                            is_complete: false,
+                            is_md: true,
+                        }))
+                        .await;
+                }
+                message
+            }
+        }))
+    }
+
+    /// Reports the reasoning tokens spent while the response is streamed, and
+    /// ends the stream once they exceed the budget before the model started
+    /// answering
+    fn with_reasoning_budget(
+        &self,
+        stream: BoxStream<ChatCompletionMessage, anyhow::Error>,
+        budget: Option<usize>,
+    ) -> BoxStream<ChatCompletionMessage, anyhow::Error> {
+        let sender = self.sender.clone();
+        let mut reasoning_len = 0;
+        let mut is_answering = false;
+        let mut is_exceeded = false;
+        Box::pin(futures::StreamExt::take_while(stream, move |message| {
+            // The message exceeding the budget is kept, the stream ends right after it
+            if is_exceeded {
+                return futures::future::ready(false);
+            }
+            if let Ok(message) = message {
+                is_answering |= message
+                    .content
+                    .as_ref()
+                    .is_some_and(|content| !content.as_str().is_empty())
+                    || !message.tool_calls.is_empty();
+                if let Some(reasoning) = message.reasoning.as_ref() {
+                    reasoning_len += reasoning.as_str().len();
+                    let tokens = estimate_token_count(reasoning_len);
+                    if let Some(sender) = sender.as_ref() {
+                        let _ =
+                            sender.try_send(Ok(ChatResponse::ReasoningProgress { tokens, budget }));
+                    }
+                    is_exceeded = !is_answering && budget.is_some_and(|budget| tokens >= budget);
+                }
+            }
+            futures::future::ready(true)
+        }))
+    }
+
     /// Checks if compaction is needed and performs it if necessary
     async fn check_and_compact(
         &self,
@@ -276,7 +648,10 @@ impl<S: AgentService> Orchestrator<S> {
             Compactor::new(self.services.clone())
                 .compact(agent, context.clone(), false)
                 .await
-                .map(Some)
+                .map(|context| {
+                    let mark = CompactionMark::new(&context);
+                    Some(context.last_compaction(mark))
+                })
         } else {
             debug!(agent_id = %agent.id, "Compaction not needed");
             Ok(None)
@@ -298,9 +673,23 @@ impl<S: AgentService> Orchestrator<S> {
             .model
             .clone()
             .ok_or(Error::MissingModel(agent.id.clone()))?;
+        Span::current().record("model", field::display(&model_id));
         let tool_supported = self.is_tool_supported(&agent)?;
         let reasoning_supported = self.is_reasoning_supported(&agent)?;
 
+        // Warned about once, when the conversation starts
+        if self.conversation.context.is_none()
+            && let Some(warning) = self
+                .models
+                .iter()
+                .find(|model| model.id == model_id)
+                .and_then(|model| agent.tool_support_warning(model))
+        {
+            warn!(agent_id = %agent.id, model_id = %model_id, "Model doesn't support tools");
+            self.send(ChatResponse::Warning { message: warning })
+                .await?;
+        }
+
         let mut context = self.conversation.context.clone().unwrap_or_default();
 
         // attach the conversation ID to the context
@@ -312,6 +701,14 @@ impl<S: AgentService> Orchestrator<S> {
         // Render the system prompts with the variables
         context = self.set_system_prompt(context, &agent, &variables).await?;
 
+        // Background given with the event goes ahead of the task
+        if let Some(background) = event.context.as_ref() {
+            context = context.add_message(ContextMessage::user(
+                Element::new("context").cdata(background),
+                agent.model.clone(),
+            ));
+        }
+
         // Render user prompts
         context = self
             .set_user_prompt(context, &agent, &variables, event)
@@ -329,15 +726,34 @@ impl<S: AgentService> Orchestrator<S> {
             context = context.top_k(top_k);
         }
 
-        if let Some(max_tokens) = agent.max_tokens {
+        if let Some(max_tokens) = self.conversation.agent_max_tokens(&agent) {
             context = context.max_tokens(max_tokens.value() as usize);
         }
 
+        if let Some(response_format) = agent.response_format.as_ref() {
+            context = context.response_format(response_format.clone());
+        }
+
+        if let Some(tool_choice) = agent.tool_choice.as_ref() {
+            context = context.tool_choice(tool_choice.clone());
+        }
+
+        if let Some(stream) = agent.stream {
+            context = context.stream(stream);
+        }
+
         if reasoning_supported {
             // Add reasoning specific params to context only if reasoning is supported
             // by underlying model
-            if let Some(reasoning) = agent.reasoning.as_ref() {
-                context = context.reasoning(reasoning.clone());
+            let mut reasoning = agent.reasoning.clone();
+            // The provider caps the thinking at the budget as well, rather than it only
+            // being stopped once exceeded
+            if let Some(budget) = agent.reasoning_budget {
+                let config = reasoning.get_or_insert_default();
+                config.max_tokens = Some(config.max_tokens.map_or(budget, |max| max.min(budget)));
+            }
+            if let Some(reasoning) = reasoning {
+                context = context.reasoning(reasoning);
             }
         }
 
@@ -368,10 +784,28 @@ impl<S: AgentService> Orchestrator<S> {
                 })
             });
 
+        if self.conversation.plan_first {
+            // Given once, later turns of the conversation still have it in the context
+            let instruction = Element::new("plan_first")
+                .text(PLAN_FIRST_INSTRUCTION)
+                .render();
+            let is_instructed = context.messages.iter().any(|message| {
+                matches!(message, ContextMessage::Text(text) if text.content == instruction)
+            });
+            if !is_instructed {
+                context =
+                    context.add_message(ContextMessage::user(instruction, model_id.clone().into()));
+            }
+        }
+
+        // Changes are held back until the user approves the plan
+        let mut is_plan_approved = !self.conversation.plan_first;
+
         // Indicates whether the tool execution has been completed
         let mut is_complete = false;
 
         let mut empty_tool_call_count = 0;
+        let mut empty_response_count = 0;
         let mut request_count = 0;
 
         // Retrieve the number of requests allowed per tick.
@@ -385,7 +819,15 @@ impl<S: AgentService> Orchestrator<S> {
             // Run the main chat request and compaction check in parallel
             let main_request = crate::retry::retry_with_config(
                 &self.environment.retry_config,
-                || self.execute_chat_turn(&model_id, context.clone(), tool_supported, reasoning_supported),
+                || {
+                    self.execute_chat_turn(
+                        &model_id,
+                        context.clone(),
+                        tool_supported,
+                        reasoning_supported,
+                        agent.reasoning_budget,
+                    )
+                },
                 self.sender.as_ref().map(|sender| {
                     let sender = sender.clone();
                     let agent_id = agent.id.clone();
@@ -412,6 +854,7 @@ impl<S: AgentService> Orchestrator<S> {
                     usage,
                     reasoning,
                     reasoning_details,
+                    segments,
                 },
                 compaction_result,
             ) = tokio::try_join!(main_request, self.check_and_compact(&agent, &context))?;
@@ -427,6 +870,12 @@ impl<S: AgentService> Orchestrator<S> {
                 }
             }
 
+            // Fall back to the configured pricing when the provider doesn't report cost
+            let usage = match self.conversation.pricing.get(&model_id) {
+                Some(pricing) => usage.apply_pricing(pricing),
+                None => usage,
+            };
+
             info!(
                 token_usage = format!("{}", usage.prompt_tokens),
                 total_tokens = format!("{}", usage.total_tokens),
@@ -435,27 +884,42 @@ impl<S: AgentService> Orchestrator<S> {
 
             // Send the usage information if available
             self.send(ChatResponse::Usage(usage.clone())).await?;
+            // Counted on the conversation, the token budget spans all of its turns
+            self.conversation.used_tokens += *usage.total_tokens;
 
             context = context.usage(usage);
 
+            // A specific tool is only forced on the first request of the turn, otherwise the
+            // model could never move on to other tools or complete the task
+            if matches!(context.tool_choice, Some(ToolChoice::Call(_))) {
+                context.tool_choice = None;
+            }
+
             let has_no_tool_calls = tool_calls.is_empty();
 
+            // Reasoning stopped by the budget before the model produced any answer
+            let exceeded_reasoning_budget = agent.reasoning_budget.filter(|budget| {
+                has_no_tool_calls
+                    && content.trim().is_empty()
+                    && reasoning
+                        .as_ref()
+                        .is_some_and(|reasoning| estimate_token_count(reasoning.len()) >= *budget)
+            });
+
+            // Neither text nor tool calls, usually a transient provider hiccup
+            let is_empty_response = has_no_tool_calls
+                && content.trim().is_empty()
+                && exceeded_reasoning_budget.is_none();
+
             debug!(agent_id = %agent.id, tool_call_count = tool_calls.len(), "Tool call count");
 
             is_complete = tool_calls.iter().any(|call| Tools::is_complete(&call.name));
 
-            if !is_complete && !has_no_tool_calls {
-                // If task is completed we would have already displayed a message so we can
-                // ignore the content that's collected from the stream
-                // NOTE: Important to send the content messages before the tool call happens
-                self.send(ChatResponse::Text {
-                    text: remove_tag_with_prefix(&content, "forge_")
-                        .as_str()
-                        .to_string(),
-                    is_complete: true,
-                    is_md: true,
-                })
-                .await?;
+            // A response that doesn't match the requested response format can't complete
+            // the task, so the model gets a chance to correct its output
+            let format_violations = Self::response_format_violations(&agent, &content);
+            if !format_violations.is_empty() {
+                is_complete = false;
             }
 
             if let Some(reasoning) = reasoning.as_ref()
@@ -467,18 +931,165 @@ impl<S: AgentService> Orchestrator<S> {
                     .await?;
             }
 
-            let mut tool_context =
-                ToolCallContext::new(self.conversation.tasks.clone()).sender(self.sender.clone());
+            let mut tool_context = ToolCallContext::new(self.conversation.tasks.clone())
+                .sender(self.sender.clone())
+                .completion_feedback(self.conversation.completion_feedback.clone())
+                .changed_files(self.conversation.changed_files.clone())
+                .reported_changed_files(self.conversation.reported_changed_files.clone())
+                .scratchpad(self.conversation.scratchpad.clone())
+                .post_response_command(self.conversation.post_response_command.clone())
+                .tool_post_processors(self.conversation.tool_post_processors.clone())
+                .require_tool_explanation(self.conversation.require_tool_explanation)
+                .formatters(self.conversation.formatters.clone())
+                .cwd(Some(self.agent_cwd(&agent)?));
 
             // Check if tool calls are within allowed limits if max_tool_failure_per_turn is
             // configured
             let mut allowed_limits_exceeded =
                 self.check_tool_call_failures(&tool_failure_attempts, &tool_calls);
 
-            // Process tool calls and update context
-            let mut tool_call_records = self
-                .execute_tool_calls(&agent, &tool_calls, &mut tool_context)
-                .await?;
+            // Replay the response in the order it was streamed, so that any explanation
+            // is displayed right before the tool call it refers to
+            let mut tool_call_records = Vec::with_capacity(tool_calls.len());
+            let mut cached_outputs = HashMap::new();
+            let pending_calls = segments
+                .iter()
+                .filter_map(|segment| match segment {
+                    ResponseSegment::ToolCall(tool_call) => Some(tool_call.clone()),
+                    ResponseSegment::Text(_) => None,
+                })
+                .collect::<Vec<_>>();
+            // Positions among the calls of this response that the user chose not to run
+            let mut skipped_calls = Vec::new();
+            // Calls of consecutive segments are run together, so that the read-only ones
+            // among them can run at once
+            let mut queued_calls = Vec::new();
+            let mut call_position = 0;
+            for segment in segments.iter() {
+                // Position of the call in this response, when the segment is a tool call
+                let position = call_position;
+                let is_runnable_call = match segment {
+                    ResponseSegment::ToolCall(tool_call) => {
+                        call_position += 1;
+                        (format_violations.is_empty() || !is_attempt_completion(tool_call))
+                            && !self.conversation.is_tool_disabled(&tool_call.name)
+                    }
+                    ResponseSegment::Text(_) => false,
+                };
+                if !is_runnable_call {
+                    self.run_queued_calls(
+                        &agent,
+                        &mut queued_calls,
+                        &mut tool_context,
+                        &mut cached_outputs,
+                        &mut tool_call_records,
+                    )
+                    .await?;
+                }
+                match segment {
+                    ResponseSegment::Text(text) => {
+                        // If task is completed we would have already displayed a message so we
+                        // can ignore the content that's collected from the stream
+                        if !is_complete && !has_no_tool_calls {
+                            self.send(ChatResponse::Text {
+                                text: remove_tag_with_prefix(text, &self.conversation.tag_prefix),
+                                is_complete: true,
+                                is_md: true,
+                            })
+                            .await?;
+                        }
+                    }
+                    ResponseSegment::ToolCall(tool_call)
+                        if !format_violations.is_empty() && is_attempt_completion(tool_call) =>
+                    {
+                        warn!(
+                            agent_id = %agent.id,
+                            violations = ?format_violations,
+                            "Completion doesn't match the response format"
+                        );
+                        let result = ToolResult::from(tool_call.clone()).failure(anyhow::anyhow!(
+                            "The response doesn't match the required response format:\n{}",
+                            format_violations.join("\n")
+                        ));
+                        tool_call_records.push((tool_call.clone(), result));
+                    }
+                    ResponseSegment::ToolCall(tool_call)
+                        if self.conversation.is_tool_disabled(&tool_call.name) =>
+                    {
+                        let result = ToolResult::from(tool_call.clone())
+                            .failure(crate::error::Error::Disabled(tool_call.name.clone()).into());
+                        self.send(ChatResponse::ToolCallStart(tool_call.clone()))
+                            .await?;
+                        self.send(ChatResponse::ToolCallEnd(result.clone())).await?;
+                        tool_call_records.push((tool_call.clone(), result));
+                    }
+                    ResponseSegment::ToolCall(tool_call) => {
+                        if !is_plan_approved && Tools::is_mutating(tool_call) {
+                            // The calls ahead of it run before the user is asked
+                            self.run_queued_calls(
+                                &agent,
+                                &mut queued_calls,
+                                &mut tool_context,
+                                &mut cached_outputs,
+                                &mut tool_call_records,
+                            )
+                            .await?;
+                            match self
+                                .services
+                                .review_plan(&pending_calls[position..])
+                                .await?
+                            {
+                                PlanReview::Approved => is_plan_approved = true,
+                                PlanReview::Skipped(skipped) => {
+                                    is_plan_approved = true;
+                                    skipped_calls
+                                        .extend(skipped.into_iter().map(|index| position + index));
+                                }
+                                PlanReview::Rejected(feedback) => {
+                                    let result = ToolResult::from(tool_call.clone()).failure(
+                                        anyhow::anyhow!(
+                                            "The user rejected the plan, so this tool wasn't run. Revise the plan with the task list tools and present it again.\nFeedback: {feedback}"
+                                        ),
+                                    );
+                                    self.send(ChatResponse::ToolCallStart(tool_call.clone()))
+                                        .await?;
+                                    self.send(ChatResponse::ToolCallEnd(result.clone())).await?;
+                                    tool_call_records.push((tool_call.clone(), result));
+                                    continue;
+                                }
+                            }
+                        }
+
+                        if skipped_calls.contains(&position) {
+                            self.run_queued_calls(
+                                &agent,
+                                &mut queued_calls,
+                                &mut tool_context,
+                                &mut cached_outputs,
+                                &mut tool_call_records,
+                            )
+                            .await?;
+                            let result = ToolResult::from(tool_call.clone())
+                                .success("This tool call was skipped by the user and wasn't run.");
+                            self.send(ChatResponse::ToolCallStart(tool_call.clone()))
+                                .await?;
+                            self.send(ChatResponse::ToolCallEnd(result.clone())).await?;
+                            tool_call_records.push((tool_call.clone(), result));
+                            continue;
+                        }
+
+                        queued_calls.push(tool_call.clone());
+                    }
+                }
+            }
+            self.run_queued_calls(
+                &agent,
+                &mut queued_calls,
+                &mut tool_context,
+                &mut cached_outputs,
+                &mut tool_call_records,
+            )
+            .await?;
 
             // Update the tool call attempts, if the tool call is an error
             // we increment the attempts, otherwise we remove it from the attempts map
@@ -504,9 +1115,93 @@ impl<S: AgentService> Orchestrator<S> {
                 });
             }
 
-            context = context.append_message(content.clone(), reasoning_details, tool_call_records);
+            // Results of pinned tools are never summarized away by compaction
+            if let Some(pinned_tools) = agent
+                .compact
+                .as_ref()
+                .and_then(|compact| compact.pinned_tools.as_ref())
+            {
+                tool_call_records
+                    .iter_mut()
+                    .filter(|(_, result)| pinned_tools.contains(&result.name))
+                    .for_each(|(_, result)| result.pinned = true);
+            }
+
+            // Oversized outputs are summarized by the compaction model when enabled
+            if let Some(compact) = agent.compact.as_ref() {
+                let compactor = Compactor::new(self.services.clone());
+                futures::future::join_all(
+                    tool_call_records
+                        .iter_mut()
+                        .map(|(_, result)| compactor.summarize_tool_output(compact, result)),
+                )
+                .await;
+            }
+
+            // An empty assistant message is left out, some providers reject it
+            if !is_empty_response {
+                context =
+                    context.append_message(content.clone(), reasoning_details, tool_call_records);
+            }
+
+            // Without a completion to reject, the violations are reported on their own
+            if !format_violations.is_empty() && !tool_calls.iter().any(is_attempt_completion) {
+                warn!(
+                    agent_id = %agent.id,
+                    violations = ?format_violations,
+                    "Response doesn't match the response format"
+                );
+                context = context.add_message(ContextMessage::user(
+                    Element::new("response_format_violations").cdata(format!(
+                        "The response doesn't match the required response format:\n{}",
+                        format_violations.join("\n")
+                    )),
+                    model_id.clone().into(),
+                ));
+            }
 
-            if has_no_tool_calls {
+            if let Some(limit) = exceeded_reasoning_budget {
+                warn!(
+                    agent_id = %agent.id,
+                    model_id = %model_id,
+                    reasoning_budget = limit,
+                    "Reasoning budget exceeded without an answer - concluding the turn"
+                );
+                self.send(ChatResponse::Interrupt {
+                    reason: InterruptionReason::MaxReasoningTokensReached { limit: limit as u64 },
+                })
+                .await?;
+                is_complete = true;
+            } else if is_empty_response {
+                let limit = self.conversation.max_empty_response_retries;
+                if empty_response_count < limit {
+                    empty_response_count += 1;
+                    warn!(
+                        agent_id = %agent.id,
+                        model_id = %model_id,
+                        empty_response_count,
+                        "Model returned an empty response - asking it to proceed"
+                    );
+                    context = context.add_message(ContextMessage::user(
+                        Element::new("empty_response").text(EMPTY_RESPONSE_NUDGE),
+                        model_id.clone().into(),
+                    ));
+                } else {
+                    warn!(
+                        agent_id = %agent.id,
+                        model_id = %model_id,
+                        empty_response_count,
+                        "Model kept returning empty responses - concluding the turn"
+                    );
+                    self.send(ChatResponse::Interrupt {
+                        reason: InterruptionReason::EmptyResponseRetriesExhausted {
+                            limit: limit as u64,
+                        },
+                    })
+                    .await?;
+                    is_complete = true;
+                }
+            } else if has_no_tool_calls {
                 // No tool calls present, which doesn't mean task is complete so reprompt the
                 // agent to ensure the task complete.
                 let content = self
@@ -514,7 +1209,8 @@ impl<S: AgentService> Orchestrator<S> {
                     .render(
                         "{{> forge-partial-tool-required.hbs}}",
                         &serde_json::json!({
-                            "tool_supported": tool_supported
+                            "tool_supported": tool_supported,
+                            "tag_prefix": self.conversation.tag_prefix,
                         }),
                     )
                     .await?;
@@ -567,6 +1263,14 @@ impl<S: AgentService> Orchestrator<S> {
             // Update context in the conversation
             context = SetModel::new(model_id.clone()).transform(context);
             self.conversation.tasks = tool_context.tasks;
+            self.conversation.changed_files = tool_context.changed_files;
+            self.conversation.reported_changed_files = tool_context.reported_changed_files;
+            // The scratchpad is part of the system prompt, so that compaction never
+            // summarizes it away, the prompt is rendered again once it changes
+            if self.conversation.scratchpad != tool_context.scratchpad {
+                self.conversation.scratchpad = tool_context.scratchpad;
+                context = self.set_system_prompt(context, &agent, &variables).await?;
+            }
             self.conversation.context = Some(context.clone());
             self.services.update(self.conversation.clone()).await?;
             request_count += 1;
@@ -592,6 +1296,28 @@ impl<S: AgentService> Orchestrator<S> {
                     is_complete = true;
                 }
             }
+
+            if !is_complete
+                && let Some(limit) = self.conversation.token_budget
+                && self.conversation.used_tokens >= limit
+            {
+                let used_tokens = self.conversation.used_tokens;
+                warn!(
+                    agent_id = %agent.id,
+                    model_id = %model_id,
+                    used_tokens,
+                    limit,
+                    "Agent has used up the token budget of the conversation"
+                );
+                self.send(ChatResponse::Interrupt {
+                    reason: InterruptionReason::TokenBudgetExhausted {
+                        limit: limit as u64,
+                        used: used_tokens as u64,
+                    },
+                })
+                .await?;
+                is_complete = true;
+            }
         }
 
         Ok(())
@@ -647,3 +1373,1921 @@ impl<S: AgentService> Orchestrator<S> {
         Ok(context)
     }
 }
+
+fn is_attempt_completion(tool_call: &ToolCallFull) -> bool {
+    tool_call.name == ToolsDiscriminants::ForgeToolAttemptCompletion.name()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use futures::StreamExt;
+    use pretty_assertions::assert_eq;
+    use url::Url;
+
+    use super::*;
+    use crate::RequestLimit;
+    use crate::operation::TempContentFiles;
+    use crate::tool_executor::{read_scratchpad, write_scratchpad};
+
+    /// Replays canned responses and records every context sent to the model
+    #[derive(Default)]
+    struct MockService {
+        responses: Mutex<VecDeque<Vec<ChatCompletionMessage>>>,
+        requests: Mutex<Vec<Context>>,
+        calls: Mutex<Vec<ToolCallFull>>,
+        reviews: Mutex<VecDeque<PlanReview>>,
+        rendered: Mutex<Vec<Value>>,
+        outputs: Mutex<VecDeque<String>>,
+        offloaded: Mutex<Vec<String>>,
+        failing_model: Option<ModelId>,
+        in_flight: Arc<AtomicUsize>,
+        max_in_flight: Arc<AtomicUsize>,
+        calls_in_flight: AtomicUsize,
+        max_calls_in_flight: AtomicUsize,
+        request_limit: RequestLimit,
+    }
+
+    /// Counts a response as in flight until its stream is dropped
+    struct InFlight(Arc<AtomicUsize>);
+
+    impl Drop for InFlight {
+        fn drop(&mut self) {
+            self.0.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    impl MockService {
+        fn new(responses: Vec<ChatCompletionMessage>) -> Self {
+            Self::streaming(
+                responses
+                    .into_iter()
+                    .map(|response| vec![response])
+                    .collect(),
+            )
+        }
+
+        /// Replays each response as a stream of the given chunks
+        fn streaming(responses: Vec<Vec<ChatCompletionMessage>>) -> Self {
+            Self {
+                responses: Mutex::new(responses.into()),
+                requests: Default::default(),
+                calls: Default::default(),
+                reviews: Default::default(),
+                rendered: Default::default(),
+                outputs: Default::default(),
+                offloaded: Default::default(),
+                failing_model: None,
+                in_flight: Default::default(),
+                max_in_flight: Default::default(),
+                calls_in_flight: Default::default(),
+                max_calls_in_flight: Default::default(),
+                request_limit: Default::default(),
+            }
+        }
+
+        /// Answers the plan reviews in order, approving once they run out
+        fn reviews(self, reviews: Vec<PlanReview>) -> Self {
+            *self.reviews.lock().unwrap() = reviews.into();
+            self
+        }
+
+        /// Returns the tool outputs in order, `ok` once they run out
+        fn outputs(self, outputs: Vec<&str>) -> Self {
+            *self.outputs.lock().unwrap() = outputs.into_iter().map(String::from).collect();
+            self
+        }
+
+        /// Fails the requests made to the model
+        fn failing_model(mut self, model: ModelId) -> Self {
+            self.failing_model = Some(model);
+            self
+        }
+
+        /// Bounds the requests in flight across the chats using the service
+        fn max_concurrent_requests(mut self, max: usize) -> Self {
+            self.request_limit = RequestLimit::new(Some(max));
+            self
+        }
+
+        fn requests(&self) -> Vec<Context> {
+            self.requests.lock().unwrap().clone()
+        }
+
+        /// Contents written to the offload directory
+        fn offloaded(&self) -> Vec<String> {
+            self.offloaded.lock().unwrap().clone()
+        }
+
+        fn calls(&self) -> Vec<ToolCallFull> {
+            self.calls.lock().unwrap().clone()
+        }
+
+        /// Objects the templates were rendered with
+        fn rendered(&self) -> Vec<Value> {
+            self.rendered.lock().unwrap().clone()
+        }
+
+        /// Objects the system prompts were rendered with
+        fn system_contexts(&self) -> Vec<Value> {
+            self.rendered()
+                .into_iter()
+                .filter(|object| object.get("supports_parallel_tool_calls").is_some())
+                .collect()
+        }
+
+        /// Most responses that were being streamed at the same time
+        fn max_in_flight(&self) -> usize {
+            self.max_in_flight.load(Ordering::SeqCst)
+        }
+
+        /// Most tool calls that were running at the same time
+        fn max_calls_in_flight(&self) -> usize {
+            self.max_calls_in_flight.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl AgentService for MockService {
+        async fn chat_agent(
+            &self,
+            id: &ModelId,
+            context: Context,
+        ) -> ResultStream<ChatCompletionMessage, anyhow::Error> {
+            if self.failing_model.as_ref() == Some(id) {
+                anyhow::bail!("{id} is unavailable");
+            }
+            self.requests.lock().unwrap().push(context);
+            let response = self
+                .responses
+                .lock()
+                .unwrap()
+                .pop_front()
+                .unwrap_or_else(|| vec![fixture_completion()]);
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            let guard = InFlight(self.in_flight.clone());
+            // Yields before every chunk so that concurrent chats interleave
+            Ok(Box::pin(futures::stream::iter(response).then(
+                move |message| {
+                    let _guard = &guard;
+                    async move {
+                        tokio::task::yield_now().await;
+                        Ok(message)
+                    }
+                },
+            )))
+        }
+
+        async fn call(
+            &self,
+            _agent: &Agent,
+            context: &mut ToolCallContext,
+            call: ToolCallFull,
+        ) -> ToolResult {
+            self.calls.lock().unwrap().push(call.clone());
+            let in_flight = self.calls_in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_calls_in_flight
+                .fetch_max(in_flight, Ordering::SeqCst);
+            // Yields so that concurrent calls overlap, searches report their progress
+            // on either side of it
+            let search = call
+                .call_id
+                .as_ref()
+                .filter(|_| call.name.as_str() == "forge_tool_fs_search")
+                .map(|id| id.as_str().to_string());
+            if let Some(id) = &search {
+                context.send_text(format!("searching {id}")).await.unwrap();
+            }
+            tokio::task::yield_now().await;
+            if let Some(id) = &search {
+                context.send_text(format!("searched {id}")).await.unwrap();
+            }
+            self.calls_in_flight.fetch_sub(1, Ordering::SeqCst);
+            // The scratchpad tools only touch the tool context, so they run for real
+            let scratchpad = match Tools::try_from(call.clone()) {
+                Ok(Tools::ForgeToolScratchpadWrite(input)) => {
+                    Some(write_scratchpad(input, context))
+                }
+                Ok(Tools::ForgeToolScratchpadRead(input)) => Some(read_scratchpad(input, context)),
+                _ => None,
+            };
+            if let Some(operation) = scratchpad {
+                let output = operation.into_tool_output(
+                    call.name.clone(),
+                    TempContentFiles::default(),
+                    &fixture_environment(),
+                );
+                return ToolResult::new(call.name)
+                    .call_id(call.call_id)
+                    .output(Ok(output));
+            }
+            let output = self.outputs.lock().unwrap().pop_front();
+            ToolResult::new(call.name)
+                .call_id(call.call_id)
+                .success(output.as_deref().unwrap_or("ok"))
+        }
+
+        async fn render(
+            &self,
+            template: &str,
+            object: &(impl serde::Serialize + Sync),
+        ) -> anyhow::Result<String> {
+            self.rendered
+                .lock()
+                .unwrap()
+                .push(serde_json::to_value(object)?);
+            Ok(template.to_string())
+        }
+
+        async fn update(&self, _conversation: Conversation) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn offload(&self, prefix: &str, content: &str) -> anyhow::Result<PathBuf> {
+            let mut offloaded = self.offloaded.lock().unwrap();
+            offloaded.push(content.to_string());
+            Ok(PathBuf::from(format!(
+                "/home/user/project/offload/{prefix}{}.txt",
+                offloaded.len()
+            )))
+        }
+
+        async fn review_plan(&self, _pending: &[ToolCallFull]) -> anyhow::Result<PlanReview> {
+            Ok(self
+                .reviews
+                .lock()
+                .unwrap()
+                .pop_front()
+                .unwrap_or(PlanReview::Approved))
+        }
+
+        async fn acquire_request_permit(
+            &self,
+        ) -> anyhow::Result<Option<tokio::sync::OwnedSemaphorePermit>> {
+            self.request_limit.acquire().await
+        }
+    }
+
+    fn fixture_environment() -> Environment {
+        Environment {
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            pid: 12345,
+            cwd: PathBuf::from("/home/user/project"),
+            home: Some(PathBuf::from("/home/user")),
+            shell: "/bin/bash".to_string(),
+            base_path: PathBuf::from("/home/user/project"),
+            retry_config: RetryConfig {
+                initial_backoff_ms: 1,
+                min_delay_ms: 1,
+                backoff_factor: 1,
+                max_retry_attempts: 0,
+                retry_status_codes: vec![],
+                content_filter_codes: vec![],
+                max_delay: None,
+            },
+            max_search_lines: 25,
+            fetch_truncation_limit: 55,
+            fetch_cache_ttl: 0,
+            max_read_size: 10,
+            stdout_max_prefix_length: 10,
+            stdout_max_suffix_length: 10,
+            tool_limits: Default::default(),
+            http: Default::default(),
+            max_file_size: 256 << 10,
+            forge_api_url: Url::parse("http://forgecode.dev/api").unwrap(),
+            shell_env: Default::default(),
+            truncation_template: None,
+            otlp_endpoint: None,
+            debug_raw_output: false,
+            sandbox_root: None,
+            max_concurrent_requests: None,
+            redact_home: false,
+            diff_ignore_whitespace: false,
+            diff_ignore_trailing_newline: false,
+            offload_dir: None,
+        }
+    }
+
+    fn fixture_conversation() -> Conversation {
+        let mut agent = Agent::new(AgentId::default())
+            .model(ModelId::new("test-model"))
+            .tool_supported(true)
+            .tools(vec![ToolName::new("forge_tool_fs_read")]);
+        agent.add_subscription(AgentId::default());
+        Conversation::new(ConversationId::generate(), Workflow::new(), vec![]).agents(vec![agent])
+    }
+
+    fn fixture_tool_call(name: &str) -> ChatCompletionMessage {
+        ChatCompletionMessage::assistant(Content::full("")).add_tool_call(
+            ToolCallFull::new(ToolName::new(name)).call_id(ToolCallId::new("call_1")),
+        )
+    }
+
+    fn fixture_completion() -> ChatCompletionMessage {
+        fixture_tool_call("forge_tool_attempt_completion")
+    }
+
+    fn fixture_event(value: Option<&str>) -> Event {
+        Event::new(format!("{}/user_task_update", AgentId::default()), value)
+    }
+
+    #[tokio::test]
+    async fn test_continue_after_max_request_interruption_resumes_saved_context() {
+        let services = Arc::new(MockService::new(vec![fixture_tool_call(
+            "forge_tool_fs_read",
+        )]));
+        let mut conversation = fixture_conversation();
+        conversation.max_requests_per_turn = Some(1);
+
+        // First turn is interrupted after a single request
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        );
+        orch.chat(fixture_event(Some("Refactor the parser")))
+            .await
+            .unwrap();
+        let saved = orch.get_conversation().clone();
+
+        // Continuing starts a new orchestrator from the persisted conversation
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            saved.clone(),
+            chrono::Local::now(),
+        );
+        orch.chat(fixture_event(None)).await.unwrap();
+
+        let requests = services.requests();
+        let actual = requests.last().unwrap().messages.clone();
+        let expected = saved.context.unwrap().messages;
+        assert_eq!(requests.len(), 2);
+        assert_eq!(actual, expected);
+        assert!(actual.iter().any(|message| message.has_tool_call()));
+    }
+
+    #[tokio::test]
+    async fn test_interleaved_text_and_tool_calls_are_emitted_in_stream_order() {
+        let fixture_read = |id: &str, path: &str| {
+            ToolCallFull::new(ToolName::new("forge_tool_fs_read"))
+                .call_id(ToolCallId::new(id))
+                .arguments(serde_json::json!({"path": path}))
+        };
+        let services = Arc::new(MockService::streaming(vec![vec![
+            ChatCompletionMessage::assistant(Content::part("Reading the manifest.")),
+            ChatCompletionMessage::default().add_tool_call(fixture_read("call_1", "Cargo.toml")),
+            ChatCompletionMessage::assistant(Content::part("Now the lockfile.")),
+            ChatCompletionMessage::default().add_tool_call(fixture_read("call_2", "Cargo.lock")),
+        ]]));
+        let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+
+        let mut orch = Orchestrator::new(
+            services,
+            fixture_environment(),
+            fixture_conversation(),
+            chrono::Local::now(),
+        )
+        .sender(Arc::new(tx));
+        orch.chat(fixture_event(Some("Check the dependencies")))
+            .await
+            .unwrap();
+        drop(orch);
+
+        let mut actual = Vec::new();
+        while let Some(response) = rx.recv().await {
+            match response.unwrap() {
+                ChatResponse::Text { text, is_complete: true, .. } => {
+                    actual.push(format!("text: {text}"))
+                }
+                ChatResponse::ToolCallStart(call) => actual.push(format!("tool: {}", call.name)),
+                _ => {}
+            }
+        }
+
+        let expected = vec![
+            "text: Reading the manifest.".to_string(),
+            "tool: forge_tool_fs_read".to_string(),
+            "text: Now the lockfile.".to_string(),
+            "tool: forge_tool_fs_read".to_string(),
+            "tool: forge_tool_attempt_completion".to_string(),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_partial_tool_call_reports_progress_before_assembled_call() {
+        let fixture_part =
+            |part: ToolCallPart| ChatCompletionMessage::default().add_tool_call(part);
+        let services = Arc::new(MockService::streaming(vec![vec![
+            fixture_part(
+                ToolCallPart::default()
+                    .call_id(ToolCallId::new("call_1"))
+                    .name(ToolName::new("forge_tool_fs_read"))
+                    .arguments_part(r#"{"path": "#),
+            ),
+            fixture_part(ToolCallPart::default().arguments_part(r#""Cargo.toml"}"#)),
+        ]]));
+        let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            fixture_conversation(),
+            chrono::Local::now(),
+        )
+        .sender(Arc::new(tx));
+        orch.chat(fixture_event(Some("Check the dependencies")))
+            .await
+            .unwrap();
+        drop(orch);
+
+        let mut actual = Vec::new();
+        while let Some(response) = rx.recv().await {
+            match response.unwrap() {
+                ChatResponse::ToolCallProgress { name, bytes } => {
+                    actual.push(format!("progress: {name} {bytes}"))
+                }
+                ChatResponse::ToolCallStart(call) => actual.push(format!("tool: {}", call.name)),
+                _ => {}
+            }
+        }
+
+        let expected = vec![
+            "progress: forge_tool_fs_read 9".to_string(),
+            "progress: forge_tool_fs_read 22".to_string(),
+            "tool: forge_tool_fs_read".to_string(),
+            "tool: forge_tool_attempt_completion".to_string(),
+        ];
+        assert_eq!(actual, expected);
+        // The call is assembled exactly as it would be without progress reporting
+        assert_eq!(
+            services.calls()[0].arguments,
+            serde_json::json!({"path": "Cargo.toml"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_disabled_tools_are_withheld_until_enabled_again() {
+        let services = Arc::new(MockService::new(vec![fixture_tool_call(
+            "forge_tool_process_shell",
+        )]));
+        let mut conversation = fixture_conversation();
+        conversation.agents[0].tools = Some(vec![
+            ToolName::new("forge_tool_fs_read"),
+            ToolName::new("forge_tool_process_shell"),
+        ]);
+        conversation.disable_tool(ToolName::new("forge_tool_process_shell"));
+        let tool_definitions = vec![
+            ToolsDiscriminants::ForgeToolFsRead.definition(),
+            ToolsDiscriminants::ForgeToolProcessShell.definition(),
+        ];
+        let orch = |conversation: Conversation| {
+            Orchestrator::new(
+                services.clone(),
+                fixture_environment(),
+                conversation,
+                chrono::Local::now(),
+            )
+            .tool_definitions(tool_definitions.clone())
+        };
+
+        let mut disabled = orch(conversation);
+        disabled
+            .chat(fixture_event(Some("Build it")))
+            .await
+            .unwrap();
+        let mut conversation = disabled.get_conversation().clone();
+        conversation.enable_tool(&ToolName::new("forge_tool_process_shell"));
+        orch(conversation)
+            .chat(fixture_event(Some("Build it now")))
+            .await
+            .unwrap();
+
+        let requests = services.requests();
+        let actual = [0, requests.len() - 1].map(|index| {
+            requests[index]
+                .tools
+                .iter()
+                .map(|tool| tool.name.to_string())
+                .collect::<Vec<_>>()
+        });
+        let expected = [
+            vec!["forge_tool_fs_read", "forge_tool_attempt_completion"],
+            vec![
+                "forge_tool_fs_read",
+                "forge_tool_process_shell",
+                "forge_tool_attempt_completion",
+            ],
+        ]
+        .map(|names| names.into_iter().map(String::from).collect::<Vec<_>>());
+        assert_eq!(actual, expected);
+        // The call the model made anyway while the tool was disabled wasn't run
+        assert!(
+            services
+                .calls()
+                .iter()
+                .all(|call| call.name.as_str() != "forge_tool_process_shell")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_budget_interrupts_the_turn_once_used_up() {
+        let fixture_usage = Usage { total_tokens: TokenCount::Actual(150), ..Default::default() };
+        let services = Arc::new(MockService::new(vec![
+            fixture_tool_call("forge_tool_fs_read").usage(fixture_usage.clone()),
+            fixture_tool_call("forge_tool_fs_read").usage(fixture_usage.clone()),
+            fixture_tool_call("forge_tool_fs_read").usage(fixture_usage),
+        ]));
+        let mut conversation = fixture_conversation();
+        conversation.token_budget = Some(250);
+        let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        )
+        .sender(Arc::new(tx));
+        orch.chat(fixture_event(Some("Read the manifest")))
+            .await
+            .unwrap();
+        drop(orch);
+
+        let mut actual = Vec::new();
+        while let Some(response) = rx.recv().await {
+            if let ChatResponse::Interrupt { reason } = response.unwrap() {
+                actual.push(reason);
+            }
+        }
+
+        let expected = vec![InterruptionReason::TokenBudgetExhausted { limit: 250, used: 300 }];
+        assert_eq!(actual, expected);
+        assert_eq!(services.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_token_budget_spans_the_turns_of_the_conversation() {
+        let fixture_usage = Usage { total_tokens: TokenCount::Actual(150), ..Default::default() };
+        let services = Arc::new(MockService::new(vec![
+            fixture_completion().usage(fixture_usage.clone()),
+            fixture_tool_call("forge_tool_fs_read").usage(fixture_usage.clone()),
+            fixture_tool_call("forge_tool_fs_read").usage(fixture_usage),
+        ]));
+        let mut conversation = fixture_conversation();
+        conversation.token_budget = Some(250);
+
+        // The first turn completes within the budget
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        );
+        orch.chat(fixture_event(Some("Read the manifest")))
+            .await
+            .unwrap();
+        let saved = orch.get_conversation().clone();
+
+        // The next turn starts from the persisted conversation
+        let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            saved.clone(),
+            chrono::Local::now(),
+        )
+        .sender(Arc::new(tx));
+        orch.chat(fixture_event(Some("Read the lockfile")))
+            .await
+            .unwrap();
+        drop(orch);
+
+        let mut actual = Vec::new();
+        while let Some(response) = rx.recv().await {
+            if let ChatResponse::Interrupt { reason } = response.unwrap() {
+                actual.push(reason);
+            }
+        }
+
+        let expected = vec![InterruptionReason::TokenBudgetExhausted { limit: 250, used: 300 }];
+        assert_eq!(saved.used_tokens, 150);
+        assert_eq!(actual, expected);
+        assert_eq!(services.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reasoning_budget_concludes_turn_without_answer() {
+        let fixture_reasoning =
+            |text: String| ChatCompletionMessage::default().reasoning(Content::part(text));
+        let services = Arc::new(MockService::streaming(vec![vec![
+            fixture_reasoning("a".repeat(40)),
+            fixture_reasoning("b".repeat(40)),
+            fixture_reasoning("c".repeat(40)),
+            ChatCompletionMessage::assistant(Content::part("Too late")),
+        ]]));
+        let mut conversation = fixture_conversation();
+        conversation.agents[0].reasoning_budget = Some(20);
+        let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        )
+        .sender(Arc::new(tx));
+        orch.chat(fixture_event(Some("Prove the theorem")))
+            .await
+            .unwrap();
+        drop(orch);
+
+        let mut actual = Vec::new();
+        while let Some(response) = rx.recv().await {
+            match response.unwrap() {
+                ChatResponse::ReasoningProgress { tokens, budget } => {
+                    actual.push(format!("progress: {tokens} of {budget:?}"))
+                }
+                ChatResponse::Interrupt {
+                    reason: InterruptionReason::MaxReasoningTokensReached { limit },
+                } => actual.push(format!("interrupt: {limit}")),
+                _ => {}
+            }
+        }
+
+        let expected = vec![
+            "progress: 10 of Some(20)".to_string(),
+            "progress: 20 of Some(20)".to_string(),
+            "interrupt: 20".to_string(),
+        ];
+        assert_eq!(actual, expected);
+        // The turn concludes instead of reprompting the model for a tool call
+        assert_eq!(services.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reasoning_budget_caps_thinking_tokens_of_request() {
+        let services = Arc::new(MockService::new(vec![]));
+        let mut conversation = fixture_conversation();
+        conversation.agents[0].reasoning = Some(
+            ReasoningConfig::default()
+                .enabled(true)
+                .max_tokens(4096_usize),
+        );
+        conversation.agents[0].reasoning_budget = Some(2048);
+        let model = Model {
+            id: ModelId::new("test-model"),
+            name: None,
+            description: None,
+            context_length: None,
+            tools_supported: Some(true),
+            supports_parallel_tool_calls: None,
+            supports_reasoning: Some(true),
+            supports_vision: None,
+        };
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        )
+        .models(vec![model]);
+        orch.chat(fixture_event(Some("Prove the theorem")))
+            .await
+            .unwrap();
+
+        let actual = services.requests()[0].reasoning.clone();
+        let expected = Some(
+            ReasoningConfig::default()
+                .enabled(true)
+                .max_tokens(2048_usize),
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_empty_response_is_retried_once_then_given_up() {
+        let services = Arc::new(MockService::new(vec![
+            ChatCompletionMessage::assistant(Content::full("")),
+            ChatCompletionMessage::assistant(Content::full("")),
+        ]));
+        let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            fixture_conversation(),
+            chrono::Local::now(),
+        )
+        .sender(Arc::new(tx));
+        orch.chat(fixture_event(Some("Fix the build")))
+            .await
+            .unwrap();
+        drop(orch);
+
+        let mut actual = Vec::new();
+        while let Some(response) = rx.recv().await {
+            if let ChatResponse::Interrupt {
+                reason: InterruptionReason::EmptyResponseRetriesExhausted { limit },
+            } = response.unwrap()
+            {
+                actual.push(limit);
+            }
+        }
+
+        let expected = vec![1];
+        assert_eq!(actual, expected);
+        // The model is asked to proceed once, and not reprompted after that
+        let requests = services.requests();
+        assert_eq!(requests.len(), 2);
+        let nudged = requests[1].messages.iter().any(|message| match message {
+            ContextMessage::Text(message) => message.content.contains(EMPTY_RESPONSE_NUDGE),
+            _ => false,
+        });
+        assert!(nudged);
+    }
+
+    #[tokio::test]
+    async fn test_model_without_tools_warns_at_conversation_start() {
+        let services = Arc::new(MockService::new(vec![]));
+        let mut conversation = fixture_conversation();
+        conversation.agents[0].tool_supported = None;
+        let model = Model {
+            id: ModelId::new("test-model"),
+            name: None,
+            description: None,
+            context_length: None,
+            tools_supported: Some(false),
+            supports_parallel_tool_calls: None,
+            supports_reasoning: None,
+            supports_vision: None,
+        };
+        let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        )
+        .models(vec![model])
+        .sender(Arc::new(tx));
+        orch.chat(fixture_event(Some("Read the manifest")))
+            .await
+            .unwrap();
+        drop(orch);
+
+        let mut actual = Vec::new();
+        while let Some(response) = rx.recv().await {
+            if let ChatResponse::Warning { message } = response.unwrap() {
+                actual.push(message);
+            }
+        }
+
+        assert_eq!(actual.len(), 1);
+        assert!(actual[0].contains("test-model doesn't support tool calling"));
+    }
+
+    #[tokio::test]
+    async fn test_model_with_unknown_tool_support_falls_back_to_xml_tool_calls() {
+        let services = Arc::new(MockService::new(vec![]));
+        let mut conversation = fixture_conversation();
+        conversation.agents[0].tool_supported = None;
+        conversation.agents[0].system_prompt = Some(Template::new("system"));
+        // e.g. a local model whose capabilities the server doesn't report
+        let model = Model::new(ModelId::new("test-model"));
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        )
+        .models(vec![model]);
+        orch.chat(fixture_event(Some("Read the manifest")))
+            .await
+            .unwrap();
+
+        let actual = (
+            services.rendered().first().cloned().unwrap()["tool_supported"].clone(),
+            services.requests()[0].tools.len(),
+        );
+        let expected = (serde_json::json!(false), 0);
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_request_limit_serializes_sub_agent_chats() {
+        let services = Arc::new(MockService::new(vec![]).max_concurrent_requests(1));
+        let mut first = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            fixture_conversation(),
+            chrono::Local::now(),
+        );
+        let mut second = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            fixture_conversation(),
+            chrono::Local::now(),
+        );
+
+        let (first_result, second_result) = tokio::join!(
+            first.chat(fixture_event(Some("Review the parser"))),
+            second.chat(fixture_event(Some("Review the lexer"))),
+        );
+        first_result.unwrap();
+        second_result.unwrap();
+
+        let actual = (services.requests().len(), services.max_in_flight());
+        let expected = (2, 1);
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_tool_output_is_replaced_with_summary() {
+        let fixture_output = "fn parse() {} ".repeat(100);
+        let services = Arc::new(
+            MockService::new(vec![
+                fixture_tool_call("forge_tool_fs_read"),
+                ChatCompletionMessage::assistant(Content::full(
+                    "Defines 100 empty parse functions",
+                )),
+            ])
+            .outputs(vec![&fixture_output]),
+        );
+        let mut conversation = fixture_conversation();
+        conversation.agents[0].compact =
+            Some(Compact::new(ModelId::new("cheap-model")).tool_output_threshold(100_usize));
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        );
+        orch.chat(fixture_event(Some("Explain the parser")))
+            .await
+            .unwrap();
+
+        let requests = services.requests();
+        let tool_output = |context: &Context| {
+            context.messages.iter().find_map(|message| match message {
+                ContextMessage::Tool(result) => result.output.as_str().map(str::to_string),
+                _ => None,
+            })
+        };
+        // The summarizer is sent the full output, the agent only its summary
+        assert!(services.rendered().iter().any(|object| {
+            object["context"]
+                .as_str()
+                .is_some_and(|context| context.contains(&fixture_output))
+        }));
+        let actual = tool_output(&requests[2]).unwrap();
+        assert!(actual.contains("Defines 100 empty parse functions"));
+        assert!(!actual.contains("fn parse()"));
+        // The full output can still be read back from where it was offloaded
+        assert!(actual.contains("/home/user/project/offload/forge_summarized_1.txt"));
+        assert_eq!(services.offloaded(), vec![fixture_output]);
+    }
+
+    #[tokio::test]
+    async fn test_tool_output_summaries_count_against_request_limit() {
+        let fixture_output = "fn parse() {} ".repeat(100);
+        let fixture_read = |id: &str, path: &str| {
+            ToolCallFull::new(ToolName::new("forge_tool_fs_read"))
+                .call_id(ToolCallId::new(id))
+                .arguments(serde_json::json!({"path": path}))
+        };
+        let services = Arc::new(
+            MockService::new(vec![
+                ChatCompletionMessage::assistant(Content::full(""))
+                    .add_tool_call(fixture_read("call_1", "/home/user/project/parser.rs"))
+                    .add_tool_call(fixture_read("call_2", "/home/user/project/lexer.rs")),
+                ChatCompletionMessage::assistant(Content::full("Summary of the parser")),
+                ChatCompletionMessage::assistant(Content::full("Summary of the lexer")),
+            ])
+            .outputs(vec![&fixture_output, &fixture_output])
+            .max_concurrent_requests(1),
+        );
+        let mut conversation = fixture_conversation();
+        conversation.agents[0].compact =
+            Some(Compact::new(ModelId::new("cheap-model")).tool_output_threshold(100_usize));
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        );
+        orch.chat(fixture_event(Some("Explain the parser and the lexer")))
+            .await
+            .unwrap();
+
+        let requests = services.requests();
+        let actual = requests[3]
+            .messages
+            .iter()
+            .filter_map(|message| match message {
+                ContextMessage::Tool(result) => result.output.as_str().map(str::to_string),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(actual.len(), 2);
+        assert!(actual[0].contains("Summary of the parser"));
+        assert!(actual[1].contains("Summary of the lexer"));
+        assert_eq!(services.max_in_flight(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tool_output_is_kept_when_summarizing_fails() {
+        let fixture_output = "fn parse() {} ".repeat(100);
+        let services = Arc::new(
+            MockService::new(vec![fixture_tool_call("forge_tool_fs_read")])
+                .outputs(vec![&fixture_output])
+                .failing_model(ModelId::new("cheap-model")),
+        );
+        let mut conversation = fixture_conversation();
+        conversation.agents[0].compact =
+            Some(Compact::new(ModelId::new("cheap-model")).tool_output_threshold(100_usize));
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        );
+        orch.chat(fixture_event(Some("Explain the parser")))
+            .await
+            .unwrap();
+
+        let requests = services.requests();
+        let actual = requests[1]
+            .messages
+            .iter()
+            .find_map(|message| match message {
+                ContextMessage::Tool(result) => result.output.as_str().map(str::to_string),
+                _ => None,
+            });
+        let expected = Some(fixture_output);
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_completion_not_matching_response_format_is_reported_to_model() {
+        let fixture_completion_with = |content: &str| {
+            ChatCompletionMessage::assistant(Content::full(content)).add_tool_call(
+                ToolCallFull::new(ToolName::new("forge_tool_attempt_completion"))
+                    .call_id(ToolCallId::new("call_1"))
+                    .arguments(serde_json::json!({"result": "Summarized the change"})),
+            )
+        };
+        let services = Arc::new(MockService::new(vec![
+            fixture_completion_with(r#"{"summary": "done"}"#),
+            fixture_completion_with(r#"{"title": "done"}"#),
+        ]));
+        let mut conversation = fixture_conversation();
+        conversation.agents[0].response_format = Some(ResponseFormat::JsonSchema {
+            name: "report".to_string(),
+            schema: serde_json::json!({"type": "object", "required": ["title"]}),
+            strict: None,
+        });
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        );
+        orch.chat(fixture_event(Some("Summarize the change")))
+            .await
+            .unwrap();
+
+        let requests = services.requests();
+        let actual = requests
+            .last()
+            .unwrap()
+            .messages
+            .iter()
+            .find_map(|message| match message {
+                ContextMessage::Tool(result) => Some(result.clone()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(actual.is_error());
+        assert!(actual.output.values.iter().any(|value| matches!(
+            value,
+            ToolValue::Text(text) if text.contains("missing required property `title`")
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_response_not_matching_response_format_is_reported_without_completion() {
+        let services = Arc::new(MockService::new(vec![
+            ChatCompletionMessage::assistant(Content::full("The change is done"))
+                .add_tool_call(fixture_tool_call("forge_tool_fs_read").tool_calls[0].clone()),
+        ]));
+        let mut conversation = fixture_conversation();
+        conversation.agents[0].response_format = Some(ResponseFormat::JsonObject);
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        );
+        orch.chat(fixture_event(Some("Summarize the change")))
+            .await
+            .unwrap();
+
+        let requests = services.requests();
+        let actual = requests[1]
+            .messages
+            .last()
+            .and_then(|message| match message {
+                ContextMessage::Text(message) if message.role == Role::User => {
+                    Some(message.content.clone())
+                }
+                _ => None,
+            });
+        assert!(actual.is_some_and(|content| content.contains("output is not valid JSON")));
+    }
+
+    #[tokio::test]
+    async fn test_identical_read_calls_reuse_result_while_writes_execute() {
+        let fixture_call = |id: &str, name: &str, arguments: Value| {
+            ToolCallFull::new(ToolName::new(name))
+                .call_id(ToolCallId::new(id))
+                .arguments(arguments)
+        };
+        let read = serde_json::json!({"path": "/home/user/project/Cargo.toml"});
+        let write = serde_json::json!({"path": "/home/user/project/out.txt", "content": "hi"});
+        let services = Arc::new(MockService::new(vec![
+            ChatCompletionMessage::assistant(Content::full(""))
+                .add_tool_call(fixture_call("call_1", "forge_tool_fs_read", read.clone()))
+                .add_tool_call(fixture_call("call_2", "forge_tool_fs_read", read))
+                .add_tool_call(fixture_call(
+                    "call_3",
+                    "forge_tool_fs_create",
+                    write.clone(),
+                ))
+                .add_tool_call(fixture_call("call_4", "forge_tool_fs_create", write)),
+        ]));
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            fixture_conversation(),
+            chrono::Local::now(),
+        );
+        orch.chat(fixture_event(Some("Write the output")))
+            .await
+            .unwrap();
+
+        let actual = services
+            .calls()
+            .into_iter()
+            .map(|call| call.name)
+            .collect::<Vec<_>>();
+        let expected = [
+            "forge_tool_fs_read",
+            "forge_tool_fs_create",
+            "forge_tool_fs_create",
+            "forge_tool_attempt_completion",
+        ]
+        .map(ToolName::new);
+        assert_eq!(actual, expected);
+
+        // The reused result still answers the repeated call
+        let results = services.requests()[1]
+            .messages
+            .iter()
+            .filter_map(|message| match message {
+                ContextMessage::Tool(result) => Some(result.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let actual = results
+            .iter()
+            .map(|result| result.call_id.clone().unwrap())
+            .collect::<Vec<_>>();
+        let expected = ["call_1", "call_2", "call_3", "call_4"].map(ToolCallId::new);
+        assert_eq!(actual, expected);
+        assert!(
+            results[1]
+                .output
+                .values
+                .ends_with(&results[0].output.values)
+        );
+        assert!(results[1].output.as_str().unwrap().contains("reused"));
+    }
+
+    fn fixture_tool_calls() -> ChatCompletionMessage {
+        let fixture_call = |id: &str, name: &str, arguments: Value| {
+            ToolCallFull::new(ToolName::new(name))
+                .call_id(ToolCallId::new(id))
+                .arguments(arguments)
+        };
+        ChatCompletionMessage::assistant(Content::full(""))
+            .add_tool_call(fixture_call(
+                "call_1",
+                "forge_tool_fs_read",
+                serde_json::json!({"path": "/home/user/project/Cargo.toml"}),
+            ))
+            .add_tool_call(fixture_call(
+                "call_2",
+                "forge_tool_net_fetch",
+                serde_json::json!({"url": "https://docs.rs"}),
+            ))
+            .add_tool_call(fixture_call(
+                "call_3",
+                "forge_tool_fs_create",
+                serde_json::json!({"path": "/home/user/project/out.txt", "content": "hi"}),
+            ))
+            .add_tool_call(fixture_call(
+                "call_4",
+                "forge_tool_fs_patch",
+                serde_json::json!({"path": "/home/user/project/out.txt"}),
+            ))
+            .add_tool_call(fixture_call(
+                "call_5",
+                "forge_tool_fs_search",
+                serde_json::json!({"path": "/home/user/project", "regex": "hi"}),
+            ))
+            .add_tool_call(fixture_call(
+                "call_6",
+                "forge_tool_fs_read",
+                serde_json::json!({"path": "/home/user/project/out.txt"}),
+            ))
+            .add_tool_call(fixture_call(
+                "call_7",
+                "forge_tool_fs_remove",
+                serde_json::json!({"path": "/home/user/project/out.txt"}),
+            ))
+    }
+
+    #[tokio::test]
+    async fn test_read_only_calls_run_at_once_while_mutating_calls_keep_their_order() {
+        let services = Arc::new(MockService::new(vec![fixture_tool_calls()]));
+        let (tx, mut rx) = tokio::sync::mpsc::channel(64);
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            fixture_conversation(),
+            chrono::Local::now(),
+        )
+        .sender(Arc::new(tx));
+        orch.chat(fixture_event(Some("Write the output")))
+            .await
+            .unwrap();
+        drop(orch);
+
+        let actual = services
+            .calls()
+            .into_iter()
+            .map(|call| call.call_id.unwrap())
+            .collect::<Vec<_>>();
+        let expected = [
+            "call_1", "call_2", "call_3", "call_4", "call_5", "call_6", "call_7", "call_1",
+        ]
+        .map(ToolCallId::new);
+        assert_eq!(actual, expected);
+        assert_eq!(services.max_calls_in_flight(), 2);
+
+        // Every call starts before it ends and the results come in call order
+        let mut actual = Vec::new();
+        while let Some(response) = rx.recv().await {
+            match response.unwrap() {
+                ChatResponse::ToolCallStart(call) => {
+                    actual.push(format!("start {}", call.call_id.unwrap().as_str()))
+                }
+                ChatResponse::ToolCallEnd(result) => {
+                    actual.push(format!("end {}", result.call_id.unwrap().as_str()))
+                }
+                _ => {}
+            }
+        }
+        let expected = [
+            "start call_1",
+            "start call_2",
+            "end call_1",
+            "end call_2",
+            "start call_3",
+            "end call_3",
+            "start call_4",
+            "end call_4",
+            "start call_5",
+            "start call_6",
+            "end call_5",
+            "end call_6",
+            "start call_7",
+            "end call_7",
+            "start call_1",
+            "end call_1",
+        ]
+        .map(String::from);
+        assert_eq!(actual, expected);
+
+        // The results are sent back in the order of the calls
+        let actual = services.requests()[1]
+            .messages
+            .iter()
+            .filter_map(|message| match message {
+                ContextMessage::Tool(result) => result.call_id.clone(),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let expected = [
+            "call_1", "call_2", "call_3", "call_4", "call_5", "call_6", "call_7",
+        ]
+        .map(ToolCallId::new);
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_output_of_concurrent_calls_is_sent_in_call_order() {
+        let fixture_search = |id: &str| {
+            ToolCallFull::new(ToolName::new("forge_tool_fs_search"))
+                .call_id(ToolCallId::new(id))
+                .arguments(serde_json::json!({"path": "/home/user/project", "regex": id}))
+        };
+        let services = Arc::new(MockService::new(vec![
+            ChatCompletionMessage::assistant(Content::full(""))
+                .add_tool_call(fixture_search("call_1"))
+                .add_tool_call(fixture_search("call_2")),
+        ]));
+        let (tx, mut rx) = tokio::sync::mpsc::channel(64);
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            fixture_conversation(),
+            chrono::Local::now(),
+        )
+        .sender(Arc::new(tx));
+        orch.chat(fixture_event(Some("Search the project")))
+            .await
+            .unwrap();
+        drop(orch);
+
+        let mut actual = Vec::new();
+        while let Some(response) = rx.recv().await {
+            match response.unwrap() {
+                ChatResponse::ToolCallStart(call) => {
+                    actual.push(format!("start {}", call.call_id.unwrap().as_str()))
+                }
+                ChatResponse::Text { text, .. } if text.starts_with("search") => actual.push(text),
+                ChatResponse::ToolCallEnd(result) => {
+                    actual.push(format!("end {}", result.call_id.unwrap().as_str()))
+                }
+                _ => {}
+            }
+        }
+
+        let expected = [
+            "start call_1",
+            "start call_2",
+            "searching call_1",
+            "searched call_1",
+            "end call_1",
+            "searching call_2",
+            "searched call_2",
+            "end call_2",
+        ]
+        .map(String::from);
+        assert_eq!(actual, expected);
+        assert_eq!(services.max_calls_in_flight(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_tool_calls_can_be_disabled_for_the_agent() {
+        let services = Arc::new(MockService::new(vec![fixture_tool_calls()]));
+        let mut conversation = fixture_conversation();
+        conversation.agents[0].concurrent_tool_calls = Some(false);
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        );
+        orch.chat(fixture_event(Some("Write the output")))
+            .await
+            .unwrap();
+
+        let actual = services
+            .calls()
+            .into_iter()
+            .map(|call| call.call_id.unwrap())
+            .collect::<Vec<_>>();
+        let expected = [
+            "call_1", "call_2", "call_3", "call_4", "call_5", "call_6", "call_7", "call_1",
+        ]
+        .map(ToolCallId::new);
+        assert_eq!(actual, expected);
+        assert_eq!(services.max_calls_in_flight(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_plan_first_holds_mutating_tools_until_approved() {
+        let fixture_call = |id: &str, name: &str, arguments: Value| {
+            ToolCallFull::new(ToolName::new(name))
+                .call_id(ToolCallId::new(id))
+                .arguments(arguments)
+        };
+        let write = serde_json::json!({"path": "/home/user/project/out.txt", "content": "hi"});
+        let services =
+            Arc::new(
+                MockService::new(vec![
+                    ChatCompletionMessage::assistant(Content::full(""))
+                        .add_tool_call(fixture_call(
+                            "call_1",
+                            "forge_tool_fs_read",
+                            serde_json::json!({"path": "/home/user/project/Cargo.toml"}),
+                        ))
+                        .add_tool_call(fixture_call(
+                            "call_2",
+                            "forge_tool_fs_create",
+                            write.clone(),
+                        )),
+                    ChatCompletionMessage::assistant(Content::full(""))
+                        .add_tool_call(fixture_call("call_3", "forge_tool_fs_create", write)),
+                ])
+                .reviews(vec![PlanReview::Rejected("Keep the old file".to_string())]),
+            );
+        let mut conversation = fixture_conversation();
+        conversation.plan_first = true;
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        );
+        orch.chat(fixture_event(Some("Write the output")))
+            .await
+            .unwrap();
+
+        let actual = services
+            .calls()
+            .into_iter()
+            .map(|call| call.call_id.unwrap())
+            .collect::<Vec<_>>();
+        let expected = ["call_1", "call_3"].map(ToolCallId::new);
+        assert_eq!(actual[..2], expected);
+
+        let rejected = services.requests()[1]
+            .messages
+            .iter()
+            .find_map(|message| match message {
+                ContextMessage::Tool(result)
+                    if result.call_id == Some(ToolCallId::new("call_2")) =>
+                {
+                    Some(result.clone())
+                }
+                _ => None,
+            })
+            .unwrap();
+        assert!(rejected.is_error());
+        assert!(
+            rejected
+                .output
+                .as_str()
+                .unwrap()
+                .contains("Keep the old file")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_plan_first_instruction_is_given_once_per_conversation() {
+        let services = Arc::new(MockService::new(vec![]));
+        let mut conversation = fixture_conversation();
+        conversation.plan_first = true;
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        );
+        orch.chat(fixture_event(Some("Write the output")))
+            .await
+            .unwrap();
+        orch.chat(fixture_event(Some("Write it again")))
+            .await
+            .unwrap();
+
+        let actual = services
+            .requests()
+            .last()
+            .unwrap()
+            .messages
+            .iter()
+            .filter(|message| message.to_text().contains("<plan_first>"))
+            .count();
+        assert_eq!(actual, 1);
+    }
+
+    #[tokio::test]
+    async fn test_plan_review_skips_selected_tool_calls() {
+        let fixture_call = |id: &str, file: &str| {
+            ToolCallFull::new(ToolName::new("forge_tool_fs_create"))
+                .call_id(ToolCallId::new(id))
+                .arguments(serde_json::json!({
+                    "path": format!("/home/user/project/{file}"),
+                    "content": "hi"
+                }))
+        };
+        let services = Arc::new(
+            MockService::new(vec![
+                ChatCompletionMessage::assistant(Content::full(""))
+                    .add_tool_call(fixture_call("call_1", "a.txt"))
+                    .add_tool_call(fixture_call("call_2", "b.txt"))
+                    .add_tool_call(fixture_call("call_3", "c.txt")),
+            ])
+            .reviews(vec![PlanReview::Skipped(vec![1])]),
+        );
+        let mut conversation = fixture_conversation();
+        conversation.plan_first = true;
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        );
+        orch.chat(fixture_event(Some("Write the files")))
+            .await
+            .unwrap();
+
+        let actual = services
+            .calls()
+            .into_iter()
+            .map(|call| call.call_id.unwrap())
+            .collect::<Vec<_>>();
+        let expected = ["call_1", "call_3"].map(ToolCallId::new);
+        assert_eq!(actual[..2], expected);
+
+        let skipped = services.requests()[1]
+            .messages
+            .iter()
+            .find_map(|message| match message {
+                ContextMessage::Tool(result)
+                    if result.call_id == Some(ToolCallId::new("call_2")) =>
+                {
+                    Some(result.clone())
+                }
+                _ => None,
+            })
+            .unwrap();
+        assert!(!skipped.is_error());
+        assert!(
+            skipped
+                .output
+                .as_str()
+                .unwrap()
+                .contains("skipped by the user")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_tokens_is_sent_with_regular_turn() {
+        let services = Arc::new(MockService::new(vec![]));
+        let mut conversation = fixture_conversation();
+        conversation.set_max_tokens(Some(MaxTokens::new(1500).unwrap()));
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        );
+        orch.chat(fixture_event(Some("Explain the parser")))
+            .await
+            .unwrap();
+
+        let actual = services
+            .requests()
+            .iter()
+            .map(|request| request.max_tokens)
+            .collect::<Vec<_>>();
+        let expected = vec![Some(1500)];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_forced_tool_is_only_sent_with_first_request() {
+        let fixture = [
+            ToolChoice::Required,
+            ToolChoice::Call(ToolName::new("forge_tool_fs_read")),
+        ];
+
+        let mut actual = vec![];
+        for tool_choice in fixture {
+            let services = Arc::new(MockService::new(vec![fixture_tool_call(
+                "forge_tool_fs_read",
+            )]));
+            let mut conversation = fixture_conversation();
+            conversation.agents[0].tool_choice = Some(tool_choice);
+
+            let mut orch = Orchestrator::new(
+                services.clone(),
+                fixture_environment(),
+                conversation,
+                chrono::Local::now(),
+            );
+            orch.chat(fixture_event(Some("Plan the compiler")))
+                .await
+                .unwrap();
+
+            actual.push(
+                services
+                    .requests()
+                    .into_iter()
+                    .map(|request| request.tool_choice)
+                    .collect::<Vec<_>>(),
+            );
+        }
+
+        let expected = vec![
+            vec![Some(ToolChoice::Required), Some(ToolChoice::Required)],
+            vec![
+                Some(ToolChoice::Call(ToolName::new("forge_tool_fs_read"))),
+                None,
+            ],
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_agent_working_dir_scopes_cwd_and_file_listing() {
+        let services = Arc::new(MockService::new(vec![]));
+        let mut conversation = fixture_conversation();
+        conversation.agents[0].working_dir = Some(PathBuf::from("docs"));
+        conversation.agents[0].system_prompt = Some(Template::new("system"));
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        )
+        .files(
+            [
+                "README.md",
+                "docs/",
+                "docs/guide.md",
+                "docs/api/",
+                "docs/api/index.md",
+                "src/main.rs",
+            ]
+            .map(|path| File {
+                path: path.to_string(),
+                is_dir: path.ends_with('/'),
+                size: 0,
+                modified: None,
+            })
+            .to_vec(),
+        );
+        orch.chat(fixture_event(Some("Document the parser")))
+            .await
+            .unwrap();
+
+        let system_context = services.rendered().first().cloned().unwrap();
+        let actual = (
+            system_context["env"]["cwd"].clone(),
+            system_context["files"].clone(),
+        );
+        let expected = (
+            serde_json::json!("/home/user/project/docs"),
+            serde_json::json!(["api/", "api/index.md", "guide.md"]),
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_agent_working_dir_outside_the_project_is_rejected() {
+        let services = Arc::new(MockService::new(vec![]));
+        let mut conversation = fixture_conversation();
+        conversation.agents[0].working_dir = Some(PathBuf::from("docs/../.."));
+        conversation.agents[0].system_prompt = Some(Template::new("system"));
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        );
+        let actual = orch
+            .chat(fixture_event(Some("Document the parser")))
+            .await
+            .is_err();
+
+        assert!(actual);
+        assert_eq!(services.requests().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_project_rules_are_added_to_the_agent_rules() {
+        let services = Arc::new(MockService::new(vec![]));
+        let mut conversation = fixture_conversation();
+        conversation.agents[0].custom_rules = Some("Answer briefly".to_string());
+        conversation.agents[0].system_prompt = Some(Template::new("system"));
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        )
+        .project_rules("Run cargo test before committing");
+        orch.chat(fixture_event(Some("Fix the parser")))
+            .await
+            .unwrap();
+
+        let actual = services.rendered().first().cloned().unwrap()["custom_rules"].clone();
+        let expected = serde_json::json!("Answer briefly\n\nRun cargo test before committing");
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_event_context_precedes_the_prompt() {
+        let services = Arc::new(MockService::new(vec![]));
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            fixture_conversation(),
+            chrono::Local::now(),
+        );
+        orch.chat(
+            fixture_event(Some("Write the release notes")).context("Version 2.0 drops the v1 API"),
+        )
+        .await
+        .unwrap();
+
+        let actual = services.requests()[0]
+            .messages
+            .iter()
+            .filter(|message| message.has_role(Role::User))
+            .cloned()
+            .collect::<Vec<_>>();
+        let expected = vec![
+            ContextMessage::user(
+                Element::new("context").cdata("Version 2.0 drops the v1 API"),
+                Some(ModelId::new("test-model")),
+            ),
+            ContextMessage::user(
+                "\"Write the release notes\"",
+                Some(ModelId::new("test-model")),
+            ),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_stale_system_messages_are_replaced_by_current_agent_prompt() {
+        let services = Arc::new(MockService::new(vec![]));
+        let mut conversation = fixture_conversation();
+        conversation.agents[0].system_prompt = Some(Template::new("current system prompt"));
+        conversation.context = Some(
+            Context::default()
+                .add_message(ContextMessage::system("previous agent prompt"))
+                .add_message(ContextMessage::user("Plan it", None))
+                .add_message(ContextMessage::system("stale workflow prompt")),
+        );
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        );
+        orch.chat(fixture_event(Some("Do it"))).await.unwrap();
+
+        let actual = services.requests()[0]
+            .messages
+            .iter()
+            .filter(|message| message.has_role(Role::System))
+            .cloned()
+            .collect::<Vec<_>>();
+        let expected = vec![ContextMessage::system("current system prompt")];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_scratchpad_written_by_tool_is_sent_with_next_request() {
+        let fixture = "## Plan\n- [x] parse\n- [ ] emit";
+        let services = Arc::new(MockService::new(vec![
+            ChatCompletionMessage::assistant(Content::full("")).add_tool_call(
+                ToolCallFull::new(ToolName::new("forge_tool_scratchpad_write"))
+                    .call_id(ToolCallId::new("call_1"))
+                    .arguments(serde_json::json!({"content": fixture})),
+            ),
+            ChatCompletionMessage::assistant(Content::full("")).add_tool_call(
+                ToolCallFull::new(ToolName::new("forge_tool_scratchpad_read"))
+                    .call_id(ToolCallId::new("call_2"))
+                    .arguments(serde_json::json!({})),
+            ),
+        ]));
+        let mut conversation = fixture_conversation();
+        conversation.agents[0].system_prompt = Some(Template::new("system"));
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        );
+        orch.chat(fixture_event(Some("Plan the compiler")))
+            .await
+            .unwrap();
+
+        let actual = services
+            .system_contexts()
+            .into_iter()
+            .map(|system_context| system_context["scratchpad"].clone())
+            .collect::<Vec<_>>();
+        let expected = vec![serde_json::json!(""), serde_json::json!(fixture)];
+        assert_eq!(actual, expected);
+        assert_eq!(orch.get_conversation().scratchpad, fixture);
+
+        // Reading it back in a later response returns what was written
+        let requests = services.requests();
+        let read = requests[2]
+            .messages
+            .iter()
+            .filter_map(|message| match message {
+                ContextMessage::Tool(result) => Some(result.clone()),
+                _ => None,
+            })
+            .next_back()
+            .unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(read.output.values.iter().any(|value| matches!(
+            value,
+            ToolValue::Text(text) if text.contains(fixture)
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_scratchpad_is_rendered_into_the_single_system_message() {
+        let fixture = "Decided to keep the public API unchanged";
+        let services = Arc::new(MockService::new(vec![]));
+        let mut conversation = fixture_conversation();
+        conversation.scratchpad = fixture.to_string();
+        conversation.agents[0].system_prompt = Some(Template::new("system"));
+        // A compacted context only retains the summary of earlier messages
+        conversation.context = Some(Context::default().add_message(ContextMessage::user(
+            "Summary of the refactoring done so far",
+            None,
+        )));
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        );
+        orch.chat(fixture_event(None)).await.unwrap();
+
+        let actual = services.system_contexts()[0]["scratchpad"].clone();
+        let expected = serde_json::json!(fixture);
+        assert_eq!(actual, expected);
+        let system_messages = services.requests()[0]
+            .messages
+            .iter()
+            .filter(|message| message.has_role(Role::System))
+            .count();
+        assert_eq!(system_messages, 1);
+    }
+
+    #[tokio::test]
+    async fn test_scratchpad_is_sent_without_a_system_prompt() {
+        let fixture = "Decided to keep the public API unchanged";
+        let services = Arc::new(MockService::new(vec![]));
+        let mut conversation = fixture_conversation();
+        conversation.scratchpad = fixture.to_string();
+        conversation.agents[0].system_prompt = None;
+
+        let mut orch = Orchestrator::new(
+            services.clone(),
+            fixture_environment(),
+            conversation,
+            chrono::Local::now(),
+        );
+        orch.chat(fixture_event(None)).await.unwrap();
+
+        let actual = services.requests()[0]
+            .messages
+            .iter()
+            .filter(|message| message.has_role(Role::System))
+            .cloned()
+            .collect::<Vec<_>>();
+        let expected = vec![ContextMessage::system(format!(
+            "<scratchpad><![CDATA[{fixture}]]></scratchpad>"
+        ))];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_turn_emits_spans_for_requests_and_tool_calls() {
+        use opentelemetry::trace::TracerProvider as _;
+        use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        let _guard = tracing::subscriber::set_default(subscriber);
+        let fixture_usage = Usage {
+            prompt_tokens: TokenCount::Actual(120),
+            completion_tokens: TokenCount::Actual(30),
+            total_tokens: TokenCount::Actual(150),
+            ..Default::default()
+        };
+        let services = Arc::new(MockService::new(vec![
+            fixture_tool_call("forge_tool_fs_read").usage(fixture_usage),
+        ]));
+
+        let mut orch = Orchestrator::new(
+            services,
+            fixture_environment(),
+            fixture_conversation(),
+            chrono::Local::now(),
+        );
+        orch.chat(fixture_event(Some("Read the manifest")))
+            .await
+            .unwrap();
+        provider.force_flush().unwrap();
+
+        let keys = [
+            "model",
+            "prompt_tokens",
+            "completion_tokens",
+            "tool_name",
+            "status",
+        ];
+        let actual = exporter
+            .get_finished_spans()
+            .unwrap()
+            .into_iter()
+            .map(|span| {
+                let mut attributes = span
+                    .attributes
+                    .iter()
+                    .filter(|attribute| keys.contains(&attribute.key.as_str()))
+                    .map(|attribute| format!("{}={}", attribute.key, attribute.value))
+                    .collect::<Vec<_>>();
+                attributes.sort();
+                (span.name.to_string(), attributes)
+            })
+            .collect::<Vec<_>>();
+
+        let expected = [
+            (
+                "provider_request",
+                vec![
+                    "completion_tokens=30",
+                    "model=test-model",
+                    "prompt_tokens=120",
+                ],
+            ),
+            (
+                "tool_call",
+                vec!["status=success", "tool_name=forge_tool_fs_read"],
+            ),
+            (
+                "provider_request",
+                vec!["completion_tokens=0", "model=test-model", "prompt_tokens=0"],
+            ),
+            (
+                "tool_call",
+                vec!["status=success", "tool_name=forge_tool_attempt_completion"],
+            ),
+            ("turn", vec!["model=test-model"]),
+        ]
+        .map(|(name, attributes)| {
+            (
+                name.to_string(),
+                attributes.into_iter().map(String::from).collect::<Vec<_>>(),
+            )
+        })
+        .to_vec();
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,216 @@
+use std::path::PathBuf;
+
+use crate::FsReadService;
+
+/// Rules of a layer, along with the file they were read from
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerRules {
+    pub path: PathBuf,
+    pub rules: String,
+}
+
+/// Reads the rules of every layer from the first of its files that exists
+/// and isn't empty, and concatenates them in the order of the layers. Layers
+/// without rules are skipped. They are read for every chat, so edits apply
+/// from the next message
+pub async fn read_project_rules<S: FsReadService>(
+    services: &S,
+    layers: impl IntoIterator<Item = Vec<PathBuf>>,
+) -> Option<String> {
+    let mut rules = Vec::new();
+    for paths in layers {
+        if let Some(layer) = find_layer_rules(services, paths).await {
+            rules.push(layer.rules);
+        }
+    }
+    (!rules.is_empty()).then(|| rules.join("\n\n"))
+}
+
+/// Returns the rules of the first of the files of a layer that exists and
+/// isn't empty, which is the file the rules of the layer come from. The whole
+/// file is read, whatever the read limit of the tools
+pub async fn find_layer_rules<S: FsReadService>(
+    services: &S,
+    paths: Vec<PathBuf>,
+) -> Option<LayerRules> {
+    for path in paths {
+        let Ok(rules) = services.read_all(path.display().to_string()).await else {
+            continue;
+        };
+        let rules = rules.trim();
+        if !rules.is_empty() {
+            return Some(LayerRules { path, rules: rules.to_string() });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{Content, ReadOutput};
+
+    /// Lines returned by a read without a range, as the read tool limits them
+    const MAX_READ_SIZE: usize = 500;
+
+    /// Files kept in memory, keyed by path
+    #[derive(Default)]
+    struct MockFiles(Mutex<HashMap<String, String>>);
+
+    impl MockFiles {
+        fn write(&self, path: &str, content: &str) {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(path.to_string(), content.to_string());
+        }
+
+        fn get(&self, path: &str) -> anyhow::Result<String> {
+            self.0
+                .lock()
+                .unwrap()
+                .get(path)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("File not found: {path}"))
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FsReadService for MockFiles {
+        async fn read(
+            &self,
+            path: String,
+            _start_line: Option<u64>,
+            _end_line: Option<u64>,
+        ) -> anyhow::Result<ReadOutput> {
+            let content = self.get(&path)?;
+            let total_lines = content.lines().count() as u64;
+            let content = content
+                .lines()
+                .take(MAX_READ_SIZE)
+                .collect::<Vec<_>>()
+                .join("\n");
+            Ok(ReadOutput {
+                content: Content::File(content),
+                start_line: 1,
+                end_line: total_lines.min(MAX_READ_SIZE as u64),
+                total_lines,
+            })
+        }
+
+        async fn read_all(&self, path: String) -> anyhow::Result<String> {
+            self.get(&path)
+        }
+
+        async fn read_revision(
+            &self,
+            path: String,
+            _revision: String,
+            start_line: Option<u64>,
+            end_line: Option<u64>,
+        ) -> anyhow::Result<ReadOutput> {
+            self.read(path, start_line, end_line).await
+        }
+    }
+
+    fn fixture_layers() -> Vec<Vec<PathBuf>> {
+        vec![
+            vec![PathBuf::from("/home/user/.forge/rules.md")],
+            vec![
+                PathBuf::from("/home/user/project/.forge/rules.md"),
+                PathBuf::from("/home/user/project/AGENTS.md"),
+            ],
+            vec![PathBuf::from("/home/user/project/.forge/rules.local.md")],
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_forge_rules_take_precedence_over_agents_file() {
+        let fixture = MockFiles::default();
+        fixture.write("/home/user/project/AGENTS.md", "Use tabs");
+        fixture.write("/home/user/project/.forge/rules.md", "\nUse spaces\n");
+
+        let actual = read_project_rules(&fixture, fixture_layers()).await;
+
+        let expected = Some("Use spaces".to_string());
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_layers_are_concatenated_in_precedence_order() {
+        let fixture = MockFiles::default();
+        fixture.write(
+            "/home/user/project/.forge/rules.local.md",
+            "Skip the slow tests",
+        );
+        fixture.write("/home/user/project/AGENTS.md", "Run all tests");
+        fixture.write("/home/user/.forge/rules.md", "Answer briefly");
+
+        let actual = read_project_rules(&fixture, fixture_layers()).await;
+
+        let expected = Some("Answer briefly\n\nRun all tests\n\nSkip the slow tests".to_string());
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_missing_and_empty_layers_are_skipped() {
+        let fixture = MockFiles::default();
+        fixture.write("/home/user/.forge/rules.md", "Answer briefly");
+        fixture.write("/home/user/project/.forge/rules.local.md", "  \n");
+
+        let actual = read_project_rules(&fixture, fixture_layers()).await;
+
+        let expected = Some("Answer briefly".to_string());
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_empty_file_is_passed_over_for_the_next_one() {
+        let fixture = MockFiles::default();
+        fixture.write("/home/user/project/.forge/rules.md", "\n");
+        fixture.write("/home/user/project/AGENTS.md", "Use tabs");
+
+        let actual = find_layer_rules(&fixture, fixture_layers().remove(1)).await;
+
+        let expected = Some(LayerRules {
+            path: PathBuf::from("/home/user/project/AGENTS.md"),
+            rules: "Use tabs".to_string(),
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_rules_are_reloaded_after_an_edit() {
+        let fixture = MockFiles::default();
+        let before = read_project_rules(&fixture, fixture_layers()).await;
+
+        fixture.write(
+            "/home/user/project/AGENTS.md",
+            "Run cargo test before committing",
+        );
+        let actual = (before, read_project_rules(&fixture, fixture_layers()).await);
+
+        let expected = (None, Some("Run cargo test before committing".to_string()));
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_rules_past_the_read_limit_are_read_whole() {
+        let fixture = MockFiles::default();
+        let rules = (1..=600)
+            .map(|rule| format!("- Rule {rule}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        fixture.write("/home/user/project/AGENTS.md", &rules);
+
+        let actual = read_project_rules(&fixture, fixture_layers()).await;
+
+        let expected = Some(rules);
+        assert_eq!(actual, expected);
+    }
+}
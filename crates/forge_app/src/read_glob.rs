@@ -0,0 +1,258 @@
+use std::path::Path;
+
+use anyhow::Context as _;
+use forge_domain::File;
+use glob::{MatchOptions, Pattern};
+
+use crate::{Content, FsReadService, ReadOutput};
+
+/// Number of files read unless the tool call asks for another limit
+pub const DEFAULT_MAX_FILES: usize = 20;
+
+/// Files walked at most to find the ones matching the pattern, so that a
+/// pattern over a large tree doesn't walk all of it
+pub const MAX_WALKED_FILES: usize = 10_000;
+
+/// A file matching the pattern along with what was read from it
+#[derive(Debug)]
+pub struct GlobFile {
+    pub path: String,
+    pub output: ReadOutput,
+}
+
+/// Files read for a glob pattern
+#[derive(Debug, Default)]
+pub struct GlobReadOutput {
+    pub files: Vec<GlobFile>,
+    /// Matching files that couldn't be read, because they're binary or too
+    /// large
+    pub skipped: Vec<String>,
+    /// Matching files left out once the limit on the number of files or on
+    /// the total size was reached
+    pub omitted: Vec<String>,
+    /// Whether the walk stopped at `MAX_WALKED_FILES` files, so that files
+    /// past them were never matched against the pattern
+    pub is_walk_truncated: bool,
+}
+
+impl GlobReadOutput {
+    /// Number of files that matched the pattern
+    pub fn total_matches(&self) -> usize {
+        self.files.len() + self.skipped.len() + self.omitted.len()
+    }
+}
+
+/// Returns the absolute paths of the files matching the pattern, sorted. The
+/// paths of the walked files are expected to be relative to `root`.
+pub fn matching_files(root: &Path, pattern: &str, files: Vec<File>) -> anyhow::Result<Vec<String>> {
+    let glob = Pattern::new(pattern).with_context(|| format!("Invalid glob pattern: {pattern}"))?;
+    // `*` stays within a directory, `**` is needed to match nested files
+    let options = MatchOptions { require_literal_separator: true, ..MatchOptions::new() };
+
+    let mut paths = files
+        .into_iter()
+        .filter(|file| !file.is_dir && glob.matches_with(&file.path, options))
+        .map(|file| root.join(file.path).display().to_string())
+        .collect::<Vec<_>>();
+    paths.sort();
+    Ok(paths)
+}
+
+/// Reads the files in order, each with the same limits as a single read,
+/// until `max_files` files were read or the next one would take the total
+/// size of their content beyond `max_bytes`
+pub async fn read_files<S: FsReadService>(
+    services: &S,
+    paths: Vec<String>,
+    max_files: usize,
+    max_bytes: usize,
+) -> GlobReadOutput {
+    let mut output = GlobReadOutput::default();
+    let mut total_bytes = 0;
+    let mut paths = paths.into_iter();
+
+    for path in paths.by_ref() {
+        if output.files.len() >= max_files {
+            output.omitted.push(path);
+            break;
+        }
+
+        match services.read(path.clone(), None, None).await {
+            Ok(read) => {
+                let Content::File(content) = &read.content;
+                if total_bytes + content.len() > max_bytes {
+                    output.omitted.push(path);
+                    break;
+                }
+                total_bytes += content.len();
+                output.files.push(GlobFile { path, output: read });
+            }
+            Err(error) => {
+                tracing::debug!(path = %path, error = ?error, "Skipping file matched by glob");
+                output.skipped.push(path);
+            }
+        }
+    }
+
+    output.omitted.extend(paths);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Serves the content of the files it was given, any other path is
+    /// rejected like a binary file would be
+    struct MockReader(HashMap<String, String>);
+
+    impl MockReader {
+        fn new(files: &[(&str, &str)]) -> Self {
+            Self(
+                files
+                    .iter()
+                    .map(|(path, content)| (path.to_string(), content.to_string()))
+                    .collect(),
+            )
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FsReadService for MockReader {
+        async fn read(
+            &self,
+            path: String,
+            _start_line: Option<u64>,
+            _end_line: Option<u64>,
+        ) -> anyhow::Result<ReadOutput> {
+            let content = self
+                .0
+                .get(&path)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Binary files are not supported"))?;
+            let total_lines = content.lines().count() as u64;
+            Ok(ReadOutput {
+                content: Content::File(content),
+                start_line: 1,
+                end_line: total_lines,
+                total_lines,
+            })
+        }
+
+        async fn read_all(&self, _path: String) -> anyhow::Result<String> {
+            unimplemented!()
+        }
+
+        async fn read_revision(
+            &self,
+            _path: String,
+            _revision: String,
+            _start_line: Option<u64>,
+            _end_line: Option<u64>,
+        ) -> anyhow::Result<ReadOutput> {
+            unimplemented!()
+        }
+    }
+
+    fn fixture_files() -> Vec<File> {
+        [
+            ("README.md", false),
+            ("src/", true),
+            ("src/b.rs", false),
+            ("src/a.rs", false),
+            ("src/c.rs", false),
+            ("src/logo.png", false),
+            ("src/nested/", true),
+            ("src/nested/d.rs", false),
+        ]
+        .map(|(path, is_dir)| File { path: path.to_string(), is_dir, size: 0, modified: None })
+        .to_vec()
+    }
+
+    fn read_paths(output: &GlobReadOutput) -> Vec<&str> {
+        output.files.iter().map(|file| file.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_matching_files_stay_within_directory() {
+        let root = Path::new("/project");
+
+        let actual = [
+            matching_files(root, "src/*.rs", fixture_files()).unwrap(),
+            matching_files(root, "**/*.rs", fixture_files()).unwrap(),
+        ];
+
+        let expected = [
+            vec![
+                "/project/src/a.rs",
+                "/project/src/b.rs",
+                "/project/src/c.rs",
+            ],
+            vec![
+                "/project/src/a.rs",
+                "/project/src/b.rs",
+                "/project/src/c.rs",
+                "/project/src/nested/d.rs",
+            ],
+        ]
+        .map(|paths| paths.into_iter().map(String::from).collect::<Vec<_>>());
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_read_files_returns_matches_up_to_max_files() {
+        let services = MockReader::new(&[
+            ("/project/src/a.rs", "fn a() {}"),
+            ("/project/src/b.rs", "fn b() {}"),
+            ("/project/src/c.rs", "fn c() {}"),
+        ]);
+        let fixture = matching_files(Path::new("/project"), "src/*", fixture_files()).unwrap();
+
+        let all = read_files(&services, fixture.clone(), DEFAULT_MAX_FILES, 1024).await;
+        let bounded = read_files(&services, fixture, 2, 1024).await;
+
+        // The png is skipped like any file that can't be read as text
+        assert_eq!(
+            read_paths(&all),
+            vec![
+                "/project/src/a.rs",
+                "/project/src/b.rs",
+                "/project/src/c.rs"
+            ]
+        );
+        assert_eq!(all.skipped, vec!["/project/src/logo.png"]);
+        assert!(all.omitted.is_empty());
+        assert_eq!(
+            read_paths(&bounded),
+            vec!["/project/src/a.rs", "/project/src/b.rs"]
+        );
+        assert_eq!(
+            bounded.omitted,
+            vec!["/project/src/c.rs", "/project/src/logo.png"]
+        );
+        assert_eq!(bounded.total_matches(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_read_files_stops_at_aggregate_byte_cap() {
+        let services = MockReader::new(&[
+            ("/project/src/a.rs", &"a".repeat(40)),
+            ("/project/src/b.rs", &"b".repeat(40)),
+            ("/project/src/c.rs", &"c".repeat(10)),
+        ]);
+        let fixture = matching_files(Path::new("/project"), "src/*.rs", fixture_files()).unwrap();
+
+        let actual = read_files(&services, fixture, DEFAULT_MAX_FILES, 64).await;
+
+        // Files are never cut in the middle, and none are read past the cap
+        assert_eq!(read_paths(&actual), vec!["/project/src/a.rs"]);
+        assert_eq!(
+            actual.omitted,
+            vec!["/project/src/b.rs", "/project/src/c.rs"]
+        );
+    }
+}
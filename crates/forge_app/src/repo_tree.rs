@@ -0,0 +1,176 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use forge_domain::File;
+
+/// Depth the tree is expanded to unless the tool call asks for another one
+pub const DEFAULT_MAX_DEPTH: usize = 3;
+
+/// Number of entries listed unless the tool call asks for another limit
+pub const DEFAULT_MAX_ENTRIES: usize = 200;
+
+/// Directory structure of the workspace, built from the files found by the
+/// walker
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DirTree {
+    dirs: BTreeMap<String, DirTree>,
+    files: BTreeSet<String>,
+}
+
+/// Lines of a rendered tree and the number of entries left out of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedTree {
+    pub lines: Vec<String>,
+    pub hidden_entries: usize,
+}
+
+impl DirTree {
+    /// Builds the tree from paths relative to the workspace root, directories
+    /// are expected to end with `/`
+    pub fn from_files(files: impl IntoIterator<Item = File>) -> Self {
+        let mut tree = Self::default();
+        for file in files {
+            let mut components = file
+                .path
+                .split('/')
+                .filter(|component| !component.is_empty())
+                .collect::<Vec<_>>();
+            let name = if file.is_dir { None } else { components.pop() };
+
+            let dir = components.into_iter().fold(&mut tree, |dir, component| {
+                dir.dirs.entry(component.to_string()).or_default()
+            });
+            if let Some(name) = name {
+                dir.files.insert(name.to_string());
+            }
+        }
+        tree
+    }
+
+    /// Number of files in this directory and all of its subdirectories
+    pub fn file_count(&self) -> usize {
+        self.files.len() + self.dirs.values().map(Self::file_count).sum::<usize>()
+    }
+
+    /// Number of entries the tree would show when expanded up to `max_depth`
+    fn entry_count(&self, max_depth: usize) -> usize {
+        if max_depth == 0 {
+            return 0;
+        }
+        self.files.len()
+            + self
+                .dirs
+                .values()
+                .map(|dir| 1 + dir.entry_count(max_depth - 1))
+                .sum::<usize>()
+    }
+
+    /// Renders one line per entry, directories first and indented by their
+    /// depth. Directories below `max_depth` are shown with their file count
+    /// but not expanded, and rendering stops after `max_entries` lines.
+    pub fn render(&self, max_depth: usize, max_entries: usize) -> RenderedTree {
+        let mut lines = Vec::new();
+        self.render_into(0, max_depth, max_entries, &mut lines);
+        let hidden_entries = self.entry_count(max_depth) - lines.len();
+        RenderedTree { lines, hidden_entries }
+    }
+
+    fn render_into(
+        &self,
+        depth: usize,
+        max_depth: usize,
+        max_entries: usize,
+        lines: &mut Vec<String>,
+    ) {
+        if depth >= max_depth {
+            return;
+        }
+
+        let indent = "  ".repeat(depth);
+        for (name, dir) in &self.dirs {
+            if lines.len() >= max_entries {
+                return;
+            }
+            let count = dir.file_count();
+            let noun = if count == 1 { "file" } else { "files" };
+            lines.push(format!("{indent}{name}/ ({count} {noun})"));
+            dir.render_into(depth + 1, max_depth, max_entries, lines);
+        }
+        for name in &self.files {
+            if lines.len() >= max_entries {
+                return;
+            }
+            lines.push(format!("{indent}{name}"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture_tree() -> DirTree {
+        DirTree::from_files(
+            [
+                ("/", true),
+                ("Cargo.toml", false),
+                ("README.md", false),
+                ("crates/", true),
+                ("crates/forge_app/", true),
+                ("crates/forge_app/Cargo.toml", false),
+                ("crates/forge_app/src/", true),
+                ("crates/forge_app/src/lib.rs", false),
+                ("crates/forge_app/src/orch.rs", false),
+                ("crates/forge_domain/", true),
+                ("crates/forge_domain/src/", true),
+                ("crates/forge_domain/src/lib.rs", false),
+                ("docs/", true),
+            ]
+            .map(|(path, is_dir)| File {
+                path: path.to_string(),
+                is_dir,
+                size: 0,
+                modified: None,
+            }),
+        )
+    }
+
+    #[test]
+    fn test_render_limits_depth() {
+        let fixture = fixture_tree();
+
+        let actual = fixture.render(2, 100);
+
+        let expected = RenderedTree {
+            lines: vec![
+                "crates/ (4 files)".to_string(),
+                "  forge_app/ (3 files)".to_string(),
+                "  forge_domain/ (1 file)".to_string(),
+                "docs/ (0 files)".to_string(),
+                "Cargo.toml".to_string(),
+                "README.md".to_string(),
+            ],
+            hidden_entries: 0,
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_render_caps_entries() {
+        let fixture = fixture_tree();
+
+        let actual = fixture.render(usize::MAX, 4);
+
+        let expected = RenderedTree {
+            lines: vec![
+                "crates/ (4 files)".to_string(),
+                "  forge_app/ (3 files)".to_string(),
+                "    src/ (2 files)".to_string(),
+                "      lib.rs".to_string(),
+            ],
+            hidden_entries: 8,
+        };
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Bounds the provider requests in flight. Clones share the permits, so the
+/// orchestrators of sub-agents, which run on the same services, count
+/// against the same limit as their parent.
+#[derive(Clone, Debug, Default)]
+pub struct RequestLimit(Option<Arc<Semaphore>>);
+
+impl RequestLimit {
+    /// Creates a limit of `max` requests in flight, `None` meaning requests
+    /// aren't bounded
+    pub fn new(max: Option<usize>) -> Self {
+        Self(max.map(|max| Arc::new(Semaphore::new(max))))
+    }
+
+    /// Waits until fewer than the maximum requests are in flight. The request
+    /// is counted until the returned permit is dropped.
+    pub async fn acquire(&self) -> anyhow::Result<Option<OwnedSemaphorePermit>> {
+        match self.0.as_ref() {
+            Some(permits) => Ok(Some(permits.clone().acquire_owned().await?)),
+            None => Ok(None),
+        }
+    }
+}
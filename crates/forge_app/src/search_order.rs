@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use forge_domain::SearchOrder;
+
+use crate::Match;
+
+/// Reorders the matches of a search by file. Sorting is stable, so the
+/// matches within a file keep the order they were found in.
+pub fn order_matches(mut matches: Vec<Match>, order: SearchOrder) -> Vec<Match> {
+    match order {
+        SearchOrder::PathAsc => matches.sort_by(|a, b| a.path.cmp(&b.path)),
+        SearchOrder::PathDesc => matches.sort_by(|a, b| b.path.cmp(&a.path)),
+        SearchOrder::MatchCount => {
+            let mut counts = HashMap::<String, usize>::new();
+            for found in &matches {
+                *counts.entry(found.path.clone()).or_default() += 1;
+            }
+            // Files with as many matches stay in the order they were first found
+            let mut first_seen = HashMap::<String, usize>::new();
+            for (index, found) in matches.iter().enumerate() {
+                first_seen.entry(found.path.clone()).or_insert(index);
+            }
+            matches.sort_by_key(|found| {
+                (
+                    std::cmp::Reverse(counts[&found.path]),
+                    first_seen[&found.path],
+                )
+            });
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::MatchResult;
+
+    fn fixture_matches() -> Vec<Match> {
+        [
+            ("src/b.rs", 1),
+            ("src/a.rs", 1),
+            ("src/c.rs", 1),
+            ("src/c.rs", 2),
+            ("src/a.rs", 2),
+            ("src/a.rs", 3),
+        ]
+        .into_iter()
+        .map(|(path, line_number)| Match {
+            path: path.to_string(),
+            result: Some(MatchResult::Found { line_number, line: "needle".to_string() }),
+        })
+        .collect()
+    }
+
+    fn positions(matches: &[Match]) -> Vec<(&str, usize)> {
+        matches
+            .iter()
+            .map(|found| match &found.result {
+                Some(MatchResult::Found { line_number, .. }) => (found.path.as_str(), *line_number),
+                _ => (found.path.as_str(), 0),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_order_matches_by_path() {
+        let actual = order_matches(fixture_matches(), SearchOrder::PathAsc);
+
+        let expected = vec![
+            ("src/a.rs", 1),
+            ("src/a.rs", 2),
+            ("src/a.rs", 3),
+            ("src/b.rs", 1),
+            ("src/c.rs", 1),
+            ("src/c.rs", 2),
+        ];
+        assert_eq!(positions(&actual), expected);
+    }
+
+    #[test]
+    fn test_order_matches_by_match_count() {
+        let actual = order_matches(fixture_matches(), SearchOrder::MatchCount);
+
+        let expected = vec![
+            ("src/a.rs", 1),
+            ("src/a.rs", 2),
+            ("src/a.rs", 3),
+            ("src/c.rs", 1),
+            ("src/c.rs", 2),
+            ("src/b.rs", 1),
+        ];
+        assert_eq!(positions(&actual), expected);
+    }
+}
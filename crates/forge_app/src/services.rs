@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use forge_domain::{
@@ -8,7 +9,7 @@ use forge_domain::{
 use merge::Merge;
 
 use crate::user::User;
-use crate::{AppConfig, InitAuth, LoginInfo, Walker};
+use crate::{AppConfig, InitAuth, LoginInfo, RequestLimit, Toolchain, ToolchainCache, Walker};
 
 #[derive(Debug)]
 pub struct ShellOutput {
@@ -53,15 +54,27 @@ pub enum MatchResult {
     Found { line_number: usize, line: String },
 }
 
-#[derive(Debug)]
+/// Request of a fetch, a plain GET of the URL unless a method, headers or a
+/// body are given
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FetchRequest {
+    pub url: String,
+    pub method: Option<String>,
+    pub headers: BTreeMap<String, String>,
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone)]
 pub struct HttpResponse {
     pub content: String,
     pub code: u16,
     pub context: ResponseContext,
     pub content_type: String,
+    /// Whether the response was fetched earlier and reused
+    pub cached: bool,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ResponseContext {
     Parsed,
     Raw,
@@ -220,6 +233,18 @@ pub trait FsReadService: Send + Sync {
         start_line: Option<u64>,
         end_line: Option<u64>,
     ) -> anyhow::Result<ReadOutput>;
+
+    /// Reads the whole file at the specified path, however many lines it has.
+    async fn read_all(&self, path: String) -> anyhow::Result<String>;
+
+    /// Reads the file at the specified path as it was at the git revision.
+    async fn read_revision(
+        &self,
+        path: String,
+        revision: String,
+        start_line: Option<u64>,
+        end_line: Option<u64>,
+    ) -> anyhow::Result<ReadOutput>;
 }
 
 #[async_trait::async_trait]
@@ -248,6 +273,14 @@ pub trait FollowUpService: Send + Sync {
         options: Vec<String>,
         multiple: Option<bool>,
     ) -> anyhow::Result<Option<String>>;
+
+    /// Asks the user to pick any number of the options, returning the
+    /// positions of the picked ones
+    async fn select_positions(
+        &self,
+        question: String,
+        options: Vec<String>,
+    ) -> anyhow::Result<Option<Vec<usize>>>;
 }
 
 #[async_trait::async_trait]
@@ -261,8 +294,14 @@ pub trait FsUndoService: Send + Sync {
 
 #[async_trait::async_trait]
 pub trait NetFetchService: Send + Sync {
-    /// Fetches content from a URL and returns it as a string.
-    async fn fetch(&self, url: String, raw: Option<bool>) -> anyhow::Result<HttpResponse>;
+    /// Fetches content from a URL and returns it as a string. The response
+    /// of a GET fetched earlier is reused unless `no_cache` is set.
+    async fn fetch(
+        &self,
+        request: FetchRequest,
+        raw: Option<bool>,
+        no_cache: Option<bool>,
+    ) -> anyhow::Result<HttpResponse>;
 }
 
 #[async_trait::async_trait]
@@ -274,6 +313,23 @@ pub trait ShellService: Send + Sync {
         cwd: PathBuf,
         keep_ansi: bool,
     ) -> anyhow::Result<ShellOutput>;
+
+    /// Executes a shell command with the input on its stdin, capturing the
+    /// output without ANSI codes unless they are kept
+    async fn pipe(
+        &self,
+        command: String,
+        cwd: PathBuf,
+        input: String,
+        keep_ansi: bool,
+    ) -> anyhow::Result<ShellOutput>;
+}
+
+#[async_trait::async_trait]
+pub trait ToolchainService: Send + Sync {
+    /// Returns the toolchains installed for the directory, they are only
+    /// detected once per directory
+    async fn toolchains(&self, cwd: &Path) -> Vec<Toolchain>;
 }
 
 #[async_trait::async_trait]
@@ -340,6 +396,8 @@ pub trait Services: Send + Sync + 'static + Clone {
     fn auth_service(&self) -> &Self::AuthService;
     fn app_config_service(&self) -> &Self::AppConfigService;
     fn provider_registry(&self) -> &Self::ProviderRegistry;
+    fn request_limit(&self) -> &RequestLimit;
+    fn toolchain_cache(&self) -> &ToolchainCache;
 }
 
 #[async_trait::async_trait]
@@ -500,6 +558,22 @@ impl<I: Services> FsReadService for I {
             .read(path, start_line, end_line)
             .await
     }
+
+    async fn read_all(&self, path: String) -> anyhow::Result<String> {
+        self.fs_read_service().read_all(path).await
+    }
+
+    async fn read_revision(
+        &self,
+        path: String,
+        revision: String,
+        start_line: Option<u64>,
+        end_line: Option<u64>,
+    ) -> anyhow::Result<ReadOutput> {
+        self.fs_read_service()
+            .read_revision(path, revision, start_line, end_line)
+            .await
+    }
 }
 
 #[async_trait::async_trait]
@@ -535,6 +609,16 @@ impl<I: Services> FollowUpService for I {
             .follow_up(question, options, multiple)
             .await
     }
+
+    async fn select_positions(
+        &self,
+        question: String,
+        options: Vec<String>,
+    ) -> anyhow::Result<Option<Vec<usize>>> {
+        self.follow_up_service()
+            .select_positions(question, options)
+            .await
+    }
 }
 
 #[async_trait::async_trait]
@@ -546,8 +630,13 @@ impl<I: Services> FsUndoService for I {
 
 #[async_trait::async_trait]
 impl<I: Services> NetFetchService for I {
-    async fn fetch(&self, url: String, raw: Option<bool>) -> anyhow::Result<HttpResponse> {
-        self.net_fetch_service().fetch(url, raw).await
+    async fn fetch(
+        &self,
+        request: FetchRequest,
+        raw: Option<bool>,
+        no_cache: Option<bool>,
+    ) -> anyhow::Result<HttpResponse> {
+        self.net_fetch_service().fetch(request, raw, no_cache).await
     }
 }
 
@@ -561,6 +650,25 @@ impl<I: Services> ShellService for I {
     ) -> anyhow::Result<ShellOutput> {
         self.shell_service().execute(command, cwd, keep_ansi).await
     }
+
+    async fn pipe(
+        &self,
+        command: String,
+        cwd: PathBuf,
+        input: String,
+        keep_ansi: bool,
+    ) -> anyhow::Result<ShellOutput> {
+        self.shell_service()
+            .pipe(command, cwd, input, keep_ansi)
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: Services> ToolchainService for I {
+    async fn toolchains(&self, cwd: &Path) -> Vec<Toolchain> {
+        self.toolchain_cache().get(self, cwd).await
+    }
 }
 
 impl<I: Services> EnvironmentService for I {
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use forge_domain::Environment;
+use forge_template::Element;
+use tokio::sync::Mutex;
+
+use crate::services::ShellService;
+
+/// Toolchains looked for, along with the command printing their version
+const TOOLCHAINS: &[(&str, &str)] = &[
+    ("rustc", "rustc --version"),
+    ("cargo", "cargo --version"),
+    ("node", "node --version"),
+    ("npm", "npm --version"),
+    ("python", "python3 --version"),
+    ("go", "go version"),
+    ("java", "java -version"),
+];
+
+/// A toolchain installed on the machine
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toolchain {
+    pub name: String,
+    pub version: String,
+}
+
+/// Facts about the environment the tools run in
+#[derive(Debug, Clone, PartialEq)]
+pub struct SystemFacts {
+    pub os: String,
+    pub arch: String,
+    pub shell: String,
+    pub cwd: String,
+    pub toolchains: Vec<Toolchain>,
+}
+
+impl SystemFacts {
+    pub fn new(env: &Environment, toolchains: Vec<Toolchain>) -> Self {
+        Self {
+            os: env.os.clone(),
+            arch: env.arch.clone(),
+            shell: env.shell.clone(),
+            cwd: env.cwd.display().to_string(),
+            toolchains,
+        }
+    }
+
+    pub fn to_element(&self) -> Element {
+        Element::new("environment")
+            .attr("os", &self.os)
+            .attr("arch", &self.arch)
+            .attr("shell", &self.shell)
+            .attr("cwd", &self.cwd)
+            .append(self.toolchains.iter().map(|toolchain| {
+                Element::new("toolchain")
+                    .attr("name", &toolchain.name)
+                    .attr("version", &toolchain.version)
+            }))
+    }
+}
+
+/// Toolchains detected so far, keyed by the directory they were detected
+/// in, since version managers like rustup or nvm pick them per directory.
+/// Clones share the detections.
+#[derive(Clone, Debug, Default)]
+pub struct ToolchainCache(Arc<Mutex<HashMap<PathBuf, Vec<Toolchain>>>>);
+
+impl ToolchainCache {
+    /// Returns the toolchains installed for the directory, detecting them on
+    /// the first call for it only
+    pub async fn get<S: ShellService>(&self, services: &S, cwd: &Path) -> Vec<Toolchain> {
+        let mut detected = self.0.lock().await;
+        if let Some(toolchains) = detected.get(cwd) {
+            return toolchains.clone();
+        }
+        let toolchains = detect_toolchains(services, cwd).await;
+        detected.insert(cwd.to_path_buf(), toolchains.clone());
+        toolchains
+    }
+}
+
+/// Runs the version command of every known toolchain, those that can't be
+/// run are left out
+pub async fn detect_toolchains<S: ShellService>(services: &S, cwd: &Path) -> Vec<Toolchain> {
+    let detections = TOOLCHAINS.iter().map(|(name, command)| async move {
+        // Piped rather than executed so the versions aren't echoed to the user
+        let output = services
+            .pipe(command.to_string(), cwd.to_path_buf(), String::new(), false)
+            .await
+            .ok()?
+            .output;
+        if !output.success() {
+            return None;
+        }
+        // Some toolchains, like java, print their version on stderr
+        let version = first_line(&output.stdout).or_else(|| first_line(&output.stderr))?;
+        Some(Toolchain { name: name.to_string(), version })
+    });
+
+    futures::future::join_all(detections)
+        .await
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+fn first_line(text: &str) -> Option<String> {
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use forge_domain::CommandOutput;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::ShellOutput;
+
+    /// Only knows how to run `rustc --version`, every other command fails.
+    /// The version names the directory it was run in.
+    #[derive(Default)]
+    struct MockShell {
+        commands: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ShellService for MockShell {
+        async fn execute(
+            &self,
+            command: String,
+            cwd: PathBuf,
+            keep_ansi: bool,
+        ) -> anyhow::Result<ShellOutput> {
+            self.pipe(command, cwd, String::new(), keep_ansi).await
+        }
+
+        async fn pipe(
+            &self,
+            command: String,
+            cwd: PathBuf,
+            _input: String,
+            _keep_ansi: bool,
+        ) -> anyhow::Result<ShellOutput> {
+            self.commands.lock().unwrap().push(command.clone());
+            let (stdout, exit_code) = match command.as_str() {
+                "rustc --version" => (format!("rustc 1.88.0 ({})\n", cwd.display()), 0),
+                _ => (String::new(), 127),
+            };
+            Ok(ShellOutput {
+                output: CommandOutput {
+                    command,
+                    stdout,
+                    stderr: String::new(),
+                    exit_code: Some(exit_code),
+                },
+                shell: "/bin/bash".to_string(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_detect_toolchains_skips_missing_ones() {
+        let actual =
+            detect_toolchains(&MockShell::default(), Path::new("/home/user/project")).await;
+
+        let expected = vec![Toolchain {
+            name: "rustc".to_string(),
+            version: "rustc 1.88.0 (/home/user/project)".to_string(),
+        }];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_toolchain_cache_detects_once_per_directory() {
+        let shell = MockShell::default();
+        let fixture = ToolchainCache::default();
+
+        let mut actual = Vec::new();
+        for cwd in [
+            "/home/user/project",
+            "/home/user/project",
+            "/home/user/other",
+        ] {
+            let toolchains = fixture.get(&shell, Path::new(cwd)).await;
+            actual.extend(toolchains.into_iter().map(|toolchain| toolchain.version));
+        }
+        let detections = shell.commands.lock().unwrap().len();
+
+        let expected = vec![
+            "rustc 1.88.0 (/home/user/project)".to_string(),
+            "rustc 1.88.0 (/home/user/project)".to_string(),
+            "rustc 1.88.0 (/home/user/other)".to_string(),
+        ];
+        assert_eq!(actual, expected);
+        assert_eq!(detections, TOOLCHAINS.len() * 2);
+    }
+}
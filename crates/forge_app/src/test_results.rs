@@ -0,0 +1,367 @@
+/// Summary of a test run parsed from the output of a recognized test runner
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TestResults {
+    pub passed: usize,
+    pub failed: usize,
+    pub ignored: usize,
+    pub failures: Vec<TestFailure>,
+}
+
+/// A single failing test
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestFailure {
+    pub name: String,
+    /// Where the failure was reported, formatted as `file:line`
+    pub location: Option<String>,
+    pub message: String,
+}
+
+impl TestFailure {
+    fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), location: None, message: String::new() }
+    }
+}
+
+impl TestResults {
+    /// Parses the output of `cargo test`, a JUnit XML report or a TAP stream.
+    /// Returns `None` when the output isn't recognized.
+    pub fn parse(output: &str) -> Option<Self> {
+        parse_cargo_test(output)
+            .or_else(|| parse_junit(output))
+            .or_else(|| parse_tap(output))
+    }
+}
+
+fn parse_cargo_test(output: &str) -> Option<TestResults> {
+    let mut results = TestResults::default();
+    let mut is_recognized = false;
+    let mut section: Option<&str> = None;
+    let mut lines = output.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        if let Some(summary) = line.strip_prefix("test result: ") {
+            is_recognized = true;
+            for part in summary.split(['.', ';']) {
+                let mut words = part.split_whitespace();
+                let count = words.next().and_then(|count| count.parse::<usize>().ok());
+                match (count, words.next()) {
+                    (Some(count), Some("passed")) => results.passed += count,
+                    (Some(count), Some("failed")) => results.failed += count,
+                    (Some(count), Some("ignored")) => results.ignored += count,
+                    _ => {}
+                }
+            }
+        } else if let Some(name) = line
+            .strip_prefix("test ")
+            .and_then(|line| line.strip_suffix(" ... FAILED"))
+        {
+            results.failures.push(TestFailure::new(name));
+        } else if let Some(name) = line
+            .strip_prefix("---- ")
+            .and_then(|line| line.strip_suffix(" stdout ----"))
+        {
+            section = Some(name);
+        } else if let Some((_, panic)) = line.split_once("' panicked at ")
+            && let Some(name) = section
+            && let Some(failure) = results
+                .failures
+                .iter_mut()
+                .find(|failure| failure.name == name)
+        {
+            if let Some(location) = panic.strip_suffix(':') {
+                // Since Rust 1.73 the message follows on the next lines
+                let mut message = Vec::new();
+                while let Some(line) = lines.next_if(|line| !is_end_of_panic(line)) {
+                    message.push(line);
+                }
+                failure.location = Some(trim_column(location));
+                failure.message = message.join("\n");
+            } else if let Some((message, location)) = panic.rsplit_once(", ") {
+                failure.location = Some(trim_column(location));
+                failure.message = message.trim_matches('\'').to_string();
+            }
+        }
+    }
+
+    is_recognized.then_some(results)
+}
+
+fn is_end_of_panic(line: &str) -> bool {
+    line.trim().is_empty() || line.starts_with("note: ") || line.starts_with("stack backtrace:")
+}
+
+fn parse_junit(output: &str) -> Option<TestResults> {
+    if !output.contains("<testsuite") {
+        return None;
+    }
+
+    let mut results = TestResults::default();
+    for case in output.split("<testcase").skip(1) {
+        let (attributes, body) = match case.split_once('>') {
+            Some((attributes, _)) if attributes.ends_with('/') => (attributes, ""),
+            Some((attributes, rest)) => (
+                attributes,
+                rest.split_once("</testcase>")
+                    .map_or(rest, |(body, _)| body),
+            ),
+            None => continue,
+        };
+
+        let failure = ["<failure", "<error"]
+            .into_iter()
+            .find_map(|tag| body.split_once(tag).map(|(_, rest)| rest));
+        if let Some(failure) = failure {
+            let name = match (
+                xml_attribute(attributes, "classname"),
+                xml_attribute(attributes, "name"),
+            ) {
+                (Some(class), Some(name)) => format!("{class}.{name}"),
+                (None, Some(name)) => name,
+                (class, None) => class.unwrap_or_default(),
+            };
+            let (failure_attributes, text) = failure.split_once('>').unwrap_or((failure, ""));
+            let message = xml_attribute(failure_attributes, "message").unwrap_or_else(|| {
+                let text = text.split('<').next().unwrap_or_default();
+                unescape_xml(text.trim().lines().next().unwrap_or_default())
+            });
+            let location = xml_attribute(attributes, "file").map(|file| {
+                match xml_attribute(attributes, "line") {
+                    Some(line) => format!("{file}:{line}"),
+                    None => file,
+                }
+            });
+
+            results.failed += 1;
+            results
+                .failures
+                .push(TestFailure { name, location, message });
+        } else if body.contains("<skipped") {
+            results.ignored += 1;
+        } else {
+            results.passed += 1;
+        }
+    }
+
+    Some(results)
+}
+
+fn xml_attribute(attributes: &str, name: &str) -> Option<String> {
+    let pattern = format!(" {name}=\"");
+    let start = attributes
+        .find(&pattern)
+        .or_else(|| attributes.find(&format!("\n{}", &pattern[1..])))?
+        + pattern.len();
+    let end = attributes[start..].find('"')? + start;
+    Some(unescape_xml(&attributes[start..end]))
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn parse_tap(output: &str) -> Option<TestResults> {
+    let is_tap = output.lines().any(|line| {
+        let line = line.trim();
+        line.starts_with("TAP version")
+            || line
+                .strip_prefix("1..")
+                .is_some_and(|count| count.parse::<usize>().is_ok())
+    });
+    if !is_tap {
+        return None;
+    }
+
+    let mut results = TestResults::default();
+    let mut lines = output.lines().peekable();
+    while let Some(line) = lines.next() {
+        let (is_ok, rest) = if let Some(rest) = line.strip_prefix("not ok") {
+            (false, rest)
+        } else if let Some(rest) = line.strip_prefix("ok") {
+            (true, rest)
+        } else {
+            continue;
+        };
+
+        let (description, directive) = rest.split_once(" # ").unwrap_or((rest, ""));
+        let directive = directive.to_uppercase();
+        if directive.starts_with("SKIP") || directive.starts_with("TODO") {
+            results.ignored += 1;
+            continue;
+        }
+        if is_ok {
+            results.passed += 1;
+            continue;
+        }
+
+        let description = description.trim_start_matches(|c: char| c.is_ascii_digit() || c == ' ');
+        let mut failure = TestFailure::new(description.trim_start_matches("- ").trim());
+
+        // Diagnostics are attached as an indented YAML block
+        if lines.next_if(|line| line.trim() == "---").is_some() {
+            while let Some(line) = lines.next_if(|line| line.trim() != "...") {
+                if let Some((key, value)) = line.trim().split_once(": ") {
+                    let value = value.trim_matches(['\'', '"']);
+                    match key {
+                        "message" => failure.message = value.to_string(),
+                        "at" => failure.location = Some(trim_column(value)),
+                        _ => {}
+                    }
+                }
+            }
+            lines.next();
+        }
+
+        results.failed += 1;
+        results.failures.push(failure);
+    }
+
+    Some(results)
+}
+
+/// Drops the column from a `file:line:column` location
+fn trim_column(location: &str) -> String {
+    let location = location.trim();
+    match location.rsplit_once(':') {
+        Some((file_line, column))
+            if column.parse::<usize>().is_ok()
+                && file_line
+                    .rsplit_once(':')
+                    .is_some_and(|(_, line)| line.parse::<usize>().is_ok()) =>
+        {
+            file_line.to_string()
+        }
+        _ => location.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_cargo_test_output() {
+        let fixture = r#"
+running 3 tests
+test tests::test_add ... ok
+test tests::test_sub ... FAILED
+test tests::test_div ... ignored
+
+failures:
+
+---- tests::test_sub stdout ----
+
+thread 'tests::test_sub' panicked at src/lib.rs:17:9:
+assertion `left == right` failed
+  left: 1
+ right: 2
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+
+failures:
+    tests::test_sub
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.00s
+"#;
+
+        let actual = TestResults::parse(fixture);
+
+        let expected = Some(TestResults {
+            passed: 1,
+            failed: 1,
+            ignored: 1,
+            failures: vec![TestFailure {
+                name: "tests::test_sub".to_string(),
+                location: Some("src/lib.rs:17".to_string()),
+                message: "assertion `left == right` failed\n  left: 1\n right: 2".to_string(),
+            }],
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_cargo_test_sums_all_binaries() {
+        let fixture = [
+            "test result: ok. 4 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out",
+            "test result: ok. 2 passed; 0 failed; 1 ignored; 0 measured; 0 filtered out",
+        ]
+        .join("\n");
+
+        let actual = TestResults::parse(&fixture);
+
+        let expected = Some(TestResults { passed: 6, ignored: 1, ..Default::default() });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_junit_report() {
+        let fixture = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites>
+  <testsuite name="calc" tests="3" failures="1" skipped="1">
+    <testcase classname="calc" name="adds" file="test/calc.test.js" line="4"/>
+    <testcase classname="calc" name="subtracts" file="test/calc.test.js" line="9">
+      <failure message="expected 1 to equal 2" type="AssertionError">AssertionError: expected 1 to equal 2</failure>
+    </testcase>
+    <testcase classname="calc" name="divides">
+      <skipped/>
+    </testcase>
+  </testsuite>
+</testsuites>"#;
+
+        let actual = TestResults::parse(fixture);
+
+        let expected = Some(TestResults {
+            passed: 1,
+            failed: 1,
+            ignored: 1,
+            failures: vec![TestFailure {
+                name: "calc.subtracts".to_string(),
+                location: Some("test/calc.test.js:9".to_string()),
+                message: "expected 1 to equal 2".to_string(),
+            }],
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_tap_stream() {
+        let fixture = r#"TAP version 13
+1..3
+ok 1 - adds
+not ok 2 - subtracts
+  ---
+  message: 'expected 1 to equal 2'
+  at: test/calc.js:9:5
+  ...
+ok 3 - divides # SKIP not implemented"#;
+
+        let actual = TestResults::parse(fixture);
+
+        let expected = Some(TestResults {
+            passed: 1,
+            failed: 1,
+            ignored: 1,
+            failures: vec![TestFailure {
+                name: "subtracts".to_string(),
+                location: Some("test/calc.js:9".to_string()),
+                message: "expected 1 to equal 2".to_string(),
+            }],
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_unrecognized_output() {
+        let fixture = "Compiling forge v0.1.0\nFinished dev profile";
+
+        let actual = TestResults::parse(fixture);
+
+        let expected = None;
+        assert_eq!(actual, expected);
+    }
+}
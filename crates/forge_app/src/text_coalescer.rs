@@ -0,0 +1,84 @@
+use std::time::{Duration, Instant};
+
+/// How long partial text is gathered before it's sent on
+pub const PARTIAL_TEXT_INTERVAL: Duration = Duration::from_millis(40);
+
+/// Gathers the text deltas of a streamed response so that they're rendered in
+/// batches at most once per interval, instead of one write per delta
+#[derive(Debug)]
+pub struct TextCoalescer {
+    interval: Duration,
+    pending: String,
+    last_flush: Option<Instant>,
+}
+
+impl TextCoalescer {
+    pub fn new(interval: Duration) -> Self {
+        Self { interval, pending: String::new(), last_flush: None }
+    }
+
+    /// Adds the delta, returning the text gathered so far once the interval
+    /// passed since the last batch. The first delta is returned right away
+    pub fn push(&mut self, delta: &str, now: Instant) -> Option<String> {
+        self.pending.push_str(delta);
+        let is_due = self
+            .last_flush
+            .is_none_or(|last_flush| now.duration_since(last_flush) >= self.interval);
+        if is_due {
+            self.last_flush = Some(now);
+            return self.flush();
+        }
+        None
+    }
+
+    /// Takes the text not returned yet, when the response is complete
+    pub fn flush(&mut self) -> Option<String> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        Some(std::mem::take(&mut self.pending))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_rapid_deltas_are_batched() {
+        let start = Instant::now();
+        let mut fixture = TextCoalescer::new(Duration::from_millis(40));
+
+        let actual = vec![
+            fixture.push("Hel", start),
+            fixture.push("lo", start + Duration::from_millis(10)),
+            fixture.push(", wor", start + Duration::from_millis(20)),
+            fixture.push("ld", start + Duration::from_millis(45)),
+            fixture.push("!", start + Duration::from_millis(50)),
+        ];
+
+        let expected = vec![
+            Some("Hel".to_string()),
+            None,
+            None,
+            Some("lo, world".to_string()),
+            None,
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_flush_returns_the_rest_on_completion() {
+        let start = Instant::now();
+        let mut fixture = TextCoalescer::new(Duration::from_millis(40));
+        fixture.push("Done", start);
+        fixture.push(" now", start + Duration::from_millis(5));
+
+        let actual = (fixture.flush(), fixture.flush());
+
+        let expected = (Some(" now".to_string()), None);
+        assert_eq!(actual, expected);
+    }
+}
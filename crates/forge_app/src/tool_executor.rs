@@ -1,15 +1,31 @@
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::Context;
-use forge_domain::{TaskList, ToolCallContext, ToolCallFull, ToolOutput, Tools};
+use console::strip_ansi_codes;
+use forge_display::TitleFormat;
+use forge_domain::{
+    CommandOutput, Environment, ScratchpadRead, ScratchpadWrite, TaskReport, ToolCallContext,
+    ToolCallFull, ToolName, ToolOutput, ToolPostProcessor, ToolValue, Tools, formatter_command,
+    redact,
+};
 
 use crate::error::Error;
-use crate::fmt::content::FormatContent;
+use crate::fmt::content::{ContentFormat, FormatContent};
+use crate::format_code::format_file;
+use crate::fs_list::DirListing;
 use crate::operation::Operation;
-use crate::services::ShellService;
+use crate::read_glob::{DEFAULT_MAX_FILES, MAX_WALKED_FILES, matching_files, read_files};
+use crate::repo_tree::DirTree;
+use crate::search_order::order_matches;
+use crate::services::{Content, ShellService};
+use crate::system_info::SystemFacts;
+use crate::test_results::TestResults;
 use crate::{
-    ConversationService, EnvironmentService, FollowUpService, FsCreateService, FsPatchService,
-    FsReadService, FsRemoveService, FsSearchService, FsUndoService, NetFetchService,
+    ConversationService, EnvironmentService, FetchRequest, FileDiscoveryService, FollowUpService,
+    FsCreateService, FsPatchService, FsReadService, FsRemoveService, FsSearchService,
+    FsUndoService, NetFetchService, ToolchainService, Walker,
 };
 
 pub struct ToolExecutor<S> {
@@ -27,27 +43,66 @@ impl<
         + ShellService
         + FollowUpService
         + ConversationService
-        + EnvironmentService,
+        + EnvironmentService
+        + FileDiscoveryService
+        + ToolchainService,
 > ToolExecutor<S>
 {
     pub fn new(services: Arc<S>) -> Self {
         Self { services }
     }
 
-    async fn call_internal(&self, input: Tools, tasks: &mut TaskList) -> anyhow::Result<Operation> {
+    /// Returns the environment the tools run in, with the working directory
+    /// of the agent when it has one
+    fn environment(&self, context: &ToolCallContext) -> Environment {
+        let mut env = self.services.get_environment();
+        if let Some(cwd) = &context.cwd {
+            env.cwd = cwd.clone();
+        }
+        env
+    }
+
+    /// Rejects content that carries the line numbers of a numbered read of
+    /// the file at `path`
+    async fn ensure_unnumbered(&self, path: &str, content: &str) -> anyhow::Result<()> {
+        let Some(lines) = numbered_lines(content) else {
+            return Ok(());
+        };
+        let first = lines.first().map(|(number, _)| *number);
+        let last = lines.last().map(|(number, _)| *number);
+        // Content for a file that can't be read can't come from reading it
+        let Ok(output) = self.services.read(path.to_string(), first, last).await else {
+            return Ok(());
+        };
+        let Content::File(file) = &output.content;
+        Ok(ensure_unnumbered(path, content, file, output.start_line)?)
+    }
+
+    async fn call_internal(
+        &self,
+        input: Tools,
+        context: &mut ToolCallContext,
+    ) -> anyhow::Result<Operation> {
         Ok(match input {
             Tools::ForgeToolFsRead(input) => {
-                let output = self
-                    .services
-                    .read(
-                        input.path.clone(),
-                        input.start_line.map(|i| i as u64),
-                        input.end_line.map(|i| i as u64),
-                    )
-                    .await?;
+                let start_line = input.start_line.map(|i| i as u64);
+                let end_line = input.end_line.map(|i| i as u64);
+                let output = match input.revision.clone() {
+                    Some(revision) => {
+                        self.services
+                            .read_revision(input.path.clone(), revision, start_line, end_line)
+                            .await?
+                    }
+                    None => {
+                        self.services
+                            .read(input.path.clone(), start_line, end_line)
+                            .await?
+                    }
+                };
                 (input, output).into()
             }
             Tools::ForgeToolFsCreate(input) => {
+                self.ensure_unnumbered(&input.path, &input.content).await?;
                 let output = self
                     .services
                     .create(
@@ -57,6 +112,11 @@ impl<
                         true,
                     )
                     .await?;
+                if output.before.is_some() {
+                    context.changed_files.record_modified(&input.path);
+                } else {
+                    context.changed_files.record_created(&input.path);
+                }
                 (input, output).into()
             }
             Tools::ForgeToolFsSearch(input) => {
@@ -67,14 +127,22 @@ impl<
                         input.regex.clone(),
                         input.file_pattern.clone(),
                     )
-                    .await?;
+                    .await?
+                    .map(|mut output| {
+                        if let Some(order) = input.order {
+                            output.matches = order_matches(output.matches, order);
+                        }
+                        output
+                    });
                 (input, output).into()
             }
             Tools::ForgeToolFsRemove(input) => {
                 let _output = self.services.remove(input.path.clone()).await?;
+                context.changed_files.record_removed(&input.path);
                 input.into()
             }
             Tools::ForgeToolFsPatch(input) => {
+                self.ensure_unnumbered(&input.path, &input.content).await?;
                 let output = self
                     .services
                     .patch(
@@ -84,21 +152,45 @@ impl<
                         input.content.clone(),
                     )
                     .await?;
+                context.changed_files.record_modified(&input.path);
                 (input, output).into()
             }
             Tools::ForgeToolFsUndo(input) => {
                 let output = self.services.undo(input.path.clone()).await?;
+                context.changed_files.record_modified(&input.path);
                 (input, output).into()
             }
             Tools::ForgeToolProcessShell(input) => {
+                let env = self.services.get_environment();
+                ensure_within_sandbox(&input.cwd, env.sandbox_root.as_deref())?;
                 let output = self
                     .services
                     .execute(input.command.clone(), input.cwd.clone(), input.keep_ansi)
                     .await?;
-                output.into()
+                let test_results = if input.parse_results {
+                    TestResults::parse(&strip_ansi_codes(&format!(
+                        "{}\n{}",
+                        output.output.stdout, output.output.stderr
+                    )))
+                } else {
+                    None
+                };
+                (output, test_results).into()
             }
             Tools::ForgeToolNetFetch(input) => {
-                let output = self.services.fetch(input.url.clone(), input.raw).await?;
+                let output = self
+                    .services
+                    .fetch(
+                        FetchRequest {
+                            url: input.url.clone(),
+                            method: input.method.clone(),
+                            headers: input.headers.clone().unwrap_or_default(),
+                            body: input.body.clone(),
+                        },
+                        input.raw,
+                        input.no_cache,
+                    )
+                    .await?;
                 (input, output).into()
             }
             Tools::ForgeToolFollowup(input) => {
@@ -120,71 +212,722 @@ impl<
                     .await?;
                 output.into()
             }
-            Tools::ForgeToolAttemptCompletion(_input) => {
-                crate::operation::Operation::AttemptCompletion
-            }
+            Tools::ForgeToolAttemptCompletion(input) => Operation::AttemptCompletion {
+                feedback: context.completion_feedback.clone(),
+                changed_files: context.unreported_changed_files(),
+                task_report: input
+                    .task_report
+                    .then(|| TaskReport::new(&context.tasks, &context.changed_files)),
+            },
             Tools::ForgeToolTaskListAppend(input) => {
-                let before = tasks.clone();
-                tasks.append(&input.task);
-                Operation::TaskListAppend { _input: input, before, after: tasks.clone() }
+                let before = context.tasks.clone();
+                context.tasks.append(&input.task);
+                Operation::TaskListAppend { _input: input, before, after: context.tasks.clone() }
             }
             Tools::ForgeToolTaskListAppendMultiple(input) => {
-                let before = tasks.clone();
-                tasks.append_multiple(input.tasks.clone());
-                Operation::TaskListAppendMultiple { _input: input, before, after: tasks.clone() }
+                let before = context.tasks.clone();
+                context.tasks.append_multiple(input.tasks.clone());
+                Operation::TaskListAppendMultiple {
+                    _input: input,
+                    before,
+                    after: context.tasks.clone(),
+                }
             }
             Tools::ForgeToolTaskListUpdate(input) => {
-                let before = tasks.clone();
-                tasks
+                let before = context.tasks.clone();
+                context
+                    .tasks
                     .update_status(input.task_id, input.status.clone())
                     .context("Task not found")?;
-                Operation::TaskListUpdate { _input: input, before, after: tasks.clone() }
+                Operation::TaskListUpdate { _input: input, before, after: context.tasks.clone() }
             }
             Tools::ForgeToolTaskListList(input) => {
-                let before = tasks.clone();
+                let before = context.tasks.clone();
                 // No operation needed, just return the current state
-                Operation::TaskListList { _input: input, before, after: tasks.clone() }
+                Operation::TaskListList { _input: input, before, after: context.tasks.clone() }
             }
             Tools::ForgeToolTaskListClear(input) => {
-                let before = tasks.clone();
-                tasks.clear();
-                Operation::TaskListClear { _input: input, before, after: tasks.clone() }
+                let before = context.tasks.clone();
+                context.tasks.clear();
+                Operation::TaskListClear { _input: input, before, after: context.tasks.clone() }
+            }
+            Tools::ForgeToolScratchpadWrite(input) => write_scratchpad(input, context),
+            Tools::ForgeToolScratchpadRead(input) => read_scratchpad(input, context),
+            Tools::ForgeToolRepoTree(input) => {
+                // The whole workspace is walked so that the file counts of
+                // directories that aren't expanded are still accurate
+                let walker = Walker::unlimited().cwd(self.environment(context).cwd);
+                let files = self.services.collect_files(walker).await?;
+                (input, DirTree::from_files(files)).into()
+            }
+            Tools::ForgeToolFsReadGlob(input) => {
+                let root = PathBuf::from(&input.path);
+                anyhow::ensure!(
+                    root.is_absolute(),
+                    "Path must be absolute. Please provide an absolute path starting with '/' (Unix) or 'C:\\' (Windows)"
+                );
+                // Walking one file more than the bound tells that the walk was cut short
+                let walker = Walker::unlimited()
+                    .cwd(root.clone())
+                    .max_files(MAX_WALKED_FILES + 1);
+                let files = self.services.collect_files(walker).await?;
+                let is_walk_truncated =
+                    files.iter().filter(|file| !file.is_dir).count() > MAX_WALKED_FILES;
+                let paths = matching_files(&root, &input.pattern, files)?;
+                let max_files = input
+                    .max_files
+                    .map_or(DEFAULT_MAX_FILES, |files| files.max(1) as usize);
+                // A bulk read never returns more than a single read of the largest
+                // allowed file
+                let max_bytes = self.services.get_environment().max_file_size as usize;
+                let mut output =
+                    read_files(self.services.as_ref(), paths, max_files, max_bytes).await;
+                output.is_walk_truncated = is_walk_truncated;
+                (input, output).into()
+            }
+            Tools::ForgeToolFsList(input) => {
+                let root = PathBuf::from(&input.path);
+                anyhow::ensure!(
+                    root.is_absolute(),
+                    "Path must be absolute. Please provide an absolute path starting with '/' (Unix) or 'C:\\' (Windows)"
+                );
+                // Walked like the files of the system prompt, so ignored files are left out
+                let walker = if input.recursive.unwrap_or_default() {
+                    // Only one file more than can be listed is needed to tell that the
+                    // listing was cut short, so large trees aren't walked in full
+                    let max_search_lines = self.environment(context).max_search_lines as usize;
+                    Walker::unlimited()
+                        .cwd(root)
+                        .max_files(max_search_lines + 1)
+                } else {
+                    Walker::unlimited().cwd(root).max_depth(1_usize)
+                };
+                let files = self.services.collect_files(walker).await?;
+                // The walker always yields the directory it starts from
+                anyhow::ensure!(
+                    files.iter().any(|file| file.path == "/"),
+                    "{} is not a directory",
+                    input.path
+                );
+                (input, DirListing::new(files)).into()
+            }
+            Tools::ForgeToolSystemInfo(_) => {
+                let env = self.environment(context);
+                let toolchains = self.services.toolchains(&env.cwd).await;
+                SystemFacts::new(&env, toolchains).into()
+            }
+            Tools::ForgeToolFormatCode(input) => {
+                let path = Path::new(&input.path);
+                let formatter = formatter_command(&context.formatters, path).ok_or_else(|| {
+                    anyhow::anyhow!("No formatter is configured for {}", input.path)
+                })?;
+                // The formatter runs in the directory of the file
+                let env = self.services.get_environment();
+                ensure_within_sandbox(path.parent().unwrap_or(path), env.sandbox_root.as_deref())?;
+                let output = format_file(self.services.as_ref(), path, &formatter).await?;
+                if output.is_changed() {
+                    context.changed_files.record_modified(&input.path);
+                }
+                (input, output).into()
             }
         })
     }
 
+    /// Pipes the final answer through the post-response command
+    async fn post_process(
+        &self,
+        command: String,
+        answer: &str,
+        env: &Environment,
+    ) -> anyhow::Result<String> {
+        let output = self
+            .services
+            .pipe(command, env.cwd.clone(), answer.to_string(), false)
+            .await?;
+        post_processed_answer(output.output)
+    }
+
     pub async fn execute(
         &self,
         input: ToolCallFull,
         context: &mut ToolCallContext,
     ) -> anyhow::Result<ToolOutput> {
         let tool_name = input.name.clone();
-        let tool_input = Tools::try_from(input).map_err(Error::CallArgument)?;
-        let env = self.services.get_environment();
+        let mut tool_input = Tools::try_from(input).map_err(Error::CallArgument)?;
+        ensure_explained(&tool_input, context.require_tool_explanation)?;
+        let env = self.environment(context);
+
+        // The final answer is only post-processed once it's complete, right before it's
+        // shown
+        if let Tools::ForgeToolAttemptCompletion(input) = &mut tool_input
+            && let Some(command) = context.post_response_command.clone()
+        {
+            match self.post_process(command, &input.result, &env).await {
+                Ok(answer) => input.result = answer,
+                Err(error) => {
+                    context
+                        .send(ContentFormat::from(TitleFormat::error(format!(
+                            "{error:#}"
+                        ))))
+                        .await?;
+                    return Err(error);
+                }
+            }
+        }
         if let Some(content) = tool_input.to_content(&env) {
-            context.send(content).await?;
+            context.send(content.redact_home(&env)).await?;
         }
 
         // Send tool call information
 
-        let execution_result = self
-            .call_internal(tool_input.clone(), &mut context.tasks)
-            .await;
+        let execution_result = self.call_internal(tool_input.clone(), context).await;
         if let Err(ref error) = execution_result {
             tracing::error!(error = ?error, "Tool execution failed");
         }
 
-        let execution_result = execution_result?;
+        let mut execution_result = execution_result?;
+
+        // Post-processed before anything is shown or written to disk, so that
+        // redacted text doesn't end up in the raw dump or the offloaded files
+        let processed = post_process_operation(
+            self.services.as_ref(),
+            &context.tool_post_processors,
+            &tool_name,
+            &mut execution_result,
+            &env.cwd,
+        )
+        .await?;
 
         // Send formatted output message
         if let Some(output) = execution_result.to_content(&env) {
-            context.send(output).await?;
+            context.send(output.redact_home(&env)).await?;
+        }
+
+        if env.debug_raw_output {
+            let path = env.raw_output_path().display().to_string();
+            if let Err(error) = self
+                .services
+                .create(path, execution_result.to_raw_dump(), true, false)
+                .await
+            {
+                tracing::warn!(error = ?error, "Failed to save the raw tool output");
+            }
         }
 
         let truncation_path = execution_result
             .to_create_temp(self.services.as_ref())
             .await?;
 
-        Ok(execution_result.into_tool_output(tool_name, truncation_path, &env))
+        let output = execution_result.into_tool_output(tool_name.clone(), truncation_path, &env);
+        if processed {
+            return Ok(output);
+        }
+        post_process_output(
+            self.services.as_ref(),
+            &context.tool_post_processors,
+            &tool_name,
+            output,
+            &env.cwd,
+        )
+        .await
+    }
+}
+
+/// Applies the post-processor registered for the tool to its text output,
+/// the output of other tools is returned unchanged
+async fn post_process_output<S: ShellService>(
+    services: &S,
+    processors: &HashMap<ToolName, ToolPostProcessor>,
+    tool_name: &ToolName,
+    mut output: ToolOutput,
+    cwd: &Path,
+) -> anyhow::Result<ToolOutput> {
+    let Some(processor) = processors.get(tool_name) else {
+        return Ok(output);
+    };
+    for value in output.values.iter_mut() {
+        let ToolValue::Text(text) = value else {
+            continue;
+        };
+        *text = post_process_text(services, processor, tool_name, text, cwd).await?;
+    }
+    Ok(output)
+}
+
+/// Applies the post-processor registered for the tool to the raw texts of the
+/// operation. Returns whether there were any, the output of operations
+/// without raw texts is post-processed once it's formatted instead
+async fn post_process_operation<S: ShellService>(
+    services: &S,
+    processors: &HashMap<ToolName, ToolPostProcessor>,
+    tool_name: &ToolName,
+    operation: &mut Operation,
+    cwd: &Path,
+) -> anyhow::Result<bool> {
+    let Some(processor) = processors.get(tool_name) else {
+        return Ok(false);
+    };
+    let texts = operation.raw_texts_mut();
+    let processed = !texts.is_empty();
+    for text in texts {
+        *text = post_process_text(services, processor, tool_name, text, cwd).await?;
+    }
+    Ok(processed)
+}
+
+async fn post_process_text<S: ShellService>(
+    services: &S,
+    processor: &ToolPostProcessor,
+    tool_name: &ToolName,
+    text: &str,
+    cwd: &Path,
+) -> anyhow::Result<String> {
+    match processor {
+        ToolPostProcessor::Redact => Ok(redact(text)),
+        ToolPostProcessor::Command(command) => {
+            let processed = services
+                .pipe(command.clone(), cwd.to_path_buf(), text.to_string(), false)
+                .await?
+                .output;
+            if processed.exit_code != Some(0) {
+                anyhow::bail!(
+                    "The post-processor `{command}` of {tool_name} failed, the output was withheld:\n{}",
+                    processed.stderr.trim()
+                );
+            }
+            Ok(processed.stdout)
+        }
+    }
+}
+
+/// Replaces the notes kept in the tool context, the orchestrator carries them
+/// over to the conversation once the calls of the response have run
+pub(crate) fn write_scratchpad(input: ScratchpadWrite, context: &mut ToolCallContext) -> Operation {
+    context.scratchpad = input.content.clone();
+    Operation::ScratchpadWrite { _input: input, content: context.scratchpad.clone() }
+}
+
+/// Returns the notes kept in the tool context
+pub(crate) fn read_scratchpad(input: ScratchpadRead, context: &ToolCallContext) -> Operation {
+    Operation::ScratchpadRead { _input: input, content: context.scratchpad.clone() }
+}
+
+/// Rejects calls without an explanation when explanations are required
+fn ensure_explained(input: &Tools, required: bool) -> Result<(), Error> {
+    let is_explained =
+        input.explanation().is_some() || matches!(input, Tools::ForgeToolAttemptCompletion(_));
+    if required && !is_explained {
+        Err(Error::MissingExplanation(ToolName::new(input.to_string())))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the lines of content that starts every line with a line number
+/// followed by a tab, as a read with `with_line_numbers` prefixes them, along
+/// with their numbers
+fn numbered_lines(content: &str) -> Option<Vec<(u64, &str)>> {
+    let lines = content
+        .lines()
+        .map(|line| {
+            let (number, text) = line.trim_start().split_once('\t')?;
+            Some((number.parse::<u64>().ok()?, text))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let is_consecutive = lines.len() > 1 && lines.windows(2).all(|pair| pair[1].0 == pair[0].0 + 1);
+    is_consecutive.then_some(lines)
+}
+
+/// Rejects content that is the numbered form of the file as a read with
+/// `with_line_numbers` returned it, so that the line numbers don't end up in
+/// the file. `file` holds the lines of the file from `start_line` on, numbered
+/// data such as a TSV with an id column is accepted when it doesn't match them
+fn ensure_unnumbered(path: &str, content: &str, file: &str, start_line: u64) -> Result<(), Error> {
+    let is_numbered = numbered_lines(content).is_some_and(|lines| {
+        let file = file.lines().collect::<Vec<_>>();
+        lines.iter().all(|(number, text)| {
+            number
+                .checked_sub(start_line)
+                .and_then(|index| file.get(index as usize))
+                .is_some_and(|line| line == text)
+        })
+    });
+    if is_numbered {
+        Err(Error::NumberedContent(path.to_string()))
+    } else {
+        Ok(())
+    }
+}
+
+/// Rejects working directories outside the sandbox root. `..` components are
+/// resolved first, so they can't be used to escape it
+pub(crate) fn ensure_within_sandbox(cwd: &Path, root: Option<&Path>) -> Result<(), Error> {
+    let Some(root) = root else {
+        return Ok(());
+    };
+    if normalize(cwd).starts_with(normalize(root)) {
+        Ok(())
+    } else {
+        Err(Error::OutsideSandbox { cwd: cwd.to_path_buf(), root: root.to_path_buf() })
+    }
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .fold(PathBuf::new(), |mut normalized, component| {
+            match component {
+                Component::ParentDir => {
+                    normalized.pop();
+                }
+                Component::CurDir => {}
+                component => normalized.push(component),
+            }
+            normalized
+        })
+}
+
+/// Returns the output of the post-response command that replaces the answer,
+/// failing when the command did
+fn post_processed_answer(output: CommandOutput) -> anyhow::Result<String> {
+    let status = match output.exit_code {
+        Some(0) => return Ok(output.stdout),
+        Some(code) => format!("exited with status {code}"),
+        None => "was terminated".to_string(),
+    };
+    anyhow::bail!(
+        "The post-response command `{}` {status}, the answer was withheld:\n{}",
+        output.command,
+        output.stderr.trim()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use forge_domain::{FSRead, RetryConfig};
+    use pretty_assertions::assert_eq;
+    use url::Url;
+
+    use super::*;
+    use crate::{FsCreateOutput, ShellOutput};
+
+    fn fixture_environment() -> Environment {
+        Environment {
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            pid: 12345,
+            cwd: PathBuf::from("/home/user/project"),
+            home: Some(PathBuf::from("/home/user")),
+            shell: "/bin/bash".to_string(),
+            base_path: PathBuf::from("/home/user/project"),
+            retry_config: RetryConfig {
+                initial_backoff_ms: 1,
+                min_delay_ms: 1,
+                backoff_factor: 1,
+                max_retry_attempts: 0,
+                retry_status_codes: vec![],
+                content_filter_codes: vec![],
+                max_delay: None,
+            },
+            max_search_lines: 25,
+            fetch_truncation_limit: 55,
+            fetch_cache_ttl: 0,
+            max_read_size: 10,
+            stdout_max_prefix_length: 2,
+            stdout_max_suffix_length: 2,
+            tool_limits: Default::default(),
+            http: Default::default(),
+            max_file_size: 256 << 10,
+            forge_api_url: Url::parse("http://forgecode.dev/api").unwrap(),
+            shell_env: Default::default(),
+            truncation_template: None,
+            otlp_endpoint: None,
+            debug_raw_output: false,
+            sandbox_root: None,
+            max_concurrent_requests: None,
+            redact_home: false,
+            diff_ignore_whitespace: false,
+            diff_ignore_trailing_newline: false,
+            offload_dir: None,
+        }
+    }
+
+    /// Keeps the files created in memory, offloaded outputs go to the
+    /// directory
+    struct MockFiles {
+        offload_dir: tempfile::TempDir,
+        files: Mutex<HashMap<String, String>>,
+    }
+
+    impl EnvironmentService for MockFiles {
+        fn get_environment(&self) -> Environment {
+            Environment {
+                offload_dir: Some(self.offload_dir.path().to_path_buf()),
+                ..fixture_environment()
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FsCreateService for MockFiles {
+        async fn create(
+            &self,
+            path: String,
+            content: String,
+            _overwrite: bool,
+            _capture_snapshot: bool,
+        ) -> anyhow::Result<FsCreateOutput> {
+            self.files.lock().unwrap().insert(path.clone(), content);
+            Ok(FsCreateOutput { path, before: None, warning: None })
+        }
+    }
+
+    /// Pipes the input through `tr a-z A-Z`, any other command fails
+    struct MockShell;
+
+    #[async_trait::async_trait]
+    impl ShellService for MockShell {
+        async fn execute(
+            &self,
+            command: String,
+            cwd: PathBuf,
+            keep_ansi: bool,
+        ) -> anyhow::Result<ShellOutput> {
+            self.pipe(command, cwd, String::new(), keep_ansi).await
+        }
+
+        async fn pipe(
+            &self,
+            command: String,
+            _cwd: PathBuf,
+            input: String,
+            _keep_ansi: bool,
+        ) -> anyhow::Result<ShellOutput> {
+            let (stdout, exit_code) = match command.as_str() {
+                "tr a-z A-Z" => (input.to_uppercase(), 0),
+                _ => (String::new(), 127),
+            };
+            Ok(ShellOutput {
+                output: CommandOutput {
+                    command,
+                    stdout,
+                    stderr: String::new(),
+                    exit_code: Some(exit_code),
+                },
+                shell: "/bin/bash".to_string(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_post_processor_only_transforms_registered_tool() {
+        let fixture = HashMap::from([
+            (
+                ToolName::new("forge_tool_process_shell"),
+                ToolPostProcessor::Command("tr a-z A-Z".to_string()),
+            ),
+            (
+                ToolName::new("forge_tool_net_fetch"),
+                ToolPostProcessor::Redact,
+            ),
+        ]);
+        let cwd = Path::new("/home/user/project");
+        let post_process = |name: &str, text: &str| {
+            let name = ToolName::new(name);
+            let fixture = &fixture;
+            async move {
+                post_process_output(&MockShell, fixture, &name, ToolOutput::text(text), cwd)
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let actual = vec![
+            post_process("forge_tool_process_shell", "cargo test passed").await,
+            post_process("forge_tool_net_fetch", "Mail jane@example.com").await,
+            post_process("forge_tool_fs_read", "jane@example.com").await,
+        ];
+
+        let expected = vec![
+            ToolOutput::text("CARGO TEST PASSED"),
+            ToolOutput::text("Mail [REDACTED EMAIL]"),
+            ToolOutput::text("jane@example.com"),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_offloaded_output_is_post_processed() {
+        let tool_name = ToolName::new("forge_tool_process_shell");
+        let processors = HashMap::from([(tool_name.clone(), ToolPostProcessor::Redact)]);
+        let stdout = (1..=10)
+            .map(|id| format!("{id},user{id}@example.com"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut fixture = Operation::Shell {
+            output: ShellOutput {
+                output: CommandOutput {
+                    command: "cat users.csv".to_string(),
+                    stdout,
+                    stderr: String::new(),
+                    exit_code: Some(0),
+                },
+                shell: "/bin/bash".to_string(),
+            },
+            test_results: None,
+        };
+        let files = MockFiles {
+            offload_dir: tempfile::tempdir().unwrap(),
+            files: Mutex::default(),
+        };
+
+        let processed = post_process_operation(
+            &MockShell,
+            &processors,
+            &tool_name,
+            &mut fixture,
+            Path::new("/home/user/project"),
+        )
+        .await
+        .unwrap();
+        fixture.to_create_temp(&files).await.unwrap();
+
+        let actual = files
+            .files
+            .lock()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        assert!(processed);
+        assert_eq!(actual.len(), 1);
+        assert!(actual[0].starts_with("1,[REDACTED EMAIL]\n2,[REDACTED EMAIL]"));
+        assert!(!actual[0].contains("@example.com"));
+    }
+
+    #[test]
+    fn test_post_response_command_replaces_answer() {
+        let fixture = CommandOutput {
+            command: "prettier --parser markdown".to_string(),
+            stdout: "# Done\n".to_string(),
+            stderr: String::new(),
+            exit_code: Some(0),
+        };
+
+        let actual = post_processed_answer(fixture).unwrap();
+
+        let expected = "# Done\n";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_failing_post_response_command_is_surfaced() {
+        let fixture = CommandOutput {
+            command: "./compliance-check".to_string(),
+            stdout: "# Done\n".to_string(),
+            stderr: "Answer mentions an internal hostname\n".to_string(),
+            exit_code: Some(2),
+        };
+
+        let actual = post_processed_answer(fixture).unwrap_err().to_string();
+
+        let expected = "The post-response command `./compliance-check` exited with status 2, the answer was withheld:\nAnswer mentions an internal hostname";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_call_without_explanation_is_rejected_when_required() {
+        let fixture = Tools::ForgeToolFsRead(FSRead {
+            path: "/home/user/project/src/main.rs".to_string(),
+            ..Default::default()
+        });
+
+        let actual = ensure_explained(&fixture, true).unwrap_err().to_string();
+
+        let expected = "Tool 'forge_tool_fs_read' was called without an explanation, call it again with an explanation of why it's used and how it contributes to the goal";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_call_without_explanation_is_accepted_when_not_required() {
+        let fixture = Tools::ForgeToolFsRead(FSRead {
+            path: "/home/user/project/src/main.rs".to_string(),
+            ..Default::default()
+        });
+
+        let actual = ensure_explained(&fixture, false);
+
+        assert!(actual.is_ok());
+    }
+
+    #[test]
+    fn test_content_with_read_line_numbers_is_rejected() {
+        let fixture = " 9\tfn main() {\n10\t    run();\n11\t}";
+
+        let actual = ensure_unnumbered(
+            "/home/user/project/src/main.rs",
+            fixture,
+            "fn main() {\n    run();\n}\n",
+            9,
+        )
+        .unwrap_err()
+        .to_string();
+
+        let expected = "The content for '/home/user/project/src/main.rs' starts every line with the line numbers of a numbered read, call the tool again with the content without them";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_content_without_line_numbers_is_accepted() {
+        let fixture = ["fn main() {\n    run();\n}", "1\tone\n3\tthree", "2024\t"];
+
+        let actual = fixture
+            .map(|content| ensure_unnumbered("/home/user/data.tsv", content, content, 1).is_ok());
+
+        let expected = [true, true, true];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_tsv_with_an_id_column_is_accepted() {
+        let fixture = "1\tada\tadmin\n2\tgrace\tuser\n3\tlinus\tuser\n";
+
+        let actual = ensure_unnumbered(
+            "/home/user/data/users.tsv",
+            fixture,
+            "1\tada\tuser\n2\tgrace\tuser\n",
+            1,
+        );
+
+        assert!(actual.is_ok());
+    }
+
+    #[test]
+    fn test_shell_cwd_within_sandbox_root_is_allowed() {
+        let fixture = Path::new("/home/user/project/crates/../crates/forge");
+
+        let actual = ensure_within_sandbox(fixture, Some(Path::new("/home/user/project")));
+
+        assert!(actual.is_ok());
+    }
+
+    #[test]
+    fn test_shell_cwd_outside_sandbox_root_is_blocked() {
+        let fixture = Path::new("/home/user/project/../other-project");
+
+        let actual = ensure_within_sandbox(fixture, Some(Path::new("/home/user/project")))
+            .unwrap_err()
+            .to_string();
+
+        let expected = "Working directory '/home/user/project/../other-project' is outside the sandbox root '/home/user/project', run commands within the sandbox root instead";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_shell_cwd_is_unrestricted_without_sandbox_root() {
+        let actual = ensure_within_sandbox(Path::new("/tmp"), None);
+
+        assert!(actual.is_ok());
     }
 }
@@ -14,7 +14,7 @@ use crate::agent_executor::AgentExecutor;
 use crate::error::Error;
 use crate::mcp_executor::McpExecutor;
 use crate::tool_executor::ToolExecutor;
-use crate::{McpService, Services};
+use crate::{EnvironmentService, McpService, Services};
 
 const TOOL_CALL_TIMEOUT: Duration = Duration::from_secs(300);
 
@@ -113,9 +113,10 @@ impl<S: Services> ToolRegistry<S> {
     pub async fn list(&self) -> anyhow::Result<Vec<ToolDefinition>> {
         let mcp_tools = self.mcp_executor.services.list().await?;
         let agent_tools = self.agent_executor.tool_agents().await?;
+        let env = self.mcp_executor.services.get_environment();
 
         let tools = Tools::iter()
-            .map(|tool| tool.definition())
+            .map(|tool| tool.definition_with_env(&env))
             .chain(mcp_tools.into_iter())
             .chain(agent_tools.into_iter())
             .collect::<Vec<_>>();
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use forge_domain::{
+    TaskList, ToolCallContext, ToolCallFull, ToolDefinition, ToolName, ToolOutput, Tools,
+    ToolsDiscriminants,
+};
+use strum::IntoEnumIterator;
+use tokio::sync::Mutex;
+
+use crate::error::Error;
+use crate::tool_executor::ToolExecutor;
+use crate::{EnvironmentService, Services};
+
+/// Built-in tools that need a user to talk to, which a client calling the
+/// tools doesn't provide
+const CONVERSATION_TOOLS: [ToolsDiscriminants; 2] = [
+    ToolsDiscriminants::ForgeToolFollowup,
+    ToolsDiscriminants::ForgeToolAttemptCompletion,
+];
+
+/// Serves forge's built-in tools to other clients, running them against the
+/// workspace outside of any conversation
+pub struct ToolServer<S> {
+    services: Arc<S>,
+    executor: ToolExecutor<S>,
+    /// State of the task list and scratchpad tools, kept between calls
+    context: Mutex<ToolCallContext>,
+}
+
+impl<S: Services> ToolServer<S> {
+    pub fn new(services: Arc<S>) -> Self {
+        Self {
+            executor: ToolExecutor::new(services.clone()),
+            services,
+            context: Mutex::new(ToolCallContext::new(TaskList::new())),
+        }
+    }
+
+    fn is_served(name: &ToolName) -> bool {
+        Tools::contains(name) && !CONVERSATION_TOOLS.iter().any(|tool| tool.name() == *name)
+    }
+
+    /// Definitions of the tools served
+    pub fn tools(&self) -> Vec<ToolDefinition> {
+        let env = self.services.get_environment();
+        Tools::iter()
+            .map(|tool| tool.definition_with_env(&env))
+            .filter(|definition| Self::is_served(&definition.name))
+            .collect()
+    }
+
+    /// Runs the tool call, calls are run one at a time as they share the task
+    /// list and scratchpad
+    pub async fn call(&self, call: ToolCallFull) -> anyhow::Result<ToolOutput> {
+        let mut context = self.context.lock().await;
+        self.call_in(call, &mut context).await
+    }
+
+    /// Runs the tool call with the given context, such as the one of a
+    /// conversation, instead of the one kept by the server
+    pub async fn call_in(
+        &self,
+        call: ToolCallFull,
+        context: &mut ToolCallContext,
+    ) -> anyhow::Result<ToolOutput> {
+        if !Self::is_served(&call.name) {
+            return Err(Error::NotFound(call.name).into());
+        }
+        self.executor.execute(call, context).await
+    }
+}
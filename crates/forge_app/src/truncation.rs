@@ -1,19 +1,61 @@
 use std::path::{Path, PathBuf};
 
+use forge_domain::{Environment, ToolsDiscriminants};
+use forge_template::Element;
+use serde_json::Value;
+
 use crate::utils::format_match;
-use crate::{FsCreateService, Match};
+use crate::{EnvironmentService, FsCreateService, Match};
+
+/// Note added to truncated tool output unless the environment configures
+/// another template
+pub const DEFAULT_TRUNCATION_TEMPLATE: &str =
+    "{shown}. To read more, call {tool} with the arguments {arguments}";
+
+/// Tells the model which part of a tool output was returned and the exact
+/// call that retrieves the rest
+#[derive(Debug)]
+pub struct TruncationNote {
+    shown: String,
+    tool: ToolsDiscriminants,
+    arguments: Value,
+}
+
+impl TruncationNote {
+    pub fn new(shown: impl Into<String>, tool: ToolsDiscriminants, arguments: Value) -> Self {
+        Self { shown: shown.into(), tool, arguments }
+    }
+
+    /// Renders the note with the template configured in the environment
+    pub fn render(&self, env: &Environment) -> String {
+        env.truncation_template
+            .as_deref()
+            .unwrap_or(DEFAULT_TRUNCATION_TEMPLATE)
+            .replace("{shown}", &self.shown)
+            .replace("{tool}", self.tool.name().as_str())
+            .replace("{arguments}", &self.arguments.to_string())
+    }
+
+    pub fn to_element(&self, env: &Environment) -> Element {
+        Element::new("truncated").text(self.render(env))
+    }
+}
 
-pub async fn create_temp_file<S: FsCreateService>(
+/// Offloads the content to a new file in the offload directory, where it can
+/// be read back in ranges
+pub async fn create_temp_file<S: EnvironmentService + FsCreateService>(
     services: &S,
     prefix: &str,
     ext: &str,
     content: &str,
 ) -> anyhow::Result<PathBuf> {
+    let dir = services.get_environment().offload_path();
+    tokio::fs::create_dir_all(&dir).await?;
     let path = tempfile::Builder::new()
         .disable_cleanup(true)
         .prefix(prefix)
         .suffix(ext)
-        .tempfile()?
+        .tempfile_in(dir)?
         .into_temp_path()
         .to_path_buf();
     services
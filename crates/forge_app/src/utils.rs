@@ -1,5 +1,8 @@
 use std::path::Path;
 
+use forge_display::{DiffFormat, DiffOptions, DiffResult};
+use forge_domain::Environment;
+
 use crate::{Match, MatchResult};
 
 /// Formats a path for display, converting absolute paths to relative when
@@ -32,6 +35,15 @@ pub fn format_display_path(path: &Path, cwd: &Path) -> String {
     }
 }
 
+/// Formats the diff of a file edit, leaving out the changes the environment
+/// configures as insignificant
+pub fn format_diff(old: &str, new: &str, env: &Environment) -> DiffResult {
+    let options = DiffOptions::default()
+        .ignore_whitespace(env.diff_ignore_whitespace)
+        .ignore_trailing_newline(env.diff_ignore_trailing_newline);
+    DiffFormat::format_with(old, new, options)
+}
+
 pub fn format_match(matched: &Match, base_dir: &Path) -> String {
     match &matched.result {
         Some(MatchResult::Error(err)) => format!("Error reading {}: {}", matched.path, err),
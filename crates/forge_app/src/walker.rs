@@ -1,4 +1,5 @@
 use std::path::PathBuf;
+use std::time::SystemTime;
 
 use derive_setters::Setters;
 
@@ -65,6 +66,8 @@ pub struct WalkedFile {
     pub file_name: Option<String>,
     /// Size in bytes
     pub size: u64,
+    /// Last modification time, when the platform reports it
+    pub modified: Option<SystemTime>,
 }
 
 impl WalkedFile {
@@ -1,7 +1,9 @@
+use std::borrow::Cow;
 use std::fmt;
 
 use console::{Style, style};
-use similar::{ChangeTag, TextDiff};
+use derive_setters::Setters;
+use similar::{Algorithm, ChangeTag, capture_diff_slices, group_diff_ops};
 
 struct Line(Option<usize>);
 
@@ -35,19 +37,65 @@ impl DiffResult {
     }
 }
 
+/// Changes left out of a diff, so that only the significant ones are shown.
+/// The files themselves are never normalized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Setters)]
+pub struct DiffOptions {
+    /// Lines that only differ in their whitespace are considered equal
+    pub ignore_whitespace: bool,
+    /// A newline added or removed at the end of the file isn't a change
+    pub ignore_trailing_newline: bool,
+}
+
+impl DiffOptions {
+    /// What a line is compared by
+    fn normalize<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        if self.ignore_whitespace {
+            Cow::Owned(line.split_whitespace().collect::<Vec<_>>().join(" "))
+        } else if self.ignore_trailing_newline {
+            Cow::Borrowed(line.trim_end_matches(['\n', '\r']))
+        } else {
+            Cow::Borrowed(line)
+        }
+    }
+}
+
 pub struct DiffFormat;
 
 impl DiffFormat {
     pub fn format(old: &str, new: &str) -> DiffResult {
-        let diff = TextDiff::from_lines(old, new);
-        let ops = diff.grouped_ops(3);
+        Self::format_with(old, new, DiffOptions::default())
+    }
+
+    /// Formats the diff between the texts, leaving out the changes the options
+    /// ignore. Changed lines are shown as they are in the texts.
+    pub fn format_with(old: &str, new: &str, options: DiffOptions) -> DiffResult {
+        let old_lines = old.split_inclusive('\n').collect::<Vec<_>>();
+        let new_lines = new.split_inclusive('\n').collect::<Vec<_>>();
+        let old_keys = old_lines
+            .iter()
+            .map(|line| options.normalize(line))
+            .collect::<Vec<_>>();
+        let new_keys = new_lines
+            .iter()
+            .map(|line| options.normalize(line))
+            .collect::<Vec<_>>();
+        let ops = group_diff_ops(
+            capture_diff_slices(Algorithm::Myers, &old_keys, &new_keys),
+            3,
+        );
         let mut output = String::new();
 
         let mut lines_added = 0;
         let mut lines_removed = 0;
 
         if ops.is_empty() {
-            output.push_str(&format!("{}\n", style("No changes applied").dim()));
+            let message = if old == new {
+                "No changes applied"
+            } else {
+                "No significant changes applied"
+            };
+            output.push_str(&format!("{}\n", style(message).dim()));
 
             return DiffResult { result: output, lines_added, lines_removed };
         }
@@ -57,7 +105,7 @@ impl DiffFormat {
                 output.push_str(&format!("{}\n", style("...").dim()));
             }
             for op in group {
-                for change in diff.iter_inline_changes(op) {
+                for change in op.iter_changes(&old_lines, &new_lines) {
                     let (sign, s) = match change.tag() {
                         ChangeTag::Delete => {
                             lines_removed += 1;
@@ -77,10 +125,9 @@ impl DiffFormat {
                         s.apply_to(sign),
                     ));
 
-                    for (_, value) in change.iter_strings_lossy() {
-                        output.push_str(&format!("{}", s.apply_to(value)));
-                    }
-                    if change.missing_newline() {
+                    let value = change.value();
+                    output.push_str(&format!("{}", s.apply_to(value)));
+                    if !value.ends_with('\n') {
                         output.push('\n');
                     }
                 }
@@ -95,6 +142,7 @@ impl DiffFormat {
 mod tests {
     use console::strip_ansi_codes;
     use insta::assert_snapshot;
+    use pretty_assertions::assert_eq;
 
     use super::*;
 
@@ -139,4 +187,50 @@ mod tests {
         assert_eq!(diff.lines_removed(), 1);
         assert_snapshot!(clean_diff);
     }
+
+    #[test]
+    fn test_whitespace_only_change_is_not_significant() {
+        let old = "fn main() {\n    run();\n}\n";
+        let new = "fn main() {\n\trun();  \n}";
+        let options = DiffOptions::default()
+            .ignore_whitespace(true)
+            .ignore_trailing_newline(true);
+
+        let diff = DiffFormat::format_with(old, new, options);
+
+        let actual = strip_ansi_codes(diff.diff()).to_string();
+        let expected = "No significant changes applied\n";
+        assert_eq!(actual, expected);
+        assert_eq!(diff.lines_added(), 0);
+        assert_eq!(diff.lines_removed(), 0);
+    }
+
+    #[test]
+    fn test_trailing_newline_only_change_is_not_significant() {
+        let old = "line 1\nline 2";
+        let new = "line 1\nline 2\n";
+        let options = DiffOptions::default().ignore_trailing_newline(true);
+
+        let actual =
+            strip_ansi_codes(DiffFormat::format_with(old, new, options).diff()).to_string();
+
+        let expected = "No significant changes applied\n";
+        assert_eq!(actual, expected);
+        // Whitespace within the lines is still a change
+        let diff = DiffFormat::format_with("a b\n", "a  b\n", options);
+        assert_eq!(diff.lines_added(), 1);
+    }
+
+    #[test]
+    fn test_significant_changes_are_shown_from_the_old_text() {
+        let old = "one\ntwo\n";
+        let new = "one  \nthree\n";
+        let options = DiffOptions::default().ignore_whitespace(true);
+
+        let actual =
+            strip_ansi_codes(DiffFormat::format_with(old, new, options).diff()).to_string();
+
+        let expected = "1   1    | one\n2        |-two\n    2    |+three\n";
+        assert_eq!(actual, expected);
+    }
 }
@@ -3,7 +3,7 @@ pub mod grep;
 pub mod markdown;
 pub mod title;
 
-pub use diff::DiffFormat;
+pub use diff::{DiffFormat, DiffOptions, DiffResult};
 pub use grep::GrepFormat;
 pub use markdown::MarkdownFormat;
 pub use title::*;
@@ -1,4 +1,6 @@
 use std::borrow::Cow;
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
 
 use derive_more::derive::Display;
 use derive_setters::Setters;
@@ -11,8 +13,8 @@ use crate::merge::Key;
 use crate::temperature::Temperature;
 use crate::template::Template;
 use crate::{
-    Context, Error, EventContext, MaxTokens, ModelId, Result, SystemContext, ToolDefinition,
-    ToolName, TopK, TopP,
+    Context, Error, EventContext, MaxTokens, Model, ModelId, ResponseFormat, Result, SystemContext,
+    SystemContextSection, ToolChoice, ToolDefinition, ToolName, ToolsDiscriminants, TopK, TopP,
 };
 
 // Unique identifier for an agent
@@ -100,6 +102,13 @@ pub struct Agent {
     #[merge(strategy = crate::merge::option)]
     pub max_walker_depth: Option<usize>,
 
+    /// Directory the agent works in, relative to the project root. The files
+    /// listed in its system prompt, the working directory it's given and the
+    /// repository tree and system info tools start from there
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub working_dir: Option<PathBuf>,
+
     /// Configuration for automatic context compaction
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[merge(strategy = crate::merge::option)]
@@ -110,6 +119,13 @@ pub struct Agent {
     #[merge(strategy = crate::merge::option)]
     pub custom_rules: Option<String>,
 
+    /// Sections of the system context to include in the system prompt, in
+    /// the order they should appear. If not provided, the layout of the
+    /// system prompt template is used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub system_context_sections: Option<Vec<SystemContextSection>>,
+
     /// Temperature used for agent
     ///
     /// Temperature controls the randomness in the model's output.
@@ -168,6 +184,47 @@ pub struct Agent {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[merge(strategy = crate::merge::option)]
     pub reasoning: Option<ReasoningConfig>,
+
+    /// Maximum number of reasoning tokens a response can spend before
+    /// producing an answer. Once exceeded the response is stopped and the
+    /// turn concludes, avoiding runaway thinking costs. Models supporting
+    /// reasoning are also sent it as their thinking token cap
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub reasoning_budget: Option<usize>,
+
+    /// Response format for the agent's final output.
+    /// When a JSON schema is provided, the completion result is validated
+    /// against it and any violations are reported back to the model
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub response_format: Option<ResponseFormat>,
+
+    /// Forces the model to call a tool, a specific tool or none at all.
+    /// When set to a tool name, the tool is only forced on the first request
+    /// of each turn
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub tool_choice: Option<ToolChoice>,
+
+    /// Whether responses are streamed from the provider. Set to false for
+    /// proxies that mangle streamed responses, so the whole completion is
+    /// requested at once instead. Defaults to true
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub stream: Option<bool>,
+
+    /// Whether consecutive read-only tool calls of a response, like file
+    /// reads, searches and fetches, are run at once. Other tools always run
+    /// one at a time in the order they were called. Defaults to true
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub concurrent_tool_calls: Option<bool>,
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize, Merge, Setters, JsonSchema, PartialEq)]
@@ -228,16 +285,50 @@ impl Agent {
             subscribe: Default::default(),
             max_turns: Default::default(),
             max_walker_depth: Default::default(),
+            working_dir: Default::default(),
             compact: Default::default(),
             custom_rules: Default::default(),
+            system_context_sections: Default::default(),
             temperature: Default::default(),
             top_p: Default::default(),
             top_k: Default::default(),
             max_tokens: Default::default(),
             reasoning: Default::default(),
+            reasoning_budget: Default::default(),
+            response_format: Default::default(),
+            tool_choice: Default::default(),
+            stream: Default::default(),
+            concurrent_tool_calls: Default::default(),
         }
     }
 
+    /// Returns the directory the agent works in, which is the project root
+    /// unless the agent is scoped to one of its subdirectories. Working
+    /// directories that are absolute or lead out of the project root through
+    /// `..` are rejected.
+    pub fn cwd(&self, project_root: &Path) -> Result<PathBuf> {
+        let Some(working_dir) = &self.working_dir else {
+            return Ok(project_root.to_path_buf());
+        };
+        let mut cwd = project_root.to_path_buf();
+        for component in working_dir.components() {
+            match component {
+                Component::Normal(name) => cwd.push(name),
+                Component::CurDir => {}
+                Component::ParentDir if cwd != project_root => {
+                    cwd.pop();
+                }
+                _ => {
+                    return Err(Error::WorkingDirOutsideProject(
+                        self.id.clone(),
+                        working_dir.clone(),
+                    ));
+                }
+            }
+        }
+        Ok(cwd)
+    }
+
     pub fn tool_definition(&self) -> Result<ToolDefinition> {
         if self.description.is_none() || self.description.as_ref().is_none_or(|d| d.is_empty()) {
             return Err(Error::MissingAgentDescription(self.id.clone()));
@@ -245,6 +336,22 @@ impl Agent {
         Ok(ToolDefinition::new(self.id.as_str().to_string())
             .description(self.description.clone().unwrap()))
     }
+    /// Returns the definitions of the tools this agent is allowed to call. The
+    /// completion tool is always included so that the agent can finish.
+    pub fn allowed_tools(&self, definitions: &[ToolDefinition]) -> Vec<ToolDefinition> {
+        let completion = ToolsDiscriminants::ForgeToolAttemptCompletion;
+        let allowed = self.tools.iter().flatten().collect::<HashSet<_>>();
+        let mut tools = definitions
+            .iter()
+            .filter(|tool| tool.name != completion.name())
+            .filter(|tool| allowed.contains(&tool.name))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        tools.push(completion.definition());
+        tools
+    }
+
     /// Checks if compaction should be applied
     pub fn should_compact(&self, context: &Context, token_count: usize) -> bool {
         // Return false if compaction is not configured
@@ -263,6 +370,20 @@ impl Agent {
             subscribe_list.push(event_string);
         }
     }
+
+    /// Warns when the model can't call tools natively although the agent
+    /// relies on tools, and the agent hasn't opted into XML tool calls
+    pub fn tool_support_warning(&self, model: &Model) -> Option<String> {
+        let requires_tools = self.tools.as_ref().is_some_and(|tools| !tools.is_empty());
+        if !requires_tools || self.tool_supported.is_some() || model.tools_supported != Some(false)
+        {
+            return None;
+        }
+        Some(format!(
+            "{} doesn't support tool calling, which the {} agent relies on. Tools will be described in the prompt instead, which is less reliable. Set `tool_supported: false` on the agent to use XML tool calls on purpose, or switch to a model marked with 🛠️ using /model.",
+            model.id, self.id
+        ))
+    }
 }
 
 impl Key for Agent {
@@ -637,4 +758,85 @@ mod tests {
         ];
         assert_eq!(actual, &expected);
     }
+
+    #[test]
+    fn test_allowed_tools_filters_by_agent_tools() {
+        let definitions = vec![
+            ToolsDiscriminants::ForgeToolFsRead.definition(),
+            ToolsDiscriminants::ForgeToolFsSearch.definition(),
+            ToolsDiscriminants::ForgeToolProcessShell.definition(),
+        ];
+        let fixture = Agent::new("test-agent").tools(vec![
+            ToolsDiscriminants::ForgeToolFsRead.name(),
+            ToolsDiscriminants::ForgeToolProcessShell.name(),
+        ]);
+
+        let actual = fixture
+            .allowed_tools(&definitions)
+            .into_iter()
+            .map(|tool| tool.name)
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            ToolsDiscriminants::ForgeToolFsRead.name(),
+            ToolsDiscriminants::ForgeToolProcessShell.name(),
+            ToolsDiscriminants::ForgeToolAttemptCompletion.name(),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_tool_support_warning_for_model_without_tools() {
+        let fixture = Agent::new("forge").tools(vec![ToolName::new("forge_tool_fs_read")]);
+        let model = Model {
+            id: ModelId::new("text-only"),
+            name: None,
+            description: None,
+            context_length: None,
+            tools_supported: Some(false),
+            supports_parallel_tool_calls: None,
+            supports_reasoning: None,
+            supports_vision: None,
+        };
+
+        let actual = fixture.tool_support_warning(&model).is_some();
+        assert!(actual);
+
+        // Opting into XML tool calls, or a model with tool support, is fine
+        let actual = fixture
+            .clone()
+            .tool_supported(false)
+            .tool_support_warning(&model);
+        assert_eq!(actual, None);
+        let actual = fixture.tool_support_warning(&model.tools_supported(Some(true)));
+        assert_eq!(actual, None);
+    }
+
+    #[test]
+    fn test_working_dir_resolves_within_the_project_root() {
+        let fixture = Agent::new("docs").working_dir(PathBuf::from("docs/../guides/./api"));
+
+        let actual = fixture.cwd(Path::new("/home/user/project")).unwrap();
+
+        let expected = PathBuf::from("/home/user/project/guides/api");
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_working_dir_outside_the_project_root_is_rejected() {
+        let fixture = ["docs/../..", "../other-project", "/etc"];
+
+        let actual = fixture
+            .into_iter()
+            .map(|working_dir| {
+                Agent::new("docs")
+                    .working_dir(PathBuf::from(working_dir))
+                    .cwd(Path::new("/home/user/project"))
+                    .is_err()
+            })
+            .collect::<Vec<_>>();
+
+        let expected = vec![true, true, true];
+        assert_eq!(actual, expected);
+    }
 }
@@ -0,0 +1,96 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+/// Files created, modified or removed by the agent over the course of a
+/// conversation. Each path is listed once, under its net change.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangedFiles {
+    #[serde(default)]
+    pub created: BTreeSet<String>,
+    #[serde(default)]
+    pub modified: BTreeSet<String>,
+    #[serde(default)]
+    pub removed: BTreeSet<String>,
+}
+
+impl ChangedFiles {
+    /// Records a file that didn't exist before being written
+    pub fn record_created(&mut self, path: impl Into<String>) {
+        let path = path.into();
+        // A file removed and then written again was effectively modified
+        if self.removed.remove(&path) {
+            self.modified.insert(path);
+        } else {
+            self.created.insert(path);
+        }
+    }
+
+    /// Records a change to an existing file
+    pub fn record_modified(&mut self, path: impl Into<String>) {
+        let path = path.into();
+        if !self.created.contains(&path) {
+            self.modified.insert(path);
+        }
+    }
+
+    /// Records the removal of a file
+    pub fn record_removed(&mut self, path: impl Into<String>) {
+        let path = path.into();
+        self.modified.remove(&path);
+        // Files created and then removed leave no trace
+        if !self.created.remove(&path) {
+            self.removed.insert(path);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn set(paths: &[&str]) -> BTreeSet<String> {
+        paths.iter().map(|path| path.to_string()).collect()
+    }
+
+    #[test]
+    fn test_records_net_changes() {
+        let mut fixture = ChangedFiles::default();
+
+        fixture.record_created("/project/new.rs");
+        fixture.record_modified("/project/new.rs");
+        fixture.record_modified("/project/lib.rs");
+        fixture.record_created("/project/scratch.txt");
+        fixture.record_removed("/project/scratch.txt");
+        fixture.record_modified("/project/old.rs");
+        fixture.record_removed("/project/old.rs");
+
+        let actual = fixture;
+
+        let expected = ChangedFiles {
+            created: set(&["/project/new.rs"]),
+            modified: set(&["/project/lib.rs"]),
+            removed: set(&["/project/old.rs"]),
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_recreating_removed_file_is_a_modification() {
+        let mut fixture = ChangedFiles::default();
+
+        fixture.record_removed("/project/main.rs");
+        fixture.record_created("/project/main.rs");
+
+        let actual = fixture;
+
+        let expected = ChangedFiles { modified: set(&["/project/main.rs"]), ..Default::default() };
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,10 +1,13 @@
 use std::time::Duration;
 
-use crate::{ToolCallFull, ToolResult, Usage};
+use serde::Serialize;
+
+use crate::{ToolCallFull, ToolName, ToolResult, Usage};
 
 /// Events that are emitted by the agent for external consumption. This includes
 /// events for all internal state changes.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum ChatResponse {
     Text {
         text: String,
@@ -15,6 +18,12 @@ pub enum ChatResponse {
         content: String,
     },
     ToolCallStart(ToolCallFull),
+    /// Size of the arguments of a tool call that is still being streamed.
+    /// Only meant for display, the call is reported once it's complete.
+    ToolCallProgress {
+        name: ToolName,
+        bytes: usize,
+    },
     ToolCallEnd(ToolResult),
     Usage(Usage),
     RetryAttempt {
@@ -27,15 +36,30 @@ pub enum ChatResponse {
     Reasoning {
         content: String,
     },
+    /// Reasoning tokens spent so far by the response that is still being
+    /// streamed, along with the budget they are capped at
+    ReasoningProgress {
+        tokens: usize,
+        budget: Option<usize>,
+    },
+    /// Something about the setup that is likely to cause trouble, with advice
+    /// on how to fix it
+    Warning {
+        message: String,
+    },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum InterruptionReason {
     MaxToolFailurePerTurnLimitReached { limit: u64 },
     MaxRequestPerTurnLimitReached { limit: u64 },
+    TokenBudgetExhausted { limit: u64, used: u64 },
+    MaxReasoningTokensReached { limit: u64 },
+    EmptyResponseRetriesExhausted { limit: u64 },
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct Cause(String);
 
 impl Cause {
@@ -4,7 +4,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
-use crate::{Context, ModelId, Role};
+use crate::{Context, ModelId, Role, ToolName};
 
 /// Configuration for automatic context compaction
 #[derive(Debug, Clone, Serialize, Deserialize, Merge, Setters, JsonSchema, PartialEq)]
@@ -64,6 +64,55 @@ pub struct Compact {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[merge(strategy = crate::merge::option)]
     pub on_turn_end: Option<bool>,
+
+    /// Tools whose results are never summarized away during compaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub pinned_tools: Option<Vec<ToolName>>,
+
+    /// Number of turns after a compaction during which compaction isn't
+    /// triggered again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub cooldown_turns: Option<usize>,
+
+    /// Number of tokens that have to be added to the context after a
+    /// compaction before compaction is triggered again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub cooldown_tokens: Option<usize>,
+
+    /// Tool outputs estimated above this number of tokens are replaced with a
+    /// summary generated by the compaction model instead of being added to
+    /// the context as is. Outputs are never summarized when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub tool_output_threshold: Option<usize>,
+}
+
+/// Size of the context right after it was compacted, used to tell how much it
+/// grew since
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CompactionMark {
+    pub turns: usize,
+    pub tokens: usize,
+}
+
+impl CompactionMark {
+    pub fn new(context: &Context) -> Self {
+        Self {
+            turns: turn_count(context),
+            tokens: context.token_count_approx(),
+        }
+    }
+}
+
+fn turn_count(context: &Context) -> usize {
+    context
+        .messages
+        .iter()
+        .filter(|message| message.has_role(Role::User))
+        .count()
 }
 
 fn deserialize_percentage<'de, D>(deserializer: D) -> Result<f64, D::Error>
@@ -112,18 +161,50 @@ impl Compact {
             eviction_window: 0.2, // Default to 20% compaction
             retention_window: 0,
             on_turn_end: None,
+            pinned_tools: None,
+            cooldown_turns: None,
+            cooldown_tokens: None,
+            tool_output_threshold: None,
         }
     }
 
     /// Determines if compaction should be triggered based on the current
     /// context
     pub fn should_compact(&self, context: &Context, token_count: usize) -> bool {
+        if self.is_cooling_down(context) {
+            debug!("Compaction skipped during cooldown");
+            return false;
+        }
+
         self.should_compact_due_to_tokens(token_count)
             || self.should_compact_due_to_turns(context)
             || self.should_compact_due_to_messages(context)
             || self.should_compact_on_turn_end(context)
     }
 
+    /// Checks if the context was compacted recently enough for another
+    /// compaction to be held back. The cooldown ends once either configured
+    /// limit is reached. Tokens are estimated like the mark is, the usage
+    /// reported by the provider covers more than the context.
+    fn is_cooling_down(&self, context: &Context) -> bool {
+        let Some(mark) = context.last_compaction else {
+            return false;
+        };
+        let turns_elapsed = self
+            .cooldown_turns
+            .map(|turns| turn_count(context).saturating_sub(mark.turns) >= turns);
+        let tokens_elapsed = self
+            .cooldown_tokens
+            .map(|tokens| context.token_count_approx().saturating_sub(mark.tokens) >= tokens);
+
+        match (turns_elapsed, tokens_elapsed) {
+            (None, None) => false,
+            (turns_elapsed, tokens_elapsed) => {
+                !(turns_elapsed.unwrap_or(false) || tokens_elapsed.unwrap_or(false))
+            }
+        }
+    }
+
     /// Checks if compaction should be triggered due to token count exceeding
     /// threshold
     fn should_compact_due_to_tokens(&self, token_count: usize) -> bool {
@@ -140,12 +221,7 @@ impl Compact {
     /// threshold
     fn should_compact_due_to_turns(&self, context: &Context) -> bool {
         if let Some(turn_threshold) = self.turn_threshold {
-            context
-                .messages
-                .iter()
-                .filter(|message| message.has_role(Role::User))
-                .count()
-                >= turn_threshold
+            turn_count(context) >= turn_threshold
         } else {
             false
         }
@@ -183,7 +259,7 @@ mod tests {
     use pretty_assertions::assert_eq;
 
     use super::*;
-    use crate::{ContextMessage, TextMessage};
+    use crate::{ContextMessage, TextMessage, TokenCount, Usage};
 
     /// Creates a Context from a condensed string pattern where:
     /// - 'u' = User message
@@ -490,4 +566,60 @@ mod tests {
         let expected = true;
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_should_compact_waits_for_cooldown_turns() {
+        let fixture = Compact::new(ModelId::new("test-model"))
+            .token_threshold(100_usize)
+            .cooldown_turns(2_usize);
+        let mark = CompactionMark { turns: 1, tokens: 50 };
+
+        let actual = [
+            fixture.should_compact(&ctx("ua").last_compaction(mark), 150),
+            fixture.should_compact(&ctx("uaua").last_compaction(mark), 150),
+            fixture.should_compact(&ctx("uauau").last_compaction(mark), 150),
+        ];
+
+        let expected = [false, false, true];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_should_compact_waits_for_cooldown_tokens() {
+        let fixture = Compact::new(ModelId::new("test-model"))
+            .token_threshold(100_usize)
+            .cooldown_tokens(100_usize);
+        let compacted = ctx("ua");
+        let mark = CompactionMark::new(&compacted);
+        let grown = compacted
+            .clone()
+            .add_message(ContextMessage::user("x".repeat(400), None));
+
+        let actual = [
+            fixture.should_compact(&compacted.last_compaction(mark), 150),
+            fixture.should_compact(&grown.last_compaction(mark), 150),
+        ];
+
+        let expected = [false, true];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_should_compact_cooldown_ignores_the_usage_reported_by_the_provider() {
+        let fixture = Compact::new(ModelId::new("test-model"))
+            .token_threshold(100_usize)
+            .cooldown_tokens(100_usize);
+        let compacted = ctx("ua");
+        let mark = CompactionMark::new(&compacted);
+        // The reported usage includes the system prompt and tool definitions, so
+        // it's far above the estimate of the messages right after compacting
+        let context = compacted
+            .last_compaction(mark)
+            .usage(Usage { total_tokens: TokenCount::Actual(5000), ..Default::default() });
+
+        let actual = fixture.should_compact(&context, *context.token_count());
+
+        let expected = false;
+        assert_eq!(actual, expected);
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{Context, Role};
+use crate::{Context, ContextMessage, Role};
 
 /// Strategy for context compaction that unifies different compaction approaches
 #[derive(Debug, Clone)]
@@ -146,7 +146,50 @@ fn find_sequence_preserving_last_n(
 
     // Return the sequence only if it has at least one message
     if end >= start {
-        Some((start, end))
+        exclude_pinned(messages, start, end)
+    } else {
+        None
+    }
+}
+
+/// Narrows the sequence down so that it contains no pinned tool result. A
+/// pinned result is kept along with the tool call it answers and the results
+/// next to it, which splits the sequence into segments; the first segment
+/// with at least two messages is returned.
+fn exclude_pinned(messages: &[ContextMessage], start: usize, end: usize) -> Option<(usize, usize)> {
+    if !messages[start..=end].iter().any(ContextMessage::is_pinned) {
+        return Some((start, end));
+    }
+
+    let mut segment_start = start;
+    let mut index = start;
+    while index <= end {
+        if !messages[index].is_pinned() {
+            index += 1;
+            continue;
+        }
+
+        let mut block_start = index;
+        while block_start > segment_start && !messages[block_start].has_tool_call() {
+            block_start -= 1;
+        }
+        if block_start > segment_start && block_start - segment_start >= 2 {
+            return Some((segment_start, block_start - 1));
+        }
+
+        let mut block_end = index;
+        while messages
+            .get(block_end + 1)
+            .is_some_and(|message| message.has_tool_result())
+        {
+            block_end += 1;
+        }
+        segment_start = block_end + 1;
+        index = segment_start;
+    }
+
+    if end >= segment_start && end - segment_start >= 1 {
+        Some((segment_start, end))
     } else {
         None
     }
@@ -158,7 +201,7 @@ mod tests {
     use serde_json::json;
 
     use super::*;
-    use crate::{ContextMessage, ModelId, ToolCallFull, ToolCallId, ToolName, ToolResult};
+    use crate::{ModelId, ToolCallFull, ToolCallId, ToolName, ToolResult};
 
     fn context_from_pattern(pattern: impl ToString) -> Context {
         let model_id = ModelId::new("gpt-4");
@@ -202,6 +245,11 @@ mod tests {
                 'r' => {
                     context = context.add_message(ContextMessage::tool_result(tool_result.clone()))
                 }
+                'p' => {
+                    context = context.add_message(ContextMessage::tool_result(
+                        tool_result.clone().pinned(true),
+                    ))
+                }
                 _ => panic!("Invalid character in test pattern: {c}"),
             }
         }
@@ -383,6 +431,31 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_sequence_excludes_pinned_tool_results() {
+        let actual = seq("sutrtpua", 0);
+        let expected = "s[utr]tpua";
+        assert_eq!(actual, expected);
+
+        // Too little is left before the pinned result, so what follows is compacted
+        let actual = seq("sutputrua", 0);
+        let expected = "sutp[utrua]";
+        assert_eq!(actual, expected);
+
+        // Results of the same parallel call stay with the pinned one
+        let actual = seq("sutrtrpruaua", 0);
+        let expected = "s[utr]trpruaua";
+        assert_eq!(actual, expected);
+
+        let actual = seq("sutpua", 0);
+        let expected = "sutp[ua]";
+        assert_eq!(actual, expected);
+
+        let actual = seq("sutpu", 0);
+        let expected = "sutpu";
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_compact_strategy_to_fixed_conversion() {
         // Create a simple context using 'sua' DSL: system, user, assistant
@@ -1,17 +1,20 @@
+use std::collections::HashSet;
 use std::fmt::Display;
 use std::ops::Deref;
 
 use derive_more::derive::{Display, From};
 use derive_setters::Setters;
+use forge_template::Element;
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
-use super::{ToolCallFull, ToolResult};
+use super::{ToolCallFull, ToolName, ToolResult};
 use crate::temperature::Temperature;
 use crate::top_k::TopK;
 use crate::top_p::TopP;
 use crate::{
-    ConversationId, Image, ModelId, ReasoningFull, ToolChoice, ToolDefinition, ToolValue, Usage,
+    CompactionMark, ConversationId, Image, ModelId, ReasoningFull, ResponseFormat, ToolChoice,
+    ToolDefinition, ToolOutput, ToolValue, Usage,
 };
 
 /// Represents a message being sent to the LLM provider
@@ -154,6 +157,11 @@ impl ContextMessage {
         }
     }
 
+    /// Whether this is a tool result that compaction has to leave untouched
+    pub fn is_pinned(&self) -> bool {
+        matches!(self, ContextMessage::Tool(result) if result.pinned)
+    }
+
     pub fn has_tool_call(&self) -> bool {
         match self {
             ContextMessage::Text(message) => message.tool_calls.is_some(),
@@ -258,7 +266,15 @@ pub struct Context {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub reasoning: Option<crate::agent::ReasoningConfig>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
+    /// Whether the response is streamed, providers stream when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub usage: Option<Usage>,
+    /// Size of the context right after it was last compacted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_compaction: Option<CompactionMark>,
 }
 
 impl Context {
@@ -291,22 +307,15 @@ impl Context {
         self
     }
 
-    /// Updates the set system message
-    pub fn set_first_system_message(mut self, content: impl Into<String>) -> Self {
-        if self.messages.is_empty() {
-            self.add_message(ContextMessage::system(content.into()))
-        } else {
-            if let Some(ContextMessage::Text(content_message)) = self.messages.get_mut(0) {
-                if content_message.role == Role::System {
-                    content_message.content = content.into();
-                } else {
-                    self.messages
-                        .insert(0, ContextMessage::system(content.into()));
-                }
-            }
-
-            self
-        }
+    /// Makes the content the only system message, placed first. System
+    /// messages left over from another agent or an earlier workflow are
+    /// dropped, as some providers reject more than one.
+    pub fn set_system_message(mut self, content: impl Into<String>) -> Self {
+        self.messages
+            .retain(|message| !message.has_role(Role::System));
+        self.messages
+            .insert(0, ContextMessage::system(content.into()));
+        self
     }
 
     /// Converts the context to textual format
@@ -360,14 +369,139 @@ impl Context {
 
         match actual {
             TokenCount::Actual(actual) if actual > 0 => TokenCount::Actual(actual),
-            _ => TokenCount::Approx(
-                self.messages
-                    .iter()
-                    .map(|m| m.token_count_approx())
-                    .sum::<usize>(),
-            ),
+            _ => TokenCount::Approx(self.token_count_approx()),
         }
     }
+
+    /// Estimates the number of tokens across all messages, ignoring the usage
+    /// reported by the provider
+    pub fn token_count_approx(&self) -> usize {
+        self.messages.iter().map(|m| m.token_count_approx()).sum()
+    }
+
+    /// Pins the most recent tool result so that compaction keeps it as is.
+    /// Returns the name of the tool it came from, if there was any.
+    pub fn pin_last_tool_result(&mut self) -> Option<ToolName> {
+        self.messages
+            .iter_mut()
+            .rev()
+            .find_map(|message| match message {
+                ContextMessage::Tool(result) => {
+                    result.pinned = true;
+                    Some(result.name.clone())
+                }
+                _ => None,
+            })
+    }
+
+    /// Removes the messages at the given indices. A tool call and its results
+    /// are removed together, whichever of them was selected, so that every
+    /// remaining call still has its results.
+    pub fn remove_messages(mut self, indices: &[usize]) -> Self {
+        let mut removed = HashSet::new();
+        for &index in indices {
+            if index >= self.messages.len() {
+                continue;
+            }
+
+            // A result is removed along with the call it answers
+            let mut start = index;
+            while start > 0 && self.messages[start].has_tool_result() {
+                start -= 1;
+            }
+            if !self.messages[start].has_tool_call() {
+                start = index;
+            }
+
+            // A call is removed along with all of its results
+            let mut end = start;
+            if self.messages[start].has_tool_call() {
+                while self
+                    .messages
+                    .get(end + 1)
+                    .is_some_and(|message| message.has_tool_result())
+                {
+                    end += 1;
+                }
+            }
+            removed.extend(start..=end.max(index));
+        }
+
+        self.messages = self
+            .messages
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| !removed.contains(index))
+            .map(|(_, message)| message)
+            .collect();
+        self
+    }
+
+    /// Removes the dialogue, keeping only the system messages and the pinned
+    /// tool results. A pinned result is kept along with the tool call it
+    /// answers and the results next to it, so that the call still has all of
+    /// its results.
+    pub fn clear_messages(mut self) -> Self {
+        let mut kept = self
+            .messages
+            .iter()
+            .enumerate()
+            .filter(|(_, message)| message.has_role(Role::System))
+            .map(|(index, _)| index)
+            .collect::<HashSet<_>>();
+        for index in 0..self.messages.len() {
+            if !self.messages[index].is_pinned() {
+                continue;
+            }
+            let mut start = index;
+            while start > 0 && !self.messages[start].has_tool_call() {
+                start -= 1;
+            }
+            let mut end = index;
+            while self
+                .messages
+                .get(end + 1)
+                .is_some_and(|message| message.has_tool_result())
+            {
+                end += 1;
+            }
+            kept.extend(start..=end);
+        }
+
+        self.messages = self
+            .messages
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| kept.contains(index))
+            .map(|(_, message)| message)
+            .collect();
+        // The usage reported by the provider was for the dialogue cleared above
+        self.usage = None;
+        self
+    }
+
+    /// Collapses the output of every tool result into a short placeholder,
+    /// except for pinned ones. The tool calls themselves are kept so the
+    /// conversation stays coherent.
+    pub fn trim_tool_results(mut self) -> Self {
+        self.messages = self
+            .messages
+            .into_iter()
+            .map(|message| match message {
+                ContextMessage::Tool(result) if !result.pinned => {
+                    let output = ToolOutput::text(
+                        Element::new("trimmed").text("Tool output removed to free up context"),
+                    )
+                    .is_error(result.is_error());
+                    ContextMessage::Tool(ToolResult { output, ..result })
+                }
+                message => message,
+            })
+            .collect();
+        // The usage reported by the provider no longer matches the trimmed messages
+        self.usage = None;
+        self
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -428,7 +562,7 @@ mod tests {
     fn test_override_system_message() {
         let request = Context::default()
             .add_message(ContextMessage::system("Initial system message"))
-            .set_first_system_message("Updated system message");
+            .set_system_message("Updated system message");
 
         assert_eq!(
             request.messages[0],
@@ -438,7 +572,7 @@ mod tests {
 
     #[test]
     fn test_set_system_message() {
-        let request = Context::default().set_first_system_message("A system message");
+        let request = Context::default().set_system_message("A system message");
 
         assert_eq!(
             request.messages[0],
@@ -451,7 +585,7 @@ mod tests {
         let model = ModelId::new("test-model");
         let request = Context::default()
             .add_message(ContextMessage::user("Do something", Some(model)))
-            .set_first_system_message("A system message");
+            .set_system_message("A system message");
 
         assert_eq!(
             request.messages[0],
@@ -459,6 +593,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_set_system_message_replaces_stale_ones() {
+        let model = ModelId::new("test-model");
+        let fixture = Context::default()
+            .add_message(ContextMessage::system("Muse system prompt"))
+            .add_message(ContextMessage::user("Plan it", Some(model.clone())))
+            .add_message(ContextMessage::system("Forge system prompt"))
+            .add_message(ContextMessage::user("Do it", Some(model.clone())));
+
+        let actual = fixture.set_system_message("Current system prompt");
+
+        let expected = Context::default()
+            .add_message(ContextMessage::system("Current system prompt"))
+            .add_message(ContextMessage::user("Plan it", Some(model.clone())))
+            .add_message(ContextMessage::user("Do it", Some(model)));
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_estimate_token_count() {
         // Create a context with some messages
@@ -506,6 +658,7 @@ mod tests {
                     name: crate::ToolName::new("text_tool"),
                     call_id: Some(crate::ToolCallId::new("call1")),
                     output: crate::ToolOutput::text("Text output".to_string()),
+                    pinned: false,
                 },
                 ToolResult {
                     name: crate::ToolName::new("empty_tool"),
@@ -514,6 +667,7 @@ mod tests {
                         values: vec![crate::ToolValue::Empty],
                         is_error: false,
                     },
+                    pinned: false,
                 },
             ]);
 
@@ -532,6 +686,7 @@ mod tests {
                 name: crate::ToolName::new("image_tool"),
                 call_id: Some(crate::ToolCallId::new("call1")),
                 output: crate::ToolOutput::image(image),
+                pinned: false,
             }]);
 
         let mut transformer = crate::transformer::ImageHandling::new();
@@ -556,6 +711,7 @@ mod tests {
                 ],
                 is_error: false,
             },
+            pinned: false,
         }]);
 
         let mut transformer = crate::transformer::ImageHandling::new();
@@ -575,16 +731,19 @@ mod tests {
                     name: crate::ToolName::new("text_tool"),
                     call_id: Some(crate::ToolCallId::new("call1")),
                     output: crate::ToolOutput::text("Text output".to_string()),
+                    pinned: false,
                 },
                 ToolResult {
                     name: crate::ToolName::new("image_tool1"),
                     call_id: Some(crate::ToolCallId::new("call2")),
                     output: crate::ToolOutput::image(image1),
+                    pinned: false,
                 },
                 ToolResult {
                     name: crate::ToolName::new("image_tool2"),
                     call_id: Some(crate::ToolCallId::new("call3")),
                     output: crate::ToolOutput::image(image2),
+                    pinned: false,
                 },
             ]);
 
@@ -613,6 +772,7 @@ mod tests {
                     ],
                     is_error: false,
                 },
+                pinned: false,
             }]);
 
         let mut transformer = crate::transformer::ImageHandling::new();
@@ -631,6 +791,7 @@ mod tests {
                 values: vec![crate::ToolValue::Image(image)],
                 is_error: true,
             },
+            pinned: false,
         }]);
 
         let mut transformer = crate::transformer::ImageHandling::new();
@@ -669,4 +830,112 @@ mod tests {
             .usage(usage);
         assert_eq!(fixture.token_count(), TokenCount::Approx(18));
     }
+
+    #[test]
+    fn test_trim_tool_results() {
+        let call = ToolCallFull::new(crate::ToolName::new("forge_tool_fs_read"))
+            .call_id(crate::ToolCallId::new("call_1"));
+        let fixture = Context::default()
+            .add_message(ContextMessage::user("Read the manifest", None))
+            .add_message(ContextMessage::assistant(
+                "Reading it now",
+                None,
+                Some(vec![call.clone()]),
+            ))
+            .add_tool_results(vec![
+                ToolResult::from(call.clone()).success("[package]\n".repeat(200)),
+            ])
+            .add_message(ContextMessage::assistant("It is a Rust crate", None, None));
+
+        let actual = fixture.clone().trim_tool_results();
+
+        let expected = Context::default()
+            .add_message(ContextMessage::user("Read the manifest", None))
+            .add_message(ContextMessage::assistant(
+                "Reading it now",
+                None,
+                Some(vec![call.clone()]),
+            ))
+            .add_tool_results(vec![
+                ToolResult::from(call)
+                    .success("<trimmed>Tool output removed to free up context</trimmed>"),
+            ])
+            .add_message(ContextMessage::assistant("It is a Rust crate", None, None));
+        assert_eq!(actual, expected);
+        assert!(actual.token_count_approx() < fixture.token_count_approx());
+    }
+
+    #[test]
+    fn test_trim_tool_results_resets_usage() {
+        let call = ToolCallFull::new(crate::ToolName::new("forge_tool_fs_read"))
+            .call_id(crate::ToolCallId::new("call_1"));
+        let fixture = Context::default()
+            .add_message(ContextMessage::assistant(
+                "Reading it now",
+                None,
+                Some(vec![call.clone()]),
+            ))
+            .add_tool_results(vec![
+                ToolResult::from(call).success("[package]\n".repeat(200)),
+            ])
+            .usage(crate::Usage {
+                total_tokens: TokenCount::Actual(12_000),
+                ..Default::default()
+            });
+
+        let actual = fixture.clone().trim_tool_results();
+
+        assert_eq!(actual.usage, None);
+        assert_eq!(
+            actual.token_count(),
+            TokenCount::Approx(actual.token_count_approx())
+        );
+    }
+
+    #[test]
+    fn test_trim_tool_results_keeps_pinned_result() {
+        let call = ToolCallFull::new(crate::ToolName::new("forge_tool_fs_read"))
+            .call_id(crate::ToolCallId::new("call_1"));
+        let mut fixture = Context::default()
+            .add_message(ContextMessage::user("Read the manifest", None))
+            .add_message(ContextMessage::assistant(
+                "Reading it now",
+                None,
+                Some(vec![call.clone()]),
+            ))
+            .add_tool_results(vec![ToolResult::from(call).success("[package]\n")]);
+
+        let pinned = fixture.pin_last_tool_result();
+        let actual = fixture.clone().trim_tool_results();
+
+        assert_eq!(pinned, Some(crate::ToolName::new("forge_tool_fs_read")));
+        assert_eq!(actual, fixture);
+    }
+
+    #[test]
+    fn test_remove_messages_keeps_tool_calls_with_their_results() {
+        let call = ToolCallFull::new(crate::ToolName::new("forge_tool_fs_read"))
+            .call_id(crate::ToolCallId::new("call_1"));
+        let fixture = Context::default()
+            .add_message(ContextMessage::system("You are a helpful assistant"))
+            .add_message(ContextMessage::user("Read the manifest", None))
+            .add_message(ContextMessage::assistant(
+                "Reading it now",
+                None,
+                Some(vec![call.clone()]),
+            ))
+            .add_tool_results(vec![ToolResult::from(call).success("[package]")])
+            .add_message(ContextMessage::assistant("It is a Rust crate", None, None))
+            .add_message(ContextMessage::user("Thanks", None));
+
+        // Selecting the tool result drops the call that produced it too
+        let actual = fixture.clone().remove_messages(&[1, 3, 9]);
+
+        let expected = Context::default()
+            .add_message(ContextMessage::system("You are a helpful assistant"))
+            .add_message(ContextMessage::assistant("It is a Rust crate", None, None))
+            .add_message(ContextMessage::user("Thanks", None));
+        assert_eq!(actual, expected);
+        assert_eq!(fixture.remove_messages(&[1, 2]), actual);
+    }
 }
@@ -8,7 +8,11 @@ use serde_json::Value;
 use uuid::Uuid;
 
 use crate::task::TaskList;
-use crate::{Agent, AgentId, Compact, Context, Error, Event, ModelId, Result, ToolName, Workflow};
+use crate::{
+    Agent, AgentId, ChangedFiles, Compact, Context, DEFAULT_COMPLETION_FEEDBACK,
+    DEFAULT_TAG_PREFIX, Error, Event, FileRanking, MaxTokens, ModelId, ModelPricing, Result,
+    ToolName, ToolPostProcessor, Workflow,
+};
 
 #[derive(Debug, Default, Display, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(transparent)]
@@ -32,6 +36,10 @@ impl ConversationId {
     }
 }
 
+/// Times the model is asked to proceed after an empty response when the
+/// workflow doesn't say
+pub const DEFAULT_MAX_EMPTY_RESPONSE_RETRIES: usize = 1;
+
 #[derive(Debug, Setters, Serialize, Deserialize, Clone)]
 pub struct Conversation {
     pub id: ConversationId,
@@ -43,6 +51,70 @@ pub struct Conversation {
     pub tasks: TaskList,
     pub max_tool_failure_per_turn: Option<usize>,
     pub max_requests_per_turn: Option<usize>,
+    /// Tokens the requests of the conversation may use in total, across turns
+    #[serde(default)]
+    pub token_budget: Option<usize>,
+    /// Tokens the requests of the conversation have used so far
+    #[serde(default)]
+    pub used_tokens: usize,
+    /// Times the model is asked to proceed after an empty response in a turn
+    #[serde(default = "default_max_empty_response_retries")]
+    pub max_empty_response_retries: usize,
+    #[serde(default)]
+    pub pricing: HashMap<ModelId, ModelPricing>,
+    #[serde(default = "default_tag_prefix")]
+    pub tag_prefix: String,
+    #[serde(default = "default_completion_feedback")]
+    pub completion_feedback: String,
+    #[serde(default)]
+    pub changed_files: ChangedFiles,
+    /// Files changed as of the last completion that reported them, so that an
+    /// unchanged manifest isn't reported again
+    #[serde(default)]
+    pub reported_changed_files: ChangedFiles,
+    /// Markdown notes written by the agent, kept outside the context so that
+    /// they survive compaction
+    #[serde(default)]
+    pub scratchpad: String,
+    /// Whether the agent has to present a plan and get it approved before it
+    /// may run tools that make changes
+    #[serde(default)]
+    pub plan_first: bool,
+    /// Command the final answer is piped through before it's shown
+    #[serde(default)]
+    pub post_response_command: Option<String>,
+    /// Transformations applied to the output of tools, keyed by tool name
+    #[serde(default)]
+    pub tool_post_processors: HashMap<ToolName, ToolPostProcessor>,
+    /// Whether tool calls without an explanation are rejected
+    #[serde(default)]
+    pub require_tool_explanation: bool,
+    /// How the files listed in the system prompt are ranked and capped
+    #[serde(default)]
+    pub file_ranking: FileRanking,
+    /// Formatter commands keyed by file extension
+    #[serde(default)]
+    pub formatters: HashMap<String, String>,
+    /// Tools the user disabled for the session, they are neither offered to
+    /// the model nor run
+    #[serde(default)]
+    pub disabled_tools: Vec<ToolName>,
+    /// Tokens every agent may generate per response, set for the session in
+    /// place of the values the workflow gives each agent
+    #[serde(default)]
+    pub max_tokens: Option<MaxTokens>,
+}
+
+fn default_tag_prefix() -> String {
+    DEFAULT_TAG_PREFIX.to_string()
+}
+
+fn default_completion_feedback() -> String {
+    DEFAULT_COMPLETION_FEEDBACK.to_string()
+}
+
+fn default_max_empty_response_retries() -> usize {
+    DEFAULT_MAX_EMPTY_RESPONSE_RETRIES
 }
 
 impl Conversation {
@@ -71,6 +143,18 @@ impl Conversation {
         Ok(())
     }
 
+    /// Sets the maximum number of tokens every agent in the conversation may
+    /// generate per response, `None` restores the values of the workflow
+    pub fn set_max_tokens(&mut self, max_tokens: Option<MaxTokens>) {
+        self.max_tokens = max_tokens;
+    }
+
+    /// Returns the maximum number of tokens the agent may generate per
+    /// response, the one set for the session taking precedence
+    pub fn agent_max_tokens(&self, agent: &Agent) -> Option<MaxTokens> {
+        self.max_tokens.or(agent.max_tokens)
+    }
+
     pub fn new(id: ConversationId, workflow: Workflow, additional_tools: Vec<ToolName>) -> Self {
         // Merge the workflow with the default workflow
         let mut base_workflow = Workflow::default();
@@ -87,6 +171,10 @@ impl Conversation {
                 agent.custom_rules = Some(custom_rules);
             }
 
+            if let Some(sections) = workflow.system_context_sections.clone() {
+                agent.system_context_sections = Some(sections);
+            }
+
             if let Some(max_walker_depth) = workflow.max_walker_depth {
                 agent.max_walker_depth = Some(max_walker_depth);
             }
@@ -180,9 +268,55 @@ impl Conversation {
             tasks: TaskList::new(),
             max_tool_failure_per_turn: workflow.max_tool_failure_per_turn,
             max_requests_per_turn: workflow.max_requests_per_turn,
+            token_budget: workflow.token_budget,
+            used_tokens: 0,
+            max_empty_response_retries: workflow
+                .max_empty_response_retries
+                .unwrap_or_else(default_max_empty_response_retries),
+            pricing: workflow.pricing,
+            tag_prefix: workflow
+                .tag_prefix
+                .filter(|prefix| !prefix.is_empty())
+                .unwrap_or_else(default_tag_prefix),
+            completion_feedback: workflow
+                .completion_feedback
+                .unwrap_or_else(default_completion_feedback),
+            changed_files: Default::default(),
+            reported_changed_files: Default::default(),
+            scratchpad: Default::default(),
+            plan_first: Default::default(),
+            post_response_command: workflow.post_response_command,
+            tool_post_processors: workflow.tool_post_processors,
+            require_tool_explanation: workflow.require_tool_explanation.unwrap_or_default(),
+            file_ranking: workflow.file_ranking.unwrap_or_default(),
+            formatters: workflow.formatters,
+            disabled_tools: Default::default(),
+            max_tokens: Default::default(),
         }
     }
 
+    /// Disables the tool for the rest of the session. Returns false when it
+    /// was already disabled.
+    pub fn disable_tool(&mut self, name: ToolName) -> bool {
+        if self.disabled_tools.contains(&name) {
+            return false;
+        }
+        self.disabled_tools.push(name);
+        true
+    }
+
+    /// Enables a disabled tool again. Returns false when it wasn't disabled.
+    pub fn enable_tool(&mut self, name: &ToolName) -> bool {
+        let count = self.disabled_tools.len();
+        self.disabled_tools.retain(|tool| tool != name);
+        self.disabled_tools.len() != count
+    }
+
+    /// Whether the user disabled the tool for the session
+    pub fn is_tool_disabled(&self, name: &ToolName) -> bool {
+        self.disabled_tools.contains(name)
+    }
+
     /// Returns all the agents that are subscribed to the given event.
     pub fn subscriptions(&self, event_name: &str) -> Vec<Agent> {
         self.agents
@@ -249,6 +383,13 @@ impl Conversation {
         crate::conversation_html::render_conversation_html(self)
     }
 
+    /// Wipes the dialogue while keeping the setup of the conversation, its
+    /// agents, model, variables, system prompt and pinned tool results
+    pub fn clear(&mut self) {
+        self.context = self.context.take().map(Context::clear_messages);
+        self.events.clear();
+    }
+
     /// Add an event to the conversation
     pub fn insert_event(&mut self, event: Event) -> &mut Self {
         self.events.push(event);
@@ -283,7 +424,8 @@ mod tests {
     use serde_json::json;
 
     use crate::{
-        Agent, AgentId, Command, Compact, Error, MaxTokens, ModelId, Temperature, Workflow,
+        Agent, AgentId, Command, Compact, Context, ContextMessage, Error, Event, MaxTokens,
+        ModelId, Temperature, ToolCallFull, ToolCallId, ToolName, ToolResult, Workflow,
     };
 
     #[test]
@@ -1118,4 +1260,89 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_empty_tag_prefix_falls_back_to_default() {
+        let workflow = Workflow::new().tag_prefix(String::new());
+        let id = super::ConversationId::generate();
+
+        let actual = super::Conversation::new_inner(id, workflow, vec![]).tag_prefix;
+
+        let expected = crate::DEFAULT_TAG_PREFIX.to_string();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_reset_max_tokens_restores_agent_values() {
+        let workflow = Workflow::new().agents(vec![
+            Agent::new("agent-1").max_tokens(MaxTokens::new(1000).unwrap()),
+            Agent::new("agent-2"),
+        ]);
+        let id = super::ConversationId::generate();
+        let mut conversation = super::Conversation::new_inner(id, workflow, vec![]);
+
+        conversation.set_max_tokens(Some(MaxTokens::new(200).unwrap()));
+        let overridden = conversation
+            .agents
+            .iter()
+            .map(|agent| conversation.agent_max_tokens(agent))
+            .collect::<Vec<_>>();
+        conversation.set_max_tokens(None);
+        let restored = conversation
+            .agents
+            .iter()
+            .map(|agent| conversation.agent_max_tokens(agent))
+            .collect::<Vec<_>>();
+
+        let actual = (overridden, restored);
+        let expected = (
+            vec![Some(MaxTokens::new(200).unwrap()); 2],
+            vec![Some(MaxTokens::new(1000).unwrap()), None],
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_clear_keeps_pins_model_and_variables() {
+        let workflow = Workflow::new()
+            .agents(vec![Agent::new(AgentId::default())])
+            .model(ModelId::new("sonnet-4"));
+        let id = super::ConversationId::generate();
+        let mut fixture = super::Conversation::new_inner(id, workflow, vec![]);
+        fixture.set_variable("mode".to_string(), json!("review"));
+        fixture.insert_event(Event::new(
+            "forge/user_task_init",
+            Some("Read the manifest"),
+        ));
+        let call = ToolCallFull::new(ToolName::new("forge_tool_fs_read"))
+            .call_id(ToolCallId::new("call_1"));
+        let system = ContextMessage::system("You are a helpful assistant");
+        let pinned_call =
+            ContextMessage::assistant("Reading it now", None, Some(vec![call.clone()]));
+        let pinned_result = ToolResult::from(call).success("[package]").pinned(true);
+        let mut context = Context::default()
+            .add_message(system.clone())
+            .add_message(ContextMessage::user("Read the manifest", None))
+            .add_message(pinned_call.clone())
+            .add_tool_results(vec![pinned_result.clone()])
+            .add_message(ContextMessage::assistant("It is a Rust crate", None, None))
+            .usage(crate::Usage {
+                total_tokens: crate::TokenCount::Actual(12_000),
+                ..Default::default()
+            });
+        fixture.context = Some(context.clone());
+
+        fixture.clear();
+
+        context.messages = vec![
+            system,
+            pinned_call,
+            ContextMessage::tool_result(pinned_result),
+        ];
+        context.usage = None;
+        assert_eq!(fixture.context, Some(context));
+        assert!(fixture.events.is_empty());
+        assert_eq!(fixture.main_model().unwrap(), ModelId::new("sonnet-4"));
+        assert_eq!(fixture.get_variable("mode"), Some(&json!("review")));
+    }
 }
@@ -1,16 +1,19 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use derive_setters::Setters;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::{HttpConfig, RetryConfig};
+use crate::{ConversationId, HttpConfig, RetryConfig, ShellEnvConfig, ToolLimits};
 
 const VERSION: &str = match option_env!("APP_VERSION") {
     Some(val) => val,
     None => env!("CARGO_PKG_VERSION"),
 };
 
+/// Prefix of the files tool outputs are offloaded to
+pub const OFFLOAD_PREFIX: &str = "forge_";
+
 #[derive(Debug, Setters, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[setters(strip_option)]
@@ -18,6 +21,8 @@ const VERSION: &str = match option_env!("APP_VERSION") {
 pub struct Environment {
     /// The operating system of the environment.
     pub os: String,
+    /// The CPU architecture of the environment.
+    pub arch: String,
     /// The process ID of the current process.
     pub pid: u32,
     /// The current working directory.
@@ -36,15 +41,64 @@ pub struct Environment {
     pub max_search_lines: u64,
     /// Maximum characters for fetch content
     pub fetch_truncation_limit: usize,
+    /// Seconds a fetched response is reused for repeated fetches of the same
+    /// URL, 0 disables the cache
+    #[serde(default)]
+    pub fetch_cache_ttl: u64,
     /// Maximum lines for shell output prefix
     pub stdout_max_prefix_length: usize,
     /// Maximum lines for shell output suffix
     pub stdout_max_suffix_length: usize,
+    /// Truncation limits of individual tools, taking precedence over the
+    /// global ones above
+    #[serde(default)]
+    pub tool_limits: ToolLimits,
     /// Maximum number of lines to read from a file
     pub max_read_size: u64,
     pub http: HttpConfig,
     /// Maximum file size in bytes for operations
     pub max_file_size: u64,
+    /// Environment variables passed on to shell commands
+    #[serde(default)]
+    pub shell_env: ShellEnvConfig,
+    /// Template of the note added to truncated tool output, `{shown}` is
+    /// replaced with the part that was returned and `{tool}` and `{arguments}`
+    /// with the call that retrieves the rest
+    #[serde(default)]
+    pub truncation_template: Option<String>,
+    /// Endpoint of the OTLP collector that turn and tool call spans are
+    /// exported to, nothing is exported when unset
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    /// Whether the raw output of the last tool call is kept for inspection
+    /// with `/raw`
+    #[serde(default)]
+    pub debug_raw_output: bool,
+    /// Directory shell commands are confined to, commands can run anywhere
+    /// when not set
+    #[serde(default)]
+    pub sandbox_root: Option<PathBuf>,
+    /// Maximum number of provider requests in flight at once, shared by the
+    /// main agent and its sub-agents. Requests aren't bounded when not set
+    #[serde(default)]
+    pub max_concurrent_requests: Option<usize>,
+    /// Whether the home directory is shown as `~` in the tool outputs shown to
+    /// the user, so transcripts can be shared without it
+    #[serde(default)]
+    pub redact_home: bool,
+    /// Whether changes to whitespace alone are left out of the diffs shown
+    /// for file edits
+    #[serde(default)]
+    pub diff_ignore_whitespace: bool,
+    /// Whether adding or removing the final newline of a file is left out of
+    /// the diffs shown for file edits
+    #[serde(default)]
+    pub diff_ignore_trailing_newline: bool,
+    /// Directory tool outputs too large to return whole are written to, so
+    /// they can be read back in ranges. The system temp directory when not
+    /// set
+    #[serde(default)]
+    pub offload_dir: Option<PathBuf>,
 }
 
 impl Environment {
@@ -59,6 +113,15 @@ impl Environment {
     pub fn history_path(&self) -> PathBuf {
         self.base_path.join(".forge_history")
     }
+
+    /// File holding the raw output of the last tool call in debug mode
+    pub fn raw_output_path(&self) -> PathBuf {
+        self.base_path.join("last_tool_output.txt")
+    }
+    /// Lock file that marks the conversation as open in a running process
+    pub fn conversation_lock_path(&self, id: &ConversationId) -> PathBuf {
+        self.base_path.join("locks").join(format!("{id}.lock"))
+    }
     pub fn snapshot_path(&self) -> PathBuf {
         self.base_path.join("snapshots")
     }
@@ -79,4 +142,114 @@ impl Environment {
     pub fn app_config(&self) -> PathBuf {
         self.base_path.join(".config.json")
     }
+
+    /// Directory tool outputs too large to return whole are written to. It
+    /// defaults to one owned by forge, inside the sandbox root when commands
+    /// are confined to it, rather than the shared temp directory that any
+    /// process can write to.
+    pub fn offload_path(&self) -> PathBuf {
+        match (&self.offload_dir, &self.sandbox_root) {
+            (Some(dir), _) => dir.clone(),
+            (None, Some(root)) => root.join(".forge").join("offload"),
+            (None, None) => self.base_path.join("offload"),
+        }
+    }
+
+    /// Whether the file holds a tool output offloaded by forge. These are
+    /// read in ranges whatever their size, as that's what they're kept for
+    pub fn is_offloaded(&self, path: &Path) -> bool {
+        is_offloaded_in(&self.offload_path(), path)
+    }
+
+    /// Replaces the home directory in paths of the text with `~` when home
+    /// redaction is enabled
+    pub fn redact_home_dir(&self, text: &str) -> String {
+        match self.home.as_ref().filter(|_| self.redact_home) {
+            Some(home) => replace_home(text, &home.to_string_lossy()),
+            None => text.to_string(),
+        }
+    }
+}
+
+/// Whether the file sits directly in the offload directory and is named like
+/// the offloaded outputs
+fn is_offloaded_in(dir: &Path, path: &Path) -> bool {
+    path.parent() == Some(dir)
+        && path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(OFFLOAD_PREFIX))
+}
+
+/// Replaces the home directory in paths of the text with `~`. Paths that only
+/// share a prefix with it, like `/home/user2` for `/home/user`, are left as
+/// they are.
+fn replace_home(text: &str, home: &str) -> String {
+    let home = home.trim_end_matches('/');
+    if home.is_empty() {
+        return text.to_string();
+    }
+
+    let is_path_char = |c: char| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '/');
+    let mut redacted = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, _) in text.match_indices(home) {
+        let end = start + home.len();
+        let starts_path = !text[..start].chars().next_back().is_some_and(is_path_char);
+        let ends_dir = text[end..]
+            .chars()
+            .next()
+            .is_none_or(|c| c == '/' || !is_path_char(c));
+        if starts_path && ends_dir {
+            redacted.push_str(&text[last..start]);
+            redacted.push('~');
+            last = end;
+        }
+    }
+    redacted.push_str(&text[last..]);
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_replace_home_rewrites_home_paths_only() {
+        let fixture = "Read /home/jane/project/main.rs and /home/jane, skipped /home/janet/notes and /srv/home/jane/x";
+
+        let actual = replace_home(fixture, "/home/jane");
+
+        let expected =
+            "Read ~/project/main.rs and ~, skipped /home/janet/notes and /srv/home/jane/x";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_replace_home_in_xml_attributes() {
+        let fixture = r#"<file path="/home/jane/notes.md" cwd="/tmp/jane">"#;
+
+        let actual = replace_home(fixture, "/home/jane/");
+
+        let expected = r#"<file path="~/notes.md" cwd="/tmp/jane">"#;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_is_offloaded_only_for_forge_files_in_the_offload_dir() {
+        let fixture = Path::new("/home/jane/project/.forge/offload");
+
+        let actual = [
+            "/home/jane/project/.forge/offload/forge_shell_stdout_a1.txt",
+            "/home/jane/project/.forge/offload/notes.txt",
+            "/home/jane/project/.forge/offload/nested/forge_fetch_b2.txt",
+            "/tmp/forge_shell_stdout_a1.txt",
+        ]
+        .map(|path| is_offloaded_in(fixture, Path::new(path)));
+
+        let expected = [true, false, false, false];
+        assert_eq!(actual, expected);
+    }
 }
@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use std::pin::Pin;
 
 use derive_more::From;
@@ -59,6 +60,14 @@ pub enum Error {
     #[from(skip)]
     NoModelDefined(AgentId),
 
+    #[error(
+        "Working directory '{}' of agent '{}' is outside the project root",
+        .1.display(),
+        .0
+    )]
+    #[from(skip)]
+    WorkingDirOutsideProject(AgentId, PathBuf),
+
     #[error(transparent)]
     Retryable(anyhow::Error),
 }
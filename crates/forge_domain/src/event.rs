@@ -16,6 +16,10 @@ pub struct Event {
     pub value: Option<Value>,
     pub timestamp: String,
     pub attachments: Vec<Attachment>,
+    /// Background for the task, added to the context ahead of it without
+    /// being treated as the task itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
 }
 
 #[derive(Debug, JsonSchema, Deserialize, Serialize, Clone)]
@@ -86,6 +90,7 @@ impl Event {
             value: value.map(|v| v.into()),
             timestamp,
             attachments: Vec::new(),
+            context: None,
         }
     }
 }
@@ -1,7 +1,15 @@
+use std::time::SystemTime;
+
 use serde::Serialize;
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct File {
     pub path: String,
     pub is_dir: bool,
+    /// Size in bytes
+    #[serde(skip)]
+    pub size: u64,
+    /// Last modification time, when the platform reports it
+    #[serde(skip)]
+    pub modified: Option<SystemTime>,
 }
@@ -0,0 +1,160 @@
+use std::cmp::Ordering;
+
+use derive_setters::Setters;
+use merge::Merge;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::File;
+
+/// Picks the workspace files listed in the system prompt, so that on large
+/// workspaces the most relevant ones are kept. Agents scoped to a working
+/// directory only list the files within it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, Merge, Setters)]
+#[setters(strip_option)]
+pub struct FileRanking {
+    /// Maximum number of files listed, the highest ranked are kept. All files
+    /// are listed when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub max_files: Option<usize>,
+
+    /// How much recently modified files are favoured. Defaults to 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub recency_weight: Option<f64>,
+
+    /// How much files close to the top of the working directory are favoured.
+    /// Defaults to 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub depth_weight: Option<f64>,
+}
+
+impl FileRanking {
+    /// Orders the files from the most relevant to the least, files ranked
+    /// equally are ordered by path
+    pub fn rank(&self, mut files: Vec<File>) -> Vec<File> {
+        let recency_weight = self.recency_weight.unwrap_or(1.0);
+        let depth_weight = self.depth_weight.unwrap_or(1.0);
+
+        let times = files.iter().filter_map(|file| file.modified);
+        let oldest = times.clone().min();
+        let newest = times.max();
+        let recency = |file: &File| match (file.modified, oldest, newest) {
+            (Some(modified), Some(oldest), Some(newest)) => {
+                let span = newest.duration_since(oldest).unwrap_or_default();
+                if span.is_zero() {
+                    1.0
+                } else {
+                    let age = modified.duration_since(oldest).unwrap_or_default();
+                    age.as_secs_f64() / span.as_secs_f64()
+                }
+            }
+            _ => 0.0,
+        };
+        let shallowness = |file: &File| {
+            let depth = file.path.trim_end_matches('/').matches('/').count();
+            1.0 / (1 + depth) as f64
+        };
+        let score = |file: &File| recency_weight * recency(file) + depth_weight * shallowness(file);
+
+        files.sort_by(|a, b| {
+            score(b)
+                .partial_cmp(&score(a))
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        files
+    }
+
+    /// Paths of the files listed, ordered by path. When there are more than
+    /// the maximum, the highest ranked are kept
+    pub fn select(&self, files: Vec<File>) -> Vec<String> {
+        let mut paths = match self.max_files {
+            Some(max_files) if files.len() > max_files => self
+                .rank(files)
+                .into_iter()
+                .take(max_files)
+                .map(|file| file.path)
+                .collect::<Vec<_>>(),
+            _ => files.into_iter().map(|file| file.path).collect(),
+        };
+        paths.sort();
+        paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture_file(path: &str, age_days: u64) -> File {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000 * 86_400);
+        File {
+            path: path.to_string(),
+            is_dir: false,
+            size: 0,
+            modified: Some(now - Duration::from_secs(age_days * 86_400)),
+        }
+    }
+
+    fn fixture_files() -> Vec<File> {
+        vec![
+            fixture_file("crates/core/src/legacy/parser.rs", 300),
+            fixture_file("README.md", 200),
+            fixture_file("src/main.rs", 0),
+            fixture_file("crates/core/src/lib.rs", 1),
+            fixture_file("docs/old/notes.md", 250),
+        ]
+    }
+
+    #[test]
+    fn test_rank_orders_recent_and_shallow_files_first() {
+        let fixture = FileRanking::default();
+
+        let actual = fixture
+            .rank(fixture_files())
+            .into_iter()
+            .map(|file| file.path)
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            "src/main.rs",
+            "README.md",
+            "crates/core/src/lib.rs",
+            "docs/old/notes.md",
+            "crates/core/src/legacy/parser.rs",
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_select_keeps_the_highest_ranked_files_up_to_the_maximum() {
+        let fixture = FileRanking::default().max_files(3_usize);
+
+        let actual = fixture.select(fixture_files());
+
+        let expected = vec!["README.md", "crates/core/src/lib.rs", "src/main.rs"];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_weights_change_the_ranking() {
+        let fixture = FileRanking::default().recency_weight(0.0).depth_weight(1.0);
+
+        let actual = fixture
+            .rank(fixture_files())
+            .into_iter()
+            .map(|file| file.path)
+            .take(2)
+            .collect::<Vec<_>>();
+
+        let expected = vec!["README.md", "src/main.rs"];
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::shell_quote;
+
+/// Formatters used for the extensions without a configured one. Each reads the
+/// file on stdin and writes the formatted file to stdout
+const DEFAULT_FORMATTERS: [(&str, &str); 9] = [
+    ("go", "gofmt"),
+    ("js", "prettier --stdin-filepath {path}"),
+    ("json", "prettier --stdin-filepath {path}"),
+    ("jsx", "prettier --stdin-filepath {path}"),
+    ("py", "black --quiet -"),
+    ("rs", "rustfmt --edition 2021"),
+    ("ts", "prettier --stdin-filepath {path}"),
+    ("tsx", "prettier --stdin-filepath {path}"),
+    ("yaml", "prettier --stdin-filepath {path}"),
+];
+
+/// Command formatting the file at the path, chosen by its extension. The
+/// configured formatters take precedence over the default ones, and `{path}`
+/// in the command is replaced with the quoted path of the file
+pub fn formatter_command(formatters: &HashMap<String, String>, path: &Path) -> Option<String> {
+    let extension = path.extension()?.to_str()?;
+    let command = formatters.get(extension).map(String::as_str).or_else(|| {
+        DEFAULT_FORMATTERS
+            .iter()
+            .find(|(default, _)| *default == extension)
+            .map(|(_, command)| *command)
+    })?;
+    Some(command.replace("{path}", &shell_quote(&path.display().to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_default_formatter_for_extension() {
+        let fixture = HashMap::new();
+
+        let actual = formatter_command(&fixture, Path::new("/project/src/main.rs"));
+
+        let expected = Some("rustfmt --edition 2021".to_string());
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_configured_formatter_takes_precedence() {
+        let fixture = HashMap::from([(
+            "ts".to_string(),
+            "biome format --stdin-file-path {path}".to_string(),
+        )]);
+
+        let actual = formatter_command(&fixture, Path::new("/project/it's/app.ts"));
+
+        let expected =
+            Some("biome format --stdin-file-path '/project/it'\\''s/app.ts'".to_string());
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_no_formatter_for_unknown_extension() {
+        let fixture = HashMap::new();
+
+        let actual = (
+            formatter_command(&fixture, Path::new("/project/notes.txt")),
+            formatter_command(&fixture, Path::new("/project/Makefile")),
+        );
+
+        let expected = (None, None);
+        assert_eq!(actual, expected);
+    }
+}
@@ -8,6 +8,13 @@ pub struct HttpConfig {
     pub pool_idle_timeout: u64,
     pub pool_max_idle_per_host: usize,
     pub max_redirects: usize,
+    /// Seconds to wait for the next chunk of a streamed response before the
+    /// stream is considered stalled
+    pub stream_idle_timeout: u64,
+    /// Seconds a provider request may take in total, including the time spent
+    /// streaming the response. Unlimited when unset.
+    #[serde(default)]
+    pub request_timeout: Option<u64>,
 }
 
 impl Default for HttpConfig {
@@ -19,6 +26,8 @@ impl Default for HttpConfig {
             pool_idle_timeout: 90,
             pool_max_idle_per_host: 5,
             max_redirects: 10,
+            stream_idle_timeout: 300, // 5 minutes
+            request_timeout: None,
         }
     }
 }
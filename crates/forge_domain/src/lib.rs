@@ -1,5 +1,6 @@
 mod agent;
 mod attachment;
+mod changed_files;
 mod chat_request;
 mod chat_response;
 mod compact;
@@ -12,6 +13,8 @@ mod env;
 mod error;
 mod event;
 mod file;
+mod file_ranking;
+mod formatter;
 mod http_config;
 mod image;
 mod max_tokens;
@@ -20,11 +23,16 @@ mod merge;
 mod message;
 mod model;
 mod point;
+mod pricing;
 mod provider;
 mod reasoning;
+mod remote;
+mod response_format;
 mod result_stream_ext;
 mod retry_config;
+mod rules_layer;
 mod shell;
+mod shell_env_config;
 mod suggestion;
 mod system_context;
 mod task;
@@ -35,7 +43,9 @@ mod tool_call_context;
 mod tool_call_parser;
 mod tool_choice;
 mod tool_definition;
+mod tool_limits;
 mod tool_name;
+mod tool_post_processor;
 mod tool_result;
 mod tool_usage;
 mod tools;
@@ -48,6 +58,7 @@ mod xml;
 
 pub use agent::*;
 pub use attachment::*;
+pub use changed_files::*;
 pub use chat_request::*;
 pub use chat_response::*;
 pub use compact::*;
@@ -60,6 +71,8 @@ pub use env::*;
 pub use error::*;
 pub use event::*;
 pub use file::*;
+pub use file_ranking::*;
+pub use formatter::*;
 pub use http_config::*;
 pub use image::*;
 pub use max_tokens::*;
@@ -67,11 +80,16 @@ pub use mcp::*;
 pub use message::*;
 pub use model::*;
 pub use point::*;
+pub use pricing::*;
 pub use provider::*;
 pub use reasoning::*;
+pub use remote::*;
+pub use response_format::*;
 pub use result_stream_ext::*;
 pub use retry_config::*;
+pub use rules_layer::*;
 pub use shell::*;
+pub use shell_env_config::*;
 pub use suggestion::*;
 pub use system_context::*;
 pub use task::*;
@@ -82,7 +100,9 @@ pub use tool_call_context::*;
 pub use tool_call_parser::*;
 pub use tool_choice::*;
 pub use tool_definition::*;
+pub use tool_limits::*;
 pub use tool_name::*;
+pub use tool_post_processor::*;
 pub use tool_result::*;
 pub use tool_usage::*;
 pub use tools::*;
@@ -4,8 +4,8 @@ use serde::{Deserialize, Serialize};
 use strum_macros::EnumString;
 
 use super::{ToolCall, ToolCallFull};
-use crate::TokenCount;
 use crate::reasoning::{Reasoning, ReasoningFull};
+use crate::{ModelPricing, TokenCount};
 
 #[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Usage {
@@ -16,6 +16,20 @@ pub struct Usage {
     pub cost: Option<f64>,
 }
 
+impl Usage {
+    /// Computes the cost from the given pricing when the provider didn't
+    /// report one. A provider-reported cost always takes precedence.
+    pub fn apply_pricing(mut self, pricing: &ModelPricing) -> Self {
+        if self.cost.is_none() {
+            self.cost = Some(
+                pricing.input_cost(*self.prompt_tokens)
+                    + pricing.output_cost(*self.completion_tokens),
+            );
+        }
+        self
+    }
+}
+
 /// Represents a message that was received from the LLM provider
 /// NOTE: Tool call messages are part of the larger Response object and not part
 /// of the message.
@@ -143,6 +157,16 @@ pub struct ChatCompletionMessageFull {
     pub tool_calls: Vec<ToolCallFull>,
     pub reasoning_details: Option<Vec<ReasoningFull>>,
     pub usage: Usage,
+    /// Text and tool calls in the order in which they were streamed
+    pub segments: Vec<ResponseSegment>,
+}
+
+/// A piece of an assistant response, used to replay text and tool calls in
+/// the order the model produced them
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResponseSegment {
+    Text(String),
+    ToolCall(ToolCallFull),
 }
 
 #[cfg(test)]
@@ -173,4 +197,35 @@ mod tests {
             FinishReason::Stop
         );
     }
+
+    #[test]
+    fn test_usage_apply_pricing_computes_cost() {
+        let fixture = Usage {
+            prompt_tokens: TokenCount::Actual(1000),
+            completion_tokens: TokenCount::Actual(500),
+            total_tokens: TokenCount::Actual(1500),
+            ..Default::default()
+        };
+
+        let actual = fixture.apply_pricing(&ModelPricing::new(1.0, 2.0)).cost;
+
+        let expected = Some(2.0);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_usage_apply_pricing_prefers_provider_cost() {
+        let fixture = Usage {
+            prompt_tokens: TokenCount::Actual(1000),
+            completion_tokens: TokenCount::Actual(500),
+            total_tokens: TokenCount::Actual(1500),
+            cost: Some(0.5),
+            ..Default::default()
+        };
+
+        let actual = fixture.apply_pricing(&ModelPricing::new(1.0, 2.0)).cost;
+
+        let expected = Some(0.5);
+        assert_eq!(actual, expected);
+    }
 }
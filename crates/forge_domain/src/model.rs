@@ -15,6 +15,24 @@ pub struct Model {
     pub supports_parallel_tool_calls: Option<bool>,
     /// Whether the model supports reasoning
     pub supports_reasoning: Option<bool>,
+    /// Whether the model accepts images
+    pub supports_vision: Option<bool>,
+}
+
+impl Model {
+    /// A model nothing is known about yet
+    pub fn new(id: ModelId) -> Self {
+        Self {
+            id,
+            name: None,
+            description: None,
+            context_length: None,
+            tools_supported: None,
+            supports_parallel_tool_calls: None,
+            supports_reasoning: None,
+            supports_vision: None,
+        }
+    }
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
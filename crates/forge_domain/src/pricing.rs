@@ -0,0 +1,65 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Token pricing for a model, expressed in USD per 1,000 tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ModelPricing {
+    /// Cost per 1,000 prompt (input) tokens
+    pub input: f64,
+
+    /// Cost per 1,000 completion (output) tokens
+    pub output: f64,
+}
+
+impl ModelPricing {
+    pub fn new(input: f64, output: f64) -> Self {
+        Self { input, output }
+    }
+
+    /// Returns the cost of sending the given number of prompt tokens
+    pub fn input_cost(&self, tokens: usize) -> f64 {
+        tokens as f64 * self.input / 1000.0
+    }
+
+    /// Returns the cost of receiving the given number of completion tokens
+    pub fn output_cost(&self, tokens: usize) -> f64 {
+        tokens as f64 * self.output / 1000.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_input_cost() {
+        let fixture = ModelPricing::new(0.003, 0.015);
+
+        let actual = fixture.input_cost(2500);
+
+        let expected = 0.0075;
+        assert!((actual - expected).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_output_cost() {
+        let fixture = ModelPricing::new(0.003, 0.015);
+
+        let actual = fixture.output_cost(2000);
+
+        let expected = 0.03;
+        assert!((actual - expected).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_input_cost_zero_tokens() {
+        let fixture = ModelPricing::new(0.003, 0.015);
+
+        let actual = fixture.input_cost(0);
+
+        let expected = 0.0;
+        assert_eq!(actual, expected);
+    }
+}
@@ -5,12 +5,16 @@ use url::Url;
 pub enum ProviderUrl {
     OpenAI(String),
     Anthropic(String),
+    Gemini(String),
+    Ollama(String),
 }
 impl ProviderUrl {
     pub fn into_string(self) -> String {
         match self {
             ProviderUrl::OpenAI(url) => url,
             ProviderUrl::Anthropic(url) => url,
+            ProviderUrl::Gemini(url) => url,
+            ProviderUrl::Ollama(url) => url,
         }
     }
 }
@@ -18,8 +22,26 @@ impl ProviderUrl {
 /// Providers that can be used.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Provider {
-    OpenAI { url: Url, key: Option<String> },
-    Anthropic { url: Url, key: String },
+    OpenAI {
+        url: Url,
+        key: Option<String>,
+    },
+    Anthropic {
+        url: Url,
+        key: String,
+        /// Value of the `anthropic-version` header, defaults to
+        /// [`Provider::ANTHROPIC_VERSION`]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        version: Option<String>,
+    },
+    Gemini {
+        url: Url,
+        key: String,
+    },
+    /// A local Ollama server, which doesn't take an API key
+    Ollama {
+        url: Url,
+    },
 }
 
 impl Provider {
@@ -27,6 +49,8 @@ impl Provider {
         match url {
             ProviderUrl::OpenAI(url) => self.open_ai_url(url),
             ProviderUrl::Anthropic(url) => self.anthropic_url(url),
+            ProviderUrl::Gemini(url) => self.gemini_url(url),
+            ProviderUrl::Ollama(url) => self.ollama_url(url),
         }
     }
     /// Sets the OpenAI URL if the provider is an OpenAI compatible provider
@@ -39,7 +63,7 @@ impl Provider {
                     *set_url = Url::parse(&format!("{url}/")).unwrap();
                 }
             }
-            Provider::Anthropic { .. } => {}
+            Provider::Anthropic { .. } | Provider::Gemini { .. } | Provider::Ollama { .. } => {}
         }
     }
 
@@ -53,7 +77,42 @@ impl Provider {
                     *set_url = Url::parse(&format!("{url}/")).unwrap();
                 }
             }
-            Provider::OpenAI { .. } => {}
+            Provider::OpenAI { .. } | Provider::Gemini { .. } | Provider::Ollama { .. } => {}
+        }
+    }
+
+    /// Sets the Gemini URL if the provider is Gemini
+    fn gemini_url(&mut self, url: String) {
+        match self {
+            Provider::Gemini { url: set_url, .. } => {
+                if url.ends_with("/") {
+                    *set_url = Url::parse(&url).unwrap();
+                } else {
+                    *set_url = Url::parse(&format!("{url}/")).unwrap();
+                }
+            }
+            Provider::OpenAI { .. } | Provider::Anthropic { .. } | Provider::Ollama { .. } => {}
+        }
+    }
+
+    /// Sets the Ollama URL if the provider is Ollama
+    fn ollama_url(&mut self, url: String) {
+        match self {
+            Provider::Ollama { url: set_url } => {
+                if url.ends_with("/") {
+                    *set_url = Url::parse(&url).unwrap();
+                } else {
+                    *set_url = Url::parse(&format!("{url}/")).unwrap();
+                }
+            }
+            Provider::OpenAI { .. } | Provider::Anthropic { .. } | Provider::Gemini { .. } => {}
+        }
+    }
+
+    /// Pins the API version if the provider is Anthropic
+    pub fn anthropic_version(&mut self, version: String) {
+        if let Provider::Anthropic { version: set_version, .. } = self {
+            *set_version = Some(version);
         }
     }
 
@@ -96,13 +155,27 @@ impl Provider {
         Provider::Anthropic {
             url: Url::parse(Provider::ANTHROPIC_URL).unwrap(),
             key: key.into(),
+            version: None,
         }
     }
 
+    pub fn gemini(key: &str) -> Provider {
+        Provider::Gemini {
+            url: Url::parse(Provider::GEMINI_URL).unwrap(),
+            key: key.into(),
+        }
+    }
+
+    pub fn ollama() -> Provider {
+        Provider::Ollama { url: Url::parse(Provider::OLLAMA_URL).unwrap() }
+    }
+
     pub fn key(&self) -> Option<&str> {
         match self {
             Provider::OpenAI { key, .. } => key.as_deref(),
             Provider::Anthropic { key, .. } => Some(key),
+            Provider::Gemini { key, .. } => Some(key),
+            Provider::Ollama { .. } => None,
         }
     }
 }
@@ -113,6 +186,9 @@ impl Provider {
     pub const XAI_URL: &str = "https://api.x.ai/v1/";
     pub const OPENAI_URL: &str = "https://api.openai.com/v1/";
     pub const ANTHROPIC_URL: &str = "https://api.anthropic.com/v1/";
+    pub const ANTHROPIC_VERSION: &str = "2023-06-01";
+    pub const GEMINI_URL: &str = "https://generativelanguage.googleapis.com/v1beta/";
+    pub const OLLAMA_URL: &str = "http://localhost:11434/";
     pub const FORGE_URL: &str = "https://api.forgecode.dev/api/v1/";
 
     /// Converts the provider to it's base URL
@@ -120,50 +196,63 @@ impl Provider {
         match self {
             Provider::OpenAI { url, .. } => url.clone(),
             Provider::Anthropic { url, .. } => url.clone(),
+            Provider::Gemini { url, .. } => url.clone(),
+            Provider::Ollama { url } => url.clone(),
         }
     }
 
     pub fn is_forge(&self) -> bool {
         match self {
             Provider::OpenAI { url, .. } => url.as_str().starts_with(Self::FORGE_URL),
-            Provider::Anthropic { .. } => false,
+            Provider::Anthropic { .. } | Provider::Gemini { .. } | Provider::Ollama { .. } => false,
         }
     }
 
     pub fn is_open_router(&self) -> bool {
         match self {
             Provider::OpenAI { url, .. } => url.as_str().starts_with(Self::OPEN_ROUTER_URL),
-            Provider::Anthropic { .. } => false,
+            Provider::Anthropic { .. } | Provider::Gemini { .. } | Provider::Ollama { .. } => false,
         }
     }
 
     pub fn is_requesty(&self) -> bool {
         match self {
             Provider::OpenAI { url, .. } => url.as_str().starts_with(Self::REQUESTY_URL),
-            Provider::Anthropic { .. } => false,
+            Provider::Anthropic { .. } | Provider::Gemini { .. } | Provider::Ollama { .. } => false,
         }
     }
 
     pub fn is_xai(&self) -> bool {
         match self {
             Provider::OpenAI { url, .. } => url.as_str().starts_with(Self::XAI_URL),
-            Provider::Anthropic { .. } => false,
+            Provider::Anthropic { .. } | Provider::Gemini { .. } | Provider::Ollama { .. } => false,
         }
     }
 
     pub fn is_open_ai(&self) -> bool {
         match self {
             Provider::OpenAI { url, .. } => url.as_str().starts_with(Self::OPENAI_URL),
-            Provider::Anthropic { .. } => false,
+            Provider::Anthropic { .. } | Provider::Gemini { .. } | Provider::Ollama { .. } => false,
         }
     }
 
     pub fn is_anthropic(&self) -> bool {
         match self {
-            Provider::OpenAI { .. } => false,
+            Provider::OpenAI { .. } | Provider::Gemini { .. } | Provider::Ollama { .. } => false,
             Provider::Anthropic { url, .. } => url.as_str().starts_with(Self::ANTHROPIC_URL),
         }
     }
+
+    pub fn is_gemini(&self) -> bool {
+        match self {
+            Provider::OpenAI { .. } | Provider::Anthropic { .. } | Provider::Ollama { .. } => false,
+            Provider::Gemini { url, .. } => url.as_str().starts_with(Self::GEMINI_URL),
+        }
+    }
+
+    pub fn is_ollama(&self) -> bool {
+        matches!(self, Provider::Ollama { .. })
+    }
 }
 
 #[cfg(test)]
@@ -227,6 +316,7 @@ mod tests {
         let mut provider = Provider::Anthropic {
             url: Url::from_str("https://example.com/").unwrap(),
             key: "key".to_string(),
+            version: None,
         };
 
         // Test URL without trailing slash
@@ -235,7 +325,8 @@ mod tests {
             provider,
             Provider::Anthropic {
                 url: Url::from_str("https://new-anthropic-url.com/").unwrap(),
-                key: "key".to_string()
+                key: "key".to_string(),
+                version: None
             }
         );
 
@@ -245,7 +336,8 @@ mod tests {
             provider,
             Provider::Anthropic {
                 url: Url::from_str("https://another-anthropic-url.com/").unwrap(),
-                key: "key".to_string()
+                key: "key".to_string(),
+                version: None
             }
         );
 
@@ -255,7 +347,8 @@ mod tests {
             provider,
             Provider::Anthropic {
                 url: Url::from_str("https://new-anthropic-url.com/v1/complete/").unwrap(),
-                key: "key".to_string()
+                key: "key".to_string(),
+                version: None
             }
         );
 
@@ -265,7 +358,8 @@ mod tests {
             provider,
             Provider::Anthropic {
                 url: Url::from_str("https://another-anthropic-url.com/v2/complete/").unwrap(),
-                key: "key".to_string()
+                key: "key".to_string(),
+                version: None
             }
         );
     }
@@ -281,6 +375,41 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_gemini_url() {
+        let mut provider = Provider::gemini("key");
+
+        provider.url(ProviderUrl::Gemini(
+            "https://proxy.example.com/v1beta".to_string(),
+        ));
+
+        let expected = Provider::Gemini {
+            url: Url::from_str("https://proxy.example.com/v1beta/").unwrap(),
+            key: "key".to_string(),
+        };
+        assert_eq!(provider, expected);
+    }
+
+    #[test]
+    fn test_ollama_url() {
+        let mut provider = Provider::ollama();
+
+        provider.url(ProviderUrl::Ollama("http://gpu-box:11434".to_string()));
+
+        let expected = Provider::Ollama { url: Url::from_str("http://gpu-box:11434/").unwrap() };
+        assert_eq!(provider, expected);
+        assert_eq!(provider.key(), None);
+    }
+
+    #[test]
+    fn test_is_gemini() {
+        let fixture_gemini = Provider::gemini("key");
+        assert!(fixture_gemini.is_gemini());
+
+        let fixture_other = Provider::anthropic("key");
+        assert!(!fixture_other.is_gemini());
+    }
+
     #[test]
     fn test_is_xai() {
         let fixture_xai = Provider::xai("key");
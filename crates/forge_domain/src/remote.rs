@@ -0,0 +1,79 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Workspace on another host that forge operates on over SSH, written as
+/// `user@host:/path`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteTarget {
+    destination: String,
+    root: PathBuf,
+}
+
+impl RemoteTarget {
+    /// The `user@host` part, as passed to `ssh`
+    pub fn destination(&self) -> &str {
+        &self.destination
+    }
+
+    /// Absolute path of the workspace on the remote host
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl FromStr for RemoteTarget {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let Some((destination, root)) = value.split_once(':') else {
+            anyhow::bail!("Expected the remote as user@host:/path, got '{value}'");
+        };
+        if destination.is_empty() || destination.ends_with('@') || destination.starts_with('@') {
+            anyhow::bail!("Expected the remote as user@host:/path, got '{value}'");
+        }
+        // Would be taken as an option of `ssh`, e.g. `-oProxyCommand=...`
+        if destination.starts_with('-') {
+            anyhow::bail!("The remote host can't start with '-', got '{destination}'");
+        }
+        // `~` and relative paths would depend on the remote login directory
+        if !root.starts_with('/') {
+            anyhow::bail!("The remote path must be absolute, got '{root}'");
+        }
+        Ok(Self {
+            destination: destination.to_string(),
+            root: PathBuf::from(root),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_remote_target() {
+        let actual = RemoteTarget::from_str("dev@build-box:/srv/project").unwrap();
+
+        let expected = RemoteTarget {
+            destination: "dev@build-box".to_string(),
+            root: PathBuf::from("/srv/project"),
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_remote_target_rejects_option_like_destination() {
+        let actual = RemoteTarget::from_str("-oProxyCommand=touch@x:/srv/project").is_err();
+
+        assert!(actual);
+    }
+
+    #[test]
+    fn test_parse_remote_target_requires_absolute_path() {
+        let actual = RemoteTarget::from_str("dev@build-box:project").is_err();
+
+        assert!(actual);
+    }
+}
@@ -0,0 +1,213 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Constrains the shape of the final output produced by an agent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    /// Free-form text output
+    Text,
+    /// Any valid JSON object
+    JsonObject,
+    /// JSON that conforms to the provided schema
+    JsonSchema {
+        /// Name of the schema, forwarded to the provider
+        name: String,
+        /// The JSON schema the output must conform to
+        schema: Value,
+        /// Whether the provider should strictly enforce the schema
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        strict: Option<bool>,
+    },
+}
+
+impl ResponseFormat {
+    /// Checks the output against this format and returns a description of
+    /// every violation found. An empty list means the output is valid.
+    ///
+    /// Only a subset of JSON schema is checked: `type`, `enum`, `required`,
+    /// `properties`, `additionalProperties: false` and `items`.
+    pub fn validate(&self, output: &str) -> Vec<String> {
+        let object_schema = serde_json::json!({"type": "object"});
+        let schema = match self {
+            ResponseFormat::Text => return vec![],
+            ResponseFormat::JsonObject => &object_schema,
+            ResponseFormat::JsonSchema { schema, .. } => schema,
+        };
+
+        match serde_json::from_str::<Value>(output.trim()) {
+            Ok(value) => {
+                let mut errors = Vec::new();
+                validate_value(schema, &value, "$", &mut errors);
+                errors
+            }
+            Err(error) => vec![format!("output is not valid JSON: {error}")],
+        }
+    }
+}
+
+fn validate_value(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let types = match schema.get("type") {
+        Some(Value::String(name)) => vec![name.as_str()],
+        Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    };
+    if !types.is_empty() && !types.iter().any(|name| is_type(name, value)) {
+        errors.push(format!(
+            "{path}: expected {}, found {}",
+            types.join(" or "),
+            type_name(value)
+        ));
+        return;
+    }
+
+    if let Some(Value::Array(allowed)) = schema.get("enum")
+        && !allowed.contains(value)
+    {
+        errors.push(format!("{path}: value is not one of the allowed values"));
+    }
+
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                required
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .filter(|key| !object.contains_key(*key))
+                    .for_each(|key| {
+                        errors.push(format!("{path}: missing required property `{key}`"))
+                    });
+            }
+
+            let properties = schema.get("properties").and_then(Value::as_object);
+            let additional = schema.get("additionalProperties");
+            for (key, child) in object {
+                match properties.and_then(|properties| properties.get(key)) {
+                    Some(child_schema) => {
+                        validate_value(child_schema, child, &format!("{path}.{key}"), errors)
+                    }
+                    None if additional == Some(&Value::Bool(false)) => {
+                        errors.push(format!("{path}: unexpected property `{key}`"))
+                    }
+                    None => {}
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_value(item_schema, item, &format!("{path}[{index}]"), errors);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_type(name: &str, value: &Value) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    fn fixture_schema() -> ResponseFormat {
+        ResponseFormat::JsonSchema {
+            name: "summary".to_string(),
+            schema: json!({
+                "type": "object",
+                "required": ["title", "tags"],
+                "properties": {
+                    "title": {"type": "string"},
+                    "tags": {"type": "array", "items": {"type": "string"}}
+                },
+                "additionalProperties": false
+            }),
+            strict: Some(true),
+        }
+    }
+
+    #[test]
+    fn test_validate_text_accepts_anything() {
+        let fixture = ResponseFormat::Text;
+
+        let actual = fixture.validate("not json at all");
+
+        let expected: Vec<String> = vec![];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_validate_json_object_rejects_array() {
+        let fixture = ResponseFormat::JsonObject;
+
+        let actual = fixture.validate("[1, 2]");
+
+        let expected = vec!["$: expected object, found array".to_string()];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_validate_json_schema_valid_output() {
+        let fixture = fixture_schema();
+
+        let actual = fixture.validate(r#"{"title": "Parser", "tags": ["rust"]}"#);
+
+        let expected: Vec<String> = vec![];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_validate_json_schema_invalid_output() {
+        let fixture = fixture_schema();
+
+        let actual = fixture.validate(r#"{"extra": true, "tags": ["rust", 1]}"#);
+
+        let expected = vec![
+            "$: missing required property `title`".to_string(),
+            "$: unexpected property `extra`".to_string(),
+            "$.tags[1]: expected string, found number".to_string(),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_deserialize_json_schema_format() {
+        let fixture =
+            json!({"type": "json_schema", "name": "summary", "schema": {"type": "object"}});
+
+        let actual: ResponseFormat = serde_json::from_value(fixture).unwrap();
+
+        let expected = ResponseFormat::JsonSchema {
+            name: "summary".to_string(),
+            schema: json!({"type": "object"}),
+            strict: None,
+        };
+        assert_eq!(actual, expected);
+    }
+}
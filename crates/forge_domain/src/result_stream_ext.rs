@@ -2,7 +2,10 @@ use anyhow::Context as _;
 use tokio_stream::StreamExt;
 
 use crate::reasoning::{Reasoning, ReasoningFull};
-use crate::{ChatCompletionMessage, ChatCompletionMessageFull, ToolCallFull, ToolCallPart, Usage};
+use crate::{
+    ChatCompletionMessage, ChatCompletionMessageFull, DEFAULT_TAG_PREFIX, ResponseSegment,
+    ToolCall, ToolCallFull, ToolCallId, ToolCallPart, Usage, extract_json_tag,
+};
 
 /// Extension trait for ResultStream to provide additional functionality
 #[async_trait::async_trait]
@@ -21,14 +24,32 @@ pub trait ResultStreamExt<E> {
         self,
         should_interrupt_for_xml: bool,
     ) -> Result<ChatCompletionMessageFull, E>;
+
+    /// Same as [`ResultStreamExt::into_full`] but recognizes XML tool calls
+    /// using the given internal tag prefix instead of the default one.
+    async fn into_full_with_tag_prefix(
+        self,
+        should_interrupt_for_xml: bool,
+        tag_prefix: &str,
+    ) -> Result<ChatCompletionMessageFull, E>;
 }
 
 #[async_trait::async_trait]
 impl ResultStreamExt<anyhow::Error> for crate::BoxStream<ChatCompletionMessage, anyhow::Error> {
     async fn into_full(
+        self,
+        should_interrupt_for_xml: bool,
+    ) -> anyhow::Result<ChatCompletionMessageFull> {
+        self.into_full_with_tag_prefix(should_interrupt_for_xml, DEFAULT_TAG_PREFIX)
+            .await
+    }
+
+    async fn into_full_with_tag_prefix(
         mut self,
         should_interrupt_for_xml: bool,
+        tag_prefix: &str,
     ) -> anyhow::Result<ChatCompletionMessageFull> {
+        let tool_call_tag = format!("{tag_prefix}tool_call");
         let mut messages = Vec::new();
         let mut usage: Usage = Default::default();
         let mut content = String::new();
@@ -53,11 +74,12 @@ impl ResultStreamExt<anyhow::Error> for crate::BoxStream<ChatCompletionMessage,
                     // Check for XML tool calls in the content, but only interrupt if flag is set
                     if should_interrupt_for_xml {
                         // Use match instead of ? to avoid propagating errors
-                        if let Some(tool_call) = ToolCallFull::try_from_xml(&content)
-                            .ok()
-                            .into_iter()
-                            .flatten()
-                            .next()
+                        if let Some(tool_call) =
+                            ToolCallFull::try_from_xml_with_tag(&content, &tool_call_tag)
+                                .ok()
+                                .into_iter()
+                                .flatten()
+                                .next()
                         {
                             xml_tool_calls = Some(tool_call);
                             tool_interrupted = true;
@@ -83,19 +105,21 @@ impl ResultStreamExt<anyhow::Error> for crate::BoxStream<ChatCompletionMessage,
             .collect::<Vec<_>>()
             .join("");
 
-        #[allow(clippy::collapsible_if)]
-        if tool_interrupted && !content.trim().ends_with("</forge_tool_call>") {
-            if let Some((i, right)) = content.rmatch_indices("</forge_tool_call>").next() {
-                content.truncate(i + right.len());
-
-                // Add a comment for the assistant to signal interruption
-                content.push('\n');
-                content.push_str("<forge_feedback>");
-                content.push_str(
-                    "Response interrupted by tool result. Use only one tool at the end of the message",
-                );
-                content.push_str("</forge_feedback>");
-            }
+        // Locate the end of the tool call by parsing its payload, as the arguments may
+        // themselves contain a literal closing tag
+        if tool_interrupted
+            && let Some((_, end)) = extract_json_tag(&content, &tool_call_tag)
+            && !content[end..].trim().is_empty()
+        {
+            content.truncate(end);
+
+            // Add a comment for the assistant to signal interruption
+            content.push('\n');
+            content.push_str(&format!("<{tag_prefix}feedback>"));
+            content.push_str(
+                "Response interrupted by tool result. Use only one tool at the end of the message",
+            );
+            content.push_str(&format!("</{tag_prefix}feedback>"));
         }
 
         // Extract all tool calls in a fully declarative way with combined sources
@@ -120,6 +144,16 @@ impl ResultStreamExt<anyhow::Error> for crate::BoxStream<ChatCompletionMessage,
             .with_context(|| "Failed to parse tool call".to_string())
             .map_err(crate::Error::Retryable)?;
 
+        // Preserve the order in which text and tool calls were streamed
+        let segments = match xml_tool_calls.as_ref() {
+            Some(tool_call) => (!content.is_empty())
+                .then(|| ResponseSegment::Text(content.clone()))
+                .into_iter()
+                .chain([ResponseSegment::ToolCall(tool_call.clone())])
+                .collect(),
+            None => into_segments(&messages, &initial_tool_calls, &partial_tool_calls),
+        };
+
         // Combine all sources of tool calls
         let tool_calls: Vec<ToolCallFull> = initial_tool_calls
             .into_iter()
@@ -151,10 +185,76 @@ impl ResultStreamExt<anyhow::Error> for crate::BoxStream<ChatCompletionMessage,
             reasoning: (!reasoning.is_empty()).then_some(reasoning),
             reasoning_details: (!total_reasoning_details.is_empty())
                 .then_some(total_reasoning_details),
+            segments,
         })
     }
 }
 
+/// Splits the streamed messages into text and tool call segments, keeping the
+/// order in which the model produced them. Partial tool calls are matched to
+/// their assembled counterparts by the position at which each call started.
+fn into_segments(
+    messages: &[ChatCompletionMessage],
+    full_tool_calls: &[ToolCallFull],
+    partial_tool_calls: &[ToolCallFull],
+) -> Vec<ResponseSegment> {
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut full_tool_calls = full_tool_calls.iter();
+    let mut partial_tool_calls = partial_tool_calls.iter();
+    let mut current_call_id: Option<&ToolCallId> = None;
+    let mut has_parts = false;
+
+    for message in messages {
+        if let Some(content) = message.content.as_ref() {
+            text.push_str(content.as_str());
+        }
+
+        for tool_call in &message.tool_calls {
+            let tool_call = match tool_call {
+                ToolCall::Full(_) => full_tool_calls.next(),
+                ToolCall::Part(part) => {
+                    // Mirrors how `ToolCallFull::try_from_parts` detects a new call
+                    let is_new_call = !has_parts
+                        || part.call_id.as_ref().is_some_and(|id| {
+                            current_call_id.is_some_and(|current| current.as_str() != id.as_str())
+                        });
+                    if let Some(id) = part.call_id.as_ref() {
+                        current_call_id = Some(id);
+                    }
+                    has_parts = true;
+                    if is_new_call {
+                        partial_tool_calls.next()
+                    } else {
+                        None
+                    }
+                }
+            };
+
+            if let Some(tool_call) = tool_call {
+                if !text.is_empty() {
+                    segments.push(ResponseSegment::Text(std::mem::take(&mut text)));
+                }
+                segments.push(ResponseSegment::ToolCall(tool_call.clone()));
+            }
+        }
+    }
+
+    if !text.is_empty() {
+        segments.push(ResponseSegment::Text(text));
+    }
+
+    // Any calls that couldn't be matched to a position go at the end
+    segments.extend(
+        full_tool_calls
+            .chain(partial_tool_calls)
+            .cloned()
+            .map(ResponseSegment::ToolCall),
+    );
+
+    segments
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -206,6 +306,7 @@ mod tests {
             },
             reasoning: None,
             reasoning_details: None,
+            segments: vec![ResponseSegment::Text("Hello world!".to_string())],
         };
 
         assert_eq!(actual, expected);
@@ -233,10 +334,14 @@ mod tests {
         // Expected: Content and tool calls
         let expected = ChatCompletionMessageFull {
             content: "Processing...".to_string(),
-            tool_calls: vec![tool_call],
+            tool_calls: vec![tool_call.clone()],
             usage: Usage::default(),
             reasoning: None,
             reasoning_details: None,
+            segments: vec![
+                ResponseSegment::Text("Processing...".to_string()),
+                ResponseSegment::ToolCall(tool_call),
+            ],
         };
 
         assert_eq!(actual, expected);
@@ -296,6 +401,7 @@ mod tests {
             usage: Usage::default(),
             reasoning: Some("First reasoning: thinking deeply about this...".to_string()),
             reasoning_details: None,
+            segments: vec![ResponseSegment::Text("Hello world!".to_string())],
         };
 
         assert_eq!(actual, expected);
@@ -346,6 +452,7 @@ mod tests {
             usage: Usage::default(),
             reasoning: None,
             reasoning_details: Some(expected_reasoning_details),
+            segments: vec![ResponseSegment::Text("Processing... complete".to_string())],
         };
 
         assert_eq!(actual, expected);
@@ -374,6 +481,7 @@ mod tests {
             usage: Usage::default(),
             reasoning: None, // Empty reasoning should be None
             reasoning_details: None,
+            segments: vec![ResponseSegment::Text("Hello world".to_string())],
         };
 
         assert_eq!(actual, expected);
@@ -460,6 +568,10 @@ mod tests {
             },
             reasoning: None,
             reasoning_details: None,
+            segments: vec![ResponseSegment::Text(format!(
+                "{} and more content",
+                xml_content
+            ))],
         };
 
         assert_eq!(actual, expected);
@@ -500,6 +612,9 @@ mod tests {
             },
             reasoning: None,
             reasoning_details: None,
+            segments: vec![ResponseSegment::Text(
+                "Starting processing complete".to_string(),
+            )],
         };
 
         assert_eq!(actual, expected);
@@ -541,4 +656,98 @@ mod tests {
         assert_eq!(actual.usage.total_tokens, TokenCount::Actual(25));
         assert_eq!(actual.usage.completion_tokens, TokenCount::Actual(20));
     }
+
+    #[tokio::test]
+    async fn test_into_full_xml_tool_call_with_closing_tag_in_arguments() {
+        let xml_content = r#"<forge_tool_call>
+{"name": "test_tool", "arguments": {"content": "literal </forge_tool_call> text"}}
+</forge_tool_call>"#;
+
+        let messages = vec![Ok(ChatCompletionMessage::default()
+            .content(Content::part(format!("{xml_content} trailing text"))))];
+
+        let result_stream: BoxStream<ChatCompletionMessage, anyhow::Error> =
+            Box::pin(tokio_stream::iter(messages));
+
+        let actual = result_stream.into_full(true).await.unwrap();
+
+        let expected_content = format!(
+            "{xml_content}\n<forge_feedback>Response interrupted by tool result. Use only one tool at the end of the message</forge_feedback>"
+        );
+        assert_eq!(actual.content, expected_content);
+        assert_eq!(actual.tool_calls.len(), 1);
+        assert_eq!(
+            actual.tool_calls[0].arguments,
+            serde_json::json!({"content": "literal </forge_tool_call> text"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_into_full_with_custom_tag_prefix() {
+        let xml_content = r#"<fx_tool_call>
+{"name": "test_tool", "arguments": {"content": "<forge_tool_call>{}</forge_tool_call>"}}
+</fx_tool_call>"#;
+
+        let messages = vec![Ok(ChatCompletionMessage::default()
+            .content(Content::part(format!("{xml_content} trailing text"))))];
+
+        let result_stream: BoxStream<ChatCompletionMessage, anyhow::Error> =
+            Box::pin(tokio_stream::iter(messages));
+
+        let actual = result_stream
+            .into_full_with_tag_prefix(true, "fx_")
+            .await
+            .unwrap();
+
+        let expected_content = format!(
+            "{xml_content}\n<fx_feedback>Response interrupted by tool result. Use only one tool at the end of the message</fx_feedback>"
+        );
+        assert_eq!(actual.content, expected_content);
+        assert_eq!(actual.tool_calls.len(), 1);
+        assert_eq!(actual.tool_calls[0].name.as_str(), "test_tool");
+        assert_eq!(
+            actual.tool_calls[0].arguments,
+            serde_json::json!({"content": "<forge_tool_call>{}</forge_tool_call>"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_into_full_preserves_interleaved_text_and_tool_calls() {
+        let part = |call_id: Option<&str>, arguments: &str| {
+            ToolCall::Part(ToolCallPart {
+                call_id: call_id.map(ToolCallId::new),
+                name: call_id.map(|_| ToolName::new("fs_read")),
+                arguments_part: arguments.to_string(),
+            })
+        };
+        let messages = vec![
+            Ok(ChatCompletionMessage::default().content(Content::part("Reading the manifest."))),
+            Ok(ChatCompletionMessage::default().add_tool_call(part(Some("call_1"), r#"{"path":"#))),
+            Ok(ChatCompletionMessage::default().add_tool_call(part(None, r#""Cargo.toml"}"#))),
+            Ok(ChatCompletionMessage::default().content(Content::part("Now the lockfile."))),
+            Ok(ChatCompletionMessage::default()
+                .add_tool_call(part(Some("call_2"), r#"{"path":"Cargo.lock"}"#))),
+        ];
+
+        let result_stream: BoxStream<ChatCompletionMessage, anyhow::Error> =
+            Box::pin(tokio_stream::iter(messages));
+
+        let actual = result_stream.into_full(false).await.unwrap().segments;
+
+        let expected = vec![
+            ResponseSegment::Text("Reading the manifest.".to_string()),
+            ResponseSegment::ToolCall(ToolCallFull {
+                name: ToolName::new("fs_read"),
+                call_id: Some(ToolCallId::new("call_1")),
+                arguments: serde_json::json!({"path": "Cargo.toml"}),
+            }),
+            ResponseSegment::Text("Now the lockfile.".to_string()),
+            ResponseSegment::ToolCall(ToolCallFull {
+                name: ToolName::new("fs_read"),
+                call_id: Some(ToolCallId::new("call_2")),
+                arguments: serde_json::json!({"path": "Cargo.lock"}),
+            }),
+        ];
+        assert_eq!(actual, expected);
+    }
 }
@@ -26,6 +26,13 @@ pub struct RetryConfig {
     #[merge(strategy = crate::merge::std::overwrite)]
     pub retry_status_codes: Vec<u16>,
 
+    /// Error codes and types with which providers refuse requests because of
+    /// their content filter. Such requests aren't retried, as they would be
+    /// refused again
+    #[merge(strategy = crate::merge::std::overwrite)]
+    #[serde(default = "default_content_filter_codes")]
+    pub content_filter_codes: Vec<String>,
+
     /// Maximum delay between retries in seconds
     #[merge(strategy = crate::merge::std::overwrite)]
     pub max_delay: Option<u64>,
@@ -39,11 +46,24 @@ impl Default for RetryConfig {
             backoff_factor: 2,
             max_retry_attempts: 8,
             retry_status_codes: vec![429, 500, 502, 503, 504],
+            content_filter_codes: default_content_filter_codes(),
             max_delay: None,
         }
     }
 }
 
+fn default_content_filter_codes() -> Vec<String> {
+    [
+        "content_filter",
+        "content_policy_violation",
+        "ResponsibleAIPolicyViolation",
+        "PROHIBITED_CONTENT",
+        "SAFETY",
+    ]
+    .map(String::from)
+    .to_vec()
+}
+
 impl RetryConfig {
     // Implementation moved to forge_app::retry module to avoid backon dependency
 }
@@ -84,4 +104,25 @@ mod tests {
         assert_eq!(config.max_retry_attempts, 5);
         assert_eq!(config.retry_status_codes, vec![429, 503]);
     }
+
+    #[test]
+    fn test_retry_config_without_content_filter_codes_keeps_defaults() {
+        let fixture = serde_json::json!({
+            "initial_backoff_ms": 100,
+            "min_delay_ms": 500,
+            "backoff_factor": 3,
+            "max_retry_attempts": 5,
+            "retry_status_codes": [429]
+        });
+
+        let actual = serde_json::from_value::<RetryConfig>(fixture).unwrap();
+
+        let expected = RetryConfig::default()
+            .initial_backoff_ms(100u64)
+            .min_delay_ms(500u64)
+            .backoff_factor(3u64)
+            .max_retry_attempts(5usize)
+            .retry_status_codes(vec![429]);
+        assert_eq!(actual, expected);
+    }
 }
@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use strum_macros::{Display, EnumIter};
+
+use crate::Environment;
+
+/// Layers of rules files added to the rules of the agents. The rules of every
+/// layer are concatenated in this order, so each layer takes precedence over
+/// the ones before it, the way editors layer their settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumIter)]
+pub enum RulesLayer {
+    /// Rules of the user, kept with forge's own files
+    Global,
+    /// Rules of the project, kept in the repository
+    Project,
+    /// Overrides of the project rules meant to be git-ignored
+    Local,
+}
+
+impl RulesLayer {
+    /// Files the rules of the layer are read from, the first one that has
+    /// rules is used
+    pub fn paths(&self, env: &Environment) -> Vec<PathBuf> {
+        match self {
+            RulesLayer::Global => vec![env.base_path.join("rules.md")],
+            RulesLayer::Project => vec![
+                env.cwd.join(".forge").join("rules.md"),
+                env.cwd.join("AGENTS.md"),
+            ],
+            RulesLayer::Local => vec![env.cwd.join(".forge").join("rules.local.md")],
+        }
+    }
+}
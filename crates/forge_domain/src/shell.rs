@@ -12,3 +12,23 @@ impl CommandOutput {
         self.exit_code.is_none_or(|code| code >= 0)
     }
 }
+
+/// Quotes the value as a single argument for a POSIX shell
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_shell_quote_escapes_single_quotes() {
+        let actual = shell_quote("/home/jane/it's here");
+
+        let expected = r"'/home/jane/it'\''s here'";
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,81 @@
+use derive_setters::Setters;
+use serde::{Deserialize, Serialize};
+
+/// Controls which environment variables are passed on to shell commands.
+///
+/// Patterns are either exact variable names or contain a single `*` that
+/// matches any sequence of characters, e.g. `*_API_KEY`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Setters)]
+#[serde(rename_all = "camelCase")]
+#[setters(into)]
+pub struct ShellEnvConfig {
+    /// Variables stripped from the environment of every command
+    pub deny: Vec<String>,
+
+    /// Variables passed through even when they match a deny pattern
+    pub allow: Vec<String>,
+}
+
+impl Default for ShellEnvConfig {
+    fn default() -> Self {
+        Self {
+            // Credentials forge uses to talk to the providers
+            deny: vec!["FORGE_KEY".to_string(), "*_API_KEY".to_string()],
+            allow: vec![],
+        }
+    }
+}
+
+impl ShellEnvConfig {
+    /// Returns true if the variable may be passed on to a command
+    pub fn is_allowed(&self, name: &str) -> bool {
+        let matches_any = |patterns: &[String]| {
+            patterns
+                .iter()
+                .any(|pattern| matches_pattern(pattern, name))
+        };
+        matches_any(&self.allow) || !matches_any(&self.deny)
+    }
+}
+
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => {
+            name.len() >= prefix.len() + suffix.len()
+                && name.starts_with(prefix)
+                && name.ends_with(suffix)
+        }
+        None => pattern == name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_provider_keys_are_denied_by_default() {
+        let fixture = ShellEnvConfig::default();
+
+        let actual = ["FORGE_KEY", "OPENAI_API_KEY", "PATH", "API_KEY_HOME"]
+            .map(|name| fixture.is_allowed(name));
+
+        let expected = [false, false, true, true];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_allow_list_overrides_deny_list() {
+        let fixture = ShellEnvConfig::default()
+            .deny(vec!["AWS_*".to_string()])
+            .allow(vec!["AWS_REGION".to_string()]);
+
+        let actual = ["AWS_SECRET_ACCESS_KEY", "AWS_REGION", "OPENAI_API_KEY"]
+            .map(|name| fixture.is_allowed(name));
+
+        let expected = [false, true, true];
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,14 +1,26 @@
 use std::collections::HashMap;
 
 use derive_setters::Setters;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::Environment;
+use crate::{DEFAULT_TAG_PREFIX, Environment};
+
+/// A section of the system context that is rendered into the system prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemContextSection {
+    Time,
+    Env,
+    Files,
+    Tools,
+    CustomRules,
+    Variables,
+}
 
 #[derive(Debug, Setters, Clone, Serialize, Deserialize)]
 #[setters(strip_option)]
-#[derive(Default)]
 pub struct SystemContext {
     // Current date and time at the time of context creation
     pub current_time: String,
@@ -39,4 +51,67 @@ pub struct SystemContext {
     /// Indicates whether the agent supports parallel tool calls.
     #[serde(default)]
     pub supports_parallel_tool_calls: bool,
+
+    /// Prefix used for forge-internal XML tags
+    #[serde(default = "default_tag_prefix")]
+    pub tag_prefix: String,
+
+    /// Markdown notes the agent keeps across turns
+    #[serde(default)]
+    pub scratchpad: String,
+
+    /// Order of the sections in the system prompt, when not set the template
+    /// decides the layout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sections: Option<Vec<SystemContextSection>>,
+}
+
+fn default_tag_prefix() -> String {
+    DEFAULT_TAG_PREFIX.to_string()
+}
+
+impl Default for SystemContext {
+    fn default() -> Self {
+        Self {
+            current_time: Default::default(),
+            env: Default::default(),
+            tool_information: Default::default(),
+            tool_supported: Default::default(),
+            files: Default::default(),
+            custom_rules: Default::default(),
+            variables: Default::default(),
+            supports_parallel_tool_calls: Default::default(),
+            tag_prefix: default_tag_prefix(),
+            scratchpad: Default::default(),
+            sections: Default::default(),
+        }
+    }
+}
+
+impl SystemContext {
+    /// Lays the context out in the order of the given sections, the content
+    /// of the sections that aren't listed is cleared
+    pub fn with_sections(mut self, sections: Vec<SystemContextSection>) -> Self {
+        let includes = |section| sections.contains(&section);
+        if !includes(SystemContextSection::Time) {
+            self.current_time.clear();
+        }
+        if !includes(SystemContextSection::Env) {
+            self.env = None;
+        }
+        if !includes(SystemContextSection::Files) {
+            self.files.clear();
+        }
+        if !includes(SystemContextSection::Tools) {
+            self.tool_information = None;
+        }
+        if !includes(SystemContextSection::CustomRules) {
+            self.custom_rules.clear();
+        }
+        if !includes(SystemContextSection::Variables) {
+            self.variables.clear();
+        }
+        self.sections = Some(sections);
+        self
+    }
 }
@@ -4,6 +4,8 @@ use derive_setters::Setters;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::ChangedFiles;
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, eserde::Deserialize, Default, JsonSchema)]
 pub enum Status {
     #[default]
@@ -91,6 +93,22 @@ impl TaskList {
     pub fn get_task_mut(&mut self, index: usize) -> Option<&mut Task> {
         self.tasks.get_mut(index)
     }
+
+    /// Renders the tasks as a markdown checklist where done tasks are checked
+    pub fn to_checklist(&self) -> String {
+        self.tasks
+            .iter()
+            .map(|task| {
+                let mark = if task.is_done() { "x" } else { " " };
+                let note = if task.is_in_progress() {
+                    " _(in progress)_"
+                } else {
+                    ""
+                };
+                format!("- [{mark}] {}{note}\n", task.task)
+            })
+            .collect()
+    }
 }
 
 impl TaskList {
@@ -132,6 +150,70 @@ impl TaskList {
     }
 }
 
+/// Reconciliation of the planned tasks with the work actually done
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TaskReport {
+    pub done: Vec<Task>,
+    /// Tasks not done yet, including those in progress
+    pub pending: Vec<Task>,
+    /// Changed files that no task mentions, likely work that wasn't planned
+    pub unplanned_files: Vec<String>,
+}
+
+impl TaskReport {
+    pub fn new(tasks: &TaskList, changed_files: &ChangedFiles) -> Self {
+        let (done, pending) = tasks
+            .tasks()
+            .iter()
+            .cloned()
+            .partition::<Vec<_>, _>(Task::is_done);
+        let descriptions = tasks
+            .tasks()
+            .iter()
+            .map(|task| task.task.to_lowercase())
+            .collect::<Vec<_>>();
+        // A file counts as planned when a task mentions its name, with or
+        // without the extension
+        let unplanned_files = [
+            &changed_files.created,
+            &changed_files.modified,
+            &changed_files.removed,
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|path| {
+            let path = std::path::Path::new(path.as_str());
+            let names = [path.file_name(), path.file_stem()]
+                .into_iter()
+                .flatten()
+                .map(|name| name.to_string_lossy().to_lowercase())
+                .collect::<Vec<_>>();
+            !descriptions
+                .iter()
+                .any(|description| names.iter().any(|name| description.contains(name.as_str())))
+        })
+        .cloned()
+        .collect();
+
+        Self { done, pending, unplanned_files }
+    }
+
+    /// Renders the report as a markdown summary for the user
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("**Plan vs. actual**\n");
+        for task in &self.done {
+            markdown.push_str(&format!("- [x] {}\n", task.task));
+        }
+        for task in &self.pending {
+            markdown.push_str(&format!("- [ ] {}\n", task.task));
+        }
+        for path in &self.unplanned_files {
+            markdown.push_str(&format!("- _unplanned:_ `{path}`\n"));
+        }
+        markdown
+    }
+}
+
 impl Status {
     pub fn status_name(&self) -> &'static str {
         match self {
@@ -326,4 +408,51 @@ mod tests {
 
         assert!(result.is_none());
     }
+
+    #[test]
+    fn test_checklist_reflects_task_status() {
+        let mut fixture = TaskList::new();
+        fixture.append("Parse the manifest");
+        fixture.append("Resolve dependencies");
+        fixture.append("Write the lockfile");
+        fixture.mark_done(1);
+        fixture.update_status(2, Status::InProgress);
+
+        let actual = fixture.to_checklist();
+
+        let expected = [
+            "- [x] Parse the manifest",
+            "- [ ] Resolve dependencies _(in progress)_",
+            "- [ ] Write the lockfile",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_task_report_reconciles_tasks_with_changed_files() {
+        let mut tasks = TaskList::new();
+        tasks.append("Add the tokenizer to lexer.rs");
+        tasks.append("Wire the parser into main");
+        tasks.append("Document the grammar");
+        tasks.mark_done(1);
+        tasks.update_status(2, Status::InProgress);
+        let mut changed_files = ChangedFiles::default();
+        changed_files.record_modified("/project/src/lexer.rs");
+        changed_files.record_modified("/project/src/main.rs");
+        changed_files.record_created("/project/src/cache.rs");
+
+        let actual = TaskReport::new(&tasks, &changed_files);
+
+        let expected = TaskReport {
+            done: vec![Task::new(1, "Add the tokenizer to lexer.rs").status(Status::Done)],
+            pending: vec![
+                Task::new(2, "Wire the parser into main").status(Status::InProgress),
+                Task::new(3, "Document the grammar"),
+            ],
+            unplanned_files: vec!["/project/src/cache.rs".to_string()],
+        };
+        assert_eq!(actual, expected);
+    }
 }
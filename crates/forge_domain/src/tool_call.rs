@@ -3,7 +3,7 @@ use derive_setters::Setters;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::xml::extract_tag_content;
+use crate::xml::{DEFAULT_TAG_PREFIX, extract_json_tag, extract_tag_content};
 use crate::{Error, Result, ToolName};
 
 /// Unique identifier for a using a tool
@@ -20,7 +20,7 @@ impl ToolCallId {
         &self.0
     }
 
-    fn generate() -> Self {
+    pub fn generate() -> Self {
         let id = format!("forge_call_id_{}", uuid::Uuid::new_v4());
         ToolCallId(id)
     }
@@ -147,7 +147,23 @@ impl ToolCallFull {
 
     /// Parse multiple tool calls from XML format.
     pub fn try_from_xml(input: &str) -> std::result::Result<Vec<ToolCallFull>, Error> {
-        match extract_tag_content(input, "forge_tool_call") {
+        Self::try_from_xml_with_tag(input, &format!("{DEFAULT_TAG_PREFIX}tool_call"))
+    }
+
+    /// Parse tool calls wrapped in the given XML tag.
+    ///
+    /// The JSON payload is parsed first so that tag-like text within argument
+    /// values doesn't terminate the tool call early. Falls back to a plain tag
+    /// search so that malformed payloads still surface a parse error.
+    pub fn try_from_xml_with_tag(
+        input: &str,
+        tag: &str,
+    ) -> std::result::Result<Vec<ToolCallFull>, Error> {
+        let content = extract_json_tag(input, tag)
+            .map(|(content, _)| content)
+            .or_else(|| extract_tag_content(input, tag));
+
+        match content {
             None => Ok(Default::default()),
             Some(content) => {
                 let mut tool_call: ToolCallFull =
@@ -323,4 +339,38 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_try_from_xml_with_closing_tag_in_arguments() {
+        let message = r#"<forge_tool_call>
+{"name": "forge_tool_fs_create", "arguments": {"path": "/docs/tools.md", "content": "Wrap calls in <forge_tool_call>...</forge_tool_call> tags"}}
+</forge_tool_call>"#;
+
+        let actual = ToolCallFull::try_from_xml(message).unwrap();
+
+        let expected = serde_json::json!({
+            "path": "/docs/tools.md",
+            "content": "Wrap calls in <forge_tool_call>...</forge_tool_call> tags"
+        });
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual[0].name, ToolName::new("forge_tool_fs_create"));
+        assert_eq!(actual[0].arguments, expected);
+    }
+
+    #[test]
+    fn test_try_from_xml_with_cdata() {
+        let message = r#"<forge_tool_call><![CDATA[
+{"name": "forge_tool_fs_create", "arguments": {"path": "/src/lib.rs", "content": "if a < b && b > c {}"}}
+]]></forge_tool_call>"#;
+
+        let actual = ToolCallFull::try_from_xml(message).unwrap();
+
+        let expected = serde_json::json!({
+            "path": "/src/lib.rs",
+            "content": "if a < b && b > c {}"
+        });
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual[0].name, ToolName::new("forge_tool_fs_create"));
+        assert_eq!(actual[0].arguments, expected);
+    }
 }
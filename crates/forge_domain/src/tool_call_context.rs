@@ -1,24 +1,71 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use derive_setters::Setters;
 use tokio::sync::mpsc::Sender;
 
-use crate::{ChatResponse, TaskList};
+use crate::{ChangedFiles, ChatResponse, TaskList, ToolName, ToolPostProcessor};
 
 /// Type alias for Arc<Sender<Result<ChatResponse>>>
 type ArcSender = Arc<Sender<anyhow::Result<ChatResponse>>>;
 
+/// Instruction returned to the model after a successful attempt_completion
+pub const DEFAULT_COMPLETION_FEEDBACK: &str =
+    "[Task was completed successfully. Now wait for user feedback]";
+
 /// Provides additional context for tool calls.
-#[derive(Debug, Setters)]
+#[derive(Debug, Clone, Setters)]
 pub struct ToolCallContext {
     sender: Option<ArcSender>,
     pub tasks: TaskList,
+    /// Text returned to the model after a successful completion, an empty
+    /// string suppresses it
+    pub completion_feedback: String,
+    /// Files changed by the tools, reported when the task is completed
+    pub changed_files: ChangedFiles,
+    /// Files changed as of the last completion that reported them
+    pub reported_changed_files: ChangedFiles,
+    /// Markdown notes the agent keeps across turns
+    pub scratchpad: String,
+    /// Command the final answer is piped through before it's shown
+    pub post_response_command: Option<String>,
+    /// Transformations applied to the output of tools, keyed by tool name
+    pub tool_post_processors: HashMap<ToolName, ToolPostProcessor>,
+    /// Whether tool calls without an explanation are rejected
+    pub require_tool_explanation: bool,
+    /// Formatter commands keyed by file extension
+    pub formatters: HashMap<String, String>,
+    /// Directory the agent works in, the project root when not set
+    pub cwd: Option<PathBuf>,
 }
 
 impl ToolCallContext {
     /// Creates a new ToolCallContext with default values
     pub fn new(task_list: TaskList) -> Self {
-        Self { sender: None, tasks: task_list }
+        Self {
+            sender: None,
+            tasks: task_list,
+            completion_feedback: DEFAULT_COMPLETION_FEEDBACK.to_string(),
+            changed_files: ChangedFiles::default(),
+            reported_changed_files: ChangedFiles::default(),
+            scratchpad: String::new(),
+            post_response_command: None,
+            tool_post_processors: HashMap::new(),
+            require_tool_explanation: false,
+            formatters: HashMap::new(),
+            cwd: None,
+        }
+    }
+
+    /// Returns the files changed for the completion to report, which are
+    /// empty when nothing changed since the last completion reported them
+    pub fn unreported_changed_files(&mut self) -> ChangedFiles {
+        if self.changed_files == self.reported_changed_files {
+            return ChangedFiles::default();
+        }
+        self.reported_changed_files = self.changed_files.clone();
+        self.changed_files.clone()
     }
 
     /// Send a message through the sender if available
@@ -37,6 +84,8 @@ impl ToolCallContext {
 
 #[cfg(test)]
 mod tests {
+    use pretty_assertions::assert_eq;
+
     use super::*;
 
     #[test]
@@ -45,6 +94,24 @@ mod tests {
         assert!(context.sender.is_none());
     }
 
+    #[test]
+    fn test_unreported_changed_files_only_reports_changes_once() {
+        let mut fixture = ToolCallContext::new(TaskList::new());
+        fixture.changed_files.record_created("src/parser.rs");
+
+        let first = fixture.unreported_changed_files();
+        let unchanged = fixture.unreported_changed_files();
+        fixture.changed_files.record_modified("src/lib.rs");
+        let changed = fixture.unreported_changed_files();
+
+        let mut expected = ChangedFiles::default();
+        expected.record_created("src/parser.rs");
+        assert_eq!(first, expected);
+        assert!(unchanged.is_empty());
+        expected.record_modified("src/lib.rs");
+        assert_eq!(changed, expected);
+    }
+
     #[test]
     fn test_with_sender() {
         // This is just a type check test - we don't actually create a sender
@@ -1,8 +1,14 @@
-use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::ToolName;
 
-#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+/// Controls whether and which tool the model has to call
+///
+/// Written as `auto`, `none`, `required` or the name of the tool to call.
+#[derive(Clone, Debug, Default, PartialEq)]
 pub enum ToolChoice {
     #[default]
     None,
@@ -10,3 +16,116 @@ pub enum ToolChoice {
     Required,
     Call(ToolName),
 }
+
+impl fmt::Display for ToolChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolChoice::None => write!(f, "none"),
+            ToolChoice::Auto => write!(f, "auto"),
+            ToolChoice::Required => write!(f, "required"),
+            ToolChoice::Call(tool_name) => write!(f, "{tool_name}"),
+        }
+    }
+}
+
+impl From<&str> for ToolChoice {
+    fn from(value: &str) -> Self {
+        match value {
+            "none" => ToolChoice::None,
+            "auto" => ToolChoice::Auto,
+            "required" => ToolChoice::Required,
+            tool_name => ToolChoice::Call(ToolName::new(tool_name)),
+        }
+    }
+}
+
+impl Serialize for ToolChoice {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ToolChoice {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        let value = String::deserialize(deserializer)?;
+        if value.trim().is_empty() {
+            return Err(Error::custom(
+                "tool_choice must be auto, none, required or a tool name",
+            ));
+        }
+        Ok(Self::from(value.as_str()))
+    }
+}
+
+impl JsonSchema for ToolChoice {
+    fn schema_name() -> String {
+        "ToolChoice".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::r#gen::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{InstanceType, Schema, SchemaObject};
+        Schema::Object(SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            metadata: Some(Box::new(schemars::schema::Metadata {
+                description: Some(
+                    "Either auto, none, required or the name of the tool the model has to call"
+                        .to_string(),
+                ),
+                ..Default::default()
+            })),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_tool_choice_round_trip() {
+        let fixture = [
+            ToolChoice::Auto,
+            ToolChoice::None,
+            ToolChoice::Required,
+            ToolChoice::Call(ToolName::new("forge_tool_fs_read")),
+        ];
+
+        let actual = fixture
+            .iter()
+            .map(|choice| serde_json::to_value(choice).unwrap())
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            json!("auto"),
+            json!("none"),
+            json!("required"),
+            json!("forge_tool_fs_read"),
+        ];
+        assert_eq!(actual, expected);
+        assert_eq!(
+            actual
+                .into_iter()
+                .map(|value| serde_json::from_value::<ToolChoice>(value).unwrap())
+                .collect::<Vec<_>>(),
+            fixture.to_vec()
+        );
+    }
+
+    #[test]
+    fn test_tool_choice_rejects_empty_value() {
+        let actual = serde_json::from_value::<ToolChoice>(json!(""));
+
+        assert!(actual.is_err());
+    }
+}
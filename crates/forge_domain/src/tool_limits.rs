@@ -0,0 +1,55 @@
+use derive_setters::Setters;
+use serde::{Deserialize, Serialize};
+
+use crate::Environment;
+
+/// Truncation limits of individual tools. Limits that aren't set fall back to
+/// the global ones of the environment.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Setters)]
+#[serde(rename_all = "camelCase")]
+#[setters(strip_option)]
+pub struct ToolLimits {
+    /// Maximum lines kept from the start of shell output
+    #[serde(default)]
+    pub shell_prefix_lines: Option<usize>,
+
+    /// Maximum lines kept from the end of shell output
+    #[serde(default)]
+    pub shell_suffix_lines: Option<usize>,
+
+    /// Maximum number of lines returned by a search
+    #[serde(default)]
+    pub search_lines: Option<u64>,
+
+    /// Maximum characters of fetched content
+    #[serde(default)]
+    pub fetch_chars: Option<usize>,
+}
+
+impl Environment {
+    /// Lines kept from the start and the end of shell output
+    pub fn shell_limits(&self) -> (usize, usize) {
+        (
+            self.tool_limits
+                .shell_prefix_lines
+                .unwrap_or(self.stdout_max_prefix_length),
+            self.tool_limits
+                .shell_suffix_lines
+                .unwrap_or(self.stdout_max_suffix_length),
+        )
+    }
+
+    /// Maximum number of lines returned by a search
+    pub fn search_limit(&self) -> u64 {
+        self.tool_limits
+            .search_lines
+            .unwrap_or(self.max_search_lines)
+    }
+
+    /// Maximum characters of fetched content
+    pub fn fetch_limit(&self) -> usize {
+        self.tool_limits
+            .fetch_chars
+            .unwrap_or(self.fetch_truncation_limit)
+    }
+}
@@ -0,0 +1,45 @@
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+lazy_static::lazy_static! {
+    static ref EMAIL: Regex =
+        Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").unwrap();
+    static ref PHONE: Regex =
+        Regex::new(r"\+?\d{1,3}[ .-]?\(?\d{3}\)?[ .-]?\d{3}[ .-]?\d{4}\b").unwrap();
+}
+
+/// Transformation applied to the output of a tool before it's added to the
+/// conversation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolPostProcessor {
+    /// Replaces email addresses and phone numbers with placeholders
+    Redact,
+    /// Shell command the text output is piped through, its output replaces
+    /// the tool output and a non-zero exit status withholds it
+    Command(String),
+}
+
+/// Replaces email addresses and phone numbers in the text with placeholders
+pub fn redact(text: &str) -> String {
+    let text = EMAIL.replace_all(text, "[REDACTED EMAIL]");
+    PHONE.replace_all(&text, "[REDACTED PHONE]").into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_redact_replaces_emails_and_phone_numbers() {
+        let fixture = "Contact jane.doe@example.com or +1 (555) 123-4567, order #1234 shipped";
+
+        let actual = redact(fixture);
+
+        let expected = "Contact [REDACTED EMAIL] or [REDACTED PHONE], order #1234 shipped";
+        assert_eq!(actual, expected);
+    }
+}
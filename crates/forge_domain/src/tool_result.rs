@@ -14,6 +14,9 @@ pub struct ToolResult {
     pub call_id: Option<ToolCallId>,
     #[setters(skip)]
     pub output: ToolOutput,
+    /// Whether the result is kept as is when the context is compacted
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
 }
 
 impl ToolResult {
@@ -22,6 +25,7 @@ impl ToolResult {
             name,
             call_id: Default::default(),
             output: Default::default(),
+            pinned: false,
         }
     }
 
@@ -75,6 +79,7 @@ impl From<ToolCallFull> for ToolResult {
             name: value.name,
             call_id: value.call_id,
             output: Default::default(),
+            pinned: false,
         }
     }
 }
@@ -136,4 +136,18 @@ mod tests {
         let prompt = ToolUsagePrompt::from(&tools);
         assert_snapshot!(prompt);
     }
+
+    #[test]
+    fn test_tool_usage_for_allowed_tools() {
+        let definitions = Tools::iter().map(|v| v.definition()).collect::<Vec<_>>();
+        let agent = crate::Agent::new(crate::AgentId::new("test-agent")).tools(vec![
+            ToolName::new("forge_tool_fs_read"),
+            ToolName::new("forge_tool_process_shell"),
+        ]);
+        let tools = agent.allowed_tools(&definitions);
+
+        let actual = ToolUsagePrompt::from(&tools).to_string();
+
+        assert_snapshot!(actual);
+    }
 }
@@ -1,5 +1,5 @@
 #![allow(clippy::enum_variant_names)]
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::path::PathBuf;
 
 use convert_case::{Case, Casing};
@@ -13,9 +13,13 @@ use strum::IntoEnumIterator;
 use strum_macros::{AsRefStr, Display, EnumDiscriminants, EnumIter};
 
 use crate::{
-    Status, ToolCallArgumentError, ToolCallFull, ToolDefinition, ToolDescription, ToolName,
+    Environment, Status, ToolCallArgumentError, ToolCallFull, ToolDefinition, ToolDescription,
+    ToolName,
 };
 
+/// Placeholder in tool descriptions for the number of lines read by default
+const MAX_READ_LINES: &str = "{max_read_lines}";
+
 /// Enum representing all possible tool input types.
 ///
 /// This enum contains variants for each type of input that can be passed to
@@ -52,6 +56,13 @@ pub enum Tools {
     ForgeToolTaskListUpdate(TaskListUpdate),
     ForgeToolTaskListList(TaskListList),
     ForgeToolTaskListClear(TaskListClear),
+    ForgeToolScratchpadWrite(ScratchpadWrite),
+    ForgeToolScratchpadRead(ScratchpadRead),
+    ForgeToolRepoTree(RepoTree),
+    ForgeToolFsReadGlob(FSReadGlob),
+    ForgeToolSystemInfo(SystemInfo),
+    ForgeToolFormatCode(FormatCode),
+    ForgeToolFsList(FSListInput),
 }
 
 /// Input structure for agent tool calls. This serves as the generic schema
@@ -72,12 +83,13 @@ pub struct AgentInput {
 /// Reads file contents from the specified absolute path. Ideal for analyzing
 /// code, configuration files, documentation, or textual data. Automatically
 /// extracts text from PDF and DOCX files, preserving the original formatting.
-/// Returns the content as a string. For files larger than 2,000 lines,
-/// the tool automatically returns only the first 2,000 lines. You should
-/// always rely on this default behavior and avoid specifying custom ranges
-/// unless absolutely necessary. If needed, specify a range with the start_line
-/// and end_line parameters, ensuring the total range does not exceed 2,000
-/// lines. Specifying a range exceeding this limit will result in an error.
+/// Returns the content as a string. For files larger than {max_read_lines}
+/// lines, the tool automatically returns only the first {max_read_lines}
+/// lines. You should always rely on this default behavior and avoid specifying
+/// custom ranges unless absolutely necessary. If needed, specify a range with
+/// the start_line and end_line parameters, ensuring the total range does not
+/// exceed {max_read_lines} lines. Specifying a range exceeding this limit will
+/// result in an error.
 /// Binary files are automatically detected and rejected.
 #[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
 pub struct FSRead {
@@ -93,6 +105,17 @@ pub struct FSRead {
     /// will end at this line position.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub end_line: Option<i32>,
+
+    /// Optional git revision, such as a commit, branch or tag, to read the
+    /// file at instead of its current content in the working tree.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+
+    /// Optional flag to prefix every returned line with its line number in
+    /// the file followed by a tab, to refer to lines precisely. The numbers
+    /// are not part of the file, leave them out of any content written back.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub with_line_numbers: Option<bool>,
     /// One sentence explanation as to why this specific tool is being used, and
     /// how it contributes to the goal.
     #[serde(default)]
@@ -159,12 +182,29 @@ pub struct FSSearch {
     /// If not provided, it will search all files (*).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub file_pattern: Option<String>,
+
+    /// Order of the results. 'path_asc' and 'path_desc' sort the files by
+    /// path, 'match_count' lists the files with the most matches first. The
+    /// matches of a file always stay together. If not provided, files are
+    /// listed in the order they were searched.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<SearchOrder>,
+
     /// One sentence explanation as to why this specific tool is being used, and
     /// how it contributes to the goal.
     #[serde(default)]
     pub explanation: Option<String>,
 }
 
+/// Order in which the files matched by a search are listed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchOrder {
+    PathAsc,
+    PathDesc,
+    MatchCount,
+}
+
 /// Request to remove a file at the specified path. Use this when you need to
 /// delete an existing file. The path must be absolute. This operation cannot
 /// be undone, so use it carefully.
@@ -308,6 +348,12 @@ pub struct Shell {
     #[serde(skip_serializing_if = "is_default")]
     pub keep_ansi: bool,
 
+    /// Whether to parse the output of recognized test runners (cargo test,
+    /// TAP or JUnit XML) into a structured summary of passed and failed tests.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub parse_results: bool,
+
     /// One sentence explanation as to why this specific tool is being used, and
     /// how it contributes to the goal.
     #[serde(default)]
@@ -325,6 +371,28 @@ pub struct NetFetch {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub raw: Option<bool>,
 
+    /// Fetch the URL again instead of reusing a response fetched earlier in
+    /// the session (default: false)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_cache: Option<bool>,
+
+    /// HTTP method of the request, e.g. POST or PUT (default: GET). Only the
+    /// content of a GET is converted to markdown and reused
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+
+    /// Headers sent with the request, e.g. `Authorization` or `Content-Type`
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<BTreeMap<String, String>>,
+
+    /// Body sent with the request, e.g. a JSON payload
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+
     /// One sentence explanation as to why this specific tool is being used, and
     /// how it contributes to the goal.
     #[serde(default)]
@@ -389,6 +457,12 @@ pub struct AttemptCompletion {
     /// does not require further input from the user. Don't end your result with
     /// questions or offers for further assistance.
     pub result: String,
+
+    /// Whether to report which tasks of the task list were done, which are
+    /// still pending and which changed files no task accounts for.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub task_report: bool,
 }
 
 /// Add a new task to the end of the task list. Tasks are stored in conversation
@@ -457,6 +531,106 @@ pub struct TaskListClear {
     pub explanation: Option<String>,
 }
 
+/// Replace the content of the scratchpad, a markdown note that belongs to the
+/// conversation. The scratchpad is shown to you on every turn and is never
+/// summarized away, so use it to keep your plan, decisions and findings for
+/// long running tasks. The previous content is overwritten, so always write
+/// the complete note.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct ScratchpadWrite {
+    /// The complete markdown content of the scratchpad
+    pub content: String,
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Read the current content of the scratchpad. Use this tool to review the
+/// notes written earlier in the conversation.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct ScratchpadRead {
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Shows the directory structure of the workspace as a tree, along with the
+/// number of files inside every directory. Files ignored by git are left out.
+/// Use this tool to get a quick overview of how a project is organized before
+/// exploring it with the search and read tools. Directories deeper than the
+/// maximum depth are listed without their content, and the listing stops once
+/// the maximum number of entries is reached.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct RepoTree {
+    /// Maximum depth of directories to expand. Defaults to 3.
+    pub max_depth: Option<i32>,
+
+    /// Maximum number of entries to list. Defaults to 200.
+    pub max_entries: Option<i32>,
+
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Reads all files matching a glob pattern under a directory in a single
+/// call. Use it instead of several read calls to take in a set of related
+/// files, such as all the Rust files of a module. Files ignored by git, binary
+/// files and files too large to be read are skipped. Each file is read like
+/// with the read tool, so files longer than {max_read_lines} lines only return
+/// their first {max_read_lines} lines. The number of files and the total size
+/// of the output are bounded, the files left out are listed so that they can be
+/// read separately.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct FSReadGlob {
+    /// The absolute path of the directory to search in.
+    pub path: String,
+
+    /// Glob pattern matched against the paths relative to the directory, e.g.
+    /// `**/*.rs` for all Rust files or `src/*.toml`.
+    pub pattern: String,
+
+    /// Maximum number of files to read. Defaults to 20.
+    pub max_files: Option<i32>,
+
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Returns facts about the environment: the operating system, the CPU
+/// architecture, the shell, the working directory and the versions of the
+/// toolchains installed, such as rustc, cargo, node, python or go. Use this
+/// tool instead of running several shell commands to find out what's
+/// available on the machine.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct SystemInfo {
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Formats a file with the formatter configured for its language, such as
+/// rustfmt for Rust, prettier for JavaScript and TypeScript or black for
+/// Python, and returns the changes it made as a diff. The file is left
+/// untouched when the formatter fails, and the formatting can be reverted
+/// with the undo tool. Use this tool after editing a file instead of
+/// formatting it by hand or running the formatter through the shell.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct FormatCode {
+    /// The absolute path of the file to format.
+    pub path: String,
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
 fn default_raw() -> Option<bool> {
     Some(false)
 }
@@ -484,10 +658,13 @@ pub struct FetchInput {
 /// Request to list files and directories within the specified directory. If
 /// recursive is true, it will list all files and directories recursively. If
 /// recursive is false or not provided, it will only list the top-level
-/// contents. The path must be absolute. Do not use this tool to confirm the
-/// existence of files you may have created, as the user will let you know if
-/// the files were created successfully or not.
-#[derive(Default, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+/// contents. Every entry comes with its type and, for files, its size in
+/// bytes. Files ignored by git are left out and long listings are cut short.
+/// The path must be absolute. Use this tool instead of running `ls` through
+/// the shell. Do not use this tool to confirm the existence of files you may
+/// have created, as the user will let you know if the files were created
+/// successfully or not.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
 pub struct FSListInput {
     /// The path of the directory to list contents for (absolute path required)
     pub path: String,
@@ -583,6 +760,13 @@ impl ToolDescription for Tools {
             Tools::ForgeToolTaskListUpdate(v) => v.description(),
             Tools::ForgeToolTaskListList(v) => v.description(),
             Tools::ForgeToolTaskListClear(v) => v.description(),
+            Tools::ForgeToolScratchpadWrite(v) => v.description(),
+            Tools::ForgeToolScratchpadRead(v) => v.description(),
+            Tools::ForgeToolRepoTree(v) => v.description(),
+            Tools::ForgeToolFsReadGlob(v) => v.description(),
+            Tools::ForgeToolSystemInfo(v) => v.description(),
+            Tools::ForgeToolFormatCode(v) => v.description(),
+            Tools::ForgeToolFsList(v) => v.description(),
         }
     }
 }
@@ -626,6 +810,13 @@ impl Tools {
             Tools::ForgeToolTaskListUpdate(_) => r#gen.into_root_schema_for::<TaskListUpdate>(),
             Tools::ForgeToolTaskListList(_) => r#gen.into_root_schema_for::<TaskListList>(),
             Tools::ForgeToolTaskListClear(_) => r#gen.into_root_schema_for::<TaskListClear>(),
+            Tools::ForgeToolScratchpadWrite(_) => r#gen.into_root_schema_for::<ScratchpadWrite>(),
+            Tools::ForgeToolScratchpadRead(_) => r#gen.into_root_schema_for::<ScratchpadRead>(),
+            Tools::ForgeToolRepoTree(_) => r#gen.into_root_schema_for::<RepoTree>(),
+            Tools::ForgeToolFsReadGlob(_) => r#gen.into_root_schema_for::<FSReadGlob>(),
+            Tools::ForgeToolSystemInfo(_) => r#gen.into_root_schema_for::<SystemInfo>(),
+            Tools::ForgeToolFormatCode(_) => r#gen.into_root_schema_for::<FormatCode>(),
+            Tools::ForgeToolFsList(_) => r#gen.into_root_schema_for::<FSListInput>(),
         }
     }
 
@@ -634,6 +825,25 @@ impl Tools {
             .description(self.description())
             .input_schema(self.schema())
     }
+
+    /// Returns the definition with the limits mentioned in its description
+    /// set to the values configured in the environment, so the model knows
+    /// how much it can ask for
+    pub fn definition_with_env(&self, env: &Environment) -> ToolDefinition {
+        let render = |text: &str| text.replace(MAX_READ_LINES, &env.max_read_size.to_string());
+        let mut definition = self.definition();
+        definition.description = render(&definition.description);
+        if let Some(description) = definition
+            .input_schema
+            .schema
+            .metadata
+            .as_mut()
+            .and_then(|metadata| metadata.description.as_mut())
+        {
+            *description = render(description);
+        }
+        definition
+    }
     pub fn contains(tool_name: &ToolName) -> bool {
         FORGE_TOOLS.contains(tool_name)
     }
@@ -646,6 +856,95 @@ impl Tools {
         .iter()
         .any(|v| v.to_string().to_case(Case::Snake).eq(tool_name.as_str()))
     }
+    /// Whether the call only reads state. Such calls have no side effects, so
+    /// they can run at the same time as each other, and repeating one yields
+    /// the same result.
+    pub fn is_read_only(tool_call: &ToolCallFull) -> bool {
+        // A fetch with any method but GET may change state on the server
+        if tool_call.name == ToolsDiscriminants::ForgeToolNetFetch.name() {
+            return is_get_request(&tool_call.arguments);
+        }
+        [
+            ToolsDiscriminants::ForgeToolFsRead,
+            ToolsDiscriminants::ForgeToolFsSearch,
+            ToolsDiscriminants::ForgeToolTaskListList,
+            ToolsDiscriminants::ForgeToolScratchpadRead,
+            ToolsDiscriminants::ForgeToolRepoTree,
+            ToolsDiscriminants::ForgeToolFsReadGlob,
+            ToolsDiscriminants::ForgeToolSystemInfo,
+            ToolsDiscriminants::ForgeToolFsList,
+        ]
+        .iter()
+        .any(|v| {
+            v.to_string()
+                .to_case(Case::Snake)
+                .eq(tool_call.name.as_str())
+        })
+    }
+    /// Explanation given for the call, `None` when it's missing or blank. The
+    /// completion takes no explanation, so it never has one
+    pub fn explanation(&self) -> Option<&str> {
+        let explanation = match self {
+            Tools::ForgeToolFsRead(input) => &input.explanation,
+            Tools::ForgeToolFsCreate(input) => &input.explanation,
+            Tools::ForgeToolFsSearch(input) => &input.explanation,
+            Tools::ForgeToolFsRemove(input) => &input.explanation,
+            Tools::ForgeToolFsPatch(input) => &input.explanation,
+            Tools::ForgeToolFsUndo(input) => &input.explanation,
+            Tools::ForgeToolProcessShell(input) => &input.explanation,
+            Tools::ForgeToolNetFetch(input) => &input.explanation,
+            Tools::ForgeToolFollowup(input) => &input.explanation,
+            Tools::ForgeToolTaskListAppend(input) => &input.explanation,
+            Tools::ForgeToolTaskListAppendMultiple(input) => &input.explanation,
+            Tools::ForgeToolTaskListUpdate(input) => &input.explanation,
+            Tools::ForgeToolTaskListList(input) => &input.explanation,
+            Tools::ForgeToolTaskListClear(input) => &input.explanation,
+            Tools::ForgeToolScratchpadWrite(input) => &input.explanation,
+            Tools::ForgeToolScratchpadRead(input) => &input.explanation,
+            Tools::ForgeToolRepoTree(input) => &input.explanation,
+            Tools::ForgeToolFsReadGlob(input) => &input.explanation,
+            Tools::ForgeToolSystemInfo(input) => &input.explanation,
+            Tools::ForgeToolFormatCode(input) => &input.explanation,
+            Tools::ForgeToolFsList(input) => &input.explanation,
+            Tools::ForgeToolAttemptCompletion(_) => return None,
+        };
+        explanation
+            .as_deref()
+            .filter(|explanation| !explanation.trim().is_empty())
+    }
+
+    /// Whether the call may change the workspace, or state outside of it.
+    /// Only forge's own tools are known not to, any other tool, e.g. an MCP
+    /// or agent tool, is assumed to.
+    pub fn is_mutating(tool_call: &ToolCallFull) -> bool {
+        if Self::is_read_only(tool_call) {
+            return false;
+        }
+        // Tools that only change the state of the conversation
+        ![
+            ToolsDiscriminants::ForgeToolFollowup,
+            ToolsDiscriminants::ForgeToolAttemptCompletion,
+            ToolsDiscriminants::ForgeToolTaskListAppend,
+            ToolsDiscriminants::ForgeToolTaskListAppendMultiple,
+            ToolsDiscriminants::ForgeToolTaskListUpdate,
+            ToolsDiscriminants::ForgeToolTaskListClear,
+            ToolsDiscriminants::ForgeToolScratchpadWrite,
+        ]
+        .iter()
+        .any(|v| {
+            v.to_string()
+                .to_case(Case::Snake)
+                .eq(tool_call.name.as_str())
+        })
+    }
+}
+
+/// Whether the fetch arguments ask for a GET, which is the default method
+fn is_get_request(arguments: &serde_json::Value) -> bool {
+    arguments
+        .get("method")
+        .and_then(|method| method.as_str())
+        .is_none_or(|method| method.eq_ignore_ascii_case("GET"))
 }
 
 impl ToolsDiscriminants {
@@ -689,11 +988,14 @@ impl TryFrom<&ToolCallFull> for AgentInput {
 
 #[cfg(test)]
 mod tests {
+    use std::path::PathBuf;
+
     use pretty_assertions::assert_eq;
     use serde_json::json;
     use strum::IntoEnumIterator;
+    use url::Url;
 
-    use crate::{FSRead, ToolCallFull, ToolName, Tools, ToolsDiscriminants};
+    use crate::{Environment, FSRead, ToolCallFull, ToolName, Tools, ToolsDiscriminants};
 
     #[test]
     fn foo() {
@@ -706,6 +1008,8 @@ mod tests {
             path: "/some/path/foo.txt".to_string(),
             start_line: None,
             end_line: None,
+            revision: None,
+            with_line_numbers: None,
             explanation: None,
         });
 
@@ -720,6 +1024,65 @@ mod tests {
         assert!(!Tools::is_complete(&incomplete_tool));
     }
 
+    #[test]
+    fn test_is_read_only() {
+        let fixture = [
+            ("forge_tool_fs_read", json!({})),
+            ("forge_tool_fs_search", json!({})),
+            ("forge_tool_task_list_list", json!({})),
+            ("forge_tool_fs_patch", json!({})),
+            ("forge_tool_fs_remove", json!({})),
+            ("forge_tool_task_list_append", json!({})),
+            ("mcp_github_tool_get_issue", json!({})),
+            (
+                "forge_tool_net_fetch",
+                json!({"url": "https://example.com"}),
+            ),
+            (
+                "forge_tool_net_fetch",
+                json!({"url": "https://example.com", "method": "DELETE"}),
+            ),
+        ];
+
+        let actual = fixture.map(|(name, arguments)| {
+            Tools::is_read_only(&ToolCallFull::new(ToolName::new(name)).arguments(arguments))
+        });
+
+        let expected = [true, true, true, false, false, false, false, true, false];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_is_mutating() {
+        let fixture = [
+            ("forge_tool_fs_patch", json!({})),
+            ("forge_tool_process_shell", json!({})),
+            ("forge_tool_fs_read", json!({})),
+            ("forge_tool_task_list_append", json!({})),
+            ("mcp_github_tool_create_issue", json!({})),
+            ("forge", json!({"tasks": ["Refactor the parser"]})),
+            (
+                "forge_tool_net_fetch",
+                json!({"url": "https://example.com"}),
+            ),
+            (
+                "forge_tool_net_fetch",
+                json!({"url": "https://example.com", "method": "get"}),
+            ),
+            (
+                "forge_tool_net_fetch",
+                json!({"url": "https://example.com", "method": "POST"}),
+            ),
+        ];
+
+        let actual = fixture.map(|(name, arguments)| {
+            Tools::is_mutating(&ToolCallFull::new(ToolName::new(name)).arguments(arguments))
+        });
+
+        let expected = [true, true, false, false, true, true, false, false, true];
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_tool_definition() {
         let actual = ToolsDiscriminants::ForgeToolFsRemove.name();
@@ -741,6 +1104,51 @@ mod tests {
         insta::assert_snapshot!(tools);
     }
 
+    #[test]
+    fn test_definition_reflects_configured_read_limit() {
+        let env = Environment {
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            pid: 12345,
+            cwd: PathBuf::from("/home/user/project"),
+            home: Some(PathBuf::from("/home/user")),
+            shell: "/bin/bash".to_string(),
+            base_path: PathBuf::from("/home/user/project"),
+            retry_config: Default::default(),
+            max_search_lines: 25,
+            fetch_truncation_limit: 55,
+            fetch_cache_ttl: 0,
+            max_read_size: 5000,
+            stdout_max_prefix_length: 10,
+            stdout_max_suffix_length: 10,
+            tool_limits: Default::default(),
+            http: Default::default(),
+            max_file_size: 256 << 10,
+            forge_api_url: Url::parse("http://forgecode.dev/api").unwrap(),
+            shell_env: Default::default(),
+            truncation_template: None,
+            otlp_endpoint: None,
+            debug_raw_output: false,
+            sandbox_root: None,
+            max_concurrent_requests: None,
+            redact_home: false,
+            diff_ignore_whitespace: false,
+            diff_ignore_trailing_newline: false,
+            offload_dir: None,
+        };
+        let fixture = Tools::ForgeToolFsRead(FSRead::default());
+
+        let actual = fixture.definition_with_env(&env);
+
+        let description = actual.description.replace("\n", "");
+        assert!(description.contains("first 5000 lines"));
+        assert!(description.contains("does not exceed 5000 lines"));
+        assert!(!actual.description.contains("{max_read_lines}"));
+        let schema = serde_json::to_string(&actual.input_schema).unwrap();
+        assert!(schema.contains("first 5000 lines"));
+        assert!(!schema.contains("{max_read_lines}"));
+    }
+
     #[test]
     fn test_tool_deser_failure() {
         let tool_call = ToolCallFull::new("forge_tool_fs_create".into());
@@ -0,0 +1,71 @@
+use super::Transformer;
+use crate::{Context, ContextMessage, ToolValue};
+
+/// Transformer that removes the images from the context, for models that
+/// can't see them. Images returned by tools are replaced with a note so the
+/// model knows one was left out.
+#[derive(Default)]
+pub struct DropImages;
+
+impl Transformer for DropImages {
+    type Value = Context;
+
+    fn transform(&mut self, mut value: Self::Value) -> Self::Value {
+        value
+            .messages
+            .retain(|message| !matches!(message, ContextMessage::Image(_)));
+
+        value
+            .messages
+            .iter_mut()
+            .filter_map(|message| match message {
+                ContextMessage::Tool(tool_result) => Some(tool_result),
+                _ => None,
+            })
+            .flat_map(|tool_result| tool_result.output.values.iter_mut())
+            .filter(|output_value| matches!(output_value, ToolValue::Image(_)))
+            .for_each(|output_value| {
+                *output_value = ToolValue::Text(
+                    "[The image was left out, the model doesn't accept images]".to_string(),
+                );
+            });
+
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{Image, ToolCallId, ToolName, ToolOutput, ToolResult};
+
+    #[test]
+    fn test_drop_images_removes_attachments_and_tool_images() {
+        let image = Image::new_base64("aW1hZ2U=".to_string(), "image/png");
+        let fixture = Context::default()
+            .add_message(ContextMessage::user("Describe these", None))
+            .add_message(ContextMessage::Image(image.clone()))
+            .add_tool_results(vec![ToolResult {
+                name: ToolName::new("screenshot"),
+                call_id: Some(ToolCallId::new("call_1")),
+                output: ToolOutput::image(image),
+                pinned: false,
+            }]);
+
+        let actual = DropImages.transform(fixture);
+
+        let expected = Context::default()
+            .add_message(ContextMessage::user("Describe these", None))
+            .add_tool_results(vec![ToolResult {
+                name: ToolName::new("screenshot"),
+                call_id: Some(ToolCallId::new("call_1")),
+                output: ToolOutput::text(
+                    "[The image was left out, the model doesn't accept images]".to_string(),
+                ),
+                pinned: false,
+            }]);
+        assert_eq!(actual, expected);
+    }
+}
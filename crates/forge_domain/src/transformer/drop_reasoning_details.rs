@@ -87,6 +87,7 @@ mod tests {
                 name: ToolName::new("test_tool"),
                 call_id: Some(ToolCallId::new("call_123")),
                 output: ToolOutput::text("Tool result".to_string()),
+                pinned: false,
             }])
     }
 
@@ -170,6 +171,7 @@ mod tests {
                 name: ToolName::new("preserve_tool"),
                 call_id: Some(ToolCallId::new("call_preserve")),
                 output: ToolOutput::text("Tool output".to_string()),
+                pinned: false,
             }]);
 
         let mut transformer = DropReasoningDetails::default();
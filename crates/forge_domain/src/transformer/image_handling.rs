@@ -99,6 +99,7 @@ mod tests {
                 ],
                 is_error: false,
             },
+            pinned: false,
         }])
     }
 
@@ -113,11 +114,13 @@ mod tests {
                     name: ToolName::new("image_tool_1"),
                     call_id: Some(ToolCallId::new("call_1")),
                     output: ToolOutput::image(image1),
+                    pinned: false,
                 },
                 ToolResult {
                     name: ToolName::new("image_tool_2"),
                     call_id: Some(ToolCallId::new("call_2")),
                     output: ToolOutput::image(image2),
+                    pinned: false,
                 },
             ])
     }
@@ -140,6 +143,7 @@ mod tests {
                 name: ToolName::new("text_tool"),
                 call_id: Some(ToolCallId::new("call_text")),
                 output: ToolOutput::text("Just text output".to_string()),
+                pinned: false,
             }]);
 
         let mut transformer = ImageHandling::new();
@@ -177,6 +181,7 @@ mod tests {
                 ],
                 is_error: false,
             },
+            pinned: false,
         }]);
 
         let mut transformer = ImageHandling::new();
@@ -200,6 +205,7 @@ mod tests {
                 ],
                 is_error: true,
             },
+            pinned: false,
         }]);
 
         let mut transformer = ImageHandling::new();
@@ -231,6 +237,7 @@ mod tests {
                 name: ToolName::new("image_tool"),
                 call_id: Some(ToolCallId::new("call_preserve")),
                 output: ToolOutput::image(image),
+                pinned: false,
             }]);
 
         let mut transformer = ImageHandling::new();
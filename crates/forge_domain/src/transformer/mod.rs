@@ -73,14 +73,18 @@ where
 }
 
 // Re-export specific transformers
+mod drop_images;
 mod drop_reasoning_details;
 mod image_handling;
+mod model_capability;
 mod reasoning_normalizer;
 mod set_model;
 mod transform_tool_calls;
 
+pub use drop_images::DropImages;
 pub use drop_reasoning_details::DropReasoningDetails;
 pub use image_handling::ImageHandling;
+pub use model_capability::{when_model_lacks_tools, when_model_lacks_vision};
 pub use reasoning_normalizer::ReasoningNormalizer;
 pub use set_model::SetModel;
 pub use transform_tool_calls::TransformToolCalls;
@@ -127,6 +131,7 @@ mod tests {
                 name: ToolName::new("test_tool"),
                 call_id: Some(ToolCallId::new("call_123")),
                 output: ToolOutput::text("Tool result text".to_string()),
+                pinned: false,
             }])
     }
 
@@ -0,0 +1,120 @@
+use crate::{Context, Model};
+
+/// Creates a condition that matches when the model is known not to accept
+/// images. Models whose vision support isn't known are assumed to accept
+/// them.
+///
+/// # Examples
+/// ```rust,ignore
+/// // Drop the images only for models that can't see them
+/// let transformer = DropImages.when(when_model_lacks_vision(&model));
+/// ```
+pub fn when_model_lacks_vision(model: &Model) -> impl Fn(&Context) -> bool {
+    let lacks_vision = model.supports_vision == Some(false);
+    move |_| lacks_vision
+}
+
+/// Creates a condition that matches when the model isn't known to support
+/// tool calls.
+///
+/// # Examples
+/// ```rust,ignore
+/// // Turn the tool calls into plain messages for models without tools
+/// let transformer = TransformToolCalls::new().when(when_model_lacks_tools(&model));
+/// ```
+pub fn when_model_lacks_tools(model: &Model) -> impl Fn(&Context) -> bool {
+    let lacks_tools = !model.tools_supported.unwrap_or_default();
+    move |_| lacks_tools
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        ContextMessage, DropImages, Image, ModelId, ToolCallFull, ToolCallId, ToolName, ToolOutput,
+        ToolResult, TransformToolCalls, Transformer,
+    };
+
+    fn fixture_context() -> Context {
+        Context::default()
+            .add_message(ContextMessage::user("What's in the picture?", None))
+            .add_message(ContextMessage::Image(Image::new_base64(
+                "aW1hZ2U=".to_string(),
+                "image/png",
+            )))
+    }
+
+    #[test]
+    fn test_images_are_dropped_for_a_model_without_vision() {
+        let model = Model::new(ModelId::new("text-only")).supports_vision(Some(false));
+        let mut transformer = DropImages.when(when_model_lacks_vision(&model));
+
+        let actual = transformer.transform(fixture_context());
+
+        let expected =
+            Context::default().add_message(ContextMessage::user("What's in the picture?", None));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_images_are_kept_for_a_model_with_vision() {
+        let model = Model::new(ModelId::new("multimodal")).supports_vision(Some(true));
+        let mut transformer = DropImages.when(when_model_lacks_vision(&model));
+
+        let actual = transformer.transform(fixture_context());
+
+        let expected = fixture_context();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_images_are_kept_when_vision_is_unknown() {
+        let model = Model::new(ModelId::new("unknown"));
+        let mut transformer = DropImages.when(when_model_lacks_vision(&model));
+
+        let actual = transformer.transform(fixture_context());
+
+        let expected = fixture_context();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_tool_calls_are_transformed_for_a_model_without_tools() {
+        let fixture = Context::default()
+            .add_message(ContextMessage::assistant(
+                "Reading it",
+                None,
+                Some(vec![
+                    ToolCallFull::new(ToolName::new("forge_tool_fs_read"))
+                        .call_id(ToolCallId::new("call_1")),
+                ]),
+            ))
+            .add_tool_results(vec![ToolResult {
+                name: ToolName::new("forge_tool_fs_read"),
+                call_id: Some(ToolCallId::new("call_1")),
+                output: ToolOutput::text("fn main() {}".to_string()),
+                pinned: false,
+            }]);
+        let with_tools = Model::new(ModelId::new("with-tools")).tools_supported(Some(true));
+        let without_tools = Model::new(ModelId::new("without-tools")).tools_supported(Some(false));
+
+        let actual = (
+            TransformToolCalls::new()
+                .when(when_model_lacks_tools(&with_tools))
+                .transform(fixture.clone()),
+            TransformToolCalls::new()
+                .when(when_model_lacks_tools(&without_tools))
+                .transform(fixture.clone()),
+        );
+
+        let expected = (
+            fixture,
+            Context::default()
+                .add_message(ContextMessage::assistant("Reading it", None, None))
+                .add_message(ContextMessage::user("fn main() {}", None)),
+        );
+        assert_eq!(actual, expected);
+    }
+}
@@ -63,6 +63,7 @@ impl Transformer for TransformToolCalls {
 
         value.messages = new_messages;
         value.tools = Vec::new();
+        value.tool_choice = None;
         value
     }
 }
@@ -107,6 +108,7 @@ mod tests {
                 name: ToolName::new("test_tool"),
                 call_id: Some(ToolCallId::new("call_123")),
                 output: ToolOutput::text("Tool result text".to_string()),
+                pinned: false,
             }])
     }
 
@@ -125,6 +127,7 @@ mod tests {
                 ],
                 is_error: false,
             },
+            pinned: false,
         }])
     }
 
@@ -189,6 +192,7 @@ mod tests {
             name: ToolName::new("empty_tool"),
             call_id: Some(ToolCallId::new("call_empty")),
             output: ToolOutput { values: vec![ToolValue::Empty], is_error: false },
+            pinned: false,
         }]);
 
         let mut transformer = TransformToolCalls::new();
@@ -8,7 +8,10 @@ use serde_json::Value;
 
 use crate::temperature::Temperature;
 use crate::update::Update;
-use crate::{Agent, AgentId, Compact, MaxTokens, ModelId, TopK, TopP};
+use crate::{
+    Agent, AgentId, Compact, FileRanking, MaxTokens, ModelId, ModelPricing, SystemContextSection,
+    ToolName, ToolPostProcessor, TopK, TopP,
+};
 
 /// Configuration for a workflow that contains all settings
 /// required to initialize a workflow.
@@ -46,6 +49,12 @@ pub struct Workflow {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub model: Option<ModelId>,
 
+    /// Pricing per 1,000 tokens keyed by model ID, used to estimate the cost
+    /// of requests
+    #[merge(strategy = crate::merge::hashmap)]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub pricing: HashMap<ModelId, ModelPricing>,
+
     /// Maximum depth to which the file walker should traverse for all agents
     /// If not provided, each agent's individual setting will be used
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -58,6 +67,15 @@ pub struct Workflow {
     #[merge(strategy = crate::merge::option)]
     pub custom_rules: Option<String>,
 
+    /// Sections of the system context to include in the system prompt of all
+    /// agents, in the order they should appear. Available sections are
+    /// `time`, `env`, `files`, `tools`, `custom_rules` and `variables`. If not
+    /// specified, each agent's individual setting will be used
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub system_context_sections: Option<Vec<SystemContextSection>>,
+
     /// Temperature used for all agents
     ///
     /// Temperature controls the randomness in the model's output.
@@ -133,6 +151,62 @@ pub struct Workflow {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[merge(strategy = crate::merge::option)]
     pub max_requests_per_turn: Option<usize>,
+
+    /// Maximum number of tokens, prompt and completion together, that the
+    /// requests of a conversation may use across all of its turns. The turn is
+    /// interrupted once they reach it, bounding the spend of a task by tokens
+    /// rather than by cost.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub token_budget: Option<usize>,
+
+    /// Maximum number of times the model is asked to proceed after it returns
+    /// an entirely empty response, before the turn is given up. Defaults to 1.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub max_empty_response_retries: Option<usize>,
+
+    /// Prefix used for forge-internal XML tags such as tool calls and
+    /// reasoning sections. Tags with this prefix are stripped from the
+    /// model's output. Defaults to `forge_` when not specified or empty.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub tag_prefix: Option<String>,
+
+    /// Text returned to the model after it successfully calls
+    /// attempt_completion. Set to an empty string to suppress it, which gives
+    /// automated runs a clean completion signal.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub completion_feedback: Option<String>,
+
+    /// Shell command the final answer is piped through before it's shown,
+    /// e.g. a formatter or a compliance check. Its output replaces the answer
+    /// and a non-zero exit status withholds the answer and reports the
+    /// failure instead.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub post_response_command: Option<String>,
+
+    /// Transformations applied to the output of tools before it's added to
+    /// the conversation, keyed by tool name, e.g. to redact personal data
+    /// from fetched pages
+    #[merge(strategy = crate::merge::hashmap)]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tool_post_processors: HashMap<ToolName, ToolPostProcessor>,
+
+    /// Rejects tool calls that don't explain why the tool is used, asking the
+    /// model to call it again with an explanation. Defaults to false.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub require_tool_explanation: Option<bool>,
+
     /// Configuration for automatic context compaction for all agents
     /// If specified, this will be applied to all agents in the workflow
     /// If not specified, each agent's individual setting will be used
@@ -140,6 +214,22 @@ pub struct Workflow {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[merge(strategy = crate::merge::option)]
     pub compact: Option<Compact>,
+
+    /// How the workspace files listed in the system prompt are ranked and
+    /// capped. All files are listed when not specified
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub file_ranking: Option<FileRanking>,
+
+    /// Formatter commands keyed by file extension, e.g. `rs: rustfmt`, used by
+    /// the format tool. The command reads the file on stdin and writes the
+    /// formatted file to stdout, `{path}` is replaced with the file's path.
+    /// rustfmt, prettier, black and gofmt are used for their languages unless
+    /// configured otherwise
+    #[merge(strategy = crate::merge::hashmap)]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub formatters: HashMap<String, String>,
 }
 
 impl Default for Workflow {
@@ -171,8 +261,10 @@ impl Workflow {
             variables: HashMap::new(),
             commands: Vec::new(),
             model: None,
+            pricing: HashMap::new(),
             max_walker_depth: None,
             custom_rules: None,
+            system_context_sections: None,
             temperature: None,
             top_p: None,
             top_k: None,
@@ -182,7 +274,16 @@ impl Workflow {
             templates: None,
             max_tool_failure_per_turn: None,
             max_requests_per_turn: None,
+            token_budget: None,
+            max_empty_response_retries: None,
+            tag_prefix: None,
+            completion_feedback: None,
+            post_response_command: None,
+            tool_post_processors: HashMap::new(),
+            require_tool_explanation: None,
             compact: None,
+            file_ranking: None,
+            formatters: HashMap::new(),
         }
     }
 
@@ -1,3 +1,6 @@
+/// Default prefix for forge-internal XML tags
+pub const DEFAULT_TAG_PREFIX: &str = "forge_";
+
 /// Extracts content between the specified XML-style tags
 ///
 /// # Arguments
@@ -26,6 +29,65 @@ pub fn extract_tag_content<'a>(text: &'a str, tag_name: &str) -> Option<&'a str>
     None
 }
 
+/// Extracts a JSON value enclosed in the specified XML-style tags
+///
+/// Unlike [`extract_tag_content`], the closing tag is located after the JSON
+/// value has been parsed, so tag-like text inside JSON strings (including the
+/// closing tag itself) is never mistaken for the end of the block. The JSON
+/// may optionally be wrapped in a CDATA section.
+///
+/// # Arguments
+///
+/// * `text` - The text to extract content from
+/// * `tag_name` - The name of the XML tag (without angle brackets)
+///
+/// # Returns
+///
+/// * `Some((&str, usize))` containing the JSON and the byte offset right after
+///   the closing tag
+/// * `None` if no complete block is found
+pub fn extract_json_tag<'a>(text: &'a str, tag_name: &str) -> Option<(&'a str, usize)> {
+    const CDATA_START: &str = "<![CDATA[";
+    const CDATA_END: &str = "]]>";
+
+    let opening_tag = format!("<{tag_name}>");
+    let closing_tag = format!("</{tag_name}>");
+
+    let mut position = text.find(&opening_tag)? + opening_tag.len();
+    position += leading_whitespace(&text[position..]);
+
+    let is_cdata = text[position..].starts_with(CDATA_START);
+    if is_cdata {
+        position += CDATA_START.len();
+        position += leading_whitespace(&text[position..]);
+    }
+
+    let json_start = position;
+    let mut stream = serde_json::Deserializer::from_str(&text[json_start..])
+        .into_iter::<serde::de::IgnoredAny>();
+    stream.next()?.ok()?;
+    let json_end = json_start + stream.byte_offset();
+
+    position = json_end + leading_whitespace(&text[json_end..]);
+    if is_cdata {
+        if !text[position..].starts_with(CDATA_END) {
+            return None;
+        }
+        position += CDATA_END.len();
+        position += leading_whitespace(&text[position..]);
+    }
+
+    if !text[position..].starts_with(&closing_tag) {
+        return None;
+    }
+
+    Some((&text[json_start..json_end], position + closing_tag.len()))
+}
+
+fn leading_whitespace(text: &str) -> usize {
+    text.len() - text.trim_start().len()
+}
+
 /// Removes content within XML-style tags that start with the specified prefix
 pub fn remove_tag_with_prefix(text: &str, prefix: &str) -> String {
     // First, find all unique tag names that start with the prefix
@@ -165,4 +227,37 @@ mod tests {
         let expected = "1<foo>2</foo>3";
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_extract_json_tag_ignores_closing_tag_in_string() {
+        let fixture = r#"<call>{"text": "</call>"}</call> trailing"#;
+        let actual = extract_json_tag(fixture, "call");
+        let expected = Some((r#"{"text": "</call>"}"#, 32));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_extract_json_tag_with_cdata() {
+        let fixture = r#"<call><![CDATA[ {"text": "a < b"} ]]></call>"#;
+        let actual = extract_json_tag(fixture, "call");
+        let expected = Some((r#"{"text": "a < b"}"#, fixture.len()));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_extract_json_tag_incomplete() {
+        let fixture = r#"<call>{"text": "</call>"#;
+        let actual = extract_json_tag(fixture, "call");
+        let expected = None;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_remove_tag_with_custom_prefix() {
+        let fixture =
+            "<fx_analysis>Internal</fx_analysis>Docs for <forge_tool_call>{}</forge_tool_call>";
+        let actual = remove_tag_with_prefix(fixture, "fx_");
+        let expected = "Docs for <forge_tool_call>{}</forge_tool_call>";
+        assert_eq!(actual, expected);
+    }
 }
@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+/// An advisory lock taken by creating a lock file that holds the id of the
+/// owning process. The lock file is removed once the lock is dropped. When
+/// another running process already holds the lock, nothing is locked and
+/// [`FileLock::held_by`] names that process.
+#[derive(Debug)]
+pub struct FileLock {
+    path: Option<PathBuf>,
+    held_by: Option<u32>,
+}
+
+impl FileLock {
+    /// A lock owned through the given lock file, which is removed on drop
+    pub fn owned(path: PathBuf) -> Self {
+        Self { path: Some(path), held_by: None }
+    }
+
+    /// A lock that's already held, either by this process or by the running
+    /// process with the given id
+    pub fn held(held_by: Option<u32>) -> Self {
+        Self { path: None, held_by }
+    }
+
+    /// Id of another running process that holds the lock
+    pub fn held_by(&self) -> Option<u32> {
+        self.held_by
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        if let Some(path) = self.path.take() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
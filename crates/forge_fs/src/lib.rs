@@ -10,6 +10,7 @@
 
 mod error;
 mod file_info;
+mod file_lock;
 mod file_size;
 mod is_binary;
 mod meta;
@@ -19,6 +20,7 @@ mod write;
 
 pub use crate::error::Error;
 pub use crate::file_info::FileInfo;
+pub use crate::file_lock::FileLock;
 
 /// ForgeFS provides a standardized interface for file system operations
 /// with consistent error handling.
@@ -48,6 +48,27 @@ impl crate::ForgeFS {
         let content = tokio::fs::read_to_string(path_ref)
             .await
             .with_context(|| format!("Failed to read file content from {}", path_ref.display()))?;
+        Self::select_lines(content, start_line, end_line)
+    }
+
+    /// Selects a range of lines from content read elsewhere, e.g. a file at a
+    /// git revision, the same way [`Self::read_range_utf8`] does from a file.
+    ///
+    /// # Arguments
+    /// * `content` - The full content to select the lines from
+    /// * `start_line` - Starting line number (1-based, inclusive)
+    /// * `end_line` - Ending line number (1-based, inclusive)
+    pub fn select_lines(
+        content: String,
+        start_line: u64,
+        end_line: u64,
+    ) -> Result<(String, FileInfo)> {
+        if start_line > end_line {
+            return Err(Error::StartGreaterThanEnd { start: start_line, end: end_line }.into());
+        }
+        if start_line == 0 || end_line == 0 {
+            return Err(Error::IndexStartingWithZero { start: start_line, end: end_line }.into());
+        }
         if start_line < 2 && content.is_empty() {
             // If the file is empty, return empty content
             return Ok((String::new(), FileInfo::new(start_line, end_line, 0)));
@@ -1,12 +1,13 @@
 use std::path::{Path, PathBuf};
 
-use forge_domain::{Environment, Provider, RetryConfig};
+use forge_domain::{Environment, Provider, RetryConfig, ShellEnvConfig, ToolLimits};
 use forge_services::EnvironmentInfra;
 use reqwest::Url;
 
 #[derive(Clone)]
 pub struct ForgeEnvironmentInfra {
     restricted: bool,
+    shell: Option<String>,
     cwd: PathBuf,
 }
 
@@ -16,15 +17,21 @@ impl ForgeEnvironmentInfra {
     /// # Arguments
     /// * `restricted` - If true, use restricted shell mode (rbash) If false,
     ///   use unrestricted shell mode (sh/bash)
+    /// * `shell` - Shell to run commands with instead of the platform default,
+    ///   ignored in restricted mode
     /// * `cwd` - Required working directory path
-    pub fn new(restricted: bool, cwd: PathBuf) -> Self {
+    pub fn new(restricted: bool, shell: Option<String>, cwd: PathBuf) -> Self {
         Self::dot_env(&cwd);
-        Self { restricted, cwd }
+        Self { restricted, shell, cwd }
     }
 
     /// Get path to appropriate shell based on platform and mode
     fn get_shell_path(&self) -> String {
-        if cfg!(target_os = "windows") {
+        if let Some(shell) = &self.shell
+            && (!self.restricted || cfg!(target_os = "windows"))
+        {
+            shell.clone()
+        } else if cfg!(target_os = "windows") {
             std::env::var("COMSPEC").unwrap_or("cmd.exe".to_string())
         } else if self.restricted {
             // Default to rbash in restricted mode
@@ -69,6 +76,17 @@ impl ForgeEnvironmentInfra {
             }
         }
 
+        if let Ok(val) = std::env::var("FORGE_CONTENT_FILTER_CODES") {
+            let codes: Vec<String> = val
+                .split(',')
+                .map(|code| code.trim().to_string())
+                .filter(|code| !code.is_empty())
+                .collect();
+            if !codes.is_empty() {
+                config.content_filter_codes = codes;
+            }
+        }
+
         config
     }
 
@@ -99,6 +117,63 @@ impl ForgeEnvironmentInfra {
         {
             config.max_redirects = parsed;
         }
+        if let Ok(val) = std::env::var("FORGE_HTTP_STREAM_IDLE_TIMEOUT")
+            && let Ok(parsed) = val.parse::<u64>()
+        {
+            config.stream_idle_timeout = parsed;
+        }
+        if let Ok(val) = std::env::var("FORGE_HTTP_REQUEST_TIMEOUT")
+            && let Ok(parsed) = val.parse::<u64>()
+        {
+            config.request_timeout = Some(parsed);
+        }
+
+        config
+    }
+
+    /// Resolves the number of lines read from a file when no range is given,
+    /// overridable with `FORGE_MAX_READ_LINES`
+    fn resolve_max_read_size(&self) -> u64 {
+        std::env::var("FORGE_MAX_READ_LINES")
+            .ok()
+            .and_then(|val| val.parse::<u64>().ok())
+            .filter(|lines| *lines > 0)
+            .unwrap_or(500)
+    }
+
+    /// Resolves the truncation limits of individual tools from
+    /// `FORGE_SHELL_PREFIX_LINES`, `FORGE_SHELL_SUFFIX_LINES`,
+    /// `FORGE_SEARCH_LINES` and `FORGE_FETCH_CHARS`
+    fn resolve_tool_limits(&self) -> ToolLimits {
+        fn parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok().and_then(|val| val.parse().ok())
+        }
+
+        ToolLimits {
+            shell_prefix_lines: parse("FORGE_SHELL_PREFIX_LINES"),
+            shell_suffix_lines: parse("FORGE_SHELL_SUFFIX_LINES"),
+            search_lines: parse("FORGE_SEARCH_LINES"),
+            fetch_chars: parse("FORGE_FETCH_CHARS"),
+        }
+    }
+
+    /// Resolves the shell environment policy, variables listed in
+    /// `FORGE_SHELL_ENV_DENY` are stripped in addition to the defaults
+    fn resolve_shell_env_config(&self) -> ShellEnvConfig {
+        let mut config = ShellEnvConfig::default();
+        let parse_list = |val: String| -> Vec<String> {
+            val.split(',')
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .collect()
+        };
+
+        if let Ok(val) = std::env::var("FORGE_SHELL_ENV_DENY") {
+            config.deny.extend(parse_list(val));
+        }
+        if let Ok(val) = std::env::var("FORGE_SHELL_ENV_ALLOW") {
+            config.allow = parse_list(val);
+        }
 
         config
     }
@@ -115,6 +190,7 @@ impl ForgeEnvironmentInfra {
 
         Environment {
             os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
             pid: std::process::id(),
             cwd,
             shell: self.get_shell_path(),
@@ -125,12 +201,53 @@ impl ForgeEnvironmentInfra {
             retry_config,
             max_search_lines: 200,
             fetch_truncation_limit: 40_000,
-            max_read_size: 500,
+            fetch_cache_ttl: self
+                .get_env_var("FORGE_FETCH_CACHE_TTL")
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(15 * 60),
+            max_read_size: self.resolve_max_read_size(),
             stdout_max_prefix_length: 200,
             stdout_max_suffix_length: 200,
+            tool_limits: self.resolve_tool_limits(),
             http: self.resolve_timeout_config(),
             max_file_size: 256 << 10, // 256 KiB
             forge_api_url,
+            shell_env: self.resolve_shell_env_config(),
+            truncation_template: self.get_env_var("FORGE_TRUNCATION_TEMPLATE"),
+            otlp_endpoint: self.get_env_var("FORGE_OTLP_ENDPOINT"),
+            debug_raw_output: self
+                .get_env_var("FORGE_DEBUG_RAW_OUTPUT")
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_default(),
+            sandbox_root: self.resolve_sandbox_root(),
+            max_concurrent_requests: self
+                .get_env_var("FORGE_MAX_CONCURRENT_REQUESTS")
+                .and_then(|value| value.parse().ok())
+                .filter(|max| *max > 0),
+            redact_home: self
+                .get_env_var("FORGE_REDACT_HOME")
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_default(),
+            diff_ignore_whitespace: self
+                .get_env_var("FORGE_DIFF_IGNORE_WHITESPACE")
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_default(),
+            diff_ignore_trailing_newline: self
+                .get_env_var("FORGE_DIFF_IGNORE_TRAILING_NEWLINE")
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_default(),
+            offload_dir: self
+                .get_env_var("FORGE_OFFLOAD_DIR")
+                .map(|dir| self.cwd.join(dir)),
+        }
+    }
+
+    /// Confines commands to `FORGE_SANDBOX_ROOT` when set, or to the working
+    /// directory in restricted mode. Otherwise commands are unrestricted
+    fn resolve_sandbox_root(&self) -> Option<PathBuf> {
+        match self.get_env_var("FORGE_SANDBOX_ROOT") {
+            Some(root) => Some(self.cwd.join(root)),
+            None => self.restricted.then(|| self.cwd.clone()),
         }
     }
 
@@ -275,7 +392,7 @@ mod tests {
             }
 
             // Verify that the environment service uses the same default as RetryConfig
-            let env_service = ForgeEnvironmentInfra::new(false, PathBuf::from("."));
+            let env_service = ForgeEnvironmentInfra::new(false, None, PathBuf::from("."));
             let retry_config_from_env = env_service.resolve_retry_config();
             let default_retry_config = RetryConfig::default();
 
@@ -318,7 +435,7 @@ mod tests {
                 env::set_var("FORGE_RETRY_STATUS_CODES", "429,500,502");
             }
 
-            let env_service = ForgeEnvironmentInfra::new(false, PathBuf::from("."));
+            let env_service = ForgeEnvironmentInfra::new(false, None, PathBuf::from("."));
             let config = env_service.resolve_retry_config();
 
             assert_eq!(config.initial_backoff_ms, 500);
@@ -351,7 +468,7 @@ mod tests {
                 env::set_var("FORGE_RETRY_STATUS_CODES", "503,504");
             }
 
-            let env_service = ForgeEnvironmentInfra::new(false, PathBuf::from("."));
+            let env_service = ForgeEnvironmentInfra::new(false, None, PathBuf::from("."));
             let config = env_service.resolve_retry_config();
             let default_config = RetryConfig::default();
 
@@ -388,7 +505,7 @@ mod tests {
                 env::set_var("FORGE_RETRY_STATUS_CODES", "invalid,codes,here");
             }
 
-            let env_service = ForgeEnvironmentInfra::new(false, PathBuf::from("."));
+            let env_service = ForgeEnvironmentInfra::new(false, None, PathBuf::from("."));
             let config = env_service.resolve_retry_config();
             let default_config = RetryConfig::default();
 
@@ -417,11 +534,12 @@ mod tests {
             env::remove_var("FORGE_HTTP_POOL_IDLE_TIMEOUT");
             env::remove_var("FORGE_HTTP_POOL_MAX_IDLE_PER_HOST");
             env::remove_var("FORGE_HTTP_MAX_REDIRECTS");
+            env::remove_var("FORGE_HTTP_REQUEST_TIMEOUT");
         }
 
         // Test default values
         {
-            let env_service = ForgeEnvironmentInfra::new(false, PathBuf::from("."));
+            let env_service = ForgeEnvironmentInfra::new(false, None, PathBuf::from("."));
             let config = env_service.resolve_timeout_config();
             let default_config = forge_domain::HttpConfig::default();
 
@@ -433,6 +551,7 @@ mod tests {
                 default_config.pool_max_idle_per_host
             );
             assert_eq!(config.max_redirects, default_config.max_redirects);
+            assert_eq!(config.request_timeout, None);
         }
 
         // Test environment variable overrides
@@ -443,9 +562,10 @@ mod tests {
                 env::set_var("FORGE_HTTP_POOL_IDLE_TIMEOUT", "180");
                 env::set_var("FORGE_HTTP_POOL_MAX_IDLE_PER_HOST", "10");
                 env::set_var("FORGE_HTTP_MAX_REDIRECTS", "20");
+                env::set_var("FORGE_HTTP_REQUEST_TIMEOUT", "600");
             }
 
-            let env_service = ForgeEnvironmentInfra::new(false, PathBuf::from("."));
+            let env_service = ForgeEnvironmentInfra::new(false, None, PathBuf::from("."));
             let config = env_service.resolve_timeout_config();
 
             assert_eq!(config.connect_timeout, 30);
@@ -453,6 +573,7 @@ mod tests {
             assert_eq!(config.pool_idle_timeout, 180);
             assert_eq!(config.pool_max_idle_per_host, 10);
             assert_eq!(config.max_redirects, 20);
+            assert_eq!(config.request_timeout, Some(600));
 
             // Clean up environment variables
             unsafe {
@@ -461,6 +582,7 @@ mod tests {
                 env::remove_var("FORGE_HTTP_POOL_IDLE_TIMEOUT");
                 env::remove_var("FORGE_HTTP_POOL_MAX_IDLE_PER_HOST");
                 env::remove_var("FORGE_HTTP_MAX_REDIRECTS");
+                env::remove_var("FORGE_HTTP_REQUEST_TIMEOUT");
             }
         }
 
@@ -470,7 +592,7 @@ mod tests {
                 env::set_var("FORGE_HTTP_CONNECT_TIMEOUT", "15");
             }
 
-            let env_service = ForgeEnvironmentInfra::new(false, PathBuf::from("."));
+            let env_service = ForgeEnvironmentInfra::new(false, None, PathBuf::from("."));
             let config = env_service.resolve_timeout_config();
             let default_config = forge_domain::HttpConfig::default();
 
@@ -498,7 +620,7 @@ mod tests {
                 env::set_var("FORGE_HTTP_CONNECT_TIMEOUT", "invalid");
             }
 
-            let env_service = ForgeEnvironmentInfra::new(false, PathBuf::from("."));
+            let env_service = ForgeEnvironmentInfra::new(false, None, PathBuf::from("."));
             let config = env_service.resolve_timeout_config();
             let default_config = forge_domain::HttpConfig::default();
 
@@ -511,4 +633,26 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_shell_override() {
+        let fixture = ForgeEnvironmentInfra::new(false, Some("/bin/zsh".to_string()), ".".into());
+
+        // The shell is reported to the agent and with the output of its commands
+        let actual = fixture.get_environment().shell;
+
+        let expected = "/bin/zsh";
+        assert_eq!(actual, expected);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shell_override_ignored_when_restricted() {
+        let fixture = ForgeEnvironmentInfra::new(true, Some("/bin/zsh".to_string()), ".".into());
+
+        let actual = fixture.get_environment().shell;
+
+        let expected = "/bin/rbash";
+        assert_eq!(actual, expected);
+    }
 }
@@ -4,7 +4,7 @@ use std::sync::Arc;
 
 use forge_domain::{CommandOutput, Environment};
 use forge_services::CommandInfra;
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::process::Command;
 use tokio::sync::Mutex;
 
@@ -13,6 +13,8 @@ use tokio::sync::Mutex;
 pub struct ForgeCommandExecutorService {
     restricted: bool,
     env: Environment,
+    /// Whether the output of commands is shown on the console as they run
+    console: bool,
 
     // Mutex to ensure that only one command is executed at a time
     ready: Arc<Mutex<()>>,
@@ -20,7 +22,18 @@ pub struct ForgeCommandExecutorService {
 
 impl ForgeCommandExecutorService {
     pub fn new(restricted: bool, env: Environment) -> Self {
-        Self { restricted, env, ready: Arc::new(Mutex::new(())) }
+        Self {
+            restricted,
+            env,
+            console: true,
+            ready: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Keeps the output of commands off the console, for when stdout carries
+    /// a protocol
+    pub fn quiet(self) -> Self {
+        Self { console: false, ..self }
     }
 
     fn prepare_command(&self, command_str: &str, working_dir: &Path) -> Command {
@@ -33,6 +46,12 @@ impl ForgeCommandExecutorService {
         };
         let mut command = Command::new(shell);
 
+        // Keep forge's own secrets out of the command's environment
+        self.strip_denied_vars(
+            &mut command,
+            std::env::vars_os().filter_map(|(name, _)| name.into_string().ok()),
+        );
+
         // Core color settings for general commands
         command
             .env("CLICOLOR_FORCE", "1")
@@ -50,8 +69,7 @@ impl ForgeCommandExecutorService {
         // Other common tools
         command.env("GREP_OPTIONS", "--color=always"); // GNU grep
 
-        let parameter = if is_windows { "/C" } else { "-c" };
-        command.arg(parameter);
+        command.arg(command_flag(shell));
 
         #[cfg(windows)]
         command.raw_arg(command_str);
@@ -74,6 +92,17 @@ impl ForgeCommandExecutorService {
         command
     }
 
+    /// Removes the variables the shell env config denies from the environment
+    /// the command inherits
+    fn strip_denied_vars(&self, command: &mut Command, names: impl IntoIterator<Item = String>) {
+        names
+            .into_iter()
+            .filter(|name| !self.env.shell_env.is_allowed(name))
+            .for_each(|name| {
+                command.env_remove(name);
+            });
+    }
+
     /// Internal method to execute commands with streaming to console
     async fn execute_command_internal(
         &self,
@@ -90,11 +119,17 @@ impl ForgeCommandExecutorService {
         let mut stdout_pipe = child.stdout.take();
         let mut stderr_pipe = child.stderr.take();
 
+        let (stdout, stderr): (Box<dyn Write + Send>, Box<dyn Write + Send>) = if self.console {
+            (Box::new(io::stdout()), Box::new(io::stderr()))
+        } else {
+            (Box::new(io::sink()), Box::new(io::sink()))
+        };
+
         // Stream the output of the command to stdout and stderr concurrently
         let (status, stdout_buffer, stderr_buffer) = tokio::try_join!(
             child.wait(),
-            stream(&mut stdout_pipe, io::stdout()),
-            stream(&mut stderr_pipe, io::stderr())
+            stream(&mut stdout_pipe, stdout),
+            stream(&mut stderr_pipe, stderr)
         )?;
 
         // Drop happens after `try_join` due to <https://github.com/tokio-rs/tokio/issues/4309>
@@ -111,6 +146,26 @@ impl ForgeCommandExecutorService {
     }
 }
 
+/// Decodes what a command printed for its input. It's passed on as it was
+/// printed, so it has to be valid UTF-8 rather than be patched up
+pub(crate) fn piped_stdout(command: &str, stdout: Vec<u8>) -> anyhow::Result<String> {
+    String::from_utf8(stdout)
+        .map_err(|_| anyhow::anyhow!("`{command}` printed output that isn't valid UTF-8"))
+}
+
+/// Returns the flag that makes the shell run the command string that follows
+fn command_flag(shell: &str) -> &'static str {
+    let name = Path::new(shell)
+        .file_stem()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match name.as_str() {
+        "cmd" => "/C",
+        "powershell" | "pwsh" => "-Command",
+        _ => "-c",
+    }
+}
+
 /// reads the output from A and writes it to W
 async fn stream<A: AsyncReadExt + Unpin, W: Write>(
     io: &mut Option<A>,
@@ -159,6 +214,40 @@ impl CommandInfra for ForgeCommandExecutorService {
 
         Ok(prepared_command.spawn()?.wait().await?)
     }
+
+    async fn execute_command_with_input(
+        &self,
+        command: String,
+        working_dir: PathBuf,
+        input: String,
+    ) -> anyhow::Result<CommandOutput> {
+        let ready = self.ready.lock().await;
+
+        let mut prepared_command = self.prepare_command(&command, &working_dir);
+        prepared_command.stdin(std::process::Stdio::piped());
+        let mut child = prepared_command.spawn()?;
+
+        // Write the input concurrently, a command that doesn't read all of it
+        // would otherwise never finish
+        let mut stdin = child.stdin.take();
+        let write_input = async move {
+            if let Some(stdin) = stdin.as_mut() {
+                // A command may exit without reading its input
+                let _ = stdin.write_all(input.as_bytes()).await;
+            }
+            drop(stdin);
+            Ok::<_, io::Error>(())
+        };
+        let (output, _) = tokio::try_join!(child.wait_with_output(), write_input)?;
+        drop(ready);
+
+        Ok(CommandOutput {
+            stdout: piped_stdout(&command, output.stdout)?,
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            exit_code: output.status.code(),
+            command,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -172,6 +261,7 @@ mod tests {
     fn test_env() -> Environment {
         Environment {
             os: "test".to_string(),
+            arch: "test".to_string(),
             pid: 12345,
             cwd: PathBuf::from("/test"),
             home: Some(PathBuf::from("/home/test")),
@@ -184,13 +274,25 @@ mod tests {
             base_path: PathBuf::from("/base"),
             retry_config: Default::default(),
             fetch_truncation_limit: 0,
+            fetch_cache_ttl: 0,
             stdout_max_prefix_length: 0,
             max_search_lines: 0,
             max_read_size: 0,
             stdout_max_suffix_length: 0,
+            tool_limits: Default::default(),
             http: Default::default(),
             max_file_size: 10_000_000,
             forge_api_url: Url::parse("http://forgecode.dev/api").unwrap(),
+            shell_env: Default::default(),
+            truncation_template: None,
+            otlp_endpoint: None,
+            debug_raw_output: false,
+            sandbox_root: None,
+            max_concurrent_requests: None,
+            redact_home: false,
+            diff_ignore_whitespace: false,
+            diff_ignore_trailing_newline: false,
+            offload_dir: None,
         }
     }
 
@@ -220,4 +322,90 @@ mod tests {
         assert_eq!(actual.stderr, expected.stderr);
         assert_eq!(actual.success(), expected.success());
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_executor_writes_input_to_stdin() {
+        let fixture = ForgeCommandExecutorService::new(false, test_env());
+
+        let actual = fixture
+            .execute_command_with_input(
+                "tr a-z A-Z".to_string(),
+                PathBuf::from("."),
+                "final answer".to_string(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(actual.stdout, "FINAL ANSWER");
+        assert_eq!(actual.exit_code, Some(0));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_executor_rejects_input_output_that_isnt_utf8() {
+        let fixture = ForgeCommandExecutorService::new(false, test_env());
+
+        let actual = fixture
+            .execute_command_with_input(
+                "printf '\\377'".to_string(),
+                PathBuf::from("."),
+                String::new(),
+            )
+            .await
+            .unwrap_err()
+            .to_string();
+
+        let expected = "`printf '\\377'` printed output that isn't valid UTF-8";
+        assert_eq!(actual, expected);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_executor_uses_configured_shell() {
+        let fixture =
+            ForgeCommandExecutorService::new(false, test_env().shell("/bin/sh".to_string()));
+
+        let actual = fixture
+            .execute_command("echo $0".to_string(), PathBuf::from("."))
+            .await
+            .unwrap();
+
+        let expected = "/bin/sh";
+        assert_eq!(actual.stdout.trim(), expected);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_executor_filters_environment() {
+        let env = test_env().shell_env(
+            forge_domain::ShellEnvConfig::default()
+                .allow(vec!["FORGE_TEST_ALLOWED_API_KEY".to_string()]),
+        );
+        let fixture = ForgeCommandExecutorService::new(false, env);
+        let mut command = Command::new("env");
+        command
+            .env("FORGE_TEST_DENIED_API_KEY", "denied")
+            .env("FORGE_TEST_ALLOWED_API_KEY", "allowed");
+
+        fixture.strip_denied_vars(
+            &mut command,
+            ["FORGE_TEST_DENIED_API_KEY", "FORGE_TEST_ALLOWED_API_KEY"].map(String::from),
+        );
+        let actual = command.output().await.unwrap();
+
+        let stdout = String::from_utf8_lossy(&actual.stdout);
+        assert!(!stdout.contains("FORGE_TEST_DENIED_API_KEY"));
+        assert!(stdout.contains("FORGE_TEST_ALLOWED_API_KEY=allowed"));
+    }
+
+    #[test]
+    fn test_command_flag() {
+        let fixture = ["/bin/zsh", "fish", "cmd.exe", "pwsh", "powershell.exe"];
+
+        let actual = fixture.map(command_flag);
+
+        let expected = ["-c", "-c", "/C", "-Command", "-Command"];
+        assert_eq!(actual, expected);
+    }
 }
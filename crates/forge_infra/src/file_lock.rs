@@ -0,0 +1,134 @@
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use forge_fs::FileLock;
+use sysinfo::{Pid, ProcessesToUpdate, System};
+
+/// Takes advisory locks on behalf of a process, a lock left behind by a
+/// process that's no longer running is considered stale and taken over.
+pub struct ForgeFileLockService {
+    pid: u32,
+}
+
+impl ForgeFileLockService {
+    pub fn new(pid: u32) -> Self {
+        Self { pid }
+    }
+
+    /// Locks the given lock file for this process until the returned lock is
+    /// dropped.
+    pub fn lock(&self, lock_path: &Path) -> Result<FileLock> {
+        if let Some(parent) = lock_path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+        loop {
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(lock_path)
+            {
+                Ok(mut file) => {
+                    write!(file, "{}", self.pid)?;
+                    return Ok(FileLock::owned(lock_path.to_path_buf()));
+                }
+                Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let owner = std::fs::read_to_string(lock_path)
+                        .ok()
+                        .and_then(|content| content.trim().parse::<u32>().ok());
+                    match owner {
+                        Some(owner) if owner == self.pid => return Ok(FileLock::held(None)),
+                        Some(owner) if is_running(owner) => {
+                            return Ok(FileLock::held(Some(owner)));
+                        }
+                        _ => {
+                            tracing::info!(path = %lock_path.display(), "Reclaiming stale lock");
+                            std::fs::remove_file(lock_path).or_else(|error| {
+                                // Another process may have reclaimed it first
+                                match error.kind() {
+                                    std::io::ErrorKind::NotFound => Ok(()),
+                                    _ => Err(error),
+                                }
+                            })?;
+                        }
+                    }
+                }
+                Err(error) => {
+                    return Err(error).with_context(|| {
+                        format!("Failed to create lock file {}", lock_path.display())
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn is_running(pid: u32) -> bool {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+    system.process(pid).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_second_lock_detects_running_owner() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("locks").join("conversation.lock");
+        let owner = ForgeFileLockService::new(std::process::id());
+        let fixture = ForgeFileLockService::new(std::process::id() + 1);
+
+        let _lock = owner.lock(&path).unwrap();
+        let actual = fixture.lock(&path).unwrap().held_by();
+
+        let expected = Some(std::process::id());
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_stale_lock_is_reclaimed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("conversation.lock");
+        // No process runs with the largest pid, as if the owner had crashed
+        std::fs::write(&path, u32::MAX.to_string()).unwrap();
+        let fixture = ForgeFileLockService::new(std::process::id());
+
+        let lock = fixture.lock(&path).unwrap();
+
+        assert_eq!(lock.held_by(), None);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            std::process::id().to_string()
+        );
+    }
+
+    #[test]
+    fn test_lock_is_released_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("conversation.lock");
+        let fixture = ForgeFileLockService::new(std::process::id());
+        let lock = fixture.lock(&path).unwrap();
+
+        drop(lock);
+
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_relocking_in_the_same_process_keeps_the_first_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("conversation.lock");
+        let fixture = ForgeFileLockService::new(std::process::id());
+        let _lock = fixture.lock(&path).unwrap();
+
+        drop(fixture.lock(&path).unwrap());
+
+        assert!(path.exists());
+    }
+}
@@ -1,20 +1,22 @@
 use std::path::{Path, PathBuf};
 use std::process::ExitStatus;
 use std::sync::Arc;
+use std::time::Duration;
 
 use bytes::Bytes;
-use forge_domain::{CommandOutput, Environment, McpServerConfig};
-use forge_fs::FileInfo as FileInfoData;
+use forge_domain::{CommandOutput, Environment, McpServerConfig, RemoteTarget};
+use forge_fs::{FileInfo as FileInfoData, FileLock};
 use forge_services::{
-    CommandInfra, EnvironmentInfra, FileDirectoryInfra, FileInfoInfra, FileReaderInfra,
-    FileRemoverInfra, FileWriterInfra, HttpInfra, McpServerInfra, SnapshotInfra, UserInfra,
-    WalkerInfra,
+    CommandInfra, EnvironmentInfra, FileDirectoryInfra, FileInfoInfra, FileLockInfra,
+    FileReaderInfra, FileRemoverInfra, FileWatcherInfra, FileWriterInfra, HttpInfra,
+    McpServerInfra, SnapshotInfra, UserInfra, WalkerInfra,
 };
 use reqwest::Response;
 use reqwest::header::HeaderMap;
 
 use crate::env::ForgeEnvironmentInfra;
 use crate::executor::ForgeCommandExecutorService;
+use crate::file_lock::ForgeFileLockService;
 use crate::fs_create_dirs::ForgeCreateDirsService;
 use crate::fs_meta::ForgeFileMetaService;
 use crate::fs_read::ForgeFileReadService;
@@ -25,7 +27,9 @@ use crate::http::ForgeHttpService;
 use crate::inquire::ForgeInquire;
 use crate::mcp_client::ForgeMcpClient;
 use crate::mcp_server::ForgeMcpServer;
+use crate::remote::{ForgeRemoteService, OpenSshTransport};
 use crate::walker::ForgeWalkerService;
+use crate::watcher::ForgeFileWatcherService;
 
 #[derive(Clone)]
 pub struct ForgeInfra {
@@ -40,12 +44,29 @@ pub struct ForgeInfra {
     inquire_service: Arc<ForgeInquire>,
     mcp_server: ForgeMcpServer,
     walker_service: Arc<ForgeWalkerService>,
+    file_watcher_service: Arc<ForgeFileWatcherService>,
+    file_lock_service: Arc<ForgeFileLockService>,
     http_service: Arc<ForgeHttpService>,
+    remote: Option<Arc<ForgeRemoteService<OpenSshTransport>>>,
+    /// Forge's own files, which stay local with a remote
+    base_path: PathBuf,
 }
 
 impl ForgeInfra {
-    pub fn new(restricted: bool, cwd: PathBuf) -> Self {
-        let environment_service = Arc::new(ForgeEnvironmentInfra::new(restricted, cwd));
+    /// With a remote, files are read and written and commands are run in the
+    /// remote workspace, which becomes the working directory. Forge's own
+    /// files and paths outside of the workspace stay local
+    pub fn new(
+        restricted: bool,
+        shell: Option<String>,
+        cwd: PathBuf,
+        remote: Option<RemoteTarget>,
+    ) -> Self {
+        let cwd = remote
+            .as_ref()
+            .map(|remote| remote.root().to_path_buf())
+            .unwrap_or(cwd);
+        let environment_service = Arc::new(ForgeEnvironmentInfra::new(restricted, shell, cwd));
         let env = environment_service.get_environment();
         let file_snapshot_service = Arc::new(ForgeFileSnapshotService::new(env.clone()));
         let http_service = Arc::new(ForgeHttpService::new());
@@ -66,14 +87,38 @@ impl ForgeInfra {
             inquire_service: Arc::new(ForgeInquire::new()),
             mcp_server: ForgeMcpServer,
             walker_service: Arc::new(ForgeWalkerService::new()),
+            file_watcher_service: Arc::new(ForgeFileWatcherService::new(env.cwd.clone())),
+            file_lock_service: Arc::new(ForgeFileLockService::new(std::process::id())),
             http_service,
+            remote: remote.map(|remote| Arc::new(ForgeRemoteService::ssh(&remote))),
+            base_path: env.base_path.clone(),
         }
     }
+
+    /// The remote the path is on, if any
+    fn remote_for(&self, path: &Path) -> Option<&ForgeRemoteService<OpenSshTransport>> {
+        self.remote
+            .as_deref()
+            .filter(|remote| !path.starts_with(&self.base_path) && remote.contains(path))
+    }
+
+    /// Keeps the output of shell commands off the console, for serving tools
+    /// over stdio
+    pub fn quiet(mut self) -> Self {
+        self.command_executor_service =
+            Arc::new(self.command_executor_service.as_ref().clone().quiet());
+        self
+    }
 }
 
 impl EnvironmentInfra for ForgeInfra {
     fn get_environment(&self) -> Environment {
-        self.environment_service.get_environment()
+        let mut env = self.environment_service.get_environment();
+        if self.remote.is_some() {
+            // Shell commands are confined to the remote workspace
+            env.sandbox_root = Some(env.cwd.clone());
+        }
+        env
     }
 
     fn get_env_var(&self, key: &str) -> Option<String> {
@@ -84,10 +129,16 @@ impl EnvironmentInfra for ForgeInfra {
 #[async_trait::async_trait]
 impl FileReaderInfra for ForgeInfra {
     async fn read_utf8(&self, path: &Path) -> anyhow::Result<String> {
+        if let Some(remote) = self.remote_for(path) {
+            return remote.read_utf8(path).await;
+        }
         self.file_read_service.read_utf8(path).await
     }
 
     async fn read(&self, path: &Path) -> anyhow::Result<Vec<u8>> {
+        if let Some(remote) = self.remote_for(path) {
+            return remote.read(path).await;
+        }
         self.file_read_service.read(path).await
     }
 
@@ -97,6 +148,9 @@ impl FileReaderInfra for ForgeInfra {
         start_line: u64,
         end_line: u64,
     ) -> anyhow::Result<(String, FileInfoData)> {
+        if let Some(remote) = self.remote_for(path) {
+            return remote.range_read_utf8(path, start_line, end_line).await;
+        }
         self.file_read_service
             .range_read_utf8(path, start_line, end_line)
             .await
@@ -111,6 +165,9 @@ impl FileWriterInfra for ForgeInfra {
         contents: Bytes,
         capture_snapshot: bool,
     ) -> anyhow::Result<()> {
+        if let Some(remote) = self.remote_for(path) {
+            return remote.write(path, contents, capture_snapshot).await;
+        }
         self.file_write_service
             .write(path, contents, capture_snapshot)
             .await
@@ -126,18 +183,30 @@ impl FileWriterInfra for ForgeInfra {
 #[async_trait::async_trait]
 impl FileInfoInfra for ForgeInfra {
     async fn is_binary(&self, path: &Path) -> anyhow::Result<bool> {
+        if let Some(remote) = self.remote_for(path) {
+            return remote.is_binary(path).await;
+        }
         self.file_meta_service.is_binary(path).await
     }
 
     async fn is_file(&self, path: &Path) -> anyhow::Result<bool> {
+        if let Some(remote) = self.remote_for(path) {
+            return remote.is_file(path).await;
+        }
         self.file_meta_service.is_file(path).await
     }
 
     async fn exists(&self, path: &Path) -> anyhow::Result<bool> {
+        if let Some(remote) = self.remote_for(path) {
+            return remote.exists(path).await;
+        }
         self.file_meta_service.exists(path).await
     }
 
     async fn file_size(&self, path: &Path) -> anyhow::Result<u64> {
+        if let Some(remote) = self.remote_for(path) {
+            return remote.file_size(path).await;
+        }
         self.file_meta_service.file_size(path).await
     }
 }
@@ -145,10 +214,16 @@ impl FileInfoInfra for ForgeInfra {
 #[async_trait::async_trait]
 impl SnapshotInfra for ForgeInfra {
     async fn create_snapshot(&self, file_path: &Path) -> anyhow::Result<forge_snaps::Snapshot> {
+        if let Some(remote) = self.remote_for(file_path) {
+            return remote.create_snapshot(file_path).await;
+        }
         self.file_snapshot_service.create_snapshot(file_path).await
     }
 
     async fn undo_snapshot(&self, file_path: &Path) -> anyhow::Result<()> {
+        if let Some(remote) = self.remote_for(file_path) {
+            return remote.undo_snapshot(file_path).await;
+        }
         self.file_snapshot_service.undo_snapshot(file_path).await
     }
 }
@@ -156,6 +231,9 @@ impl SnapshotInfra for ForgeInfra {
 #[async_trait::async_trait]
 impl FileRemoverInfra for ForgeInfra {
     async fn remove(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(remote) = self.remote_for(path) {
+            return remote.remove(path).await;
+        }
         self.file_remove_service.remove(path).await
     }
 }
@@ -163,6 +241,9 @@ impl FileRemoverInfra for ForgeInfra {
 #[async_trait::async_trait]
 impl FileDirectoryInfra for ForgeInfra {
     async fn create_dirs(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(remote) = self.remote_for(path) {
+            return remote.create_dirs(path).await;
+        }
         self.create_dirs_service.create_dirs(path).await
     }
 }
@@ -174,6 +255,9 @@ impl CommandInfra for ForgeInfra {
         command: String,
         working_dir: PathBuf,
     ) -> anyhow::Result<CommandOutput> {
+        if let Some(remote) = self.remote_for(&working_dir) {
+            return remote.execute_command(command, working_dir).await;
+        }
         self.command_executor_service
             .execute_command(command, working_dir)
             .await
@@ -184,10 +268,29 @@ impl CommandInfra for ForgeInfra {
         command: &str,
         working_dir: PathBuf,
     ) -> anyhow::Result<ExitStatus> {
+        if let Some(remote) = self.remote_for(&working_dir) {
+            return remote.execute_command_raw(command, working_dir).await;
+        }
         self.command_executor_service
             .execute_command_raw(command, working_dir)
             .await
     }
+
+    async fn execute_command_with_input(
+        &self,
+        command: String,
+        working_dir: PathBuf,
+        input: String,
+    ) -> anyhow::Result<CommandOutput> {
+        if let Some(remote) = self.remote_for(&working_dir) {
+            return remote
+                .execute_command_with_input(command, working_dir, input)
+                .await;
+        }
+        self.command_executor_service
+            .execute_command_with_input(command, working_dir, input)
+            .await
+    }
 }
 
 #[async_trait::async_trait]
@@ -211,6 +314,16 @@ impl UserInfra for ForgeInfra {
     ) -> anyhow::Result<Option<Vec<String>>> {
         self.inquire_service.select_many(message, options).await
     }
+
+    async fn select_many_positions(
+        &self,
+        message: &str,
+        options: Vec<String>,
+    ) -> anyhow::Result<Option<Vec<usize>>> {
+        self.inquire_service
+            .select_many_positions(message, options)
+            .await
+    }
 }
 
 #[async_trait::async_trait]
@@ -225,10 +338,33 @@ impl McpServerInfra for ForgeInfra {
 #[async_trait::async_trait]
 impl WalkerInfra for ForgeInfra {
     async fn walk(&self, config: forge_app::Walker) -> anyhow::Result<Vec<forge_app::WalkedFile>> {
+        if let Some(remote) = self.remote_for(&config.cwd) {
+            return remote.walk(config).await;
+        }
         self.walker_service.walk(config).await
     }
 }
 
+#[async_trait::async_trait]
+impl FileWatcherInfra for ForgeInfra {
+    async fn wait_for_changes(
+        &self,
+        pattern: &str,
+        debounce: Duration,
+    ) -> anyhow::Result<Vec<PathBuf>> {
+        self.file_watcher_service
+            .wait_for_changes(pattern, debounce)
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl FileLockInfra for ForgeInfra {
+    async fn lock_file(&self, path: &Path) -> anyhow::Result<FileLock> {
+        self.file_lock_service.lock(path)
+    }
+}
+
 #[async_trait::async_trait]
 impl HttpInfra for ForgeInfra {
     async fn get(&self, url: &str, headers: Option<HeaderMap>) -> anyhow::Result<Response> {
@@ -243,3 +379,30 @@ impl HttpInfra for ForgeInfra {
         self.http_service.delete(url).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_app_config_is_read_locally_with_a_remote() {
+        let base_path = tempdir().unwrap();
+        let remote = RemoteTarget::from_str("dev@build-box:/srv/project").unwrap();
+        let mut fixture = ForgeInfra::new(false, None, PathBuf::from("."), Some(remote));
+        fixture.base_path = base_path.path().to_path_buf();
+        let env = fixture
+            .get_environment()
+            .base_path(base_path.path().to_path_buf());
+        std::fs::write(env.app_config(), r#"{"profiles":{}}"#).unwrap();
+
+        let actual = fixture.read_utf8(&env.app_config()).await.unwrap();
+
+        let expected = r#"{"profiles":{}}"#;
+        assert_eq!(actual, expected);
+    }
+}
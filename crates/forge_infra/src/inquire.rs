@@ -76,4 +76,20 @@ impl UserInfra for ForgeInquire {
         })
         .await
     }
+
+    async fn select_many_positions(
+        &self,
+        message: &str,
+        options: Vec<String>,
+    ) -> Result<Option<Vec<usize>>> {
+        let message = message.to_string();
+        self.prompt(move || {
+            MultiSelect::new(&message, options)
+                .with_render_config(Self::render_config())
+                .with_help_message("Use arrow keys to navigate, Space to select/deselect, Enter to confirm, ESC to cancel")
+                .raw_prompt()
+                .map(|selected| selected.into_iter().map(|option| option.index).collect())
+        })
+        .await
+    }
 }
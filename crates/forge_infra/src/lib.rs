@@ -2,6 +2,7 @@ pub mod executor;
 
 mod env;
 mod error;
+mod file_lock;
 mod forge_infra;
 mod fs_create_dirs;
 mod fs_meta;
@@ -13,7 +14,12 @@ mod http;
 mod inquire;
 mod mcp_client;
 mod mcp_server;
+mod mcp_tool_server;
+mod remote;
 mod walker;
+mod watcher;
 
 pub use executor::ForgeCommandExecutorService;
 pub use forge_infra::*;
+pub use mcp_tool_server::ForgeMcpToolServer;
+pub use remote::{ForgeRemoteService, OpenSshTransport, SshOutput, SshTransport};
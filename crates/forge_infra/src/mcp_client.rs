@@ -5,7 +5,10 @@ use std::sync::{Arc, RwLock};
 use backon::{ExponentialBuilder, Retryable};
 use forge_domain::{Image, McpServerConfig, ToolDefinition, ToolName, ToolOutput};
 use forge_services::McpClientInfra;
-use rmcp::model::{CallToolRequestParam, ClientInfo, Implementation, InitializeRequestParam};
+use rmcp::model::{
+    CallToolRequestParam, CallToolResult, ClientInfo, Implementation, InitializeRequestParam,
+    RawContent, ResourceContents,
+};
 use rmcp::schemars::schema::RootSchema;
 use rmcp::service::RunningService;
 use rmcp::transport::TokioChildProcess;
@@ -122,28 +125,7 @@ impl ForgeMcpClient {
             })
             .await?;
 
-        let tool_contents: Vec<ToolOutput> = result
-            .content
-            .into_iter()
-            .map(|content| match content.raw {
-                rmcp::model::RawContent::Text(raw_text_content) => {
-                    Ok(ToolOutput::text(raw_text_content.text))
-                }
-                rmcp::model::RawContent::Image(raw_image_content) => Ok(ToolOutput::image(
-                    Image::new_base64(raw_image_content.data, raw_image_content.mime_type.as_str()),
-                )),
-                rmcp::model::RawContent::Resource(_) => {
-                    Err(Error::UnsupportedMcpResponse("Resource").into())
-                }
-
-                rmcp::model::RawContent::Audio(_) => {
-                    Err(Error::UnsupportedMcpResponse("Audio").into())
-                }
-            })
-            .collect::<anyhow::Result<Vec<ToolOutput>>>()?;
-
-        Ok(ToolOutput::from(tool_contents.into_iter())
-            .is_error(result.is_error.unwrap_or_default()))
+        into_tool_output(result)
     }
 
     async fn attempt_with_retry<T, F>(&self, call: impl Fn() -> F) -> anyhow::Result<T>
@@ -182,3 +164,109 @@ impl McpClientInfra for ForgeMcpClient {
             .await
     }
 }
+
+/// Maps the content blocks of an MCP result onto the matching tool values, so
+/// images reach vision models as images instead of text
+fn into_tool_output(result: CallToolResult) -> anyhow::Result<ToolOutput> {
+    let tool_contents = result
+        .content
+        .into_iter()
+        .map(|content| match content.raw {
+            RawContent::Text(raw_text_content) => Ok(ToolOutput::text(raw_text_content.text)),
+            RawContent::Image(raw_image_content) => Ok(ToolOutput::image(Image::new_base64(
+                raw_image_content.data,
+                raw_image_content.mime_type.as_str(),
+            ))),
+            RawContent::Resource(embedded) => Ok(resource_output(embedded.resource)),
+            RawContent::Audio(_) => Err(Error::UnsupportedMcpResponse("Audio").into()),
+        })
+        .collect::<anyhow::Result<Vec<ToolOutput>>>()?;
+
+    Ok(ToolOutput::from(tool_contents.into_iter()).is_error(result.is_error.unwrap_or_default()))
+}
+
+/// Embedded resources are noted along with their URI, binary ones are only
+/// passed on when they are images
+fn resource_output(resource: ResourceContents) -> ToolOutput {
+    match resource {
+        ResourceContents::TextResourceContents { uri, text, .. } => {
+            ToolOutput::text(format!("Resource {uri}:\n{text}"))
+        }
+        ResourceContents::BlobResourceContents { blob, mime_type: Some(mime_type), .. }
+            if mime_type.starts_with("image/") =>
+        {
+            ToolOutput::image(Image::new_base64(blob, mime_type))
+        }
+        ResourceContents::BlobResourceContents { uri, mime_type, .. } => ToolOutput::text(format!(
+            "Resource {uri} ({}) has binary content that can't be shown",
+            mime_type.as_deref().unwrap_or("unknown type")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use forge_domain::ToolValue;
+    use pretty_assertions::assert_eq;
+    use rmcp::model::Content;
+
+    use super::*;
+
+    #[test]
+    fn test_multi_part_result_maps_to_tool_values() {
+        let fixture = CallToolResult::success(vec![
+            Content::text("Rendered the chart"),
+            Content::image("iVBORw0KGgo=", "image/png"),
+            Content::resource(ResourceContents::TextResourceContents {
+                uri: "file:///data.csv".to_string(),
+                mime_type: Some("text/csv".to_string()),
+                text: "month,total".to_string(),
+            }),
+        ]);
+
+        let actual = into_tool_output(fixture).unwrap();
+
+        let expected = ToolOutput {
+            is_error: false,
+            values: vec![
+                ToolValue::Text("Rendered the chart".to_string()),
+                ToolValue::Image(Image::new_base64("iVBORw0KGgo=".to_string(), "image/png")),
+                ToolValue::Text("Resource file:///data.csv:\nmonth,total".to_string()),
+            ],
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_image_blob_resource_maps_to_image() {
+        let fixture = CallToolResult::error(vec![
+            Content::resource(ResourceContents::BlobResourceContents {
+                uri: "file:///chart.png".to_string(),
+                mime_type: Some("image/png".to_string()),
+                blob: "iVBORw0KGgo=".to_string(),
+            }),
+            Content::resource(ResourceContents::BlobResourceContents {
+                uri: "file:///data.bin".to_string(),
+                mime_type: None,
+                blob: "AAEC".to_string(),
+            }),
+        ]);
+
+        let actual = into_tool_output(fixture).unwrap();
+
+        let expected = ToolOutput {
+            is_error: true,
+            values: vec![
+                ToolValue::Image(Image::new_base64(
+                    "iVBORw0KGgo=".to_string(),
+                    "image/png",
+                )),
+                ToolValue::Text(
+                    "Resource file:///data.bin (unknown type) has binary content that can't be shown"
+                        .to_string(),
+                ),
+            ],
+        };
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,180 @@
+use std::sync::Arc;
+
+use forge_app::{Services, ToolServer};
+use forge_domain::{ToolCallFull, ToolDefinition, ToolName, ToolOutput, ToolValue};
+use rmcp::model::{
+    CallToolRequestParam, CallToolResult, Content, Implementation, ListToolsResult,
+    PaginatedRequestParam, ServerCapabilities, ServerInfo, Tool,
+};
+use rmcp::service::RequestContext;
+use rmcp::{RoleServer, ServerHandler, ServiceExt};
+use serde_json::Value;
+
+const VERSION: &str = match option_env!("APP_VERSION") {
+    Some(val) => val,
+    None => env!("CARGO_PKG_VERSION"),
+};
+
+/// MCP server advertising forge's built-in tools and running them against
+/// the workspace, so other MCP clients can use them
+pub struct ForgeMcpToolServer<S> {
+    server: Arc<ToolServer<S>>,
+}
+
+impl<S> Clone for ForgeMcpToolServer<S> {
+    fn clone(&self) -> Self {
+        Self { server: self.server.clone() }
+    }
+}
+
+impl<S: Services> ForgeMcpToolServer<S> {
+    pub fn new(services: Arc<S>) -> Self {
+        Self { server: Arc::new(ToolServer::new(services)) }
+    }
+
+    /// Serves the tools over stdin and stdout until the client disconnects
+    pub async fn serve_stdio(self) -> anyhow::Result<()> {
+        self.serve(rmcp::transport::stdio())
+            .await?
+            .waiting()
+            .await?;
+        Ok(())
+    }
+
+    fn list(&self) -> ListToolsResult {
+        ListToolsResult {
+            next_cursor: None,
+            tools: self.server.tools().into_iter().map(into_tool).collect(),
+        }
+    }
+
+    async fn call(&self, request: CallToolRequestParam) -> CallToolResult {
+        let call = ToolCallFull::new(ToolName::new(request.name))
+            .arguments(Value::Object(request.arguments.unwrap_or_default()));
+        match self.server.call(call).await {
+            Ok(output) => into_call_result(output),
+            Err(error) => CallToolResult::error(vec![Content::text(format!("{error:#}"))]),
+        }
+    }
+}
+
+impl<S: Services> ServerHandler for ForgeMcpToolServer<S> {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            server_info: Implementation { name: "Forge".to_string(), version: VERSION.to_string() },
+            ..Default::default()
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        _request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, rmcp::Error> {
+        Ok(self.list())
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        Ok(self.call(request).await)
+    }
+}
+
+fn into_tool(definition: ToolDefinition) -> Tool {
+    let schema = match serde_json::to_value(definition.input_schema) {
+        Ok(Value::Object(schema)) => schema,
+        _ => Default::default(),
+    };
+    Tool::new(definition.name.to_string(), definition.description, schema)
+}
+
+/// Maps the tool values onto MCP content blocks, images are sent as their
+/// base64 data
+fn into_call_result(output: ToolOutput) -> CallToolResult {
+    let content = output
+        .values
+        .into_iter()
+        .filter_map(|value| match value {
+            ToolValue::Text(text) => Some(Content::text(text)),
+            ToolValue::Image(image) => {
+                let data = image.url().split_once(',').map(|(_, data)| data)?;
+                Some(Content::image(data.to_string(), image.mime_type().clone()))
+            }
+            ToolValue::Empty => None,
+        })
+        .collect();
+    if output.is_error {
+        CallToolResult::error(content)
+    } else {
+        CallToolResult::success(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use forge_domain::{Tools, ToolsDiscriminants};
+    use forge_services::ForgeServices;
+    use pretty_assertions::assert_eq;
+    use rmcp::model::RawContent;
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::ForgeInfra;
+
+    fn fixture_server(cwd: &std::path::Path) -> ForgeMcpToolServer<ForgeServices<ForgeInfra>> {
+        let infra = Arc::new(ForgeInfra::new(false, None, cwd.to_path_buf(), None).quiet());
+        ForgeMcpToolServer::new(Arc::new(ForgeServices::new(infra)))
+    }
+
+    #[test]
+    fn test_served_tools_are_the_built_in_tools() {
+        let workspace = tempfile::tempdir().unwrap();
+        let fixture = fixture_server(workspace.path());
+
+        let actual = fixture
+            .list()
+            .tools
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect::<Vec<_>>();
+
+        let conversation_tools = [
+            ToolsDiscriminants::ForgeToolFollowup.name(),
+            ToolsDiscriminants::ForgeToolAttemptCompletion.name(),
+        ];
+        let expected = Tools::iter()
+            .map(|tool| tool.definition().name)
+            .filter(|name| !conversation_tools.contains(name))
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_call_reads_a_workspace_file() {
+        let workspace = tempfile::tempdir().unwrap();
+        let path = workspace.path().join("notes.md");
+        std::fs::write(&path, "served over MCP\n").unwrap();
+        let fixture = fixture_server(workspace.path());
+
+        let actual = fixture
+            .call(CallToolRequestParam {
+                name: "forge_tool_fs_read".into(),
+                arguments: serde_json::json!({ "path": path.display().to_string() })
+                    .as_object()
+                    .cloned(),
+            })
+            .await;
+
+        assert_eq!(actual.is_error, Some(false));
+        let text = match &actual.content[0].raw {
+            RawContent::Text(text) => text.text.clone(),
+            _ => panic!("Expected a text content"),
+        };
+        assert!(text.contains("served over MCP"));
+    }
+}
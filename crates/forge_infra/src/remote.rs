@@ -0,0 +1,658 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use bytes::Bytes;
+use forge_app::{WalkedFile, Walker};
+use forge_domain::{CommandOutput, RemoteTarget, shell_quote};
+use forge_services::{
+    CommandInfra, FileDirectoryInfra, FileInfoInfra, FileReaderInfra, FileRemoverInfra,
+    SnapshotInfra,
+};
+use forge_snaps::{Snapshot, SnapshotId};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::executor::piped_stdout;
+
+/// Number of leading bytes checked for NUL bytes to tell binary files apart
+const BINARY_PROBE_SIZE: usize = 8192;
+
+/// Output of a command run on the remote host
+#[derive(Debug, Default)]
+pub struct SshOutput {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    pub exit_code: Option<i32>,
+}
+
+/// Connection to the remote host commands are run over
+#[async_trait::async_trait]
+pub trait SshTransport: Send + Sync {
+    /// Runs the shell command on the remote host and captures its output,
+    /// `input` is written to its stdin
+    async fn run(&self, command: &str, input: Option<Vec<u8>>) -> anyhow::Result<SshOutput>;
+
+    /// Runs the shell command on the remote host attached to the local
+    /// terminal
+    async fn run_interactive(&self, command: &str) -> anyhow::Result<ExitStatus>;
+}
+
+/// Runs commands through the `ssh` client, so keys, agents and host
+/// configuration are those of the user's own SSH setup
+pub struct OpenSshTransport {
+    destination: String,
+}
+
+impl OpenSshTransport {
+    pub fn new(destination: impl Into<String>) -> Self {
+        Self { destination: destination.into() }
+    }
+
+    fn command(&self) -> Command {
+        let mut command = Command::new("ssh");
+        command.kill_on_drop(true);
+        command
+    }
+}
+
+#[async_trait::async_trait]
+impl SshTransport for OpenSshTransport {
+    async fn run(&self, command: &str, input: Option<Vec<u8>>) -> anyhow::Result<SshOutput> {
+        let mut ssh = self.command();
+        // Prompts would hang with the stdin taken by the input. The destination
+        // follows `--` so that it's never read as an option
+        ssh.args(["-o", "BatchMode=yes", "--", &self.destination, command])
+            .stdin(if input.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        tracing::info!(destination = %self.destination, command, "Executing remote command");
+        let mut child = ssh
+            .spawn()
+            .with_context(|| format!("Failed to connect to {}", self.destination))?;
+
+        let mut stdin = child.stdin.take();
+        let write_input = async move {
+            if let (Some(stdin), Some(input)) = (stdin.as_mut(), input) {
+                // The remote command may exit without reading all of it
+                let _ = stdin.write_all(&input).await;
+            }
+            drop(stdin);
+            Ok::<_, std::io::Error>(())
+        };
+        let (output, _) = tokio::try_join!(child.wait_with_output(), write_input)?;
+
+        Ok(SshOutput {
+            stdout: output.stdout,
+            stderr: output.stderr,
+            exit_code: output.status.code(),
+        })
+    }
+
+    async fn run_interactive(&self, command: &str) -> anyhow::Result<ExitStatus> {
+        let mut ssh = self.command();
+        ssh.args(["-t", "--", &self.destination, command])
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit());
+        Ok(ssh.spawn()?.wait().await?)
+    }
+}
+
+/// File and command infra operating on a workspace on another host. Every
+/// path must stay within the remote workspace.
+pub struct ForgeRemoteService<T> {
+    transport: T,
+    root: PathBuf,
+}
+
+impl ForgeRemoteService<OpenSshTransport> {
+    pub fn ssh(target: &RemoteTarget) -> Self {
+        Self::new(OpenSshTransport::new(target.destination()), target.root())
+    }
+}
+
+impl<T: SshTransport> ForgeRemoteService<T> {
+    pub fn new(transport: T, root: impl Into<PathBuf>) -> Self {
+        Self { transport, root: root.into() }
+    }
+
+    /// Whether the path is within the remote workspace
+    pub fn contains(&self, path: &Path) -> bool {
+        normalize(&self.root.join(path)).starts_with(&self.root)
+    }
+
+    /// Resolves the path against the remote workspace, failing for paths
+    /// that end up outside of it
+    fn resolve(&self, path: &Path) -> anyhow::Result<String> {
+        let path = normalize(&self.root.join(path));
+        if !path.starts_with(&self.root) {
+            anyhow::bail!(
+                "Path {} is outside of the remote workspace {}",
+                path.display(),
+                self.root.display()
+            );
+        }
+        Ok(shell_quote(&path.to_string_lossy()))
+    }
+
+    async fn run(&self, command: &str, input: Option<Vec<u8>>) -> anyhow::Result<SshOutput> {
+        self.transport.run(command, input).await
+    }
+
+    /// Runs the command, failing with its stderr when it doesn't succeed
+    async fn run_checked(&self, command: &str, input: Option<Vec<u8>>) -> anyhow::Result<Vec<u8>> {
+        let output = self.run(command, input).await?;
+        if output.exit_code != Some(0) {
+            anyhow::bail!(
+                "Remote command `{command}` failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(output.stdout)
+    }
+
+    /// Runs the test expression, its exit status being the answer
+    async fn test(&self, expression: &str) -> anyhow::Result<bool> {
+        Ok(self
+            .run(&format!("test {expression}"), None)
+            .await?
+            .exit_code
+            == Some(0))
+    }
+
+    pub async fn write(
+        &self,
+        path: &Path,
+        contents: Bytes,
+        capture_snapshot: bool,
+    ) -> anyhow::Result<()> {
+        if capture_snapshot && self.exists(path).await? {
+            self.create_snapshot(path).await?;
+        }
+        let path = self.resolve(path)?;
+        self.run_checked(
+            &format!("mkdir -p \"$(dirname {path})\" && cat > {path}"),
+            Some(contents.to_vec()),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Walks the directory with `find`, which has to be GNU find, applying
+    /// the limits of the walker to its listing. Hidden entries are skipped
+    /// like the local walker does, ignore files aren't read though
+    pub async fn walk(&self, config: Walker) -> anyhow::Result<Vec<WalkedFile>> {
+        let cwd = self.resolve(&config.cwd)?;
+        let max_depth = config
+            .max_depth
+            .map(|depth| format!(" -maxdepth {depth}"))
+            .unwrap_or_default();
+        let listing = self
+            .run_checked(
+                &format!(
+                    "cd {cwd} && find .{max_depth} -name '.?*' -prune -o -printf '%y\\t%s\\t%T@\\t%P\\n'"
+                ),
+                None,
+            )
+            .await?;
+        Ok(walked_files(&String::from_utf8_lossy(&listing), &config))
+    }
+
+    /// Snapshots are kept on the remote host, next to the offloaded outputs
+    fn snapshot_of(&self, path: &Path) -> anyhow::Result<Snapshot> {
+        Ok(Snapshot {
+            id: SnapshotId::new(),
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?,
+            path: normalize(&self.root.join(path)).display().to_string(),
+        })
+    }
+
+    fn snapshot_dir(&self) -> PathBuf {
+        self.root.join(".forge").join("snapshots")
+    }
+
+    async fn command_output(
+        &self,
+        command: String,
+        working_dir: PathBuf,
+        input: Option<String>,
+    ) -> anyhow::Result<CommandOutput> {
+        let working_dir = self.resolve(&working_dir)?;
+        let is_piped = input.is_some();
+        let output = self
+            .run(
+                &format!("cd {working_dir} && {command}"),
+                input.map(String::into_bytes),
+            )
+            .await?;
+        Ok(CommandOutput {
+            stdout: if is_piped {
+                piped_stdout(&command, output.stdout)?
+            } else {
+                String::from_utf8_lossy(&output.stdout).into_owned()
+            },
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            exit_code: output.exit_code,
+            command,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: SshTransport> FileReaderInfra for ForgeRemoteService<T> {
+    async fn read_utf8(&self, path: &Path) -> anyhow::Result<String> {
+        let bytes = self.read(path).await?;
+        String::from_utf8(bytes)
+            .with_context(|| format!("Failed to read {} as UTF-8", path.display()))
+    }
+
+    async fn read(&self, path: &Path) -> anyhow::Result<Vec<u8>> {
+        let path = self.resolve(path)?;
+        self.run_checked(&format!("cat {path}"), None).await
+    }
+
+    async fn range_read_utf8(
+        &self,
+        path: &Path,
+        start_line: u64,
+        end_line: u64,
+    ) -> anyhow::Result<(String, forge_fs::FileInfo)> {
+        if start_line == 0 || end_line == 0 || start_line > end_line {
+            anyhow::bail!("Invalid line range {start_line}-{end_line}");
+        }
+        if self.is_binary(path).await? {
+            anyhow::bail!("Binary files are not supported: {}", path.display());
+        }
+
+        let content = self.read_utf8(path).await?;
+        let lines = content.lines().collect::<Vec<_>>();
+        let total_lines = lines.len() as u64;
+        if content.is_empty() && start_line < 2 {
+            return Ok((
+                String::new(),
+                forge_fs::FileInfo::new(start_line, end_line, 0),
+            ));
+        }
+        if start_line > total_lines {
+            anyhow::bail!("Start line {start_line} is beyond the {total_lines} lines of the file");
+        }
+
+        let end = end_line.min(total_lines);
+        let info = forge_fs::FileInfo::new(start_line, end_line, total_lines);
+        if start_line == 1 && end == total_lines {
+            return Ok((content, info));
+        }
+        Ok((
+            lines[(start_line - 1) as usize..end as usize].join("\n"),
+            info,
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: SshTransport> FileInfoInfra for ForgeRemoteService<T> {
+    async fn is_binary(&self, path: &Path) -> anyhow::Result<bool> {
+        let path = self.resolve(path)?;
+        let head = self
+            .run_checked(&format!("head -c {BINARY_PROBE_SIZE} {path}"), None)
+            .await?;
+        Ok(head.contains(&0))
+    }
+
+    async fn is_file(&self, path: &Path) -> anyhow::Result<bool> {
+        self.test(&format!("-f {}", self.resolve(path)?)).await
+    }
+
+    async fn exists(&self, path: &Path) -> anyhow::Result<bool> {
+        self.test(&format!("-e {}", self.resolve(path)?)).await
+    }
+
+    async fn file_size(&self, path: &Path) -> anyhow::Result<u64> {
+        let path = self.resolve(path)?;
+        let size = self.run_checked(&format!("wc -c < {path}"), None).await?;
+        String::from_utf8_lossy(&size)
+            .trim()
+            .parse()
+            .with_context(|| format!("Failed to get the size of {path}"))
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: SshTransport> SnapshotInfra for ForgeRemoteService<T> {
+    async fn create_snapshot(&self, file_path: &Path) -> anyhow::Result<Snapshot> {
+        let source = self.resolve(file_path)?;
+        let snapshot = self.snapshot_of(file_path)?;
+        let target = shell_quote(
+            &snapshot
+                .snapshot_path(Some(self.snapshot_dir()))
+                .to_string_lossy(),
+        );
+        self.run_checked(
+            &format!("mkdir -p \"$(dirname {target})\" && cp {source} {target}"),
+            None,
+        )
+        .await?;
+        Ok(snapshot)
+    }
+
+    async fn undo_snapshot(&self, file_path: &Path) -> anyhow::Result<()> {
+        let path = self.resolve(file_path)?;
+        let snapshot = self.snapshot_of(file_path)?;
+        let dir = shell_quote(
+            &self
+                .snapshot_dir()
+                .join(snapshot.path_hash())
+                .to_string_lossy(),
+        );
+        let output = self
+            .run(
+                &format!(
+                    "latest=$(ls -1 {dir}/*.snap 2>/dev/null | sort | tail -n 1) && test -n \"$latest\" && cp \"$latest\" {path} && rm -f \"$latest\""
+                ),
+                None,
+            )
+            .await?;
+        if output.exit_code != Some(0) {
+            anyhow::bail!("No snapshots found for {:?}", file_path);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: SshTransport> FileRemoverInfra for ForgeRemoteService<T> {
+    async fn remove(&self, path: &Path) -> anyhow::Result<()> {
+        self.create_snapshot(path).await?;
+        let path = self.resolve(path)?;
+        self.run_checked(&format!("rm -f {path}"), None).await?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: SshTransport> FileDirectoryInfra for ForgeRemoteService<T> {
+    async fn create_dirs(&self, path: &Path) -> anyhow::Result<()> {
+        let path = self.resolve(path)?;
+        self.run_checked(&format!("mkdir -p {path}"), None).await?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: SshTransport> CommandInfra for ForgeRemoteService<T> {
+    async fn execute_command(
+        &self,
+        command: String,
+        working_dir: PathBuf,
+    ) -> anyhow::Result<CommandOutput> {
+        let output = self.command_output(command, working_dir, None).await?;
+        // Shown once the command is done, the output isn't streamed back
+        std::io::stdout().write_all(output.stdout.as_bytes())?;
+        std::io::stderr().write_all(output.stderr.as_bytes())?;
+        Ok(output)
+    }
+
+    async fn execute_command_raw(
+        &self,
+        command: &str,
+        working_dir: PathBuf,
+    ) -> anyhow::Result<ExitStatus> {
+        let working_dir = self.resolve(&working_dir)?;
+        self.transport
+            .run_interactive(&format!("cd {working_dir} && {command}"))
+            .await
+    }
+
+    async fn execute_command_with_input(
+        &self,
+        command: String,
+        working_dir: PathBuf,
+        input: String,
+    ) -> anyhow::Result<CommandOutput> {
+        self.command_output(command, working_dir, Some(input)).await
+    }
+}
+
+/// Turns the `find` listing of type, size, modification time and path into
+/// walked files, within the limits of the walker
+fn walked_files(listing: &str, config: &Walker) -> Vec<WalkedFile> {
+    let mut files = Vec::new();
+    let mut total_size = 0u64;
+    let mut file_count = 0usize;
+    let mut dir_entries: HashMap<PathBuf, usize> = HashMap::new();
+
+    for line in listing.lines() {
+        let mut fields = line.splitn(4, '\t');
+        let (Some(kind), Some(size), Some(modified), Some(path)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let is_dir = kind == "d";
+        if !is_dir && kind != "f" {
+            continue;
+        }
+        let path = Path::new(path);
+        let size = if is_dir { 0 } else { size.parse().unwrap_or(0) };
+
+        if let Some(parent) = path.parent().filter(|_| !path.as_os_str().is_empty()) {
+            let entries = dir_entries.entry(parent.to_path_buf()).or_insert(0);
+            *entries += 1;
+            if config.max_breadth.is_some_and(|max| *entries > max) {
+                continue;
+            }
+        }
+        if !is_dir {
+            if config.skip_binary && forge_walker::Walker::is_likely_binary(path) {
+                continue;
+            }
+            if config.max_file_size.is_some_and(|max| size > max) {
+                continue;
+            }
+            if config
+                .max_total_size
+                .is_some_and(|max| total_size + size > max)
+            {
+                break;
+            }
+            file_count += 1;
+            if config.max_files.is_some_and(|max| file_count > max) {
+                break;
+            }
+            total_size += size;
+        }
+
+        let path_string = path.to_string_lossy().to_string();
+        files.push(WalkedFile {
+            path: if is_dir {
+                format!("{path_string}/")
+            } else {
+                path_string
+            },
+            file_name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string()),
+            size,
+            modified: modified
+                .parse::<f64>()
+                .ok()
+                .map(|secs| UNIX_EPOCH + Duration::from_secs_f64(secs)),
+        });
+    }
+    files
+}
+
+/// Resolves `.` and `..` without touching the local file system
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Transport answering each command with the next canned output and
+    /// recording the commands it was asked to run
+    #[derive(Default)]
+    struct MockTransport {
+        outputs: Mutex<Vec<SshOutput>>,
+        commands: Mutex<Vec<String>>,
+    }
+
+    impl MockTransport {
+        fn new(outputs: Vec<SshOutput>) -> Self {
+            Self { outputs: Mutex::new(outputs), commands: Mutex::default() }
+        }
+
+        fn commands(&self) -> Vec<String> {
+            self.commands.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl SshTransport for MockTransport {
+        async fn run(&self, command: &str, _input: Option<Vec<u8>>) -> anyhow::Result<SshOutput> {
+            self.commands.lock().unwrap().push(command.to_string());
+            Ok(self.outputs.lock().unwrap().remove(0))
+        }
+
+        async fn run_interactive(&self, _command: &str) -> anyhow::Result<ExitStatus> {
+            unimplemented!()
+        }
+    }
+
+    fn output(stdout: &str, exit_code: i32) -> SshOutput {
+        SshOutput {
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: Vec::new(),
+            exit_code: Some(exit_code),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_runs_over_the_transport() {
+        let fixture = ForgeRemoteService::new(
+            MockTransport::new(vec![output("fn main() {}\n", 0)]),
+            "/srv/project",
+        );
+
+        let actual = fixture
+            .read_utf8(Path::new("/srv/project/src/main.rs"))
+            .await
+            .unwrap();
+
+        assert_eq!(actual, "fn main() {}\n");
+        assert_eq!(
+            fixture.transport.commands(),
+            vec!["cat '/srv/project/src/main.rs'".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_command_runs_in_the_remote_working_dir() {
+        let fixture = ForgeRemoteService::new(
+            MockTransport::new(vec![output("Cargo.toml\n", 0)]),
+            "/srv/project",
+        );
+
+        let actual = fixture
+            .execute_command_with_input(
+                "ls".to_string(),
+                PathBuf::from("/srv/project"),
+                String::new(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(actual.stdout, "Cargo.toml\n");
+        assert_eq!(actual.exit_code, Some(0));
+        assert_eq!(
+            fixture.transport.commands(),
+            vec!["cd '/srv/project' && ls".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_write_snapshots_the_existing_file_on_the_remote() {
+        let fixture = ForgeRemoteService::new(
+            MockTransport::new(vec![output("", 0), output("", 0), output("", 0)]),
+            "/srv/project",
+        );
+
+        fixture
+            .write(
+                Path::new("/srv/project/main.rs"),
+                Bytes::from("fn main() {}"),
+                true,
+            )
+            .await
+            .unwrap();
+
+        let actual = fixture.transport.commands();
+        assert_eq!(actual.len(), 3);
+        assert_eq!(actual[0], "test -e '/srv/project/main.rs'");
+        assert!(actual[1].starts_with("mkdir -p \"$(dirname '/srv/project/.forge/snapshots/"));
+        assert!(actual[1].contains("&& cp '/srv/project/main.rs' '/srv/project/.forge/snapshots/"));
+        assert!(actual[2].ends_with("cat > '/srv/project/main.rs'"));
+    }
+
+    #[test]
+    fn test_walked_files_apply_the_walker_limits() {
+        let fixture = [
+            "d\t4096\t1700000000.5\t",
+            "f\t10\t1700000000.5\tmain.rs",
+            "f\t2000\t1700000000.5\tbig.rs",
+            "f\t10\t1700000000.5\tlogo.png",
+            "d\t4096\t1700000000.5\tsrc",
+            "f\t10\t1700000000.5\tsrc/lib.rs",
+        ]
+        .join("\n");
+        let walker = Walker::unlimited().max_file_size(1000u64).skip_binary(true);
+
+        let actual = walked_files(&fixture, &walker)
+            .into_iter()
+            .map(|file| file.path)
+            .collect::<Vec<_>>();
+
+        let expected = ["/", "main.rs", "src/", "src/lib.rs"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_paths_outside_the_remote_workspace_are_rejected() {
+        let fixture = ForgeRemoteService::new(MockTransport::default(), "/srv/project");
+
+        let actual = fixture
+            .read(Path::new("/srv/project/../../etc/passwd"))
+            .await
+            .is_err();
+
+        assert!(actual);
+        assert_eq!(fixture.transport.commands(), Vec::<String>::new());
+    }
+}
@@ -9,20 +9,9 @@ impl ForgeWalkerService {
     }
 
     pub async fn walk(&self, config: Walker) -> Result<Vec<WalkedFile>> {
-        // Convert domain config to forge_walker config
-        let mut walker = if config.max_depth.is_none()
-            && config.max_breadth.is_none()
-            && config.max_file_size.is_none()
-            && config.max_files.is_none()
-            && config.max_total_size.is_none()
-            && !config.skip_binary
-        {
-            forge_walker::Walker::max_all()
-        } else {
-            forge_walker::Walker::min_all()
-        };
-
-        walker = walker.cwd(config.cwd);
+        // Convert domain config to forge_walker config, limits that aren't set
+        // don't apply
+        let mut walker = forge_walker::Walker::max_all().cwd(config.cwd);
 
         if let Some(depth) = config.max_depth {
             walker = walker.max_depth(depth);
@@ -45,7 +34,12 @@ impl ForgeWalkerService {
         let files = walker.get().await?;
         let walked_files = files
             .into_iter()
-            .map(|f| WalkedFile { path: f.path, file_name: f.file_name, size: f.size })
+            .map(|f| WalkedFile {
+                path: f.path,
+                file_name: f.file_name,
+                size: f.size,
+                modified: f.modified,
+            })
             .collect();
 
         Ok(walked_files)
@@ -59,6 +53,26 @@ mod tests {
 
     use super::*;
 
+    #[tokio::test]
+    async fn test_unset_limits_do_not_apply() {
+        let fixture = tempdir().unwrap();
+        for index in 0..12 {
+            std::fs::write(fixture.path().join(format!("file_{index}.txt")), "content").unwrap();
+        }
+        std::fs::write(fixture.path().join("logo.png"), [0u8, 1, 2]).unwrap();
+
+        let service = ForgeWalkerService::new();
+        let config = Walker::unlimited()
+            .cwd(fixture.path().to_path_buf())
+            .max_files(26_usize);
+
+        let actual = service.walk(config).await.unwrap();
+
+        let file_count = actual.iter().filter(|f| !f.is_dir()).count();
+        assert_eq!(file_count, 13);
+        assert!(actual.iter().any(|f| f.path == "logo.png"));
+    }
+
     #[tokio::test]
     async fn test_walker_service_basic_functionality() {
         let fixture = tempdir().unwrap();
@@ -0,0 +1,161 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::{Context, Result};
+
+/// Interval between two scans of the watched files
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Collects bursts of file changes and releases them once no further change
+/// has been seen for the quiet period.
+#[derive(Debug)]
+pub struct Debouncer {
+    quiet_period: Duration,
+    pending: BTreeSet<PathBuf>,
+    last_change: Option<Instant>,
+}
+
+impl Debouncer {
+    pub fn new(quiet_period: Duration) -> Self {
+        Self { quiet_period, pending: BTreeSet::new(), last_change: None }
+    }
+
+    /// Records the paths that changed at the given instant, every change
+    /// restarts the quiet period
+    pub fn record(&mut self, paths: impl IntoIterator<Item = PathBuf>, now: Instant) {
+        let mut paths = paths.into_iter().peekable();
+        if paths.peek().is_some() {
+            self.pending.extend(paths);
+            self.last_change = Some(now);
+        }
+    }
+
+    /// Returns the changed paths once the quiet period has elapsed since the
+    /// last change, resetting the debouncer
+    pub fn take_ready(&mut self, now: Instant) -> Option<Vec<PathBuf>> {
+        let last_change = self.last_change?;
+        if now.duration_since(last_change) < self.quiet_period {
+            return None;
+        }
+
+        self.last_change = None;
+        Some(std::mem::take(&mut self.pending).into_iter().collect())
+    }
+}
+
+/// Watches files in the working directory by periodically comparing their
+/// modification times
+pub struct ForgeFileWatcherService {
+    cwd: PathBuf,
+}
+
+impl ForgeFileWatcherService {
+    pub fn new(cwd: PathBuf) -> Self {
+        Self { cwd }
+    }
+
+    /// Waits until files matching the glob pattern are created, modified or
+    /// removed and returns their paths. Changes are debounced so that a burst
+    /// of edits is reported once.
+    pub async fn wait_for_changes(
+        &self,
+        pattern: &str,
+        debounce: Duration,
+    ) -> Result<Vec<PathBuf>> {
+        let pattern = glob::Pattern::new(pattern)
+            .with_context(|| format!("Invalid watch pattern: {pattern}"))?;
+        let mut debouncer = Debouncer::new(debounce);
+        let mut known = self.scan(&pattern).await?;
+
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            let current = self.scan(&pattern).await?;
+            let changed = current
+                .iter()
+                .filter(|(path, modified)| known.get(*path) != Some(*modified))
+                .map(|(path, _)| path.clone())
+                .chain(
+                    known
+                        .keys()
+                        .filter(|path| !current.contains_key(*path))
+                        .cloned(),
+                )
+                .collect::<Vec<_>>();
+            known = current;
+
+            let now = Instant::now();
+            debouncer.record(changed, now);
+            if let Some(paths) = debouncer.take_ready(now) {
+                return Ok(paths);
+            }
+        }
+    }
+
+    async fn scan(&self, pattern: &glob::Pattern) -> Result<HashMap<PathBuf, SystemTime>> {
+        let files = forge_walker::Walker::max_all()
+            .cwd(self.cwd.clone())
+            .get()
+            .await?;
+
+        Ok(files
+            .into_iter()
+            .filter(|file| !file.is_dir() && pattern.matches(&file.path))
+            .filter_map(|file| {
+                let path = self.cwd.join(&file.path);
+                let modified = std::fs::metadata(&path).and_then(|meta| meta.modified());
+                modified.ok().map(|modified| (path, modified))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_burst_of_changes_triggers_once_after_quiet_period() {
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let mut fixture = Debouncer::new(Duration::from_millis(500));
+
+        fixture.record([PathBuf::from("src/lib.rs")], at(0));
+        fixture.record([PathBuf::from("src/main.rs")], at(100));
+        fixture.record([PathBuf::from("src/lib.rs")], at(200));
+
+        let actual = [
+            fixture.take_ready(at(400)),
+            fixture.take_ready(at(650)),
+            fixture.take_ready(at(700)),
+            fixture.take_ready(at(1500)),
+        ];
+
+        let expected = [
+            None,
+            None,
+            Some(vec![
+                PathBuf::from("src/lib.rs"),
+                PathBuf::from("src/main.rs"),
+            ]),
+            None,
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_no_changes_never_triggers() {
+        let start = Instant::now();
+        let mut fixture = Debouncer::new(Duration::from_millis(500));
+
+        fixture.record(Vec::new(), start);
+
+        let actual = fixture.take_ready(start + Duration::from_secs(10));
+
+        let expected = None;
+        assert_eq!(actual, expected);
+    }
+}
@@ -32,7 +32,7 @@ impl Fixture {
     fn new(model: ModelId) -> Self {
         Self {
             model,
-            _guard: forge_tracker::init_tracing(PathBuf::from("."), tracker.clone()).unwrap(),
+            _guard: forge_tracker::init_tracing(PathBuf::from("."), None, tracker.clone()).unwrap(),
         }
     }
 
@@ -41,7 +41,9 @@ impl Fixture {
         // NOTE: In tests the CWD is not the project root
         ForgeAPI::init(
             true,
+            None,
             std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            None,
         )
     }
 
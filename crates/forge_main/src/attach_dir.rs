@@ -0,0 +1,96 @@
+use std::path::{Path, PathBuf};
+
+use forge_fs::ForgeFS;
+use forge_walker::Walker;
+
+/// Maximum number of files packed from a directory
+const MAX_FILES: usize = 50;
+/// Files larger than this are skipped
+const MAX_FILE_SIZE: u64 = 256 * 1024;
+/// Maximum bytes packed from a directory in total
+const MAX_TOTAL_SIZE: u64 = 1024 * 1024;
+
+/// Why a file of the directory wasn't packed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    Binary,
+    TooLarge(u64),
+    OverBudget,
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::Binary => write!(f, "binary file"),
+            SkipReason::TooLarge(size) => {
+                write!(f, "{size} bytes exceeds the {MAX_FILE_SIZE} bytes limit")
+            }
+            SkipReason::OverBudget => {
+                write!(f, "directory exceeds the {MAX_TOTAL_SIZE} bytes limit")
+            }
+        }
+    }
+}
+
+/// Text files of a directory to attach, each one becoming its own attachment
+/// headed by its path
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PackedDirectory {
+    pub files: Vec<PathBuf>,
+    pub skipped: Vec<(PathBuf, SkipReason)>,
+}
+
+/// Collects the text files under the directory, respecting ignore files and
+/// bounded by the number of files and bytes packed
+pub async fn pack_directory(dir: &Path) -> anyhow::Result<PackedDirectory> {
+    let walked = Walker::max_all()
+        .cwd(dir.to_path_buf())
+        .max_files(MAX_FILES)
+        .get()
+        .await?;
+
+    let mut packed = PackedDirectory::default();
+    let mut total_size = 0;
+    for file in walked.into_iter().filter(|file| !file.is_dir()) {
+        let path = dir.join(&file.path);
+        if file.size > MAX_FILE_SIZE {
+            packed.skipped.push((path, SkipReason::TooLarge(file.size)));
+        } else if ForgeFS::is_binary_file(&path).await? {
+            packed.skipped.push((path, SkipReason::Binary));
+        } else if total_size + file.size > MAX_TOTAL_SIZE {
+            packed.skipped.push((path, SkipReason::OverBudget));
+        } else {
+            total_size += file.size;
+            packed.files.push(path);
+        }
+    }
+
+    packed.files.sort();
+    packed.skipped.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(packed)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pack_directory_attaches_text_files_and_skips_binaries() {
+        let fixture = tempfile::tempdir().unwrap();
+        let dir = fixture.path();
+        std::fs::create_dir(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("app.toml"), "name = \"forge\"\n").unwrap();
+        std::fs::write(dir.join("nested/db.yaml"), "host: localhost\n").unwrap();
+        std::fs::write(dir.join("logo.dat"), [0x89, 0x00, 0xff, 0x00, 0x01]).unwrap();
+
+        let actual = pack_directory(dir).await.unwrap();
+
+        let expected = PackedDirectory {
+            files: vec![dir.join("app.toml"), dir.join("nested/db.yaml")],
+            skipped: vec![(dir.join("logo.dat"), SkipReason::Binary)],
+        };
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,61 @@
+/// Exit code used when a turn is interrupted and can't be resumed
+/// automatically
+pub const INTERRUPTED_EXIT_CODE: u8 = 3;
+
+/// Answers interruption prompts automatically up to a fixed number of times so
+/// that unattended runs don't block on user input.
+#[derive(Debug, Clone, Default)]
+pub struct AutoContinue {
+    limit: Option<usize>,
+    count: usize,
+}
+
+impl AutoContinue {
+    pub fn new(limit: Option<usize>) -> Self {
+        Self { limit, count: 0 }
+    }
+
+    /// Returns true when interruptions should be handled without prompting
+    pub fn is_enabled(&self) -> bool {
+        self.limit.is_some()
+    }
+
+    /// Consumes one automatic continuation. Returns false once the limit has
+    /// been reached or when auto-continue is disabled.
+    pub fn try_continue(&mut self) -> bool {
+        match self.limit {
+            Some(limit) if self.count < limit => {
+                self.count += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_auto_continue_resumes_up_to_limit() {
+        let mut fixture = AutoContinue::new(Some(3));
+
+        let actual = (0..5).map(|_| fixture.try_continue()).collect::<Vec<_>>();
+
+        let expected = vec![true, true, true, false, false];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_auto_continue_disabled() {
+        let mut fixture = AutoContinue::new(None);
+
+        let actual = (fixture.is_enabled(), fixture.try_continue());
+
+        let expected = (false, false);
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,275 @@
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use forge_api::{API, AgentId, ChatRequest, ChatResponse, Event, ModelId, Usage, Workflow};
+use tokio_stream::StreamExt;
+
+use crate::ui::EVENT_USER_TASK_INIT;
+
+/// Metrics collected while running the benchmark prompt against one model
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchResult {
+    pub model: ModelId,
+    pub latency: Duration,
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+    pub cost: Option<f64>,
+    pub response: String,
+    pub error: Option<String>,
+}
+
+impl BenchResult {
+    pub fn new(model: ModelId) -> Self {
+        Self {
+            model,
+            latency: Duration::ZERO,
+            prompt_tokens: 0,
+            completion_tokens: 0,
+            cost: None,
+            response: String::new(),
+            error: None,
+        }
+    }
+
+    /// Folds a response of the model into the metrics, usage is reported per
+    /// request so it's accumulated over the whole run
+    pub fn record(&mut self, response: &ChatResponse) {
+        match response {
+            ChatResponse::Text { text, is_complete: true, .. } => self.push_response(text),
+            ChatResponse::Summary { content } => self.push_response(content),
+            ChatResponse::Usage(usage) => self.record_usage(usage),
+            _ => {}
+        }
+    }
+
+    fn record_usage(&mut self, usage: &Usage) {
+        self.prompt_tokens += *usage.prompt_tokens;
+        self.completion_tokens += *usage.completion_tokens;
+        self.cost = match (self.cost, usage.cost) {
+            (Some(total), Some(cost)) => Some(total + cost),
+            (total, cost) => total.or(cost),
+        };
+    }
+
+    fn push_response(&mut self, text: &str) {
+        if text.trim().is_empty() {
+            return;
+        }
+        if !self.response.is_empty() {
+            self.response.push('\n');
+        }
+        self.response.push_str(text.trim());
+    }
+}
+
+/// Runs the prompt in a fresh conversation that uses the given model
+pub async fn run<A: API>(
+    api: &A,
+    workflow: Workflow,
+    model: ModelId,
+    prompt: String,
+) -> BenchResult {
+    let mut result = BenchResult::new(model.clone());
+    let start = Instant::now();
+    if let Err(error) = run_inner(api, workflow, &model, prompt, &mut result).await {
+        result.error = Some(error.to_string());
+    }
+    result.latency = start.elapsed();
+    result
+}
+
+async fn run_inner<A: API>(
+    api: &A,
+    workflow: Workflow,
+    model: &ModelId,
+    prompt: String,
+    result: &mut BenchResult,
+) -> Result<()> {
+    let mut conversation = api.init_conversation(workflow).await?;
+    conversation.set_model(model)?;
+    let conversation_id = conversation.id;
+    api.upsert_conversation(conversation).await?;
+
+    let event = Event::new(
+        format!("{}/{EVENT_USER_TASK_INIT}", AgentId::default()),
+        Some(prompt),
+    );
+    let mut stream = api.chat(ChatRequest::new(event, conversation_id)).await?;
+    while let Some(response) = stream.next().await {
+        result.record(&response?);
+    }
+    Ok(())
+}
+
+/// Side by side comparison of the models, in the order they were requested
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchReport {
+    pub results: Vec<BenchResult>,
+}
+
+impl BenchReport {
+    pub fn new(results: Vec<BenchResult>) -> Self {
+        Self { results }
+    }
+}
+
+impl Display for BenchReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let header = ["Model", "Latency", "Prompt", "Completion", "Cost"].map(String::from);
+        let rows = self
+            .results
+            .iter()
+            .map(|result| {
+                [
+                    result.model.to_string(),
+                    format!("{:.2}s", result.latency.as_secs_f64()),
+                    result.prompt_tokens.to_string(),
+                    result.completion_tokens.to_string(),
+                    result
+                        .cost
+                        .map_or("-".to_string(), |cost| format!("${cost:.4}")),
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        let widths = header.each_ref().map(|column| column.len());
+        let widths = rows.iter().fold(widths, |widths, row| {
+            let mut widths = widths;
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+            widths
+        });
+
+        for row in std::iter::once(&header).chain(rows.iter()) {
+            let line = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        for result in &self.results {
+            writeln!(f)?;
+            writeln!(f, "## {}", result.model)?;
+            match &result.error {
+                Some(error) => writeln!(f, "Error: {error}")?,
+                None => writeln!(f, "{}", result.response)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use forge_api::TokenCount;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture_usage(prompt: usize, completion: usize, cost: Option<f64>) -> ChatResponse {
+        ChatResponse::Usage(Usage {
+            prompt_tokens: TokenCount::Actual(prompt),
+            completion_tokens: TokenCount::Actual(completion),
+            total_tokens: TokenCount::Actual(prompt + completion),
+            cached_tokens: TokenCount::Actual(0),
+            cost,
+        })
+    }
+
+    #[test]
+    fn test_record_accumulates_metrics_per_model() {
+        let fixture = [
+            (
+                "model-a",
+                vec![
+                    fixture_usage(100, 20, Some(0.001)),
+                    ChatResponse::Text {
+                        text: "Looking".to_string(),
+                        is_complete: false,
+                        is_md: false,
+                    },
+                    fixture_usage(150, 30, Some(0.002)),
+                    ChatResponse::Summary { content: "The answer is 4".to_string() },
+                ],
+            ),
+            (
+                "model-b",
+                vec![
+                    fixture_usage(90, 10, None),
+                    ChatResponse::Text { text: "4".to_string(), is_complete: true, is_md: true },
+                ],
+            ),
+        ];
+
+        let actual = BenchReport::new(
+            fixture
+                .into_iter()
+                .map(|(model, responses)| {
+                    let mut result = BenchResult::new(ModelId::new(model));
+                    responses
+                        .iter()
+                        .for_each(|response| result.record(response));
+                    result
+                })
+                .collect(),
+        );
+
+        let expected = BenchReport::new(vec![
+            BenchResult {
+                prompt_tokens: 250,
+                completion_tokens: 50,
+                cost: Some(0.003),
+                response: "The answer is 4".to_string(),
+                ..BenchResult::new(ModelId::new("model-a"))
+            },
+            BenchResult {
+                prompt_tokens: 90,
+                completion_tokens: 10,
+                response: "4".to_string(),
+                ..BenchResult::new(ModelId::new("model-b"))
+            },
+        ]);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_report_renders_comparison_table() {
+        let fixture = BenchReport::new(vec![
+            BenchResult {
+                latency: Duration::from_millis(1500),
+                prompt_tokens: 250,
+                completion_tokens: 50,
+                cost: Some(0.003),
+                response: "The answer is 4".to_string(),
+                ..BenchResult::new(ModelId::new("model-a"))
+            },
+            BenchResult {
+                latency: Duration::from_millis(250),
+                error: Some("Model not found".to_string()),
+                ..BenchResult::new(ModelId::new("model-b"))
+            },
+        ]);
+
+        let actual = fixture.to_string();
+
+        let expected = [
+            "Model    Latency  Prompt  Completion  Cost",
+            "model-a  1.50s    250     50          $0.0030",
+            "model-b  0.25s    0       0           -",
+            "",
+            "## model-a",
+            "The answer is 4",
+            "",
+            "## model-b",
+            "Error: Model not found",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,6 +1,7 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use clap::{Parser, Subcommand, ValueEnum};
+use forge_api::RemoteTarget;
 
 #[derive(Parser)]
 #[command(version = env!("CARGO_PKG_VERSION"))]
@@ -18,6 +19,38 @@ pub struct Cli {
     #[arg(long, short = 'p')]
     pub prompt: Option<String>,
 
+    /// Background given to the model ahead of the first message.
+    ///
+    /// Accepts the text itself or `@path` to read it from a file, e.g. the
+    /// fixed priming of a templated task. It's added to the context without
+    /// being treated as the task.
+    #[arg(long)]
+    pub context: Option<String>,
+
+    /// Re-run the prompt whenever files matching the glob change.
+    ///
+    /// After the initial run forge keeps watching the files, e.g. `src/**/*.rs`,
+    /// and dispatches the prompt again once a burst of changes settles, until
+    /// interrupted with Ctrl+C. Only available together with `--prompt`.
+    #[arg(long, requires = "prompt")]
+    pub watch: Option<String>,
+
+    /// Attach the text files of a directory to the first message.
+    ///
+    /// Files are packed respecting ignore files, each one attached with its
+    /// path, up to a bounded number of files and bytes. Binary and oversized
+    /// files are skipped with a note.
+    #[arg(long)]
+    pub attach_dir: Option<PathBuf>,
+
+    /// Stream the chat events as JSON lines to a Unix socket at this path.
+    ///
+    /// Lets a separate process, like an IDE extension, render the session.
+    /// Clients connecting mid-session receive the events that follow.
+    #[cfg(unix)]
+    #[arg(long)]
+    pub event_socket: Option<PathBuf>,
+
     /// Enable verbose output mode.
     ///
     /// When enabled, shows additional debugging information and tool execution
@@ -25,6 +58,20 @@ pub struct Cli {
     #[arg(long, default_value_t = false)]
     pub verbose: bool,
 
+    /// Skip confirmation prompts.
+    ///
+    /// Starting a new conversation or exiting no longer offers to save the
+    /// current conversation first.
+    #[arg(long, short = 'y', default_value_t = false)]
+    pub yes: bool,
+
+    /// Start new conversations in plan-first mode.
+    ///
+    /// The agent presents its plan before changing files or running commands,
+    /// and nothing is changed until the plan is approved.
+    #[arg(long, default_value_t = false)]
+    pub plan_first: bool,
+
     /// Enable restricted shell mode for enhanced security.
     ///
     /// Controls the shell execution environment:
@@ -41,6 +88,21 @@ pub struct Cli {
     #[arg(long, default_value_t = false, short = 'r')]
     pub restricted: bool,
 
+    /// Shell used to run commands instead of the platform default.
+    ///
+    /// Accepts a path or a program name on the PATH, e.g. `zsh`, `fish` or
+    /// `pwsh`. On Windows this selects between `cmd` and PowerShell. Ignored
+    /// in restricted mode on Unix/Mac.
+    #[arg(long, value_parser = parse_shell)]
+    pub shell: Option<String>,
+
+    /// Work on a remote workspace over SSH, given as `user@host:/path`.
+    ///
+    /// Files are read and written and shell commands are run on the remote
+    /// host through the local `ssh` client, confined to the remote path.
+    #[arg(long, value_parser = parse_remote)]
+    pub remote: Option<RemoteTarget>,
+
     /// Path to a file containing the workflow to execute.
     #[arg(long, short = 'w')]
     pub workflow: Option<PathBuf>,
@@ -50,6 +112,14 @@ pub struct Cli {
     #[arg(long, short = 'e')]
     pub event: Option<String>,
 
+    /// Automatically continue past interruptions up to the given number of
+    /// times.
+    ///
+    /// Useful for unattended runs where the interactive confirmation would
+    /// block. Once the limit is exhausted forge exits with a non-zero status.
+    #[arg(long)]
+    pub auto_continue: Option<usize>,
+
     /// Path to a file containing the conversation to execute.
     /// This file should be in JSON format.
     #[arg(long)]
@@ -70,9 +140,79 @@ pub struct Cli {
     pub directory: Option<PathBuf>,
 }
 
+impl Cli {
+    /// Sets verbose mode, flipping it when no value is given, and returns the
+    /// new state
+    pub fn set_verbose(&mut self, verbose: Option<bool>) -> bool {
+        self.verbose = verbose.unwrap_or(!self.verbose);
+        self.verbose
+    }
+}
+
+fn parse_remote(value: &str) -> Result<RemoteTarget, String> {
+    value
+        .parse()
+        .map_err(|error: anyhow::Error| error.to_string())
+}
+
+/// Resolves the shell to its full path, failing if it doesn't exist
+fn parse_shell(value: &str) -> Result<String, String> {
+    let path = Path::new(value);
+    let candidates = if path.is_absolute() || path.components().count() > 1 {
+        vec![path.to_path_buf()]
+    } else {
+        let names = if cfg!(target_os = "windows") && path.extension().is_none() {
+            vec![value.to_string(), format!("{value}.exe")]
+        } else {
+            vec![value.to_string()]
+        };
+        std::env::var_os("PATH")
+            .map(|paths| {
+                std::env::split_paths(&paths)
+                    .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    candidates
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+        .map(|candidate| candidate.display().to_string())
+        .ok_or_else(|| format!("shell `{value}` was not found"))
+}
+
 #[derive(Subcommand, Debug, Clone)]
 pub enum TopLevelCommand {
     Mcp(McpCommandGroup),
+
+    /// Run a prompt against several models and compare the results
+    Bench(BenchArgs),
+
+    /// Check that the provider, config, MCP servers, git and the state
+    /// directories are ready to use
+    Doctor,
+
+    /// Manage the profiles used to switch between providers and accounts
+    Profile(ProfileCommandGroup),
+
+    /// Serve the built-in tools as an MCP server over stdio, running them in
+    /// the working directory
+    ServeMcp,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct BenchArgs {
+    /// Prompt sent to every model
+    pub prompt: String,
+
+    /// Comma separated list of the models to compare
+    #[arg(long, value_delimiter = ',', required = true)]
+    pub models: Vec<String>,
+
+    /// Maximum number of models running at the same time
+    #[arg(long, default_value_t = 3)]
+    pub concurrency: usize,
 }
 
 /// Group of MCP-related commands
@@ -155,6 +295,82 @@ pub struct McpAddJsonArgs {
     pub json: String,
 }
 
+/// Group of profile-related commands
+#[derive(Parser, Debug, Clone)]
+pub struct ProfileCommandGroup {
+    /// Subcommands under `profile`
+    #[command(subcommand)]
+    pub command: ProfileCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ProfileCommand {
+    /// Add a profile, replacing any profile with the same name
+    Add(ProfileAddArgs),
+
+    /// List profiles
+    List,
+
+    /// Switch to a profile
+    Use(ProfileUseArgs),
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ProfileAddArgs {
+    /// Name of the profile
+    pub name: String,
+
+    /// Provider the profile connects to
+    #[arg(short = 'p', long = "provider")]
+    pub provider: ProfileProvider,
+
+    /// API key of the provider, not needed for Ollama
+    #[arg(short = 'k', long = "api-key")]
+    pub api_key: Option<String>,
+
+    /// Base URL overriding the default one of the provider, e.g. a proxy
+    #[arg(short = 'u', long = "base-url")]
+    pub base_url: Option<String>,
+
+    /// Model used by default with the profile
+    #[arg(short = 'm', long = "model")]
+    pub model: Option<String>,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ProfileUseArgs {
+    /// Name of the profile to switch to
+    pub name: String,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum ProfileProvider {
+    Forge,
+    OpenRouter,
+    Requesty,
+    Xai,
+    #[value(name = "openai")]
+    OpenAI,
+    Anthropic,
+    Gemini,
+    Ollama,
+}
+
+impl From<ProfileProvider> for forge_api::ProfileProvider {
+    fn from(value: ProfileProvider) -> Self {
+        match value {
+            ProfileProvider::Forge => forge_api::ProfileProvider::Forge,
+            ProfileProvider::OpenRouter => forge_api::ProfileProvider::OpenRouter,
+            ProfileProvider::Requesty => forge_api::ProfileProvider::Requesty,
+            ProfileProvider::Xai => forge_api::ProfileProvider::Xai,
+            ProfileProvider::OpenAI => forge_api::ProfileProvider::OpenAI,
+            ProfileProvider::Anthropic => forge_api::ProfileProvider::Anthropic,
+            ProfileProvider::Gemini => forge_api::ProfileProvider::Gemini,
+            ProfileProvider::Ollama => forge_api::ProfileProvider::Ollama,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, ValueEnum, Default)]
 pub enum Scope {
     #[default]
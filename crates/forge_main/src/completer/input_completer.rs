@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use forge_walker::Walker;
+use forge_walker::{File, Walker};
 use reedline::{Completer, Suggestion};
 
 use crate::completer::CommandCompleter;
@@ -34,28 +34,23 @@ impl Completer for InputCompleter {
 
         if let Some(query) = SearchTerm::new(line, pos).process() {
             let files = self.walker.get_blocking().unwrap_or_default();
-            files
+            path_candidates(&files, query.term)
                 .into_iter()
-                .filter(|file| !file.is_dir())
-                .filter_map(|file| {
-                    if let Some(file_name) = file.file_name.as_ref() {
-                        let file_name_lower = file_name.to_lowercase();
-                        let query_lower = query.term.to_lowercase();
-                        if file_name_lower.contains(&query_lower) {
-                            let path_md_fmt = format!("[{}]", file.path);
-                            Some(Suggestion {
-                                description: None,
-                                value: path_md_fmt,
-                                style: None,
-                                extra: None,
-                                span: query.span,
-                                append_whitespace: true,
-                            })
-                        } else {
-                            None
-                        }
+                .map(|file| {
+                    // Directories are completed as-is so the next segment can be typed right
+                    // away, files are wrapped into an attachment
+                    let (value, append_whitespace) = if file.is_dir() {
+                        (file.path.clone(), false)
                     } else {
-                        None
+                        (format!("[{}]", file.path), true)
+                    };
+                    Suggestion {
+                        description: None,
+                        value,
+                        style: None,
+                        extra: None,
+                        span: query.span,
+                        append_whitespace,
                     }
                 })
                 .collect()
@@ -64,3 +59,118 @@ impl Completer for InputCompleter {
         }
     }
 }
+
+/// Selects the workspace entries matching a partial path typed after `@`.
+///
+/// Once the term contains a `/`, only the entries directly inside the typed
+/// directory whose name starts with the last segment are returned, so paths
+/// can be completed one segment at a time. Otherwise files are matched by name
+/// anywhere in the workspace, along with matching top-level directories.
+fn path_candidates<'a>(files: &'a [File], term: &str) -> Vec<&'a File> {
+    let term = term.to_lowercase();
+    let (dir, segment) = match term.rfind('/') {
+        Some(index) => term.split_at(index + 1),
+        None => ("", term.as_str()),
+    };
+    let is_nested = !dir.is_empty();
+
+    files
+        .iter()
+        .filter(|file| {
+            let path = file.path.to_lowercase();
+            let Some(name) = path
+                .strip_prefix(dir)
+                .map(|rest| rest.trim_end_matches('/'))
+            else {
+                return false;
+            };
+            if name.is_empty() {
+                return false;
+            }
+
+            let is_direct_child = !name.contains('/');
+            if file.is_dir() || is_nested {
+                is_direct_child && name.starts_with(segment)
+            } else {
+                file.file_name
+                    .as_ref()
+                    .is_some_and(|file_name| file_name.to_lowercase().contains(segment))
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture_files() -> Vec<File> {
+        [
+            "Cargo.toml",
+            "src/",
+            "src/completer/",
+            "src/completer/command.rs",
+            "src/config.rs",
+            "src/main.rs",
+            "tests/",
+            "tests/config_test.rs",
+        ]
+        .into_iter()
+        .map(|path| File {
+            path: path.to_string(),
+            file_name: path
+                .trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .map(str::to_string),
+            size: 0,
+            modified: None,
+        })
+        .collect()
+    }
+
+    fn fixture_paths(term: &str) -> Vec<String> {
+        let files = fixture_files();
+        path_candidates(&files, term)
+            .into_iter()
+            .map(|file| file.path.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_path_candidates_by_file_name() {
+        let actual = fixture_paths("config");
+        let expected = vec!["src/config.rs", "tests/config_test.rs"];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_path_candidates_top_level_directories() {
+        let actual = fixture_paths("sr");
+        let expected = vec!["src/"];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_path_candidates_inside_directory() {
+        let actual = fixture_paths("src/");
+        let expected = vec!["src/completer/", "src/config.rs", "src/main.rs"];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_path_candidates_incremental_segment() {
+        let actual = fixture_paths("src/co");
+        let expected = vec!["src/completer/", "src/config.rs"];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_path_candidates_nested_directory() {
+        let actual = fixture_paths("SRC/Completer/c");
+        let expected = vec!["src/completer/command.rs"];
+        assert_eq!(actual, expected);
+    }
+}
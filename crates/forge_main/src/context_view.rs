@@ -0,0 +1,193 @@
+use std::fmt::Display;
+
+use forge_api::{Context, ContextMessage, Role};
+
+use crate::info::Info;
+
+/// Number of characters of a message shown in its preview
+const PREVIEW_LENGTH: usize = 60;
+
+/// A message of the context along with an estimate of its size
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageEntry {
+    /// Position of the message in the context
+    pub index: usize,
+    pub label: String,
+    pub tokens: usize,
+}
+
+impl Display for MessageEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "#{} {} (~{} tokens)",
+            self.index, self.label, self.tokens
+        )
+    }
+}
+
+/// Lists the messages of the context with their estimated token counts
+pub fn message_entries(context: &Context) -> Vec<MessageEntry> {
+    context
+        .messages
+        .iter()
+        .enumerate()
+        .map(|(index, message)| MessageEntry {
+            index,
+            label: label(message),
+            tokens: token_estimate(message),
+        })
+        .collect()
+}
+
+/// Summarizes what would be sent with the next request: each message, the
+/// tool definitions and the total estimated token count
+pub fn context_info(context: &Context) -> Info {
+    let entries = message_entries(context);
+    let tools_tokens = serde_json::to_string(&context.tools)
+        .map(|tools| tools.chars().count().div_ceil(4))
+        .unwrap_or_default();
+    let total = entries.iter().map(|entry| entry.tokens).sum::<usize>() + tools_tokens;
+
+    let info = entries
+        .into_iter()
+        .fold(Info::new().add_title("Messages"), |info, entry| {
+            info.add_key_value(
+                format!("#{} {}", entry.index, entry.label),
+                format!("~{} tokens", entry.tokens),
+            )
+        });
+    info.add_title("Request")
+        .add_key_value(
+            format!("Tools ({})", context.tools.len()),
+            format!("~{tools_tokens} tokens"),
+        )
+        .add_key_value("Total", format!("~{total} tokens"))
+}
+
+/// Estimates the tokens of a message. Unlike the estimate used for
+/// compaction, system messages are counted as they take up space too.
+fn token_estimate(message: &ContextMessage) -> usize {
+    match message {
+        ContextMessage::Text(text) if text.role == Role::System => {
+            text.content.chars().count().div_ceil(4)
+        }
+        message => message.token_count_approx(),
+    }
+}
+
+fn label(message: &ContextMessage) -> String {
+    match message {
+        ContextMessage::Text(text) => {
+            let role = text.role.to_string().to_lowercase();
+            let calls = text
+                .tool_calls
+                .iter()
+                .flatten()
+                .map(|call| call.name.to_string())
+                .collect::<Vec<_>>();
+            match (preview(&text.content), calls.is_empty()) {
+                (Some(preview), _) => format!("{role}: {preview}"),
+                (None, false) => format!("{role}: calls {}", calls.join(", ")),
+                (None, true) => role,
+            }
+        }
+        ContextMessage::Tool(result) if result.pinned => format!("tool: {} (pinned)", result.name),
+        ContextMessage::Tool(result) => format!("tool: {}", result.name),
+        ContextMessage::Image(_) => "image".to_string(),
+    }
+}
+
+/// First line of the content, shortened to fit on a single line
+fn preview(content: &str) -> Option<String> {
+    let line = content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())?;
+    if line.chars().count() > PREVIEW_LENGTH {
+        let line = line.chars().take(PREVIEW_LENGTH).collect::<String>();
+        Some(format!("{line}..."))
+    } else {
+        Some(line.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use forge_api::{ToolCallFull, ToolCallId, ToolDefinition, ToolName, ToolResult};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture_context() -> Context {
+        let call = ToolCallFull::new(ToolName::new("forge_tool_fs_read"))
+            .call_id(ToolCallId::new("call_1"));
+        Context::default()
+            .add_message(ContextMessage::system("You are a helpful assistant"))
+            .add_message(ContextMessage::user(
+                "Read the manifest\nand summarize it",
+                None,
+            ))
+            .add_message(ContextMessage::assistant(
+                "",
+                None,
+                Some(vec![call.clone()]),
+            ))
+            .add_tool_results(vec![
+                ToolResult::from(call).success("[package]\n".repeat(10)),
+            ])
+            .add_tool(ToolDefinition::new("forge_tool_fs_read").description("Reads a file"))
+    }
+
+    #[test]
+    fn test_message_entries_estimate_tokens_per_message() {
+        let fixture = fixture_context();
+
+        let actual = message_entries(&fixture);
+
+        let expected = vec![
+            MessageEntry {
+                index: 0,
+                label: "system: You are a helpful assistant".to_string(),
+                tokens: 7,
+            },
+            MessageEntry {
+                index: 1,
+                label: "user: Read the manifest".to_string(),
+                tokens: 9,
+            },
+            MessageEntry {
+                index: 2,
+                label: "assistant: calls forge_tool_fs_read".to_string(),
+                tokens: fixture.messages[2].token_count_approx(),
+            },
+            MessageEntry {
+                index: 3,
+                label: "tool: forge_tool_fs_read".to_string(),
+                tokens: 25,
+            },
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_context_info_reports_total_with_tools() {
+        let fixture = fixture_context();
+        let tools_tokens = serde_json::to_string(&fixture.tools)
+            .unwrap()
+            .chars()
+            .count()
+            .div_ceil(4);
+        let messages_tokens = message_entries(&fixture)
+            .iter()
+            .map(|entry| entry.tokens)
+            .sum::<usize>();
+
+        let actual = context_info(&fixture).to_string();
+
+        assert!(actual.contains("#0 system: You are a helpful assistant"));
+        assert!(actual.contains("#3 tool: forge_tool_fs_read"));
+        assert!(actual.contains(&format!("~{tools_tokens} tokens")));
+        assert!(actual.contains(&format!("~{} tokens", messages_tokens + tools_tokens)));
+    }
+}
@@ -0,0 +1,268 @@
+use std::fmt::Display;
+use std::path::Path;
+
+use anyhow::Result;
+use forge_api::{API, McpConfig, Model, Workflow};
+
+/// Outcome of a single check, only failures make the setup unusable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// Result of one readiness check along with a hint on how to fix it
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+    pub hint: Option<String>,
+}
+
+impl CheckResult {
+    pub fn pass(name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Pass,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    pub fn warn(name: impl Into<String>, message: impl Into<String>, hint: &str) -> Self {
+        Self {
+            status: CheckStatus::Warn,
+            hint: Some(hint.to_string()),
+            ..Self::pass(name, message)
+        }
+    }
+
+    pub fn fail(name: impl Into<String>, message: impl Into<String>, hint: &str) -> Self {
+        Self {
+            status: CheckStatus::Fail,
+            hint: Some(hint.to_string()),
+            ..Self::pass(name, message)
+        }
+    }
+}
+
+/// Checks that the provider answers with the configured key, listing the
+/// models is the cheapest request that needs a valid key
+pub fn check_provider(models: Result<Vec<Model>>) -> CheckResult {
+    match models {
+        Ok(models) => CheckResult::pass(
+            "Provider",
+            format!("Reachable, {} models available", models.len()),
+        ),
+        Err(error) => CheckResult::fail(
+            "Provider",
+            format!("{error:#}"),
+            "Check your network connection and the provider key in .env, or run forge and log in again",
+        ),
+    }
+}
+
+/// Checks that the workflow config could be read and parsed
+pub fn check_config(workflow: Result<Workflow>) -> CheckResult {
+    match workflow {
+        Ok(workflow) => CheckResult::pass(
+            "Config",
+            format!("Valid, {} agents configured", workflow.agents.len()),
+        ),
+        Err(error) => CheckResult::fail(
+            "Config",
+            format!("{error:#}"),
+            "Fix the reported error in forge.yaml, it's validated against forge.schema.json",
+        ),
+    }
+}
+
+/// Checks that the configured MCP servers could be connected to, their tools
+/// are loaded along with the built-in ones
+pub fn check_mcp<T>(config: Result<McpConfig>, tools: Result<Vec<T>>) -> CheckResult {
+    let hint = "Inspect the servers with `forge mcp list` and remove or fix the failing ones";
+    let servers = match config {
+        Ok(config) => config.mcp_servers.len(),
+        Err(error) => return CheckResult::warn("MCP", format!("{error:#}"), hint),
+    };
+    if servers == 0 {
+        return CheckResult::pass("MCP", "No servers configured");
+    }
+
+    match tools {
+        Ok(_) => CheckResult::pass("MCP", format!("Connected to {servers} servers")),
+        Err(error) => CheckResult::warn("MCP", format!("{error:#}"), hint),
+    }
+}
+
+/// Checks that git can be run, it's needed for checkpoints and commits but
+/// forge works without it
+pub fn check_git(version: std::io::Result<std::process::Output>) -> CheckResult {
+    let hint = "Install git and make sure it's on the PATH";
+    match version {
+        Ok(output) if output.status.success() => CheckResult::pass(
+            "Git",
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ),
+        Ok(output) => CheckResult::warn(
+            "Git",
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            hint,
+        ),
+        Err(error) => CheckResult::warn("Git", format!("Not available: {error}"), hint),
+    }
+}
+
+/// Checks that files can be created in the directory by writing and removing
+/// a probe file, creating the directory when it's missing
+pub fn check_writable(name: &str, dir: &Path) -> CheckResult {
+    let probe = dir.join(format!(".forge-doctor-{}", std::process::id()));
+    let result = std::fs::create_dir_all(dir)
+        .and_then(|_| std::fs::write(&probe, b"forge"))
+        .and_then(|_| std::fs::remove_file(&probe));
+
+    match result {
+        Ok(_) => CheckResult::pass(name, format!("{} is writable", dir.display())),
+        Err(error) => CheckResult::fail(
+            name,
+            format!("{} is not writable: {error}", dir.display()),
+            "Fix the permissions of the directory or free up disk space",
+        ),
+    }
+}
+
+/// Runs every check against the given API
+pub async fn run<A: API>(api: &A, workflow: Option<&Path>) -> DoctorReport {
+    let env = api.environment();
+    let git = tokio::process::Command::new("git")
+        .arg("--version")
+        .output()
+        .await;
+
+    DoctorReport::new(vec![
+        check_config(api.read_workflow(workflow).await),
+        check_provider(api.models().await),
+        check_mcp(api.read_mcp_config().await, api.tools().await),
+        check_git(git),
+        check_writable("State directory", &env.base_path),
+        check_writable("Temp directory", &std::env::temp_dir()),
+    ])
+}
+
+/// Results of all the checks, in the order they were run
+#[derive(Debug, Clone, PartialEq)]
+pub struct DoctorReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    pub fn new(checks: Vec<CheckResult>) -> Self {
+        Self { checks }
+    }
+
+    /// Whether none of the checks failed, warnings are tolerated
+    pub fn is_ready(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| check.status != CheckStatus::Fail)
+    }
+}
+
+impl Display for DoctorReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for check in &self.checks {
+            let status = match check.status {
+                CheckStatus::Pass => "PASS",
+                CheckStatus::Warn => "WARN",
+                CheckStatus::Fail => "FAIL",
+            };
+            writeln!(f, "[{status}] {}: {}", check.name, check.message)?;
+            if let Some(hint) = &check.hint {
+                writeln!(f, "       {hint}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_check_config_detects_invalid_workflow() {
+        let fixture = serde_yml::from_str::<Workflow>("agents: not-a-list")
+            .map_err(anyhow::Error::from)
+            .map_err(|error| error.context("Failed to parse forge.yaml"));
+
+        let actual = check_config(fixture);
+
+        assert_eq!(actual.status, CheckStatus::Fail);
+        assert!(actual.message.starts_with("Failed to parse forge.yaml: "));
+        assert!(actual.hint.is_some());
+    }
+
+    #[test]
+    fn test_check_writable_passes_for_temp_dir() {
+        let fixture = tempfile::tempdir().unwrap();
+
+        let actual = check_writable("Temp directory", &fixture.path().join("state"));
+
+        assert_eq!(actual.status, CheckStatus::Pass);
+        assert_eq!(
+            std::fs::read_dir(fixture.path().join("state"))
+                .unwrap()
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_check_writable_detects_unwritable_dir() {
+        // A directory can't be created below a regular file, even as root
+        let fixture = tempfile::NamedTempFile::new().unwrap();
+
+        let actual = check_writable("Temp directory", &fixture.path().join("state"));
+
+        assert_eq!(actual.status, CheckStatus::Fail);
+        assert!(actual.message.contains("is not writable"));
+    }
+
+    #[test]
+    fn test_check_mcp_without_servers_passes() {
+        let actual = check_mcp::<()>(Ok(McpConfig::default()), Err(anyhow::anyhow!("unused")));
+
+        let expected = CheckResult::pass("MCP", "No servers configured");
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_report_fails_only_on_critical_checks() {
+        let fixture = DoctorReport::new(vec![
+            CheckResult::pass("Config", "Valid, 2 agents configured"),
+            CheckResult::warn("Git", "Not available", "Install git"),
+        ]);
+        assert!(fixture.is_ready());
+
+        let fixture = DoctorReport::new(vec![
+            CheckResult::pass("Config", "Valid, 2 agents configured"),
+            CheckResult::fail("Provider", "401 Unauthorized", "Check the key"),
+        ]);
+        assert!(!fixture.is_ready());
+
+        let actual = fixture.to_string();
+
+        let expected = [
+            "[PASS] Config: Valid, 2 agents configured",
+            "[FAIL] Provider: 401 Unauthorized",
+            "       Check the key",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use forge_api::Environment;
@@ -13,6 +14,9 @@ use crate::model::ForgeCommandManager;
 // TODO: Store the last `HISTORY_CAPACITY` commands in the history file
 const HISTORY_CAPACITY: usize = 1024 * 1024;
 const COMPLETION_MENU: &str = "completion_menu";
+/// Prompts starting with this marker are kept out of the history file, so
+/// sensitive input isn't persisted across sessions
+const HISTORY_EXCLUSION_MARKER: &str = " ";
 
 pub struct ForgeEditor {
     editor: Reedline,
@@ -63,13 +67,15 @@ impl ForgeEditor {
         keybindings
     }
 
+    /// Opens the history persisted to the file, so that the prompts of earlier
+    /// sessions can be recalled. An unreadable file leaves the history empty
+    fn history(path: PathBuf) -> FileBackedHistory {
+        FileBackedHistory::with_file(HISTORY_CAPACITY, path).unwrap_or_default()
+    }
+
     pub fn new(env: Environment, manager: Arc<ForgeCommandManager>) -> Self {
         // Store file history in system config directory
-        let history_file = env.history_path();
-
-        let history = Box::new(
-            FileBackedHistory::with_file(HISTORY_CAPACITY, history_file).unwrap_or_default(),
-        );
+        let history = Box::new(Self::history(env.history_path()));
         let completion_menu = Box::new(
             ColumnarMenu::default()
                 .with_name(COMPLETION_MENU)
@@ -83,6 +89,7 @@ impl ForgeEditor {
         let editor = Reedline::create()
             .with_completer(Box::new(InputCompleter::new(env.cwd, manager)))
             .with_history(history)
+            .with_history_exclusion_prefix(Some(HISTORY_EXCLUSION_MARKER.to_string()))
             .with_hinter(Box::new(
                 DefaultHinter::default().with_style(Style::new().fg(Color::DarkGray)),
             ))
@@ -116,3 +123,79 @@ impl From<Signal> for ReadResult {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use reedline::{History, HistoryItem, SearchDirection, SearchQuery};
+
+    use super::*;
+
+    fn save(history: &mut FileBackedHistory, prompts: &[&str]) {
+        for prompt in prompts {
+            history
+                .save(HistoryItem::from_command_line(*prompt))
+                .unwrap();
+        }
+        history.sync().unwrap();
+    }
+
+    fn prompts(history: &FileBackedHistory) -> Vec<String> {
+        history
+            .search(SearchQuery::everything(SearchDirection::Forward, None))
+            .unwrap()
+            .into_iter()
+            .map(|item| item.command_line)
+            .collect()
+    }
+
+    #[test]
+    fn test_history_is_written_to_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".forge_history");
+        let mut fixture = ForgeEditor::history(path.clone());
+
+        save(
+            &mut fixture,
+            &["Explain the parser", "Fix the failing test"],
+        );
+
+        let actual = std::fs::read_to_string(path).unwrap();
+        assert!(actual.contains("Explain the parser"), "{actual}");
+        assert!(actual.contains("Fix the failing test"), "{actual}");
+    }
+
+    #[test]
+    fn test_history_is_reloaded_in_the_next_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".forge_history");
+        save(
+            &mut ForgeEditor::history(path.clone()),
+            &["Explain the parser"],
+        );
+
+        let mut fixture = ForgeEditor::history(path.clone());
+        save(&mut fixture, &["Fix the failing test"]);
+        let actual = prompts(&ForgeEditor::history(path));
+
+        let expected = vec!["Explain the parser", "Fix the failing test"];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_repeated_prompt_is_saved_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".forge_history");
+        save(
+            &mut ForgeEditor::history(path.clone()),
+            &["Run the tests", "Run the tests"],
+        );
+
+        let mut fixture = ForgeEditor::history(path.clone());
+        save(&mut fixture, &["Run the tests"]);
+        let actual = prompts(&ForgeEditor::history(path));
+
+        let expected = vec!["Run the tests"];
+        assert_eq!(actual, expected);
+    }
+}
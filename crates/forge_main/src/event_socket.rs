@@ -0,0 +1,158 @@
+use std::os::unix::fs::FileTypeExt;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use forge_api::ChatResponse;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+/// Events a client may fall behind by before it misses some
+const EVENT_BUFFER: usize = 1024;
+
+/// Streams the chat events as JSON lines to the clients connected to a Unix
+/// socket, for a separate process to render them. Clients receive the events
+/// published after they connected.
+pub struct EventSocket {
+    path: PathBuf,
+    events: broadcast::Sender<String>,
+    listener: JoinHandle<()>,
+}
+
+impl EventSocket {
+    /// Listens on the socket at the path, replacing a socket left behind by a
+    /// previous session. Fails rather than removing anything else found at the
+    /// path.
+    pub fn bind(path: &Path) -> anyhow::Result<Self> {
+        if let Ok(metadata) = std::fs::symlink_metadata(path) {
+            anyhow::ensure!(
+                metadata.file_type().is_socket(),
+                "Refusing to replace {}, it is not a socket",
+                path.display()
+            );
+            std::fs::remove_file(path)
+                .with_context(|| format!("Failed to remove the socket {}", path.display()))?;
+        }
+        let listener = UnixListener::bind(path)
+            .with_context(|| format!("Failed to listen on the socket {}", path.display()))?;
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+
+        let sender = events.clone();
+        let listener = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(forward(stream, sender.subscribe()));
+            }
+        });
+
+        Ok(Self { path: path.to_path_buf(), events, listener })
+    }
+
+    /// Sends the event to the connected clients
+    pub fn publish(&self, event: &ChatResponse) {
+        match serde_json::to_string(event) {
+            // Sending only fails when no client is connected
+            Ok(line) => {
+                let _ = self.events.send(line);
+            }
+            Err(error) => tracing::warn!(error = ?error, "Failed to serialize the event"),
+        }
+    }
+}
+
+impl Drop for EventSocket {
+    fn drop(&mut self) {
+        self.listener.abort();
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Writes the events to the client, one per line, until it disconnects
+async fn forward(mut stream: UnixStream, mut events: broadcast::Receiver<String>) {
+    loop {
+        let line = match events.recv().await {
+            Ok(line) => line,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                tracing::warn!(skipped, "Event socket client fell behind");
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+        if stream
+            .write_all(format!("{line}\n").as_bytes())
+            .await
+            .is_err()
+        {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use forge_api::{ToolCallFull, ToolName};
+    use pretty_assertions::assert_eq;
+    use serde_json::Value;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_events_are_streamed_as_ordered_json_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("forge.sock");
+        let fixture = EventSocket::bind(&path).unwrap();
+        let client = UnixStream::connect(&path).await.unwrap();
+        while fixture.events.receiver_count() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        fixture.publish(&ChatResponse::Text {
+            text: "Reading the file".to_string(),
+            is_complete: true,
+            is_md: false,
+        });
+        fixture.publish(&ChatResponse::ToolCallStart(ToolCallFull::new(
+            ToolName::new("forge_tool_fs_read"),
+        )));
+        fixture.publish(&ChatResponse::Reasoning { content: "Done".to_string() });
+
+        let mut lines = BufReader::new(client).lines();
+        let mut actual = Vec::new();
+        for _ in 0..3 {
+            let line = lines.next_line().await.unwrap().unwrap();
+            let event: Value = serde_json::from_str(&line).unwrap();
+            actual.push(event["type"].as_str().unwrap().to_string());
+        }
+
+        let expected = vec![
+            "text".to_string(),
+            "tool_call_start".to_string(),
+            "reasoning".to_string(),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_bind_refuses_to_replace_a_file_that_is_not_a_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("forge.sock");
+        std::fs::write(&path, "notes").unwrap();
+
+        let actual = EventSocket::bind(&path).is_err();
+
+        assert!(actual);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "notes");
+    }
+
+    #[tokio::test]
+    async fn test_bind_replaces_a_stale_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("forge.sock");
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+        let actual = EventSocket::bind(&path).is_ok();
+
+        assert!(actual);
+    }
+}
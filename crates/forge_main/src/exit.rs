@@ -0,0 +1,16 @@
+use std::fmt;
+
+/// Ends the session with the given process exit status. It is returned as an
+/// error rather than exiting in place, so that it unwinds through `main` and
+/// the destructors on the way, e.g. lock files and the tracker guard, still
+/// run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Exit(pub u8);
+
+impl fmt::Display for Exit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Exiting with status {}", self.0)
+    }
+}
+
+impl std::error::Error for Exit {}
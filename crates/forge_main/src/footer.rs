@@ -0,0 +1,56 @@
+use forge_api::{ModelId, Usage};
+
+/// Content of the status footer shown below the spinner and above the prompt,
+/// with the model, the tokens used so far and what they cost when it is known
+pub fn format_footer(model: Option<&ModelId>, usage: &Usage) -> String {
+    let mut parts = Vec::new();
+    if let Some(model) = model {
+        parts.push(model.to_string());
+    }
+    parts.push(format!(
+        "{} tokens ({} prompt, {} completion, {} cached)",
+        usage.total_tokens, usage.prompt_tokens, usage.completion_tokens, usage.cached_tokens
+    ));
+    if let Some(cost) = usage.cost {
+        parts.push(format!("${cost:.4}"));
+    }
+    parts.join(" · ")
+}
+
+#[cfg(test)]
+mod tests {
+    use forge_api::TokenCount;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture_usage() -> Usage {
+        Usage {
+            prompt_tokens: TokenCount::Actual(1200),
+            completion_tokens: TokenCount::Actual(300),
+            total_tokens: TokenCount::Actual(1500),
+            cached_tokens: TokenCount::Actual(800),
+            cost: Some(0.01234),
+        }
+    }
+
+    #[test]
+    fn test_format_footer_with_model_and_cost() {
+        let fixture = fixture_usage();
+
+        let actual = format_footer(Some(&ModelId::new("anthropic/claude-sonnet-4")), &fixture);
+
+        let expected = "anthropic/claude-sonnet-4 · 1500 tokens (1200 prompt, 300 completion, 800 cached) · $0.0123";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_format_footer_without_model_or_cost() {
+        let fixture = Usage { cost: None, ..fixture_usage() };
+
+        let actual = format_footer(None, &fixture);
+
+        let expected = "1500 tokens (1200 prompt, 300 completion, 800 cached)";
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,93 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// Creates issues in a GitHub repository using a personal access token
+pub struct GithubIssues {
+    client: reqwest::Client,
+    token: String,
+    repository: String,
+}
+
+#[derive(Deserialize)]
+struct CreatedIssue {
+    html_url: String,
+}
+
+impl GithubIssues {
+    /// Creates a client for the repository given as `owner/name`
+    pub fn new(token: impl Into<String>, repository: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            token: token.into(),
+            repository: repository.into(),
+        }
+    }
+
+    /// Creates an issue and returns its URL
+    pub async fn create(&self, title: &str, body: &str) -> Result<String> {
+        let issue = self
+            .client
+            .post(format!("{GITHUB_API_URL}/repos/{}/issues", self.repository))
+            .bearer_auth(&self.token)
+            .header(reqwest::header::USER_AGENT, "forge")
+            .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+            .json(&serde_json::json!({ "title": title, "body": body }))
+            .send()
+            .await?
+            .error_for_status()
+            .with_context(|| format!("Failed to create issue in {}", self.repository))?
+            .json::<CreatedIssue>()
+            .await?;
+
+        Ok(issue.html_url)
+    }
+}
+
+/// Extracts the `owner/name` of a GitHub repository from a git remote URL,
+/// both the https and ssh forms are supported
+pub fn parse_repository(remote_url: &str) -> Option<String> {
+    let path = remote_url
+        .trim()
+        .strip_prefix("https://github.com/")
+        .or_else(|| remote_url.trim().strip_prefix("git@github.com:"))
+        .or_else(|| remote_url.trim().strip_prefix("ssh://git@github.com/"))?;
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+
+    match path.split_once('/') {
+        Some((owner, name)) if !owner.is_empty() && !name.is_empty() && !name.contains('/') => {
+            Some(path.to_string())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_repository() {
+        let fixture = [
+            "https://github.com/antinomyhq/forge.git",
+            "git@github.com:antinomyhq/forge.git\n",
+            "ssh://git@github.com/antinomyhq/forge",
+            "https://gitlab.com/antinomyhq/forge.git",
+            "https://github.com/antinomyhq",
+        ];
+
+        let actual = fixture.map(parse_repository);
+
+        let expected = [
+            Some("antinomyhq/forge".to_string()),
+            Some("antinomyhq/forge".to_string()),
+            Some("antinomyhq/forge".to_string()),
+            None,
+            None,
+        ];
+        assert_eq!(actual, expected);
+    }
+}
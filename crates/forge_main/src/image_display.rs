@@ -0,0 +1,151 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use base64::Engine;
+use forge_api::Image;
+
+/// Size of the chunks the kitty protocol expects the image to be sent in
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+/// Image protocols supported by some terminals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageProtocol {
+    Kitty,
+    Iterm,
+}
+
+impl ImageProtocol {
+    /// Detects the protocol supported by the terminal from its environment
+    /// variables
+    pub fn detect(var: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let is = |name: &str, value: &str| var(name).is_some_and(|found| found == value);
+        if var("KITTY_WINDOW_ID").is_some() || is("TERM", "xterm-kitty") {
+            Some(Self::Kitty)
+        } else if is("TERM_PROGRAM", "iTerm.app")
+            || is("TERM_PROGRAM", "WezTerm")
+            || is("LC_TERMINAL", "iTerm2")
+        {
+            Some(Self::Iterm)
+        } else {
+            None
+        }
+    }
+}
+
+/// How an image gets shown to the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageDisplay {
+    Inline(ImageProtocol),
+    /// Saved to a temporary file whose path is printed instead
+    File,
+}
+
+impl ImageDisplay {
+    /// Picks inline rendering when the terminal can display the image, kitty
+    /// only accepting PNG images to be sent as is
+    pub fn new(protocol: Option<ImageProtocol>, mime_type: &str) -> Self {
+        match protocol {
+            Some(ImageProtocol::Kitty) if mime_type != "image/png" => Self::File,
+            Some(protocol) => Self::Inline(protocol),
+            None => Self::File,
+        }
+    }
+}
+
+/// Returns what to print to show the image, either the escape sequence that
+/// renders it or the path of the file it was saved to
+pub fn render_image(image: &Image, display: ImageDisplay) -> anyhow::Result<String> {
+    let data = image
+        .url()
+        .split_once(";base64,")
+        .map(|(_, data)| data)
+        .context("Only base64 encoded images can be displayed")?;
+
+    match display {
+        ImageDisplay::Inline(ImageProtocol::Kitty) => {
+            let chunks = data.as_bytes().chunks(KITTY_CHUNK_SIZE).collect::<Vec<_>>();
+            let last = chunks.len().saturating_sub(1);
+            Ok(chunks
+                .iter()
+                .enumerate()
+                .map(|(index, chunk)| {
+                    let more = u8::from(index != last);
+                    let chunk = String::from_utf8_lossy(chunk);
+                    if index == 0 {
+                        format!("\x1b_Gf=100,a=T,m={more};{chunk}\x1b\\")
+                    } else {
+                        format!("\x1b_Gm={more};{chunk}\x1b\\")
+                    }
+                })
+                .collect())
+        }
+        ImageDisplay::Inline(ImageProtocol::Iterm) => {
+            Ok(format!("\x1b]1337;File=inline=1:{data}\x07"))
+        }
+        ImageDisplay::File => {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(data)
+                .context("Failed to decode image")?;
+            let path = temp_path(image.mime_type());
+            std::fs::write(&path, bytes)
+                .with_context(|| format!("Failed to save image to {}", path.display()))?;
+            Ok(format!("Image saved to {}", path.display()))
+        }
+    }
+}
+
+fn temp_path(mime_type: &str) -> PathBuf {
+    let extension = mime_type
+        .strip_prefix("image/")
+        .map(|subtype| subtype.split('+').next().unwrap_or(subtype))
+        .unwrap_or("bin");
+    let name = format!(
+        "forge-image-{}.{extension}",
+        chrono::Local::now().format("%Y%m%d%H%M%S%3f")
+    );
+    std::env::temp_dir().join(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn detect(vars: &[(&str, &str)]) -> Option<ImageProtocol> {
+        let vars = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect::<HashMap<_, _>>();
+        ImageProtocol::detect(|name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_image_display_depends_on_terminal_and_format() {
+        let fixture = [
+            (detect(&[("TERM", "xterm-kitty")]), "image/png"),
+            (detect(&[("KITTY_WINDOW_ID", "1")]), "image/jpeg"),
+            (detect(&[("TERM_PROGRAM", "iTerm.app")]), "image/jpeg"),
+            (detect(&[("LC_TERMINAL", "iTerm2")]), "image/png"),
+            (detect(&[("TERM", "xterm-256color")]), "image/png"),
+            (detect(&[]), "image/png"),
+        ];
+
+        let actual = fixture
+            .into_iter()
+            .map(|(protocol, mime_type)| ImageDisplay::new(protocol, mime_type))
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            ImageDisplay::Inline(ImageProtocol::Kitty),
+            ImageDisplay::File,
+            ImageDisplay::Inline(ImageProtocol::Iterm),
+            ImageDisplay::Inline(ImageProtocol::Iterm),
+            ImageDisplay::File,
+            ImageDisplay::File,
+        ];
+        assert_eq!(actual, expected);
+    }
+}
@@ -3,6 +3,7 @@ use std::path::{Path, PathBuf};
 
 use colored::Colorize;
 use forge_api::{Environment, LoginInfo};
+use forge_domain::{Agent, RulesLayer};
 use forge_tracker::VERSION;
 
 use crate::model::ForgeCommandManager;
@@ -44,6 +45,23 @@ impl Info {
         self
     }
 
+    /// Lists the rules layers in the order of precedence, with the file each
+    /// one is read from
+    pub fn add_rules(
+        self,
+        home: &Option<PathBuf>,
+        layers: impl IntoIterator<Item = (RulesLayer, Option<PathBuf>)>,
+    ) -> Self {
+        layers
+            .into_iter()
+            .fold(self.add_title("Rules"), |info, (layer, path)| {
+                let value = path.map_or("(not found)".to_string(), |path| {
+                    format_path_zsh_style(home, &path)
+                });
+                info.add_key_value(layer, value)
+            })
+    }
+
     pub fn extend(mut self, other: Info) -> Self {
         self.sections.extend(other.sections);
         self
@@ -167,6 +185,76 @@ fn get_git_branch() -> Option<String> {
     }
 }
 
+/// Renders the resolved configuration of an agent, with its model and the
+/// tools it's allowed to use
+impl From<&Agent> for Info {
+    fn from(agent: &Agent) -> Self {
+        let mut info = Info::new()
+            .add_title("Agent")
+            .add_key_value("ID", &agent.id);
+        let settings = [
+            ("Title", agent.title.clone()),
+            ("Model", agent.model.as_ref().map(ToString::to_string)),
+            (
+                "Temperature",
+                agent.temperature.map(|value| value.to_string()),
+            ),
+            ("Top P", agent.top_p.map(|value| value.to_string())),
+            ("Top K", agent.top_k.map(|value| value.to_string())),
+            (
+                "Max Tokens",
+                agent.max_tokens.map(|value| value.to_string()),
+            ),
+            ("Max Turns", agent.max_turns.map(|value| value.to_string())),
+            (
+                "Tool Supported",
+                agent.tool_supported.map(|value| value.to_string()),
+            ),
+            (
+                "Concurrent Tool Calls",
+                agent.concurrent_tool_calls.map(|value| value.to_string()),
+            ),
+        ];
+        for (key, value) in settings {
+            if let Some(value) = value {
+                info = info.add_key_value(key, value);
+            }
+        }
+
+        info = info.add_title("Tools");
+        for tool in agent.tools.iter().flatten() {
+            info = info.add_key(tool);
+        }
+
+        if let Some(compact) = &agent.compact {
+            info = info
+                .add_title("Compaction")
+                .add_key_value("Model", &compact.model)
+                .add_key_value("Retention Window", compact.retention_window)
+                .add_key_value("Eviction Window", compact.eviction_window);
+            let thresholds = [
+                ("Token Threshold", compact.token_threshold),
+                ("Turn Threshold", compact.turn_threshold),
+                ("Message Threshold", compact.message_threshold),
+                ("Max Tokens", compact.max_tokens),
+            ];
+            for (key, value) in thresholds {
+                if let Some(value) = value {
+                    info = info.add_key_value(key, value);
+                }
+            }
+        }
+
+        if let Some(system_prompt) = &agent.system_prompt {
+            info = info
+                .add_title("System Prompt")
+                .add_key(&system_prompt.template);
+        }
+
+        info
+    }
+}
+
 /// Create an info instance for available commands from a ForgeCommandManager
 impl From<&ForgeCommandManager> for Info {
     fn from(command_manager: &ForgeCommandManager) -> Self {
@@ -210,11 +298,40 @@ fn truncate_key(key: &str) -> String {
 
 #[cfg(test)]
 mod tests {
+    use std::path::PathBuf;
+
     use forge_api::LoginInfo;
+    use forge_domain::{Agent, ModelId, RulesLayer, ToolName};
     use pretty_assertions::assert_eq;
 
     use crate::info::Info;
 
+    #[test]
+    fn test_rules_info_reports_missing_layers() {
+        let home = Some(PathBuf::from("/home/user"));
+        let fixture = [
+            (
+                RulesLayer::Global,
+                Some(PathBuf::from("/home/user/forge/rules.md")),
+            ),
+            (
+                RulesLayer::Project,
+                Some(PathBuf::from("/work/app/AGENTS.md")),
+            ),
+            (RulesLayer::Local, None),
+        ];
+
+        let actual = Info::new().add_rules(&home, fixture);
+
+        let expected = Info::new()
+            .add_title("Rules")
+            .add_key_value("Global", "~/forge/rules.md")
+            .add_key_value("Project", "/work/app/AGENTS.md")
+            .add_key_value("Local", "(not found)");
+
+        assert_eq!(actual.sections, expected.sections);
+    }
+
     #[test]
     fn test_login_info_display() {
         let fixture = LoginInfo {
@@ -256,4 +373,26 @@ mod tests {
 
         assert_eq!(actual.sections, expected.sections);
     }
+
+    #[test]
+    fn test_agent_info_lists_allowed_tools_and_model() {
+        let fixture = Agent::new("forge")
+            .model(ModelId::new("claude-sonnet-4"))
+            .tools(vec![
+                ToolName::new("forge_tool_fs_read"),
+                ToolName::new("forge_tool_attempt_completion"),
+            ]);
+
+        let actual = Info::from(&fixture);
+
+        let expected = Info::new()
+            .add_title("Agent")
+            .add_key_value("ID", "forge")
+            .add_key_value("Model", "claude-sonnet-4")
+            .add_title("Tools")
+            .add_key("forge_tool_fs_read")
+            .add_key("forge_tool_attempt_completion");
+
+        assert_eq!(actual.sections, expected.sections);
+    }
 }
@@ -1,11 +1,26 @@
+mod attach_dir;
+mod auto_continue;
 mod banner;
+mod bench;
 mod cli;
 mod completer;
+mod context_view;
+mod doctor;
 mod editor;
+#[cfg(unix)]
+mod event_socket;
+mod exit;
+mod footer;
+mod github;
+mod image_display;
 mod info;
 mod input;
+mod mention;
 mod model;
+mod pick_files;
 mod prompt;
+mod recent;
+mod regenerate;
 mod select;
 mod state;
 mod tools_display;
@@ -13,7 +28,7 @@ pub mod tracker;
 mod ui;
 mod update;
 
-pub use cli::Cli;
+pub use cli::{Cli, TopLevelCommand};
 use lazy_static::lazy_static;
 pub use ui::UI;
 
@@ -1,14 +1,15 @@
 use std::panic;
 use std::path::PathBuf;
+use std::process::ExitCode;
 
 use anyhow::Result;
 use clap::Parser;
 use forge_api::ForgeAPI;
 use forge_display::TitleFormat;
-use forge_main::{Cli, UI, tracker};
+use forge_main::{Cli, TopLevelCommand, UI, tracker};
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     // Set up panic hook for better error display
     panic::set_hook(Box::new(|panic_info| {
         let message = if let Some(s) = panic_info.payload().downcast_ref::<&str>() {
@@ -39,12 +40,22 @@ async fn main() -> Result<()> {
 
     // Initialize the ForgeAPI with the restricted mode if specified
     let restricted = cli.restricted;
+    let shell = cli.shell.clone();
+    let remote = cli.remote.clone();
     let neo_ui = cli.neo_ui;
     if neo_ui {
-        return forge_main_neo::main_neo(cwd).await;
+        return forge_main_neo::main_neo(cwd)
+            .await
+            .map(|_| ExitCode::SUCCESS);
     }
-    let mut ui = UI::init(cli, move || ForgeAPI::init(restricted, cwd.clone()))?;
-    ui.run().await;
-
-    Ok(())
+    // Stdout carries the protocol, nothing else may be printed to it
+    if let Some(TopLevelCommand::ServeMcp) = cli.subcommands {
+        return ForgeAPI::serve_mcp(restricted, shell, cwd)
+            .await
+            .map(|_| ExitCode::SUCCESS);
+    }
+    let mut ui = UI::init(cli, move || {
+        ForgeAPI::init(restricted, shell.clone(), cwd.clone(), remote.clone())
+    })?;
+    Ok(ui.run().await)
 }
@@ -0,0 +1,137 @@
+use std::path::{Component, Path};
+
+/// Characters that commonly trail a mention in prose and are not part of the
+/// referenced path, e.g. "look at @src/main.rs."
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')', '"', '\''];
+
+/// Result of resolving `@path` mentions in a user message
+#[derive(Debug, PartialEq)]
+pub struct Mentions {
+    /// The message with every resolved mention rewritten as `@[path]`
+    pub content: String,
+    /// Mentions that didn't point to an attachable file and were left as-is
+    pub unresolved: Vec<String>,
+}
+
+/// Rewrites bare `@path` mentions into the `@[path]` attachment syntax so
+/// that the referenced files get attached to the dispatched event. Only
+/// mentions of files inside `cwd` accepted by `is_attachable` are rewritten.
+pub fn resolve_mentions(text: &str, cwd: &Path, is_attachable: impl Fn(&Path) -> bool) -> Mentions {
+    let mut content = String::with_capacity(text.len());
+    let mut unresolved = Vec::new();
+    let mut rest = text;
+
+    while let Some(index) = rest.find('@') {
+        content.push_str(&rest[..index]);
+        let after = &rest[index + 1..];
+
+        let is_at_boundary = content.chars().last().is_none_or(char::is_whitespace);
+        let token = &after[..after.find(char::is_whitespace).unwrap_or(after.len())];
+        let mention = token.trim_end_matches(TRAILING_PUNCTUATION);
+
+        // Leave emails, explicit `@[path]` attachments and lone `@` untouched
+        if !is_at_boundary || mention.is_empty() || mention.starts_with('[') {
+            content.push('@');
+            rest = after;
+            continue;
+        }
+
+        let path = Path::new(mention);
+        if is_within(cwd, path) && is_attachable(&cwd.join(path)) {
+            content.push_str(&format!("@[{mention}]"));
+        } else {
+            content.push('@');
+            content.push_str(mention);
+            unresolved.push(mention.to_string());
+        }
+        rest = &after[mention.len()..];
+    }
+
+    content.push_str(rest);
+    Mentions { content, unresolved }
+}
+
+/// Checks that the path can't escape the working directory
+fn is_within(cwd: &Path, path: &Path) -> bool {
+    !path
+        .components()
+        .any(|component| matches!(component, Component::ParentDir))
+        && (path.is_relative() || path.starts_with(cwd))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use forge_domain::Attachment;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture_cwd() -> PathBuf {
+        PathBuf::from("/home/user/project")
+    }
+
+    fn fixture_is_attachable(path: &Path) -> bool {
+        [
+            "/home/user/project/src/main.rs",
+            "/home/user/project/Cargo.toml",
+            "/home/user/secret.txt",
+        ]
+        .iter()
+        .any(|file| Path::new(file) == path)
+    }
+
+    #[test]
+    fn test_two_mentions_produce_two_attachments() {
+        let fixture = "look at @src/main.rs and @Cargo.toml.";
+
+        let actual = resolve_mentions(fixture, &fixture_cwd(), fixture_is_attachable);
+
+        let expected = Mentions {
+            content: "look at @[src/main.rs] and @[Cargo.toml].".to_string(),
+            unresolved: vec![],
+        };
+        assert_eq!(actual, expected);
+        assert_eq!(Attachment::parse_all(&actual.content).len(), 2);
+    }
+
+    #[test]
+    fn test_unresolved_mentions_are_left_as_text() {
+        let fixture = "ask @alice about @src/missing.rs";
+
+        let actual = resolve_mentions(fixture, &fixture_cwd(), fixture_is_attachable);
+
+        let expected = Mentions {
+            content: fixture.to_string(),
+            unresolved: vec!["alice".to_string(), "src/missing.rs".to_string()],
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_mentions_outside_cwd_are_not_attached() {
+        let fixture = "read @../secret.txt and @/home/user/secret.txt";
+
+        let actual = resolve_mentions(fixture, &fixture_cwd(), fixture_is_attachable);
+
+        let expected = Mentions {
+            content: fixture.to_string(),
+            unresolved: vec![
+                "../secret.txt".to_string(),
+                "/home/user/secret.txt".to_string(),
+            ],
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_emails_and_explicit_attachments_are_ignored() {
+        let fixture = "mail user@example.com about @[src/main.rs]";
+
+        let actual = resolve_mentions(fixture, &fixture_cwd(), fixture_is_attachable);
+
+        let expected = Mentions { content: fixture.to_string(), unresolved: vec![] };
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,6 +1,6 @@
 use std::sync::{Arc, Mutex};
 
-use forge_api::{Model, Workflow};
+use forge_api::{Model, ToolName, Workflow};
 use strum::{EnumProperty, IntoEnumIterator};
 use strum_macros::{EnumIter, EnumProperty};
 
@@ -167,9 +167,11 @@ impl ForgeCommandManager {
         match command {
             "/compact" => Ok(Command::Compact),
             "/new" => Ok(Command::New),
+            "/clear" => Ok(Command::Clear),
             "/info" => Ok(Command::Info),
             "/exit" => Ok(Command::Exit),
             "/update" => Ok(Command::Update),
+            "/updates" => Ok(Command::Updates),
             "/dump" => {
                 if !parameters.is_empty() && parameters[0] == "html" {
                     Ok(Command::Dump(Some("html".to_string())))
@@ -182,7 +184,135 @@ impl ForgeCommandManager {
             "/help" => Ok(Command::Help),
             "/model" => Ok(Command::Model),
             "/tools" => Ok(Command::Tools),
+            "/tool-usage" => Ok(Command::ToolUsage),
+            "/cost" => Ok(Command::Cost),
+            "/trim" => Ok(Command::Trim),
+            "/tasks-export" => Ok(Command::TasksExport(
+                parameters.first().is_some_and(|target| *target == "github"),
+            )),
+            "/verbose" => match parameters.first().copied() {
+                None => Ok(Command::Verbose(None)),
+                Some("on") => Ok(Command::Verbose(Some(true))),
+                Some("off") => Ok(Command::Verbose(Some(false))),
+                Some(value) => Err(anyhow::anyhow!(
+                    "Invalid value '{value}' for /verbose, expected 'on' or 'off'"
+                )),
+            },
+            "/footer" => match parameters.first().copied() {
+                None => Ok(Command::Footer(None)),
+                Some("on") => Ok(Command::Footer(Some(true))),
+                Some("off") => Ok(Command::Footer(Some(false))),
+                Some(value) => Err(anyhow::anyhow!(
+                    "Invalid value '{value}' for /footer, expected 'on' or 'off'"
+                )),
+            },
+            "/recent" => match parameters.first() {
+                None => Ok(Command::Recent(None)),
+                Some(value) => value
+                    .parse()
+                    .map(|count| Command::Recent(Some(count)))
+                    .map_err(|_| {
+                        anyhow::anyhow!("Invalid count '{value}' for /recent, expected a number")
+                    }),
+            },
+            "/raw" => Ok(Command::Raw),
+            "/logs" => Ok(Command::Logs),
+            "/read" => Ok(Command::Read),
+            "/pin" => Ok(Command::Pin),
+            "/context" => match parameters.first().copied() {
+                None => Ok(Command::Context(false)),
+                Some("--edit") => Ok(Command::Context(true)),
+                Some(value) => Err(anyhow::anyhow!(
+                    "Invalid option '{value}' for /context, expected '--edit'"
+                )),
+            },
+            "/rules" => match parameters.first().copied() {
+                None => Ok(Command::Rules(false)),
+                Some("edit") => Ok(Command::Rules(true)),
+                Some(value) => Err(anyhow::anyhow!(
+                    "Invalid option '{value}' for /rules, expected 'edit'"
+                )),
+            },
+            "/tab" => match parameters.first().copied() {
+                Some("new") => Ok(Command::Tab(TabAction::New)),
+                Some("next") => Ok(Command::Tab(TabAction::Next)),
+                Some("prev") => Ok(Command::Tab(TabAction::Prev)),
+                Some("close") => Ok(Command::Tab(TabAction::Close)),
+                Some(value) => Err(anyhow::anyhow!(
+                    "Invalid value '{value}' for /tab, expected 'new', 'next', 'prev' or 'close'"
+                )),
+                None => Err(anyhow::anyhow!(
+                    "Missing action for /tab, expected 'new', 'next', 'prev' or 'close'"
+                )),
+            },
+            "/plan-first" => match parameters.first().copied() {
+                None => Ok(Command::PlanFirst(None)),
+                Some("on") => Ok(Command::PlanFirst(Some(true))),
+                Some("off") => Ok(Command::PlanFirst(Some(false))),
+                Some(value) => Err(anyhow::anyhow!(
+                    "Invalid value '{value}' for /plan-first, expected 'on' or 'off'"
+                )),
+            },
+            "/maxtokens" => match parameters.first() {
+                None => Ok(Command::MaxTokens(None)),
+                Some(value) => value
+                    .parse::<u32>()
+                    .map(|value| Command::MaxTokens(Some(value)))
+                    .map_err(|_| anyhow::anyhow!("Invalid token count '{value}' for /maxtokens")),
+            },
+            "/continue" => {
+                let message = parameters.join(" ");
+                if message.trim().is_empty() {
+                    Ok(Command::Continue(None))
+                } else {
+                    Ok(Command::Continue(Some(message)))
+                }
+            }
+            "/regenerate" => {
+                let instruction = parameters.join(" ");
+                if instruction.trim().is_empty() {
+                    Ok(Command::Regenerate(None))
+                } else {
+                    Ok(Command::Regenerate(Some(instruction)))
+                }
+            }
+            "/run-tool" => {
+                let Some(name) = parameters.first() else {
+                    return Err(anyhow::anyhow!(
+                        "Missing tool name for /run-tool, expected /run-tool <name> <json-args>"
+                    ));
+                };
+                // The arguments are taken as typed, so whitespace inside of strings is kept
+                let arguments = input
+                    .trim()
+                    .strip_prefix(command)
+                    .and_then(|rest| rest.trim_start().strip_prefix(name))
+                    .unwrap_or_default()
+                    .trim();
+                let arguments = if arguments.is_empty() {
+                    serde_json::Value::Object(Default::default())
+                } else {
+                    serde_json::from_str(arguments).map_err(|error| {
+                        anyhow::anyhow!("Invalid JSON arguments for /run-tool: {error}")
+                    })?
+                };
+                Ok(Command::RunTool(name.to_string(), arguments))
+            }
+            "/disable-tool" | "/enable-tool" => {
+                let Some(name) = parameters.first() else {
+                    return Err(anyhow::anyhow!(
+                        "Missing tool name for {command}, expected {command} <name>"
+                    ));
+                };
+                let name = ToolName::new(*name);
+                if command == "/disable-tool" {
+                    Ok(Command::DisableTool(name))
+                } else {
+                    Ok(Command::EnableTool(name))
+                }
+            }
             "/agent" => Ok(Command::Agent),
+            "/agent-info" => Ok(Command::AgentInfo),
             "/login" => Ok(Command::Login),
             "/logout" => Ok(Command::Logout),
             text => {
@@ -207,6 +337,18 @@ impl ForgeCommandManager {
     }
 }
 
+/// What the '/tab' command does with the conversation tabs
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TabAction {
+    /// Opens a tab with a fresh conversation
+    #[default]
+    New,
+    Next,
+    Prev,
+    /// Closes the active tab
+    Close,
+}
+
 /// Represents user input types in the chat application.
 ///
 /// This enum encapsulates all forms of input including:
@@ -223,6 +365,11 @@ pub enum Command {
     /// This can be triggered with the '/new' command.
     #[strum(props(usage = "Start a new conversation"))]
     New,
+    /// Wipe the dialogue of the conversation while keeping the agent, model,
+    /// variables and pinned tool results.
+    /// This can be triggered with the '/clear' command.
+    #[strum(props(usage = "Clear the conversation but keep its setup and pins"))]
+    Clear,
     /// A regular text message from the user to be processed by the chat system.
     /// Any input that doesn't start with '/' is treated as a message.
     #[strum(props(usage = "Send a regular message"))]
@@ -237,6 +384,11 @@ pub enum Command {
     /// Updates the forge version
     #[strum(props(usage = "Updates to the latest compatible version of forge"))]
     Update,
+    /// Lists the available updates of forge and its update settings to review
+    /// and apply them.
+    /// This can be triggered with the '/updates' command.
+    #[strum(props(usage = "Review and apply forge updates and update settings"))]
+    Updates,
     /// Switch to "forge" agent.
     /// This can be triggered with the '/forge' command.
     #[strum(props(usage = "Enable implementation mode with code changes"))]
@@ -260,6 +412,123 @@ pub enum Command {
     /// This can be triggered with the '/tools' command.
     #[strum(props(usage = "List all available tools with their descriptions and schema"))]
     Tools,
+    /// Renders the tool usage instructions injected into the system prompt for
+    /// models without native tool support.
+    /// This can be triggered with the '/tool-usage' command.
+    #[strum(props(usage = "Show the tool usage instructions sent to models without tool support"))]
+    ToolUsage,
+    /// Estimates the input cost of the next request from the current context.
+    /// This can be triggered with the '/cost' command.
+    #[strum(props(usage = "Estimate the input cost of the next request"))]
+    Cost,
+    /// Collapses tool outputs in the current context while keeping the tool
+    /// calls. This can be triggered with the '/trim' command.
+    #[strum(props(usage = "Remove tool outputs from the context to free up space"))]
+    Trim,
+    /// Resumes an interrupted turn from the saved context, optionally with an
+    /// extra instruction. This can be triggered with the '/continue' command.
+    #[strum(props(
+        usage = "Resume the interrupted task, optionally with an extra instruction (use /continue <message>)"
+    ))]
+    Continue(Option<String>),
+    /// Sends the last task again, optionally with an instruction on how the
+    /// new answer should differ. This can be triggered with the '/regenerate'
+    /// command.
+    #[strum(props(
+        usage = "Ask for the last answer again, optionally with a tweak (use /regenerate <instruction>)"
+    ))]
+    Regenerate(Option<String>),
+    /// Writes the task list to a markdown checklist and, with `github`, opens
+    /// an issue for every task that isn't done yet.
+    /// This can be triggered with the '/tasks-export' command.
+    #[strum(props(
+        usage = "Export the task list as a markdown checklist (use /tasks-export github to also create GitHub issues)"
+    ))]
+    TasksExport(bool),
+    /// Turns verbose output on or off for the rest of the session, flipping it
+    /// when no value is given.
+    /// This can be triggered with the '/verbose' command.
+    #[strum(props(usage = "Toggle showing tool outputs (use /verbose on or /verbose off)"))]
+    Verbose(Option<bool>),
+    /// Shows or hides the status footer with the model, tokens and cost of
+    /// the conversation, flipping it when no value is given.
+    /// This can be triggered with the '/footer' command.
+    #[strum(props(usage = "Toggle the usage footer (use /footer on or /footer off)"))]
+    Footer(Option<bool>),
+    /// Turns plan-first mode on or off for the current conversation, flipping
+    /// it when no value is given. While it's on, changes wait until the plan
+    /// is approved. This can be triggered with the '/plan-first' command.
+    #[strum(props(
+        usage = "Approve the plan before any change is made (use /plan-first on or /plan-first off)"
+    ))]
+    PlanFirst(Option<bool>),
+    /// Lists the most recently modified files in the workspace and offers to
+    /// attach some of them to the next message.
+    /// This can be triggered with the '/recent' command.
+    #[strum(props(usage = "List recently modified files to attach (use /recent <count>)"))]
+    Recent(Option<usize>),
+    /// Opens a picker of the workspace files and reads the picked ones into
+    /// the context in a single read.
+    /// This can be triggered with the '/read' command.
+    #[strum(props(usage = "Pick files of the workspace to read into the context"))]
+    Read,
+    /// Prints the raw output of the last tool call, before it was truncated
+    /// and formatted for the model. Only available when
+    /// `FORGE_DEBUG_RAW_OUTPUT` is set. This can be triggered with the '/raw'
+    /// command.
+    #[strum(props(usage = "Show the unformatted output of the last tool call"))]
+    Raw,
+    /// Prints the path of the log file followed by its last lines.
+    /// This can be triggered with the '/logs' command.
+    #[strum(props(usage = "Show where logs are written and their last lines"))]
+    Logs,
+    /// Pins the output of the last tool call so that compaction keeps it as
+    /// is. This can be triggered with the '/pin' command.
+    #[strum(props(usage = "Keep the output of the last tool call out of compaction"))]
+    Pin,
+    /// Shows the messages and tools that will be sent with the next request,
+    /// along with their estimated token counts. With `--edit`, selected
+    /// messages can be removed before the next request is sent.
+    /// This can be triggered with the '/context' command.
+    #[strum(props(
+        usage = "Show the context of the next request (use /context --edit to remove messages)"
+    ))]
+    Context(bool),
+    /// Shows the global, project and local rules that are added to the
+    /// agent's rules. With `edit`, the project rules file is opened in
+    /// `$EDITOR` and the edited rules apply from the next message.
+    /// This can be triggered with the '/rules' command.
+    #[strum(props(
+        usage = "Show the rules files (use /rules edit to edit the project rules in $EDITOR)"
+    ))]
+    Rules(bool),
+    /// Opens, switches between or closes the conversations kept side by side
+    /// in the session. This can be triggered with the '/tab' command.
+    #[strum(props(usage = "Manage conversation tabs (use /tab new, next, prev or close)"))]
+    Tab(TabAction),
+    /// Limits the number of tokens the model may generate per response for the
+    /// rest of the conversation, without a value the limits of the workflow
+    /// apply again. This can be triggered with the '/maxtokens' command.
+    #[strum(props(
+        usage = "Limit the tokens generated per response (use /maxtokens <count>, or /maxtokens to reset)"
+    ))]
+    MaxTokens(Option<u32>),
+    /// Runs a built-in tool directly with the given JSON arguments, without
+    /// the model, and prints its output.
+    /// This can be triggered with the '/run-tool' command.
+    #[strum(props(usage = "Run a tool without the model (use /run-tool <name> <json-args>)"))]
+    RunTool(String, serde_json::Value),
+    /// Stops the agent from using the tool for the rest of the session, the
+    /// tool is no longer offered to the model.
+    /// This can be triggered with the '/disable-tool' command.
+    #[strum(props(
+        usage = "Stop the agent from using a tool for this session (use /disable-tool <name>)"
+    ))]
+    DisableTool(ToolName),
+    /// Lets the agent use a disabled tool again.
+    /// This can be triggered with the '/enable-tool' command.
+    #[strum(props(usage = "Let the agent use a disabled tool again (use /enable-tool <name>)"))]
+    EnableTool(ToolName),
     /// Handles custom command defined in workflow file.
     Custom(PartialEvent),
     /// Executes a native shell command.
@@ -273,6 +542,12 @@ pub enum Command {
     ))]
     Agent,
 
+    /// Shows the resolved configuration of the active agent, with its model,
+    /// the tools it's allowed to use, its compaction settings and its system
+    /// prompt. This can be triggered with the '/agent-info' command.
+    #[strum(props(usage = "Show the configuration of the active agent"))]
+    AgentInfo,
+
     /// Log into the default provider.
     #[strum(props(usage = "Log into the Forge provider"))]
     Login,
@@ -287,8 +562,10 @@ impl Command {
         match self {
             Command::Compact => "/compact",
             Command::New => "/new",
+            Command::Clear => "/clear",
             Command::Message(_) => "/message",
             Command::Update => "/update",
+            Command::Updates => "/updates",
             Command::Info => "/info",
             Command::Exit => "/exit",
             Command::Forge => "/forge",
@@ -297,9 +574,31 @@ impl Command {
             Command::Dump(_) => "/dump",
             Command::Model => "/model",
             Command::Tools => "/tools",
+            Command::ToolUsage => "/tool-usage",
+            Command::Cost => "/cost",
+            Command::Trim => "/trim",
+            Command::RunTool(..) => "/run-tool",
+            Command::Continue(_) => "/continue",
+            Command::Regenerate(_) => "/regenerate",
+            Command::TasksExport(_) => "/tasks-export",
+            Command::Verbose(_) => "/verbose",
+            Command::Footer(_) => "/footer",
+            Command::PlanFirst(_) => "/plan-first",
+            Command::Recent(_) => "/recent",
+            Command::Raw => "/raw",
+            Command::Logs => "/logs",
+            Command::Read => "/read",
+            Command::Pin => "/pin",
+            Command::Context(_) => "/context",
+            Command::Rules(_) => "/rules",
+            Command::Tab(_) => "/tab",
+            Command::MaxTokens(_) => "/maxtokens",
+            Command::DisableTool(_) => "/disable-tool",
+            Command::EnableTool(_) => "/enable-tool",
             Command::Custom(event) => &event.name,
             Command::Shell(_) => "!shell",
             Command::Agent => "/agent",
+            Command::AgentInfo => "/agent-info",
             Command::Login => "/login",
             Command::Logout => "/logout",
         }
@@ -510,4 +809,213 @@ mod tests {
             "Shell command should not be in default commands"
         );
     }
+
+    #[test]
+    fn test_parse_continue_command() {
+        let fixture = ForgeCommandManager::default();
+
+        let actual = fixture.parse("/continue").unwrap();
+
+        let expected = Command::Continue(None);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_continue_command_with_message() {
+        let fixture = ForgeCommandManager::default();
+
+        let actual = fixture.parse("/continue focus on the tests").unwrap();
+
+        let expected = Command::Continue(Some("focus on the tests".to_string()));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_regenerate_command_with_instruction() {
+        let fixture = ForgeCommandManager::default();
+
+        let actual = fixture.parse("/regenerate be more concise").unwrap();
+
+        let expected = Command::Regenerate(Some("be more concise".to_string()));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_verbose_command() {
+        let fixture = ForgeCommandManager::default();
+
+        let actual =
+            ["/verbose", "/verbose on", "/verbose off"].map(|input| fixture.parse(input).unwrap());
+
+        let expected = [
+            Command::Verbose(None),
+            Command::Verbose(Some(true)),
+            Command::Verbose(Some(false)),
+        ];
+        assert_eq!(actual, expected);
+        assert!(fixture.parse("/verbose loud").is_err());
+    }
+
+    #[test]
+    fn test_parse_recent_command() {
+        let fixture = ForgeCommandManager::default();
+
+        let actual = ["/recent", "/recent 20"].map(|input| fixture.parse(input).unwrap());
+
+        let expected = [Command::Recent(None), Command::Recent(Some(20))];
+        assert_eq!(actual, expected);
+        assert!(fixture.parse("/recent many").is_err());
+    }
+
+    #[test]
+    fn test_parse_raw_command() {
+        let fixture = ForgeCommandManager::default();
+
+        let actual = fixture.parse("/raw").unwrap();
+
+        assert_eq!(actual, Command::Raw);
+    }
+
+    #[test]
+    fn test_parse_updates_command() {
+        let fixture = ForgeCommandManager::default();
+
+        let actual = fixture.parse("/updates").unwrap();
+
+        let expected = Command::Updates;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_run_tool_command() {
+        let fixture = ForgeCommandManager::default();
+
+        let actual = fixture
+            .parse(r#"/run-tool forge_tool_fs_search {"path": ".", "regex": "fn  main"}"#)
+            .unwrap();
+
+        let expected = Command::RunTool(
+            "forge_tool_fs_search".to_string(),
+            serde_json::json!({ "path": ".", "regex": "fn  main" }),
+        );
+        assert_eq!(actual, expected);
+        assert!(
+            fixture
+                .parse("/run-tool forge_tool_fs_read {path}")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_pin_command() {
+        let fixture = ForgeCommandManager::default();
+
+        let actual = fixture.parse("/pin").unwrap();
+
+        assert_eq!(actual, Command::Pin);
+    }
+
+    #[test]
+    fn test_parse_context_command() {
+        let fixture = ForgeCommandManager::default();
+
+        let actual = [
+            fixture.parse("/context").unwrap(),
+            fixture.parse("/context --edit").unwrap(),
+        ];
+
+        assert_eq!(actual, [Command::Context(false), Command::Context(true)]);
+        assert!(fixture.parse("/context all").is_err());
+    }
+
+    #[test]
+    fn test_parse_disable_and_enable_tool_commands() {
+        let fixture = ForgeCommandManager::default();
+
+        let actual = [
+            fixture
+                .parse("/disable-tool forge_tool_process_shell")
+                .unwrap(),
+            fixture
+                .parse("/enable-tool forge_tool_process_shell")
+                .unwrap(),
+        ];
+
+        let name = ToolName::new("forge_tool_process_shell");
+        assert_eq!(
+            actual,
+            [
+                Command::DisableTool(name.clone()),
+                Command::EnableTool(name)
+            ]
+        );
+        assert!(fixture.parse("/disable-tool").is_err());
+    }
+
+    #[test]
+    fn test_parse_agent_info_command() {
+        let fixture = ForgeCommandManager::default();
+
+        let actual = fixture.parse("/agent-info").unwrap();
+
+        assert_eq!(actual, Command::AgentInfo);
+    }
+
+    #[test]
+    fn test_parse_rules_command() {
+        let fixture = ForgeCommandManager::default();
+
+        let actual = [
+            fixture.parse("/rules").unwrap(),
+            fixture.parse("/rules edit").unwrap(),
+        ];
+
+        assert_eq!(actual, [Command::Rules(false), Command::Rules(true)]);
+        assert!(fixture.parse("/rules reload").is_err());
+    }
+
+    #[test]
+    fn test_parse_tab_command() {
+        let fixture = ForgeCommandManager::default();
+
+        let actual = ["/tab new", "/tab next", "/tab prev", "/tab close"]
+            .map(|input| fixture.parse(input).unwrap());
+
+        let expected = [
+            Command::Tab(TabAction::New),
+            Command::Tab(TabAction::Next),
+            Command::Tab(TabAction::Prev),
+            Command::Tab(TabAction::Close),
+        ];
+        assert_eq!(actual, expected);
+        assert!(fixture.parse("/tab").is_err());
+        assert!(fixture.parse("/tab open").is_err());
+    }
+
+    #[test]
+    fn test_parse_plan_first_command() {
+        let fixture = ForgeCommandManager::default();
+
+        let actual = ["/plan-first", "/plan-first on", "/plan-first off"]
+            .map(|input| fixture.parse(input).unwrap());
+
+        let expected = [
+            Command::PlanFirst(None),
+            Command::PlanFirst(Some(true)),
+            Command::PlanFirst(Some(false)),
+        ];
+        assert_eq!(actual, expected);
+        assert!(fixture.parse("/plan-first maybe").is_err());
+    }
+
+    #[test]
+    fn test_parse_max_tokens_command() {
+        let fixture = ForgeCommandManager::default();
+
+        let actual = ["/maxtokens 4000", "/maxtokens"].map(|input| fixture.parse(input).unwrap());
+
+        let expected = [Command::MaxTokens(Some(4000)), Command::MaxTokens(None)];
+        assert_eq!(actual, expected);
+        assert!(fixture.parse("/maxtokens many").is_err());
+    }
 }
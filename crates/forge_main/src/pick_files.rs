@@ -0,0 +1,58 @@
+use std::path::Path;
+
+use anyhow::Result;
+
+/// Lists the files of the workspace to pick from, relative to `cwd` and
+/// sorted. Hidden files and the ones excluded by `.gitignore` or `.ignore`
+/// rules are skipped.
+pub async fn workspace_files(cwd: &Path) -> Result<Vec<String>> {
+    let files = forge_walker::Walker::max_all()
+        .cwd(cwd.to_path_buf())
+        .get()
+        .await?;
+
+    let mut files = files
+        .into_iter()
+        .filter(|file| !file.is_dir())
+        .map(|file| file.path)
+        .collect::<Vec<_>>();
+    files.sort();
+    Ok(files)
+}
+
+/// Absolute paths of the picked files to read, in the order they were picked
+/// and each only once
+pub fn read_request(cwd: &Path, picked: Vec<String>) -> Vec<String> {
+    let mut paths: Vec<String> = Vec::with_capacity(picked.len());
+    for file in picked {
+        let path = cwd.join(file).display().to_string();
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_read_request_resolves_picked_files_once() {
+        let fixture = vec![
+            "src/main.rs".to_string(),
+            "Cargo.toml".to_string(),
+            "src/main.rs".to_string(),
+        ];
+
+        let actual = read_request(Path::new("/home/user/project"), fixture);
+
+        let expected = vec![
+            "/home/user/project/src/main.rs".to_string(),
+            "/home/user/project/Cargo.toml".to_string(),
+        ];
+        assert_eq!(actual, expected);
+    }
+}
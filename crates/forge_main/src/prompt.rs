@@ -22,6 +22,8 @@ pub struct ForgePrompt {
     pub usage: Option<Usage>,
     pub agent_id: AgentId,
     pub model: Option<ModelId>,
+    /// Status line shown above the prompt while it waits for input
+    pub footer: Option<String>,
 }
 
 impl Prompt for ForgePrompt {
@@ -45,6 +47,10 @@ impl Prompt for ForgePrompt {
         // Use a string buffer to reduce allocations
         let mut result = String::with_capacity(64); // Pre-allocate a reasonable size
 
+        if let Some(footer) = self.footer.as_ref() {
+            writeln!(result, "{}", Style::new().dimmed().paint(footer)).unwrap();
+        }
+
         // Build the string step-by-step
         write!(
             result,
@@ -178,6 +184,7 @@ mod tests {
                 usage: None,
                 agent_id: AgentId::default(),
                 model: None,
+                footer: None,
             }
         }
     }
@@ -193,6 +200,19 @@ mod tests {
         assert!(actual.contains(RIGHT_CHEVRON));
     }
 
+    #[test]
+    fn test_render_prompt_left_with_footer() {
+        let mut prompt = ForgePrompt::default();
+        prompt.footer("test-model · 30 tokens".to_string());
+
+        let actual = prompt.render_prompt_left();
+
+        let (footer, rest) = actual.split_once('\n').unwrap();
+        assert!(footer.contains("test-model · 30 tokens"));
+        assert!(rest.contains("FORGE"));
+        assert!(rest.contains(RIGHT_CHEVRON));
+    }
+
     #[test]
     fn test_render_prompt_left_with_custom_prompt() {
         // Set $PROMPT environment variable temporarily for this test
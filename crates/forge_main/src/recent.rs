@@ -0,0 +1,126 @@
+use std::fmt::Display;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+
+/// Number of files listed when `/recent` is given no count
+pub const DEFAULT_RECENT_FILES: usize = 10;
+
+/// A file in the workspace along with when it was last modified
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecentFile {
+    /// Path relative to the workspace root
+    pub path: String,
+    pub modified: SystemTime,
+}
+
+impl Display for RecentFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let age = SystemTime::now()
+            .duration_since(self.modified)
+            .unwrap_or_default();
+        write!(f, "{} ({})", self.path, format_age(age))
+    }
+}
+
+/// Lists the `limit` most recently modified files in `cwd`, newest first.
+/// Hidden files and the ones excluded by `.gitignore` or `.ignore` rules are
+/// skipped.
+pub async fn recent_files(cwd: &Path, limit: usize) -> Result<Vec<RecentFile>> {
+    let files = forge_walker::Walker::max_all()
+        .cwd(cwd.to_path_buf())
+        .get()
+        .await?;
+
+    let mut files = files
+        .into_iter()
+        .filter(|file| !file.is_dir())
+        .filter_map(|file| {
+            let modified = std::fs::metadata(cwd.join(&file.path))
+                .and_then(|meta| meta.modified())
+                .ok()?;
+            Some(RecentFile { path: file.path, modified })
+        })
+        .collect::<Vec<_>>();
+
+    files.sort_by(|a, b| {
+        b.modified
+            .cmp(&a.modified)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    files.truncate(limit);
+    Ok(files)
+}
+
+fn format_age(age: Duration) -> String {
+    match age.as_secs() {
+        0..60 => "just now".to_string(),
+        secs @ 60..3600 => format!("{}m ago", secs / 60),
+        secs @ 3600..86400 => format!("{}h ago", secs / 3600),
+        secs => format!("{}d ago", secs / 86400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn write_file(dir: &Path, path: &str, minutes_ago: u64) {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let file = std::fs::File::create(&path).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(minutes_ago * 60))
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_recent_files_are_ordered_by_modification_time() {
+        let fixture = tempfile::tempdir().unwrap();
+        write_file(fixture.path(), "src/lib.rs", 30);
+        write_file(fixture.path(), "src/main.rs", 5);
+        write_file(fixture.path(), "README.md", 120);
+        write_file(fixture.path(), "Cargo.toml", 10);
+
+        let actual = recent_files(fixture.path(), 3)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|file| file.path)
+            .collect::<Vec<_>>();
+
+        let expected = vec!["src/main.rs", "Cargo.toml", "src/lib.rs"];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_recent_files_skip_ignored_files() {
+        let fixture = tempfile::tempdir().unwrap();
+        std::fs::write(fixture.path().join(".ignore"), "target/\n*.log\n").unwrap();
+        write_file(fixture.path(), "target/debug/build.rs", 1);
+        write_file(fixture.path(), "forge.log", 2);
+        write_file(fixture.path(), "src/main.rs", 5);
+
+        let actual = recent_files(fixture.path(), DEFAULT_RECENT_FILES)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|file| file.path)
+            .collect::<Vec<_>>();
+
+        let expected = vec!["src/main.rs"];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_format_age() {
+        let fixture = [10, 90, 7200, 172800].map(Duration::from_secs);
+
+        let actual = fixture.map(format_age);
+
+        let expected = ["just now", "1m ago", "2h ago", "2d ago"].map(String::from);
+        assert_eq!(actual, expected);
+    }
+}
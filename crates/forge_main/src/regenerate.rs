@@ -0,0 +1,107 @@
+use forge_api::Event;
+
+use crate::ui::{EVENT_USER_TASK_INIT, EVENT_USER_TASK_UPDATE};
+
+/// The last task the user sent, along with the instruction on how the new
+/// answer should differ from the previous one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regeneration {
+    pub task: String,
+    pub instruction: Option<String>,
+}
+
+impl Regeneration {
+    /// Returns the background the instruction is sent as. It's kept apart
+    /// from the task, so that regenerating again starts from the task as the
+    /// user sent it
+    pub fn context(&self) -> Option<String> {
+        self.instruction
+            .as_ref()
+            .map(|instruction| format!("Answer this again, this time: {instruction}"))
+    }
+}
+
+/// Returns the last task the user sent, with the instruction on how the new
+/// answer should differ from the previous one
+pub fn regenerate_content(events: &[Event], instruction: Option<&str>) -> Option<Regeneration> {
+    let task = events
+        .iter()
+        .rev()
+        .filter(|event| {
+            event.name.ends_with(&format!("/{EVENT_USER_TASK_INIT}"))
+                || event.name.ends_with(&format!("/{EVENT_USER_TASK_UPDATE}"))
+        })
+        .find_map(|event| {
+            event
+                .value
+                .as_ref()?
+                .as_str()
+                .filter(|value| !value.trim().is_empty())
+        })?;
+
+    Some(Regeneration {
+        task: task.to_string(),
+        instruction: instruction.map(str::to_string),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture_events() -> Vec<Event> {
+        vec![
+            Event::new("forge/user_task_init", Some("Explain the parser")),
+            Event::new("forge/user_task_update", Some("Now explain the lexer")),
+            // Resuming with /continue sends no content
+            Event::new("forge/user_task_update", None::<String>),
+            Event::new("forge/custom_event", Some("Not a task")),
+        ]
+    }
+
+    #[test]
+    fn test_regenerate_content_keeps_instruction_apart_from_last_task() {
+        let fixture = fixture_events();
+
+        let actual = regenerate_content(&fixture, Some("be more concise")).unwrap();
+
+        let expected = Regeneration {
+            task: "Now explain the lexer".to_string(),
+            instruction: Some("be more concise".to_string()),
+        };
+        assert_eq!(actual, expected);
+        assert_eq!(
+            actual.context(),
+            Some("Answer this again, this time: be more concise".to_string())
+        );
+    }
+
+    #[test]
+    fn test_regenerating_twice_starts_from_the_original_task() {
+        let mut fixture = fixture_events();
+        let first = regenerate_content(&fixture, Some("be more concise")).unwrap();
+        fixture.push(
+            Event::new("forge/user_task_update", Some(first.task.clone()))
+                .context(first.context().unwrap()),
+        );
+
+        let actual = regenerate_content(&fixture, Some("use an example")).unwrap();
+
+        let expected = Regeneration {
+            task: "Now explain the lexer".to_string(),
+            instruction: Some("use an example".to_string()),
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_regenerate_content_without_task() {
+        let fixture = vec![Event::new("forge/custom_event", Some("Not a task"))];
+
+        let actual = regenerate_content(&fixture, None);
+
+        assert_eq!(actual, None);
+    }
+}
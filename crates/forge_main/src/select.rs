@@ -1,6 +1,6 @@
 use anyhow::Result;
 use inquire::ui::{RenderConfig, Styled};
-use inquire::{Confirm, InquireError, Select};
+use inquire::{Confirm, InquireError, MultiSelect, Select};
 
 /// Centralized inquire select functionality with consistent error handling
 pub struct ForgeSelect;
@@ -44,6 +44,19 @@ impl ForgeSelect {
         }
     }
 
+    /// Prompts for any number of the options, none of them being selected
+    /// initially
+    pub fn multi_select<T: std::fmt::Display>(
+        message: impl Into<String>,
+        options: Vec<T>,
+    ) -> Result<Option<Vec<T>>> {
+        let message = message.into();
+        let select = MultiSelect::new(&message, options)
+            .with_render_config(Self::default_render_config())
+            .with_help_message("Use arrow keys to navigate, Space to select, Enter to confirm");
+        Self::handle_inquire_error(select.prompt())
+    }
+
     /// Convenience method for confirm (yes/no)
     pub fn confirm(message: impl Into<String>) -> SelectBuilder<bool> {
         SelectBuilder {
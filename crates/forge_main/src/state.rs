@@ -17,6 +17,11 @@ pub struct UIState {
     pub is_first: bool,
     pub model: Option<ModelId>,
     pub provider: Option<Provider>,
+    /// Conversation last dumped, along with the number of messages in its
+    /// context at the time
+    pub dumped: Option<(ConversationId, usize)>,
+    /// Files attached to the next message
+    pub attachments: Vec<String>,
 }
 
 impl UIState {
@@ -45,8 +50,18 @@ impl UIState {
             model: workflow.model,
             operating_agent,
             provider: Default::default(),
+            dumped: Default::default(),
+            attachments: Default::default(),
         }
     }
+
+    /// Number of messages of the conversation that were dumped, none when
+    /// the dump was of another conversation
+    pub fn dumped_messages(&self, conversation_id: &ConversationId) -> usize {
+        self.dumped
+            .filter(|(id, _)| id == conversation_id)
+            .map_or(0, |(_, count)| count)
+    }
 }
 
 impl From<UIState> for ForgePrompt {
@@ -56,6 +71,191 @@ impl From<UIState> for ForgePrompt {
             usage: Some(state.usage),
             model: state.model,
             agent_id: state.operating_agent,
+            footer: None,
+        }
+    }
+}
+
+/// Conversations open side by side in the session, each one keeps its own
+/// state and exactly one of them is active at a time
+#[derive(Debug, Clone)]
+pub struct Tabs {
+    states: Vec<UIState>,
+    active: usize,
+}
+
+impl Default for Tabs {
+    fn default() -> Self {
+        Self { states: vec![UIState::default()], active: 0 }
+    }
+}
+
+impl Tabs {
+    /// State of the active tab
+    pub fn active(&self) -> &UIState {
+        &self.states[self.active]
+    }
+
+    pub fn active_mut(&mut self) -> &mut UIState {
+        &mut self.states[self.active]
+    }
+
+    /// Position of the active tab, starting at 1
+    pub fn position(&self) -> usize {
+        self.active + 1
+    }
+
+    /// Number of open tabs
+    pub fn count(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Opens a tab with the given state after the last one and activates it
+    pub fn open(&mut self, state: UIState) {
+        self.states.push(state);
+        self.active = self.states.len() - 1;
+    }
+
+    /// Activates the tab at the given index, ignoring indices that are out of
+    /// range
+    pub fn select(&mut self, index: usize) {
+        if index < self.states.len() {
+            self.active = index;
+        }
+    }
+
+    /// Activates the tab after the active one, wrapping around to the first
+    pub fn next(&mut self) {
+        self.active = (self.active + 1) % self.states.len();
+    }
+
+    /// Activates the tab before the active one, wrapping around to the last
+    pub fn prev(&mut self) {
+        self.active = (self.active + self.states.len() - 1) % self.states.len();
+    }
+
+    /// Closes the active tab and activates the one before it. The last open
+    /// tab can't be closed, in which case `None` is returned.
+    pub fn close(&mut self) -> Option<UIState> {
+        if self.states.len() == 1 {
+            return None;
         }
+        let state = self.states.remove(self.active);
+        self.active = self.active.saturating_sub(1);
+        Some(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture_state(model: &str) -> UIState {
+        UIState::default()
+            .conversation_id(ConversationId::generate())
+            .model(ModelId::new(model))
+    }
+
+    fn models(tabs: &Tabs) -> Vec<Option<ModelId>> {
+        tabs.states
+            .iter()
+            .map(|state| state.model.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_open_activates_new_tab() {
+        let mut fixture = Tabs::default();
+        *fixture.active_mut() = fixture_state("model-a");
+
+        fixture.open(fixture_state("model-b"));
+
+        let actual = (
+            fixture.count(),
+            fixture.position(),
+            fixture.active().model.clone(),
+        );
+        let expected = (2, 2, Some(ModelId::new("model-b")));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_switching_preserves_each_tab_state() {
+        let mut fixture = Tabs::default();
+        *fixture.active_mut() = fixture_state("model-a");
+        let first = fixture.active().conversation_id;
+        fixture.open(fixture_state("model-b"));
+        fixture.open(fixture_state("model-c"));
+        let dumped = fixture.active().conversation_id.unwrap();
+        fixture.active_mut().dumped = Some((dumped, 3));
+
+        let mut actual = Vec::new();
+        fixture.next();
+        actual.push((
+            fixture.position(),
+            fixture.active().dumped_messages(&dumped),
+        ));
+        fixture.next();
+        actual.push((
+            fixture.position(),
+            fixture.active().dumped_messages(&dumped),
+        ));
+        fixture.prev();
+        fixture.prev();
+        actual.push((
+            fixture.position(),
+            fixture.active().dumped_messages(&dumped),
+        ));
+
+        let expected = vec![(1, 0), (2, 0), (3, 3)];
+        assert_eq!(actual, expected);
+        fixture.select(0);
+        assert_eq!(fixture.active().conversation_id, first);
+    }
+
+    #[test]
+    fn test_close_activates_previous_tab() {
+        let mut fixture = Tabs::default();
+        *fixture.active_mut() = fixture_state("model-a");
+        fixture.open(fixture_state("model-b"));
+        fixture.open(fixture_state("model-c"));
+        fixture.select(1);
+
+        let closed = fixture.close().map(|state| state.model);
+
+        assert_eq!(closed, Some(Some(ModelId::new("model-b"))));
+        assert_eq!(fixture.position(), 1);
+        assert_eq!(
+            models(&fixture),
+            vec![Some(ModelId::new("model-a")), Some(ModelId::new("model-c"))]
+        );
+    }
+
+    #[test]
+    fn test_last_tab_cant_be_closed() {
+        let mut fixture = Tabs::default();
+        *fixture.active_mut() = fixture_state("model-a");
+
+        let actual = fixture.close();
+
+        assert!(actual.is_none());
+        assert_eq!(models(&fixture), vec![Some(ModelId::new("model-a"))]);
+    }
+
+    #[test]
+    fn test_dumped_messages_only_count_for_the_dumped_conversation() {
+        let fixture = fixture_state("model-a");
+        let dumped = fixture.conversation_id.unwrap();
+        let fixture = UIState { dumped: Some((dumped, 3)), ..fixture };
+
+        let actual = (
+            fixture.dumped_messages(&dumped),
+            fixture.dumped_messages(&ConversationId::generate()),
+        );
+
+        let expected = (3, 0);
+        assert_eq!(actual, expected);
     }
 }
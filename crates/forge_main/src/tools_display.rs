@@ -1,27 +1,159 @@
-use forge_api::ToolDefinition;
+use std::collections::BTreeMap;
 
-/// Formats the list of tools for display in the shell UI, showing only the tool
-/// name as a blue bold heading with numbering for each tool.
+use colored::Colorize;
+use forge_api::{ToolDefinition, Tools};
+
+/// Width the descriptions are wrapped at
+const DESCRIPTION_WIDTH: usize = 60;
+
+/// Where a tool comes from, the tools are listed grouped by it
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum ToolSource {
+    BuiltIn,
+    Mcp(String),
+    Agent,
+}
+
+impl ToolSource {
+    fn of(tool: &ToolDefinition) -> Self {
+        if Tools::contains(&tool.name) {
+            return ToolSource::BuiltIn;
+        }
+        // MCP tools are registered as `mcp_{server}_tool_{name}`
+        match tool
+            .name
+            .as_str()
+            .strip_prefix("mcp_")
+            .and_then(|name| name.split_once("_tool_"))
+        {
+            Some((server, _)) => ToolSource::Mcp(server.to_string()),
+            None => ToolSource::Agent,
+        }
+    }
+
+    fn title(&self) -> String {
+        match self {
+            ToolSource::BuiltIn => "Built-in".to_string(),
+            ToolSource::Mcp(server) => format!("MCP: {server}"),
+            ToolSource::Agent => "Agents".to_string(),
+        }
+    }
+}
+
+/// Formats the list of tools for display in the shell UI. The tools are
+/// grouped by source, built-in tools first and MCP tools by server, with
+/// their names aligned and the first sentence of their description dimmed
+/// and wrapped next to them.
 pub fn format_tools(tools: &[ToolDefinition]) -> String {
-    let mut output = String::new();
-
-    // Calculate the number of digits in the total count
-    let max_digits = tools.len().to_string().len();
-
-    for (i, tool) in tools.iter().enumerate() {
-        // Add numbered tool name with consistent padding
-        output.push_str(&format!(
-            "{:>width$}. {}",
-            i + 1,
-            tool.name,
-            width = max_digits
-        ));
-
-        // Add newline between tools
-        if i < tools.len() - 1 {
-            output.push('\n');
+    let mut groups = BTreeMap::<ToolSource, Vec<&ToolDefinition>>::new();
+    for tool in tools {
+        groups.entry(ToolSource::of(tool)).or_default().push(tool);
+    }
+    let width = tools
+        .iter()
+        .map(|tool| tool.name.as_str().len())
+        .max()
+        .unwrap_or_default();
+
+    groups
+        .into_iter()
+        .map(|(source, tools)| {
+            let mut lines = vec![source.title().bold().to_string()];
+            for tool in tools {
+                let name = format!("{:<width$}", tool.name.as_str()).cyan();
+                let mut description = wrap(&summary(&tool.description), DESCRIPTION_WIDTH)
+                    .into_iter()
+                    .map(|line| line.dimmed());
+                match description.next() {
+                    Some(first) => lines.push(format!("  {name}  {first}")),
+                    None => lines.push(format!("  {name}")),
+                }
+                lines.extend(description.map(|line| format!("  {:width$}  {line}", "")));
+            }
+            lines.join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// First sentence of the description, on a single line
+fn summary(description: &str) -> String {
+    let description = description.split_whitespace().collect::<Vec<_>>().join(" ");
+    match description.find(". ") {
+        Some(end) => description[..=end].to_string(),
+        None => description,
+    }
+}
+
+/// Splits the text into lines of at most `width` characters, breaking
+/// between words. Words longer than the width are kept whole.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.len() + 1 + word.len() <= width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
         }
     }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use console::strip_ansi_codes;
+    use pretty_assertions::assert_eq;
 
-    output
+    use super::*;
+
+    fn fixture_tool(name: &str, description: &str) -> ToolDefinition {
+        ToolDefinition::new(name).description(description)
+    }
+
+    #[test]
+    fn test_format_tools_groups_by_source_and_aligns_names() {
+        let fixture = vec![
+            fixture_tool("mcp_github_tool_create_issue", "Creates an issue."),
+            fixture_tool(
+                "forge_tool_fs_read",
+                "Reads file contents from the specified absolute path. Ideal for analyzing code.",
+            ),
+            fixture_tool("mcp_browser_tool_open", "Opens a page."),
+            fixture_tool(
+                "sage",
+                "Researches the codebase without making changes to it, reporting back what it found out.",
+            ),
+            fixture_tool("forge_tool_fs_remove", "Request to remove a file."),
+        ];
+
+        let actual = strip_ansi_codes(&format_tools(&fixture)).to_string();
+
+        let expected = [
+            "Built-in",
+            "  forge_tool_fs_read            Reads file contents from the specified absolute path.",
+            "  forge_tool_fs_remove          Request to remove a file.",
+            "",
+            "MCP: browser",
+            "  mcp_browser_tool_open         Opens a page.",
+            "",
+            "MCP: github",
+            "  mcp_github_tool_create_issue  Creates an issue.",
+            "",
+            "Agents",
+            "  sage                          Researches the codebase without making changes to it,",
+            "                                reporting back what it found out.",
+        ]
+        .join("\n");
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_wrap_breaks_between_words() {
+        let actual = wrap("one two three four", 9);
+
+        let expected = vec!["one two", "three", "four"];
+        assert_eq!(actual, expected);
+    }
 }
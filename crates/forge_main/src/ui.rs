@@ -1,17 +1,22 @@
 use std::collections::BTreeMap;
 use std::fmt::Display;
+use std::path::Path;
+use std::process::ExitCode;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use colored::Colorize;
 use convert_case::{Case, Casing};
 use forge_api::{
-    API, AgentId, AppConfig, ChatRequest, ChatResponse, Conversation, ConversationId, Event,
-    InterruptionReason, Model, ModelId, Workflow,
+    API, AgentId, AppConfig, ChatRequest, ChatResponse, ContextMessage, Conversation,
+    ConversationId, Event, InterruptionReason, MaxTokens, Model, ModelId, Profile, ProfileProvider,
+    Role, ToolCallFull, ToolName, ToolResult, ToolUsagePrompt, ToolValue, ToolsDiscriminants,
+    Workflow,
 };
 use forge_display::{MarkdownFormat, TitleFormat};
-use forge_domain::{McpConfig, McpServerConfig, Provider, Scope};
-use forge_fs::ForgeFS;
+use forge_domain::{McpConfig, McpServerConfig, Provider, RulesLayer, Scope, shell_quote};
+use forge_fs::{FileLock, ForgeFS};
 use forge_spinner::SpinnerManager;
 use forge_tracker::ToolCallPayload;
 use merge::Merge;
@@ -19,15 +24,37 @@ use serde::Deserialize;
 use serde_json::Value;
 use tokio_stream::StreamExt;
 
-use crate::cli::{Cli, McpCommand, TopLevelCommand, Transport};
+use crate::attach_dir::pack_directory;
+use crate::auto_continue::{AutoContinue, INTERRUPTED_EXIT_CODE};
+use crate::bench::{self, BenchReport};
+use crate::cli::{BenchArgs, Cli, McpCommand, ProfileCommand, TopLevelCommand, Transport};
+use crate::context_view::{context_info, message_entries};
+use crate::doctor;
+use crate::exit::Exit;
+use crate::footer::format_footer;
+use crate::github;
+use crate::image_display::{ImageDisplay, ImageProtocol, render_image};
 use crate::info::Info;
 use crate::input::Console;
-use crate::model::{Command, ForgeCommandManager};
+use crate::mention::resolve_mentions;
+use crate::model::{Command, ForgeCommandManager, TabAction};
+use crate::pick_files::{read_request, workspace_files};
+use crate::prompt::ForgePrompt;
+use crate::recent::{DEFAULT_RECENT_FILES, recent_files};
+use crate::regenerate::regenerate_content;
 use crate::select::ForgeSelect;
-use crate::state::UIState;
-use crate::update::on_update;
+use crate::state::{Tabs, UIState};
+use crate::update::{
+    PendingUpdate, execute_update_command, latest_version, on_update, pending_updates,
+};
 use crate::{TRACKER, banner, tracker};
 
+/// Quiet period after the last file change before a watched prompt is re-run
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Number of lines shown from the end of the log file
+const LOG_TAIL_LINES: usize = 20;
+
 // Event type constants moved to UI layer
 pub const EVENT_USER_TASK_INIT: &str = "user_task_init";
 pub const EVENT_USER_TASK_UPDATE: &str = "user_task_update";
@@ -52,13 +79,27 @@ impl From<PartialEvent> for Event {
 
 pub struct UI<A, F: Fn() -> A> {
     markdown: MarkdownFormat,
-    state: UIState,
+    tabs: Tabs,
     api: Arc<F::Output>,
     new_api: Arc<F>,
     console: Console,
     command: Arc<ForgeCommandManager>,
     cli: Cli,
     spinner: SpinnerManager,
+    auto_continue: AutoContinue,
+    /// Whether the usage footer is shown, below the spinner while it runs
+    /// and above the prompt while it waits for input
+    footer: bool,
+    /// Text of the response streamed so far. It's previewed on the spinner
+    /// until the complete text is written
+    partial_text: String,
+    /// Socket the chat events are streamed to
+    #[cfg(unix)]
+    event_socket: Option<crate::event_socket::EventSocket>,
+    /// Lock on the conversation loaded with `--conversation`. It's held by
+    /// the UI rather than the API so that it outlives `/new`, and released
+    /// when the UI is dropped.
+    conversation_lock: Option<(ConversationId, FileLock)>,
     #[allow(dead_code)] // The guard is kept alive by being held in the struct
     _guard: forge_tracker::Guard,
 }
@@ -70,6 +111,21 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
         self.spinner.write_ln(content)
     }
 
+    /// Usage of the active conversation, unless the footer is turned off
+    fn footer(&self) -> Option<String> {
+        self.footer.then(|| {
+            let state = self.tabs.active();
+            format_footer(state.model.as_ref(), &state.usage)
+        })
+    }
+
+    /// Shows the usage of the active conversation below the spinner, or
+    /// removes it when the footer is turned off
+    fn update_footer(&mut self) {
+        let footer = self.footer();
+        self.spinner.set_footer(footer);
+    }
+
     /// Retrieve available models
     async fn get_models(&mut self) -> Result<Vec<Model>> {
         self.spinner.start(Some("Loading"))?;
@@ -80,13 +136,58 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
 
     // Handle creating a new conversation
     async fn on_new(&mut self) -> Result<()> {
-        self.api = Arc::new((self.new_api)());
+        // Conversations are kept by the API, so it's only recreated when no
+        // other tab still needs its conversation
+        if self.tabs.count() == 1 {
+            self.api = Arc::new((self.new_api)());
+        }
         self.init_state(false).await?;
         banner::display()?;
         self.trace_user();
         Ok(())
     }
 
+    /// Offers to dump the current conversation before it's discarded, unless
+    /// prompts are skipped with `--yes` or turned off in the app config
+    async fn offer_to_save(&mut self) -> Result<()> {
+        if self.cli.yes {
+            return Ok(());
+        }
+        let Some(conversation_id) = self.tabs.active().conversation_id else {
+            return Ok(());
+        };
+        let Some(conversation) = self.api.conversation(&conversation_id).await? else {
+            return Ok(());
+        };
+        let dumped_messages = self.tabs.active().dumped_messages(&conversation_id);
+        if !has_unsaved_content(&conversation, dumped_messages) {
+            return Ok(());
+        }
+        let mut config = self.api.app_config().await.unwrap_or_default();
+        if config.skip_save_prompt {
+            return Ok(());
+        }
+
+        const SAVE: &str = "Save as JSON";
+        const DISCARD: &str = "Don't save";
+        const NEVER_ASK: &str = "Don't save and don't ask again";
+        let choice = ForgeSelect::select(
+            "The current conversation hasn't been saved, save it first?",
+            vec![SAVE, DISCARD, NEVER_ASK],
+        )
+        .prompt()?;
+
+        match choice {
+            Some(SAVE) => self.on_dump(None).await?,
+            Some(NEVER_ASK) => {
+                config.skip_save_prompt = true;
+                self.api.write_app_config(&config).await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     async fn active_workflow(&self) -> Result<Workflow> {
         // Read the current workflow to validate the agent
         let workflow = self.api.read_workflow(self.cli.workflow.as_deref()).await?;
@@ -109,8 +210,8 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
         }
 
         // Reset is_first to true when switching agents
-        self.state.is_first = true;
-        self.state.operating_agent = agent.id.clone();
+        self.tabs.active_mut().is_first = true;
+        self.tabs.active_mut().operating_agent = agent.id.clone();
 
         // Update the workflow with the new operating agent.
         self.api
@@ -135,7 +236,7 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
         content: Option<V>,
         event_name: &str,
     ) -> anyhow::Result<Event> {
-        let operating_agent = &self.state.operating_agent;
+        let operating_agent = &self.tabs.active().operating_agent;
         Ok(Event::new(
             format!("{operating_agent}/{event_name}"),
             content,
@@ -147,30 +248,53 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
         let api = Arc::new(f());
         let env = api.environment();
         let command = Arc::new(ForgeCommandManager::default());
+        #[cfg(unix)]
+        let event_socket = cli
+            .event_socket
+            .as_deref()
+            .map(crate::event_socket::EventSocket::bind)
+            .transpose()?;
         Ok(Self {
-            state: Default::default(),
+            tabs: Default::default(),
             api,
             new_api: Arc::new(f),
             console: Console::new(env.clone(), command.clone()),
+            auto_continue: AutoContinue::new(cli.auto_continue),
             cli,
             command,
             spinner: SpinnerManager::new(),
+            footer: false,
+            partial_text: String::new(),
+            #[cfg(unix)]
+            event_socket,
+            conversation_lock: None,
             markdown: MarkdownFormat::new(),
-            _guard: forge_tracker::init_tracing(env.log_path(), TRACKER.clone())?,
+            _guard: forge_tracker::init_tracing(
+                env.log_path(),
+                env.otlp_endpoint.clone(),
+                TRACKER.clone(),
+            )?,
         })
     }
 
     async fn prompt(&self) -> Result<Command> {
         // Prompt the user for input
-        self.console.prompt(self.state.clone().into()).await
+        let mut prompt: ForgePrompt = self.tabs.active().clone().into();
+        prompt.footer = self.footer();
+        self.console.prompt(prompt).await
     }
 
-    pub async fn run(&mut self) {
+    /// Runs the session and returns the status the process should exit with
+    pub async fn run(&mut self) -> ExitCode {
         match self.run_inner().await {
-            Ok(_) => {}
+            Ok(_) => ExitCode::SUCCESS,
             Err(error) => {
+                if let Some(Exit(status)) = error.downcast_ref::<Exit>() {
+                    return ExitCode::from(*status);
+                }
                 tracing::error!(error = ?error);
                 eprintln!("{}", TitleFormat::error(format!("{error:?}")));
+                ExitCode::FAILURE
             }
         }
     }
@@ -188,7 +312,10 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
         // Handle direct prompt if provided
         let prompt = self.cli.prompt.clone();
         if let Some(prompt) = prompt {
-            self.on_message(Some(prompt)).await?;
+            self.on_message(Some(prompt.clone())).await?;
+            if let Some(pattern) = self.cli.watch.clone() {
+                self.on_watch(&pattern, prompt).await?;
+            }
             return Ok(());
         }
 
@@ -211,8 +338,9 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
                 result = self.on_command(command) => {
                     match result {
                         Ok(exit) => if exit {return Ok(())},
+                        Err(error) if error.is::<Exit>() => return Err(error),
                         Err(error) => {
-                            if let Some(conversation_id) = self.state.conversation_id.as_ref()
+                            if let Some(conversation_id) = self.tabs.active().conversation_id.as_ref()
                                 && let Some(conversation) = self.api.conversation(conversation_id).await.ok().flatten() {
                                     TRACKER.set_conversation(conversation).await;
                                 }
@@ -232,6 +360,31 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
         }
     }
 
+    /// Dispatches the prompt again every time files matching the pattern
+    /// change, until interrupted
+    async fn on_watch(&mut self, pattern: &str, prompt: String) -> Result<()> {
+        loop {
+            self.writeln(TitleFormat::info(format!("Watching {pattern} for changes")))?;
+            let changed = tokio::select! {
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+                changed = self.api.wait_for_changes(pattern, WATCH_DEBOUNCE) => changed?,
+            };
+
+            self.writeln(TitleFormat::action(format!(
+                "{} file(s) changed, running the prompt again",
+                changed.len()
+            )))?;
+            if let Err(error) = self.on_message(Some(prompt.clone())).await {
+                if error.is::<Exit>() {
+                    return Err(error);
+                }
+                tracing::error!(error = ?error);
+                self.spinner.stop(None)?;
+                eprintln!("{}", TitleFormat::error(format!("{error:?}")));
+            }
+        }
+    }
+
     async fn handle_subcommands(&mut self, subcommand: TopLevelCommand) -> anyhow::Result<()> {
         match subcommand {
             TopLevelCommand::Mcp(mcp_command) => match mcp_command.command {
@@ -307,10 +460,125 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
                     )))?;
                 }
             },
+            TopLevelCommand::Bench(args) => self.on_bench(args).await?,
+            TopLevelCommand::Doctor => self.on_doctor().await?,
+            TopLevelCommand::Profile(profile) => self.on_profile(profile.command).await?,
+            TopLevelCommand::ServeMcp => {
+                unreachable!("The MCP server is started before the UI")
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds, lists or switches the profiles stored in the app config
+    async fn on_profile(&mut self, command: ProfileCommand) -> Result<()> {
+        let mut config = self.api.app_config().await.unwrap_or_default();
+        match command {
+            ProfileCommand::Add(args) => {
+                let provider: ProfileProvider = args.provider.into();
+                let api_key = match args.api_key {
+                    Some(api_key) => api_key,
+                    None if provider == ProfileProvider::Ollama => String::new(),
+                    None => {
+                        return Err(anyhow::anyhow!(
+                            "--api-key is required for {:?} profiles",
+                            provider
+                        ));
+                    }
+                };
+                let profile = Profile {
+                    provider,
+                    api_key,
+                    base_url: args.base_url,
+                    model: args.model.map(ModelId::new),
+                };
+                config.add_profile(args.name.clone(), profile);
+                self.api.write_app_config(&config).await?;
+                self.writeln(TitleFormat::info(format!("Added profile '{}'", args.name)))?;
+            }
+            ProfileCommand::List => {
+                if config.profiles.is_empty() {
+                    self.writeln(TitleFormat::error("No profiles found"))?;
+                }
+                for (name, profile) in &config.profiles {
+                    let marker = if config.active_profile.as_ref() == Some(name) {
+                        "*"
+                    } else {
+                        " "
+                    };
+                    let mut line = format!("{marker} {name}: {:?}", profile.provider);
+                    if let Some(url) = profile.base_url.as_ref() {
+                        line.push_str(&format!(" via {url}"));
+                    }
+                    if let Some(model) = profile.model.as_ref() {
+                        line.push_str(&format!(", {model}"));
+                    }
+                    self.writeln(line)?;
+                }
+            }
+            ProfileCommand::Use(args) => {
+                let model = config.use_profile(&args.name)?.model.clone();
+                self.api.write_app_config(&config).await?;
+                if let Some(model) = model {
+                    self.api
+                        .update_workflow(self.cli.workflow.as_deref(), |workflow| {
+                            workflow.model = Some(model.clone());
+                        })
+                        .await?;
+                }
+                self.writeln(TitleFormat::info(format!(
+                    "Switched to profile '{}'",
+                    args.name
+                )))?;
+            }
         }
         Ok(())
     }
 
+    /// Prints the readiness report and exits with a failure status when a
+    /// critical check fails
+    async fn on_doctor(&mut self) -> Result<()> {
+        self.spinner.start(Some("Checking"))?;
+        let report = doctor::run(self.api.as_ref(), self.cli.workflow.as_deref()).await;
+        self.spinner.stop(None)?;
+
+        self.writeln(&report)?;
+        if !report.is_ready() {
+            return Err(Exit(1).into());
+        }
+        Ok(())
+    }
+
+    /// Runs the prompt against every model in a fresh conversation, a bounded
+    /// number of them at a time, and prints the comparison
+    async fn on_bench(&mut self, args: BenchArgs) -> Result<()> {
+        self.init_provider().await?;
+        let workflow = self.api.read_workflow(self.cli.workflow.as_deref()).await?;
+        let semaphore = Arc::new(tokio::sync::Semaphore::new(args.concurrency.max(1)));
+
+        self.spinner.start(Some("Benchmarking"))?;
+        let mut runs = tokio::task::JoinSet::new();
+        for (index, model) in args.models.into_iter().enumerate() {
+            let api = self.api.clone();
+            let workflow = workflow.clone();
+            let prompt = args.prompt.clone();
+            let semaphore = semaphore.clone();
+            runs.spawn(async move {
+                let _permit = semaphore.acquire_owned().await;
+                let result = bench::run(api.as_ref(), workflow, ModelId::new(model), prompt).await;
+                (index, result)
+            });
+        }
+
+        let mut results = runs.join_all().await;
+        results.sort_by_key(|(index, _)| *index);
+        self.spinner.stop(None)?;
+
+        self.writeln(BenchReport::new(
+            results.into_iter().map(|(_, result)| result).collect(),
+        ))
+    }
+
     async fn on_command(&mut self, command: Command) -> anyhow::Result<bool> {
         match command {
             Command::Compact => {
@@ -322,10 +590,26 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
                 self.on_dump(format).await?;
             }
             Command::New => {
+                self.offer_to_save().await?;
                 self.on_new().await?;
             }
+            Command::Clear => {
+                self.offer_to_save().await?;
+                self.on_clear().await?;
+            }
             Command::Info => {
-                let mut info = Info::from(&self.state).extend(Info::from(&self.api.environment()));
+                let env = self.api.environment();
+                let rules = self.api.rules().await;
+                let mut info = Info::from(self.tabs.active())
+                    .extend(Info::from(&env))
+                    .extend(
+                        Info::new().add_rules(
+                            &env.home,
+                            rules
+                                .into_iter()
+                                .map(|(layer, rules)| (layer, rules.map(|rules| rules.path))),
+                        ),
+                    );
 
                 // Add user information if available
                 if let Ok(config) = self.api.app_config().await
@@ -358,10 +642,92 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
                 let output = format_tools(&tools);
                 self.writeln(output)?;
             }
+            Command::ToolUsage => {
+                let workflow = self.active_workflow().await?;
+                let agent = workflow.get_agent(&self.tabs.active().operating_agent)?;
+                let tools = agent.allowed_tools(&self.api.tools().await?);
+                self.writeln(ToolUsagePrompt::from(&tools))?;
+            }
+            Command::Cost => {
+                self.on_cost().await?;
+            }
+            Command::Trim => {
+                self.on_trim().await?;
+            }
+            Command::RunTool(name, arguments) => {
+                self.on_run_tool(name, arguments).await?;
+            }
+            Command::TasksExport(github) => {
+                self.on_tasks_export(github).await?;
+            }
+            Command::MaxTokens(max_tokens) => {
+                self.on_max_tokens(max_tokens).await?;
+            }
+            Command::Verbose(verbose) => {
+                let state = if self.cli.set_verbose(verbose) {
+                    "on"
+                } else {
+                    "off"
+                };
+                self.writeln(TitleFormat::action(format!("Verbose mode {state}")))?;
+            }
+            Command::Footer(footer) => {
+                self.footer = footer.unwrap_or(!self.footer);
+                self.update_footer();
+                let state = if self.footer { "on" } else { "off" };
+                self.writeln(TitleFormat::action(format!("Usage footer {state}")))?;
+            }
+            Command::Recent(count) => {
+                self.on_recent(count.unwrap_or(DEFAULT_RECENT_FILES))
+                    .await?;
+            }
+            Command::Raw => {
+                self.on_raw().await?;
+            }
+            Command::Logs => {
+                self.on_logs().await?;
+            }
+            Command::Read => {
+                self.on_read().await?;
+            }
+            Command::Pin => {
+                self.on_pin().await?;
+            }
+            Command::Context(edit) => {
+                self.on_context(edit).await?;
+            }
+            Command::Rules(edit) => {
+                self.on_rules(edit).await?;
+            }
+            Command::Tab(action) => {
+                self.on_tab(action).await?;
+            }
+            Command::PlanFirst(plan_first) => {
+                self.on_plan_first(plan_first).await?;
+            }
+            Command::DisableTool(name) => {
+                self.on_disable_tool(name).await?;
+            }
+            Command::EnableTool(name) => {
+                self.on_enable_tool(name).await?;
+            }
+            Command::Continue(content) => {
+                self.on_continue(content).await?;
+            }
+            Command::Regenerate(instruction) => {
+                self.on_regenerate(instruction).await?;
+            }
             Command::Update => {
                 on_update(self.api.clone(), None).await;
             }
+            Command::Updates => {
+                self.on_updates().await?;
+            }
             Command::Exit => {
+                for index in 0..self.tabs.count() {
+                    self.tabs.select(index);
+                    self.offer_to_save().await?;
+                }
                 return Ok(true);
             }
 
@@ -421,6 +787,9 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
                     self.on_agent_change(selected_agent.id).await?;
                 }
             }
+            Command::AgentInfo => {
+                self.on_agent_info().await?;
+            }
             Command::Login => {
                 self.spinner.start(Some("Logging in"))?;
                 self.api.logout().await?;
@@ -458,6 +827,252 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
         Ok(())
     }
 
+    async fn on_cost(&mut self) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let token_count = self
+            .api
+            .conversation(&conversation_id)
+            .await?
+            .and_then(|conversation| conversation.context)
+            .map(|context| context.token_count())
+            .unwrap_or_default();
+
+        let workflow = self.active_workflow().await?;
+        let model = self.tabs.active().model.clone();
+        let pricing = model.as_ref().and_then(|id| workflow.pricing.get(id));
+
+        let mut info = Info::new()
+            .add_title("Cost Estimate")
+            .add_key_value("Prompt Tokens", &token_count);
+        if let Some(model) = &model {
+            info = info.add_key_value("Model", model);
+        }
+        info = match pricing {
+            Some(pricing) => info.add_key_value(
+                "Input Cost",
+                format!("${:.4}", pricing.input_cost(*token_count)),
+            ),
+            None => info.add_key_value("Input Cost", "unknown (no pricing configured)"),
+        };
+
+        self.writeln(info)?;
+        Ok(())
+    }
+
+    async fn on_trim(&mut self) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let Some(mut conversation) = self.api.conversation(&conversation_id).await? else {
+            return Ok(());
+        };
+        let Some(context) = conversation.context.take() else {
+            self.writeln(TitleFormat::error("Nothing to trim, start a task first"))?;
+            return Ok(());
+        };
+
+        let original_tokens = context.token_count_approx();
+        let context = context.trim_tool_results();
+        let reclaimed_tokens = original_tokens.saturating_sub(context.token_count_approx());
+
+        conversation.context = Some(context);
+        self.api.upsert_conversation(conversation).await?;
+
+        self.writeln(TitleFormat::action(format!(
+            "Trimmed tool outputs, reclaimed ~{reclaimed_tokens} tokens"
+        )))?;
+        Ok(())
+    }
+
+    async fn on_clear(&mut self) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let Some(mut conversation) = self.api.conversation(&conversation_id).await? else {
+            return Ok(());
+        };
+        conversation.clear();
+        self.api.upsert_conversation(conversation).await?;
+
+        // The next message starts a task again
+        let state = self.tabs.active_mut();
+        state.is_first = true;
+        state.usage = Default::default();
+        state.dumped = None;
+
+        self.writeln(TitleFormat::action(
+            "Cleared the conversation, kept the pinned tool results",
+        ))?;
+        Ok(())
+    }
+
+    /// Lists the available updates, applying the one picked
+    async fn on_updates(&mut self) -> Result<()> {
+        self.spinner.start(Some("Checking for updates"))?;
+        let latest = latest_version().await;
+        self.spinner.stop(None)?;
+        if latest.is_none() {
+            self.writeln(TitleFormat::info("Forge is up to date"))?;
+        }
+
+        let workflow = self.api.read_workflow(self.cli.workflow.as_deref()).await?;
+        let updates = pending_updates(latest.as_deref(), workflow.updates.as_ref());
+        let Some(update) = ForgeSelect::select("Select an update to apply:", updates).prompt()?
+        else {
+            return Ok(());
+        };
+
+        match update {
+            PendingUpdate::Install(_) => execute_update_command(self.api.clone()).await,
+            update => {
+                self.api
+                    .update_workflow(self.cli.workflow.as_deref(), |workflow| {
+                        update.apply(workflow)
+                    })
+                    .await?;
+                self.writeln(TitleFormat::action(format!("Updated: {update}")))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the tool directly, bypassing the model, and prints its output
+    async fn on_run_tool(&mut self, name: String, arguments: Value) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let call = ToolCallFull::new(ToolName::new(name.as_str())).arguments(arguments);
+        self.spinner.start(Some("Running"))?;
+        let output = self.api.run_tool(&conversation_id, call).await;
+        self.spinner.stop(None)?;
+        let output = output?;
+
+        let text = output
+            .values
+            .iter()
+            .filter_map(|value| value.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if output.is_error {
+            self.writeln(TitleFormat::error(format!("{name} failed")))?;
+        } else {
+            self.writeln(TitleFormat::action(format!("Ran {name}")))?;
+        }
+        if !text.trim().is_empty() {
+            self.writeln(text)?;
+        }
+        Ok(())
+    }
+
+    async fn on_pin(&mut self) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let Some(mut conversation) = self.api.conversation(&conversation_id).await? else {
+            return Ok(());
+        };
+        let Some(tool_name) = conversation
+            .context
+            .as_mut()
+            .and_then(|context| context.pin_last_tool_result())
+        else {
+            self.writeln(TitleFormat::info("No tool has been called yet"))?;
+            return Ok(());
+        };
+
+        self.api.upsert_conversation(conversation).await?;
+
+        self.writeln(TitleFormat::action(format!(
+            "Pinned the output of {tool_name}"
+        )))?;
+        Ok(())
+    }
+
+    /// Shows the context of the next request and, when editing, removes the
+    /// messages the user selects from it
+    async fn on_context(&mut self, edit: bool) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let Some(mut conversation) = self.api.conversation(&conversation_id).await? else {
+            return Ok(());
+        };
+        let Some(context) = conversation.context.take() else {
+            self.writeln(TitleFormat::info(
+                "The context is empty, start a task first",
+            ))?;
+            return Ok(());
+        };
+
+        self.writeln(context_info(&context))?;
+        if !edit {
+            return Ok(());
+        }
+
+        let Some(selected) = ForgeSelect::multi_select(
+            "Remove messages from the context",
+            message_entries(&context),
+        )?
+        else {
+            return Ok(());
+        };
+        if selected.is_empty() {
+            return Ok(());
+        }
+
+        let indices = selected.iter().map(|entry| entry.index).collect::<Vec<_>>();
+        let original_count = context.messages.len();
+        let context = context.remove_messages(&indices);
+        let removed_count = original_count - context.messages.len();
+
+        conversation.context = Some(context);
+        self.api.upsert_conversation(conversation).await?;
+
+        self.writeln(TitleFormat::action(format!(
+            "Removed {removed_count} message(s) from the context"
+        )))?;
+        Ok(())
+    }
+
+    async fn on_tasks_export(&mut self, github: bool) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let tasks = self
+            .api
+            .conversation(&conversation_id)
+            .await?
+            .map(|conversation| conversation.tasks)
+            .unwrap_or_default();
+        if tasks.tasks().is_empty() {
+            self.writeln(TitleFormat::error("No tasks to export"))?;
+            return Ok(());
+        }
+
+        let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
+        let path = format!("{timestamp}-tasks.md");
+        tokio::fs::write(
+            path.as_str(),
+            format!("# Tasks\n\n{}", tasks.to_checklist()),
+        )
+        .await?;
+        self.writeln(TitleFormat::action("Task list exported").sub_title(path))?;
+
+        if !github {
+            return Ok(());
+        }
+
+        let token = std::env::var("GITHUB_TOKEN")
+            .context("Set GITHUB_TOKEN to create GitHub issues for the tasks")?;
+        let remote = self
+            .api
+            .execute_shell_command("git remote get-url origin", self.api.environment().cwd)
+            .await?;
+        let repository = github::parse_repository(&remote.stdout).with_context(|| {
+            format!(
+                "The origin remote is not a GitHub repository: {}",
+                remote.stdout.trim()
+            )
+        })?;
+
+        let issues = github::GithubIssues::new(token, repository);
+        for task in tasks.tasks().iter().filter(|task| !task.is_done()) {
+            let url = issues
+                .create(&task.task, "Exported from the forge task list.")
+                .await?;
+            self.writeln(TitleFormat::action("GitHub issue created").sub_title(url))?;
+        }
+        Ok(())
+    }
+
     /// Select a model from the available models
     /// Returns Some(ModelId) if a model was selected, or None if selection was
     /// canceled
@@ -512,6 +1127,9 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
         if let Some(mut conversation) = self.api.conversation(&conversation_id).await? {
             // Update the model in the conversation
             conversation.set_model(&model)?;
+            let agent = conversation
+                .get_agent(&self.tabs.active().operating_agent)?
+                .clone();
 
             // Upsert the updated conversation
             self.api.upsert_conversation(conversation).await?;
@@ -520,6 +1138,337 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
             self.update_model(model.clone());
 
             self.writeln(TitleFormat::action(format!("Switched to model: {model}")))?;
+
+            if let Some(warning) = self
+                .api
+                .models()
+                .await?
+                .iter()
+                .find(|candidate| candidate.id == model)
+                .and_then(|candidate| agent.tool_support_warning(candidate))
+            {
+                self.writeln(TitleFormat::info(warning))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Limits the tokens generated per response by every agent of the current
+    /// conversation
+    async fn on_max_tokens(&mut self, max_tokens: Option<u32>) -> Result<()> {
+        let max_tokens = max_tokens
+            .map(MaxTokens::new)
+            .transpose()
+            .map_err(anyhow::Error::msg)?;
+        let conversation_id = self.init_conversation().await?;
+
+        if let Some(mut conversation) = self.api.conversation(&conversation_id).await? {
+            conversation.set_max_tokens(max_tokens);
+            self.api.upsert_conversation(conversation).await?;
+
+            let title = match max_tokens {
+                Some(max_tokens) => format!("Max tokens per response set to {max_tokens}"),
+                None => "Max tokens per response reset to the workflow defaults".to_string(),
+            };
+            self.writeln(TitleFormat::action(title))?;
+        }
+
+        Ok(())
+    }
+
+    /// Lists the recently modified files and attaches the selected ones to the
+    /// next message
+    async fn on_recent(&mut self, count: usize) -> Result<()> {
+        let cwd = self.api.environment().cwd;
+        self.spinner.start(Some("Loading"))?;
+        let files = recent_files(&cwd, count).await?;
+        self.spinner.stop(None)?;
+
+        if files.is_empty() {
+            self.writeln(TitleFormat::info("No files found in the workspace"))?;
+            return Ok(());
+        }
+
+        let Some(selected) = ForgeSelect::multi_select("Attach files to the next message", files)?
+        else {
+            return Ok(());
+        };
+        if selected.is_empty() {
+            return Ok(());
+        }
+
+        let paths = selected
+            .into_iter()
+            .map(|file| file.path)
+            .collect::<Vec<_>>();
+        self.writeln(TitleFormat::action(format!(
+            "Attaching {} to the next message",
+            paths.join(", ")
+        )))?;
+        self.tabs.active_mut().attachments.extend(paths);
+        Ok(())
+    }
+
+    async fn on_read(&mut self) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let cwd = self.api.environment().cwd;
+        self.spinner.start(Some("Loading"))?;
+        let files = workspace_files(&cwd).await?;
+        self.spinner.stop(None)?;
+
+        if files.is_empty() {
+            self.writeln(TitleFormat::info("No files found in the workspace"))?;
+            return Ok(());
+        }
+
+        let Some(picked) = ForgeSelect::multi_select("Read files into the context", files)? else {
+            return Ok(());
+        };
+        let paths = read_request(&cwd, picked);
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        let requested = paths.len();
+        self.spinner.start(Some("Reading"))?;
+        let read = self.api.read_files(&conversation_id, paths).await?;
+        self.spinner.stop(None)?;
+
+        self.writeln(TitleFormat::action(format!(
+            "Read {} of {requested} files into the context",
+            read.len()
+        )))?;
+        Ok(())
+    }
+
+    /// Queues the text files of the directory as attachments of the next
+    /// message, noting the ones that were skipped
+    async fn attach_dir(&mut self, dir: &Path) -> Result<()> {
+        let packed = pack_directory(dir).await?;
+        for (path, reason) in &packed.skipped {
+            self.writeln(TitleFormat::info(format!(
+                "Skipped {}: {reason}",
+                path.display()
+            )))?;
+        }
+        self.writeln(TitleFormat::action(format!(
+            "Attaching {} files from {}",
+            packed.files.len(),
+            dir.display()
+        )))?;
+        self.tabs
+            .active_mut()
+            .attachments
+            .extend(packed.files.iter().map(|path| path.display().to_string()));
+        Ok(())
+    }
+
+    /// Prints the raw output saved for the last tool call
+    async fn on_raw(&mut self) -> Result<()> {
+        let env = self.api.environment();
+        if !env.debug_raw_output {
+            self.writeln(TitleFormat::info(
+                "Set FORGE_DEBUG_RAW_OUTPUT=true to keep the raw output of tool calls",
+            ))?;
+            return Ok(());
+        }
+
+        match tokio::fs::read_to_string(env.raw_output_path()).await {
+            Ok(dump) => self.writeln(dump),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => self.writeln(
+                TitleFormat::info("No tool has been called since the last message"),
+            ),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    async fn on_logs(&mut self) -> Result<()> {
+        let path = forge_tracker::log_file(&self.api.environment().log_path());
+        self.writeln(TitleFormat::info(format!("Logs: {}", path.display())))?;
+
+        match tokio::fs::read_to_string(&path).await {
+            Ok(logs) => {
+                let lines = logs.lines().collect::<Vec<_>>();
+                let tail = lines[lines.len().saturating_sub(LOG_TAIL_LINES)..].join("\n");
+                self.writeln(tail.dimmed())
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                self.writeln(TitleFormat::info(
+                    "Nothing has been logged to a file, usage tracking sends logs instead",
+                ))
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Shows the active agent as the conversation resolved it, with the
+    /// tools disabled for the session left out
+    async fn on_agent_info(&mut self) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let Some(conversation) = self.api.conversation(&conversation_id).await? else {
+            return Ok(());
+        };
+        let mut agent = conversation
+            .get_agent(&self.tabs.active().operating_agent)?
+            .clone();
+        let tools = agent
+            .allowed_tools(&self.api.tools().await?)
+            .into_iter()
+            .map(|tool| tool.name)
+            .filter(|name| !conversation.is_tool_disabled(name))
+            .collect::<Vec<_>>();
+        agent.tools = Some(tools);
+
+        self.writeln(Info::from(&agent))?;
+        Ok(())
+    }
+
+    async fn on_rules(&mut self, edit: bool) -> Result<()> {
+        let env = self.api.environment();
+        let layers = self.api.rules().await;
+
+        if !edit {
+            let mut found = false;
+            for (layer, rules) in &layers {
+                if let Some(rules) = rules {
+                    self.writeln(TitleFormat::info(format!(
+                        "{layer} rules: {}",
+                        rules.path.display()
+                    )))?;
+                    self.writeln(rules.rules.as_str().dimmed())?;
+                    found = true;
+                }
+            }
+            if !found {
+                self.writeln(TitleFormat::info(
+                    "No rules files, use /rules edit to write the project rules",
+                ))?;
+            }
+            return Ok(());
+        }
+
+        // A new rules file is created where forge keeps its project files
+        let project_rules = layers
+            .into_iter()
+            .find_map(|(layer, rules)| (layer == RulesLayer::Project).then_some(rules).flatten());
+        let path = match project_rules {
+            Some(rules) => rules.path,
+            None => {
+                let path = RulesLayer::Project.paths(&env).remove(0);
+                if !path.exists() {
+                    if let Some(parent) = path.parent() {
+                        tokio::fs::create_dir_all(parent).await?;
+                    }
+                    tokio::fs::write(&path, "").await?;
+                }
+                path
+            }
+        };
+        let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_string());
+        let quoted = shell_quote(&path.display().to_string());
+        let status = self
+            .api
+            .execute_shell_command_raw(&format!("{editor} {quoted}"))
+            .await?;
+        if !status.success() {
+            return Err(anyhow::anyhow!("{editor} exited with {status}"));
+        }
+        self.writeln(TitleFormat::action(format!(
+            "Rules saved to {}, they apply from the next message",
+            path.display()
+        )))
+    }
+
+    async fn on_tab(&mut self, action: TabAction) -> Result<()> {
+        match action {
+            TabAction::New => {
+                let state = UIState {
+                    conversation_id: None,
+                    usage: Default::default(),
+                    is_first: true,
+                    dumped: None,
+                    attachments: Vec::new(),
+                    ..self.tabs.active().clone()
+                };
+                self.tabs.open(state);
+            }
+            TabAction::Next => self.tabs.next(),
+            TabAction::Prev => self.tabs.prev(),
+            TabAction::Close => {
+                if self.tabs.count() == 1 {
+                    return Err(anyhow::anyhow!(
+                        "Can't close the last tab, use /new to start over"
+                    ));
+                }
+                self.offer_to_save().await?;
+                self.tabs.close();
+            }
+        }
+        self.update_footer();
+
+        self.writeln(TitleFormat::action(format!(
+            "Switched to tab {} of {}",
+            self.tabs.position(),
+            self.tabs.count()
+        )))?;
+        Ok(())
+    }
+
+    async fn on_plan_first(&mut self, plan_first: Option<bool>) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+
+        if let Some(mut conversation) = self.api.conversation(&conversation_id).await? {
+            conversation.plan_first = plan_first.unwrap_or(!conversation.plan_first);
+            let state = if conversation.plan_first { "on" } else { "off" };
+            self.api.upsert_conversation(conversation).await?;
+
+            self.writeln(TitleFormat::action(format!("Plan-first mode {state}")))?;
+        }
+
+        Ok(())
+    }
+
+    async fn on_disable_tool(&mut self, name: ToolName) -> Result<()> {
+        if name == ToolsDiscriminants::ForgeToolAttemptCompletion.name() {
+            return Err(anyhow::anyhow!(
+                "{name} can't be disabled, the agent needs it to finish"
+            ));
+        }
+        let tools = self.api.tools().await?;
+        if !tools.iter().any(|tool| tool.name == name) {
+            return Err(anyhow::anyhow!(
+                "Unknown tool {name}, use /tools to list them"
+            ));
+        }
+        let conversation_id = self.init_conversation().await?;
+
+        if let Some(mut conversation) = self.api.conversation(&conversation_id).await? {
+            let message = if conversation.disable_tool(name.clone()) {
+                format!("Disabled {name} for this session")
+            } else {
+                format!("{name} is already disabled")
+            };
+            self.api.upsert_conversation(conversation).await?;
+
+            self.writeln(TitleFormat::action(message))?;
+        }
+
+        Ok(())
+    }
+
+    async fn on_enable_tool(&mut self, name: ToolName) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+
+        if let Some(mut conversation) = self.api.conversation(&conversation_id).await? {
+            let message = if conversation.enable_tool(&name) {
+                format!("Enabled {name} again")
+            } else {
+                format!("{name} isn't disabled")
+            };
+            self.api.upsert_conversation(conversation).await?;
+
+            self.writeln(TitleFormat::action(message))?;
         }
 
         Ok(())
@@ -539,8 +1488,29 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
         self.on_chat(chat).await
     }
 
+    /// Locks the conversation unless this UI already holds its lock, and warns
+    /// when another forge process has it open
+    async fn lock_conversation(&mut self, id: &ConversationId, path: &Path) -> Result<()> {
+        if self
+            .conversation_lock
+            .as_ref()
+            .is_some_and(|(locked, _)| locked == id)
+        {
+            return Ok(());
+        }
+        let lock = self.api.lock_conversation(id).await?;
+        if let Some(pid) = lock.held_by() {
+            self.writeln(TitleFormat::error(format!(
+                "{} is already open in another forge process (pid {pid}), changes made by either one may be lost",
+                path.display()
+            )))?;
+        }
+        self.conversation_lock = Some((*id, lock));
+        Ok(())
+    }
+
     async fn init_conversation(&mut self) -> Result<ConversationId> {
-        match self.state.conversation_id {
+        match self.tabs.active().conversation_id {
             Some(ref id) => Ok(*id),
             None => {
                 self.spinner.start(Some("Initializing"))?;
@@ -548,21 +1518,27 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
                 // Select a model if workflow doesn't have one
                 let workflow = self.init_state(false).await?;
                 // We need to try and get the conversation ID first before fetching the model
-                let id = if let Some(ref path) = self.cli.conversation {
+                let id = if let Some(path) = self.cli.conversation.clone() {
                     let conversation: Conversation =
                         serde_json::from_str(ForgeFS::read_utf8(path.as_os_str()).await?.as_str())
                             .context("Failed to parse Conversation")?;
+                    self.lock_conversation(&conversation.id, &path).await?;
 
                     let conversation_id = conversation.id;
-                    self.state.conversation_id = Some(conversation_id);
+                    self.tabs.active_mut().conversation_id = Some(conversation_id);
                     self.update_model(conversation.main_model()?);
                     self.api.upsert_conversation(conversation).await?;
                     conversation_id
                 } else {
-                    let conversation = self.api.init_conversation(workflow).await?;
-                    self.state.conversation_id = Some(conversation.id);
+                    let mut conversation = self.api.init_conversation(workflow).await?;
+                    self.tabs.active_mut().conversation_id = Some(conversation.id);
                     self.update_model(conversation.main_model()?);
-                    conversation.id
+                    let conversation_id = conversation.id;
+                    if self.cli.plan_first {
+                        conversation.plan_first = true;
+                        self.api.upsert_conversation(conversation).await?;
+                    }
+                    conversation_id
                 };
 
                 Ok(id)
@@ -592,7 +1568,9 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
             .await?;
 
         self.command.register_all(&base_workflow);
-        self.state = UIState::new(self.api.environment(), base_workflow).provider(provider);
+        *self.tabs.active_mut() =
+            UIState::new(self.api.environment(), base_workflow).provider(provider);
+        self.update_footer();
 
         Ok(workflow)
     }
@@ -633,11 +1611,33 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
 
     async fn on_message(&mut self, content: Option<String>) -> Result<()> {
         let conversation_id = self.init_conversation().await?;
+        let content = match content {
+            Some(content) => {
+                if self.tabs.active().is_first
+                    && let Some(dir) = self.cli.attach_dir.clone()
+                {
+                    self.attach_dir(&dir).await?;
+                }
+                let attachments = std::mem::take(&mut self.tabs.active_mut().attachments);
+                let content = attachments
+                    .iter()
+                    .map(|path| format!("@[{path}]"))
+                    .chain(std::iter::once(content))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                Some(self.attach_mentions(&content)?)
+            }
+            None => None,
+        };
 
         // Create a ChatRequest with the appropriate event type
-        let event = if self.state.is_first {
-            self.state.is_first = false;
-            self.create_task_event(content, EVENT_USER_TASK_INIT)?
+        let event = if self.tabs.active().is_first {
+            self.tabs.active_mut().is_first = false;
+            let event = self.create_task_event(content, EVENT_USER_TASK_INIT)?;
+            match self.cli.context.as_deref() {
+                Some(value) => event.context(read_context(value).await?),
+                None => event,
+            }
         } else {
             self.create_task_event(content, EVENT_USER_TASK_UPDATE)?
         };
@@ -648,6 +1648,82 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
         self.on_chat(chat).await
     }
 
+    /// Turns `@path` mentions of files in the working directory into
+    /// attachments, warning about the ones that can't be attached
+    fn attach_mentions(&mut self, content: &str) -> Result<String> {
+        let env = self.api.environment();
+        let mentions = resolve_mentions(content, &env.cwd, |path| {
+            path.metadata()
+                .is_ok_and(|metadata| metadata.is_file() && metadata.len() <= env.max_file_size)
+        });
+
+        for mention in &mentions.unresolved {
+            self.writeln(TitleFormat::error(format!(
+                "Could not attach @{mention}, leaving it as text"
+            )))?;
+        }
+
+        Ok(mentions.content)
+    }
+
+    /// Resumes the last turn from the saved context. Unlike a new message this
+    /// never initializes a new task, so the orchestrator picks up exactly
+    /// where the previous turn stopped.
+    async fn on_continue(&mut self, content: Option<String>) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let has_context = self
+            .api
+            .conversation(&conversation_id)
+            .await?
+            .is_some_and(|conversation| conversation.context.is_some());
+
+        if !has_context {
+            self.writeln(TitleFormat::error(
+                "Nothing to continue, start a task first",
+            ))?;
+            return Ok(());
+        }
+
+        self.spinner.start(None)?;
+        self.tabs.active_mut().is_first = false;
+        let event = self.create_task_event(content, EVENT_USER_TASK_UPDATE)?;
+        let chat = ChatRequest::new(event, conversation_id);
+
+        self.on_chat(chat).await
+    }
+
+    /// Sends the last task again into the same conversation, so the new
+    /// answer follows the previous one with the same model and context
+    async fn on_regenerate(&mut self, instruction: Option<String>) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let regeneration =
+            self.api
+                .conversation(&conversation_id)
+                .await?
+                .and_then(|conversation| {
+                    regenerate_content(&conversation.events, instruction.as_deref())
+                });
+
+        let Some(regeneration) = regeneration else {
+            self.writeln(TitleFormat::error(
+                "Nothing to regenerate, send a task first",
+            ))?;
+            return Ok(());
+        };
+
+        self.spinner.start(None)?;
+        self.tabs.active_mut().is_first = false;
+        let event =
+            self.create_task_event(Some(regeneration.task.clone()), EVENT_USER_TASK_UPDATE)?;
+        let event = match regeneration.context() {
+            Some(context) => event.context(context),
+            None => event,
+        };
+        let chat = ChatRequest::new(event, conversation_id);
+
+        self.on_chat(chat).await
+    }
+
     async fn on_chat(&mut self, chat: ChatRequest) -> Result<()> {
         let mut stream = self.api.chat(chat).await?;
 
@@ -668,16 +1744,21 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
 
     /// Modified version of handle_dump that supports HTML format
     async fn on_dump(&mut self, format: Option<String>) -> Result<()> {
-        if let Some(conversation_id) = self.state.conversation_id {
+        if let Some(conversation_id) = self.tabs.active().conversation_id {
             let conversation = self.api.conversation(&conversation_id).await?;
             if let Some(conversation) = conversation {
                 let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
+                let message_count = conversation
+                    .context
+                    .as_ref()
+                    .map_or(0, |context| context.messages.len());
                 if let Some(format) = format {
                     if format == "html" {
                         // Export as HTML
                         let html_content = conversation.to_html();
                         let path = format!("{timestamp}-dump.html");
                         tokio::fs::write(path.as_str(), html_content).await?;
+                        self.tabs.active_mut().dumped = Some((conversation_id, message_count));
 
                         self.writeln(
                             TitleFormat::action("Conversation HTML dump created".to_string())
@@ -693,6 +1774,7 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
                     let path = format!("{timestamp}-dump.json");
                     let content = serde_json::to_string_pretty(&conversation)?;
                     tokio::fs::write(path.as_str(), content).await?;
+                    self.tabs.active_mut().dumped = Some((conversation_id, message_count));
 
                     self.writeln(
                         TitleFormat::action("Conversation JSON dump created".to_string())
@@ -712,10 +1794,42 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
         Ok(())
     }
 
+    /// Shows the images returned by a tool inline when the terminal supports
+    /// it, otherwise saves them and prints where they were saved
+    fn show_images(&mut self, result: &ToolResult) -> Result<()> {
+        let protocol = ImageProtocol::detect(|name| std::env::var(name).ok());
+        for value in &result.output.values {
+            if let ToolValue::Image(image) = value {
+                let display = ImageDisplay::new(protocol, image.mime_type());
+                match render_image(image, display) {
+                    Ok(rendered) => self.writeln(rendered)?,
+                    Err(error) => {
+                        tracing::debug!(error = ?error, "Failed to display image");
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     async fn handle_chat_response(&mut self, message: ChatResponse) -> Result<()> {
+        #[cfg(unix)]
+        if let Some(socket) = &self.event_socket {
+            socket.publish(&message);
+        }
         match message {
-            ChatResponse::Text { mut text, is_complete, is_md } => {
-                if is_complete && !text.trim().is_empty() {
+            ChatResponse::Text { text, is_complete: false, .. } => {
+                self.partial_text.push_str(&text);
+                if let Some(preview) = preview_line(&self.partial_text) {
+                    self.spinner.set_message(&preview)?;
+                }
+            }
+            ChatResponse::Text { mut text, is_md, .. } => {
+                // The spinner would come back showing the preview otherwise
+                if !std::mem::take(&mut self.partial_text).is_empty() {
+                    self.spinner.stop(None)?;
+                }
+                if !text.trim().is_empty() {
                     if is_md {
                         tracing::info!(message = %text, "Agent Response");
                         text = self.markdown.render(&text);
@@ -734,6 +1848,10 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
             ChatResponse::ToolCallStart(_) => {
                 self.spinner.stop(None)?;
             }
+            ChatResponse::ToolCallProgress { name, bytes } => {
+                self.spinner
+                    .set_message(&format!("Preparing {name} ({bytes} bytes)"))?;
+            }
             ChatResponse::ToolCallEnd(toolcall_result) => {
                 // Only track toolcall name in case of success else track the error.
                 let payload = if toolcall_result.is_error() {
@@ -747,17 +1865,22 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
                 };
                 tracker::tool_call(payload);
 
-                self.spinner.start(None)?;
-                if !self.cli.verbose {
-                    return Ok(());
+                if let Some(output) = format_tool_result(&toolcall_result, self.cli.verbose) {
+                    self.writeln(output)?;
                 }
+                self.show_images(&toolcall_result)?;
+                self.spinner.start(None)?;
             }
             ChatResponse::Usage(mut usage) => {
                 // accumulate the cost
                 usage.cost = usage
                     .cost
-                    .map(|cost| cost + self.state.usage.cost.as_ref().map_or(0.0, |c| *c));
-                self.state.usage = usage;
+                    .map(|cost| cost + self.tabs.active().usage.cost.as_ref().map_or(0.0, |c| *c));
+                self.tabs.active_mut().usage = usage;
+                self.update_footer();
+            }
+            ChatResponse::Warning { message } => {
+                self.writeln(TitleFormat::info(message))?;
             }
             ChatResponse::RetryAttempt { cause, duration: _ } => {
                 self.spinner.start(Some("Retrying"))?;
@@ -765,14 +1888,25 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
             }
             ChatResponse::Interrupt { reason } => {
                 self.spinner.stop(None)?;
+                // The interrupted response never completes, its text isn't carried over
+                self.partial_text.clear();
 
                 let title = match reason {
                     InterruptionReason::MaxRequestPerTurnLimitReached { limit } => {
                         format!("Maximum request ({limit}) per turn achieved")
                     }
+                    InterruptionReason::TokenBudgetExhausted { limit, used } => {
+                        format!("Token budget ({limit}) exhausted, {used} tokens used")
+                    }
                     InterruptionReason::MaxToolFailurePerTurnLimitReached { limit } => {
                         format!("Maximum tool failure limit ({limit}) reached for this turn")
                     }
+                    InterruptionReason::MaxReasoningTokensReached { limit } => {
+                        format!("Reasoning budget ({limit} tokens) exceeded without an answer")
+                    }
+                    InterruptionReason::EmptyResponseRetriesExhausted { limit } => {
+                        format!("Model kept returning empty responses after {limit} retries")
+                    }
                 };
 
                 self.writeln(TitleFormat::action(title))?;
@@ -783,18 +1917,32 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
                     self.writeln(content.dimmed())?;
                 }
             }
+            ChatResponse::ReasoningProgress { tokens, budget } => {
+                let message = match budget {
+                    Some(budget) => format!("Thinking ({tokens}/{budget} tokens)"),
+                    None => format!("Thinking ({tokens} tokens)"),
+                };
+                self.spinner.set_message(&message)?;
+            }
         }
         Ok(())
     }
 
     async fn should_continue(&mut self) -> anyhow::Result<()> {
-        let should_continue = ForgeSelect::confirm("Do you want to continue anyway?")
-            .with_default(true)
-            .prompt()?;
+        let should_continue = if self.auto_continue.is_enabled() {
+            if !self.auto_continue.try_continue() {
+                self.writeln(TitleFormat::error("Auto-continue limit reached, exiting"))?;
+                return Err(Exit(INTERRUPTED_EXIT_CODE).into());
+            }
+            Some(true)
+        } else {
+            ForgeSelect::confirm("Do you want to continue anyway?")
+                .with_default(true)
+                .prompt()?
+        };
 
         if should_continue.unwrap_or(false) {
-            self.spinner.start(None)?;
-            Box::pin(self.on_message(None)).await?;
+            Box::pin(self.on_continue(None)).await?;
         }
 
         Ok(())
@@ -802,7 +1950,7 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
 
     fn update_model(&mut self, model: ModelId) {
         tracker::set_model(model.to_string());
-        self.state.model = Some(model);
+        self.tabs.active_mut().model = Some(model);
     }
 
     async fn on_custom_event(&mut self, event: Event) -> Result<()> {
@@ -831,6 +1979,16 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
     }
 }
 
+/// Reads the `--context` value, `@path` referring to the file holding it
+async fn read_context(value: &str) -> Result<String> {
+    match value.strip_prefix('@') {
+        Some(path) => tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read the context from {path}")),
+        None => Ok(value.to_string()),
+    }
+}
+
 fn parse_env(env: Vec<String>) -> BTreeMap<String, String> {
     env.into_iter()
         .filter_map(|s| {
@@ -844,6 +2002,40 @@ fn parse_env(env: Vec<String>) -> BTreeMap<String, String> {
         .collect()
 }
 
+/// Returns true if the conversation has messages from the user that were added
+/// after it was last dumped
+fn has_unsaved_content(conversation: &Conversation, dumped_messages: usize) -> bool {
+    conversation.context.as_ref().is_some_and(|context| {
+        context
+            .messages
+            .iter()
+            .skip(dumped_messages)
+            .any(|message| message.has_role(Role::User))
+    })
+}
+
+/// Formats the output of a finished tool call, which is only shown in verbose
+/// mode
+fn format_tool_result(result: &ToolResult, verbose: bool) -> Option<String> {
+    let output = result
+        .output
+        .values
+        .iter()
+        .filter_map(|value| value.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    (verbose && !output.trim().is_empty()).then(|| output.dimmed().to_string())
+}
+
+/// Returns the end of the last line of the streamed text, short enough to be
+/// shown on the spinner
+fn preview_line(text: &str) -> Option<String> {
+    const PREVIEW_CHARS: usize = 60;
+    let line = text.lines().map(str::trim).rfind(|line| !line.is_empty())?;
+    let skipped = line.chars().count().saturating_sub(PREVIEW_CHARS);
+    Some(line.chars().skip(skipped).collect())
+}
+
 struct CliModel(Model);
 
 impl Display for CliModel {
@@ -880,7 +2072,9 @@ impl Display for CliModel {
 
 #[cfg(test)]
 mod tests {
+    use clap::Parser;
     use console::strip_ansi_codes;
+    use forge_api::Context;
     use forge_domain::{Model, ModelId};
     use pretty_assertions::assert_eq;
 
@@ -899,9 +2093,56 @@ mod tests {
             tools_supported,
             supports_parallel_tool_calls: None,
             supports_reasoning: None,
+            supports_vision: None,
         }
     }
 
+    #[test]
+    fn test_has_unsaved_content() {
+        let fixture = |messages: Vec<ContextMessage>| {
+            let mut conversation =
+                Conversation::new(ConversationId::generate(), Workflow::default(), vec![]);
+            conversation.context = Some(Context::default().messages(messages));
+            conversation
+        };
+        let system = ContextMessage::system("You are a helpful assistant");
+        let user = ContextMessage::user("Refactor the parser", None);
+
+        let actual = [
+            has_unsaved_content(
+                &Conversation::new(ConversationId::generate(), Workflow::default(), vec![]),
+                0,
+            ),
+            has_unsaved_content(&fixture(vec![system.clone()]), 0),
+            has_unsaved_content(&fixture(vec![system.clone(), user.clone()]), 0),
+            has_unsaved_content(&fixture(vec![system.clone(), user.clone()]), 2),
+            has_unsaved_content(&fixture(vec![system, user.clone(), user]), 2),
+        ];
+
+        let expected = [false, false, true, false, true];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_toggling_verbose_changes_tool_result_rendering() {
+        let mut cli = Cli::parse_from(["forge"]);
+        let fixture =
+            ToolResult::new(forge_api::ToolName::new("forge_tool_fs_read")).success("fn main() {}");
+
+        let mut actual = vec![format_tool_result(&fixture, cli.verbose)];
+        cli.set_verbose(None);
+        actual.push(format_tool_result(&fixture, cli.verbose));
+        cli.set_verbose(Some(false));
+        actual.push(format_tool_result(&fixture, cli.verbose));
+        let actual = actual
+            .into_iter()
+            .map(|output| output.map(|output| strip_ansi_codes(&output).to_string()))
+            .collect::<Vec<_>>();
+
+        let expected = vec![None, Some("fn main() {}".to_string()), None];
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_cli_model_display_with_context_and_tools() {
         let fixture = create_model_fixture("gpt-4", Some(128000), Some(true));
@@ -1000,4 +2241,22 @@ mod tests {
         let expected = "edge-1001 [ 1k ]";
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_preview_line_shows_the_end_of_the_last_line() {
+        let fixture = format!("First line\n{}\n\n", "x".repeat(70) + "end");
+
+        let actual = preview_line(&fixture);
+
+        let expected = Some("x".repeat(57) + "end");
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_preview_line_is_none_without_text() {
+        let actual = preview_line("\n  \n");
+
+        let expected = None;
+        assert_eq!(actual, expected);
+    }
 }
@@ -1,7 +1,9 @@
+use std::fmt;
 use std::sync::Arc;
+use std::time::Duration;
 
 use colored::Colorize;
-use forge_api::{API, Update};
+use forge_api::{API, Update, UpdateFrequency, Workflow};
 use forge_tracker::VERSION;
 use update_informer::{Check, Version, registry};
 
@@ -9,7 +11,7 @@ use update_informer::{Check, Version, registry};
 const FORGE_NPM_PACKAGE: &str = "forgecode";
 
 /// Runs npm update in the background, failing silently
-async fn execute_update_command(api: Arc<impl API>) {
+pub async fn execute_update_command(api: Arc<impl API>) {
     // Spawn a new task that won't block the main application
     let output = api
         .execute_shell_command_raw(&format!("npm update -g {FORGE_NPM_PACKAGE} --force"))
@@ -82,9 +84,148 @@ pub async fn on_update(api: Arc<impl API>, update: Option<&Update>) {
     }
 }
 
+/// Latest version of forge published, when it's newer than the running one.
+/// Looks it up regardless of the configured frequency
+pub async fn latest_version() -> Option<String> {
+    if VERSION.contains("dev") || VERSION == "0.1.0" {
+        return None;
+    }
+
+    // The registry is queried with a blocking request, kept off the runtime
+    tokio::task::spawn_blocking(|| {
+        update_informer::new(registry::Npm, FORGE_NPM_PACKAGE, VERSION)
+            .interval(Duration::ZERO)
+            .check_version()
+            .ok()
+            .flatten()
+            .map(|version| version.to_string())
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// An update the user can review and apply with `/updates`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PendingUpdate {
+    /// Installs the newer version of forge
+    Install(String),
+    /// Turns installing new versions without asking on or off
+    AutoUpdate(bool),
+    /// Changes how often new versions are looked for at startup
+    Frequency(UpdateFrequency),
+}
+
+impl PendingUpdate {
+    /// Records the update settings in the workflow, installing a version
+    /// leaves it unchanged
+    pub fn apply(&self, workflow: &mut Workflow) {
+        let update = workflow.updates.get_or_insert_with(Update::default);
+        match self {
+            PendingUpdate::Install(_) => {}
+            PendingUpdate::AutoUpdate(auto_update) => update.auto_update = Some(*auto_update),
+            PendingUpdate::Frequency(frequency) => update.frequency = Some(frequency.clone()),
+        }
+    }
+}
+
+impl fmt::Display for PendingUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PendingUpdate::Install(version) => {
+                write!(f, "Install forge {version} (current {VERSION})")
+            }
+            PendingUpdate::AutoUpdate(true) => write!(f, "Install new versions without asking"),
+            PendingUpdate::AutoUpdate(false) => write!(f, "Ask before installing new versions"),
+            PendingUpdate::Frequency(UpdateFrequency::Daily) => {
+                write!(f, "Check for new versions daily")
+            }
+            PendingUpdate::Frequency(UpdateFrequency::Weekly) => {
+                write!(f, "Check for new versions weekly")
+            }
+            PendingUpdate::Frequency(UpdateFrequency::Always) => {
+                write!(f, "Check for new versions on every start")
+            }
+        }
+    }
+}
+
+/// Updates that can be applied, the latest version first when there's one and
+/// then the changes to the update settings of the workflow
+pub fn pending_updates(latest: Option<&str>, update: Option<&Update>) -> Vec<PendingUpdate> {
+    let update = update.cloned().unwrap_or_default();
+    let frequency = update.frequency.unwrap_or_default();
+    let auto_update = update.auto_update.unwrap_or_default();
+
+    latest
+        .map(|version| PendingUpdate::Install(version.to_string()))
+        .into_iter()
+        .chain([PendingUpdate::AutoUpdate(!auto_update)])
+        .chain(
+            [
+                UpdateFrequency::Daily,
+                UpdateFrequency::Weekly,
+                UpdateFrequency::Always,
+            ]
+            .into_iter()
+            .filter(|option| *option != frequency)
+            .map(PendingUpdate::Frequency),
+        )
+        .collect()
+}
+
 /// Sends an event to the tracker when an update fails
 async fn send_update_failure_event(error_msg: &str) -> anyhow::Result<()> {
     tracing::error!(error = error_msg, "Update failed");
     // Always return Ok since we want to fail silently
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_pending_updates_list_the_new_version_and_other_settings() {
+        let fixture = Update {
+            frequency: Some(UpdateFrequency::Daily),
+            auto_update: Some(false),
+        };
+
+        let actual = pending_updates(Some("1.2.0"), Some(&fixture));
+
+        let expected = vec![
+            PendingUpdate::Install("1.2.0".to_string()),
+            PendingUpdate::AutoUpdate(true),
+            PendingUpdate::Frequency(UpdateFrequency::Weekly),
+            PendingUpdate::Frequency(UpdateFrequency::Always),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_pending_updates_without_a_new_version() {
+        let actual = pending_updates(None, None);
+
+        let expected = vec![
+            PendingUpdate::AutoUpdate(true),
+            PendingUpdate::Frequency(UpdateFrequency::Daily),
+            PendingUpdate::Frequency(UpdateFrequency::Weekly),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_apply_records_the_setting_in_the_workflow() {
+        let mut fixture = Workflow::new();
+
+        PendingUpdate::AutoUpdate(true).apply(&mut fixture);
+        PendingUpdate::Frequency(UpdateFrequency::Weekly).apply(&mut fixture);
+
+        let actual = fixture.updates.unwrap();
+        assert_eq!(actual.auto_update, Some(true));
+        assert_eq!(actual.frequency, Some(UpdateFrequency::Weekly));
+    }
+}
@@ -43,6 +43,11 @@ fn messages_to_lines(messages: &[Message]) -> Vec<Line<'_>> {
                     }
                 }
                 ChatResponse::ToolCallStart(_) => vec![].into_iter(),
+                ChatResponse::ToolCallProgress { .. } => vec![].into_iter(),
+                ChatResponse::ReasoningProgress { .. } => vec![].into_iter(),
+                ChatResponse::Warning { message } => {
+                    vec![Line::styled(message.clone(), Style::default().yellow())].into_iter()
+                }
                 ChatResponse::ToolCallEnd(_) => vec![].into_iter(),
                 ChatResponse::Usage(_) => vec![].into_iter(),
                 ChatResponse::Interrupt { reason: _ } => {
@@ -10,7 +10,7 @@ use tokio_stream::StreamExt;
 use tracing::debug;
 
 use super::request::Request;
-use super::response::{EventData, ListModelResponse};
+use super::response::{EventData, ListModelResponse, MessageStart};
 use crate::anthropic::transforms::ReasoningTransform;
 use crate::error::Error;
 use crate::utils::format_http_context;
@@ -67,16 +67,21 @@ impl Anthropic {
         context: Context,
     ) -> ResultStream<ChatCompletionMessage, anyhow::Error> {
         let max_tokens = context.max_tokens.unwrap_or(4000);
+        let stream = context.stream.unwrap_or(true);
         // transform the context to match the request format
         let context = ReasoningTransform.transform(context);
 
         let request = Request::try_from(context)?
             .model(model.as_str().to_string())
-            .stream(true)
+            .stream(stream)
             .max_tokens(max_tokens as u64);
 
         let url = self.url("/messages")?;
         debug!(url = %url, model = %model, "Connecting Upstream");
+
+        if !stream {
+            return self.complete(url, request).await;
+        }
         let es = self
             .client
             .post(url.clone())
@@ -146,6 +151,47 @@ impl Anthropic {
         Ok(Box::pin(stream.filter_map(|x| x)))
     }
 
+    /// Requests the whole message at once, for proxies that mangle streamed
+    /// responses, and replays it as the parts it would have been streamed in
+    async fn complete(
+        &self,
+        url: Url,
+        request: Request,
+    ) -> ResultStream<ChatCompletionMessage, anyhow::Error> {
+        let response = self
+            .client
+            .post(url.clone())
+            .headers(self.headers())
+            .json(&request)
+            .send()
+            .await
+            .with_context(|| format_http_context(None, "POST", &url))?;
+        let status = response.status();
+        let ctx_msg = format_http_context(Some(status), "POST", &url);
+        let text = response
+            .text()
+            .await
+            .with_context(|| ctx_msg.clone())
+            .with_context(|| "Failed to decode response into text")?;
+        if !status.is_success() {
+            return Err(Error::InvalidStatusCode(status.as_u16()))
+                .with_context(|| format!("Invalid status code: {status} Reason: {text}"))
+                .with_context(|| ctx_msg);
+        }
+
+        let messages = serde_json::from_str::<MessageStart>(&text)
+            .with_context(|| "Failed to parse Anthropic message")
+            .and_then(|message| {
+                message
+                    .into_messages()
+                    .with_context(|| format!("Failed to create completion message: {text}"))
+            })
+            .with_context(|| ctx_msg)?;
+        debug!("Received completion from Upstream");
+
+        Ok(Box::pin(tokio_stream::iter(messages.into_iter().map(Ok))))
+    }
+
     pub async fn models(&self) -> anyhow::Result<Vec<Model>> {
         let url = self.url("models")?;
         debug!(url = %url, "Fetching models");
@@ -286,6 +332,7 @@ mod tests {
                 name: ToolName::new("math"),
                 call_id: Some(ToolCallId::new("math-1")),
                 output: ToolOutput::text(serde_json::json!({"result": 4}).to_string()),
+                pinned: false,
             }])
             .tool_choice(ToolChoice::Call(ToolName::new("math")));
         let request = Request::try_from(context)
@@ -296,6 +343,32 @@ mod tests {
         insta::assert_snapshot!(serde_json::to_string_pretty(&request).unwrap());
     }
 
+    #[test]
+    fn test_request_carries_tool_choice() {
+        let fixture = [
+            ToolChoice::Auto,
+            ToolChoice::None,
+            ToolChoice::Required,
+            ToolChoice::Call(ToolName::new("math")),
+        ];
+
+        let actual = fixture
+            .map(|tool_choice| {
+                let request =
+                    Request::try_from(Context::default().tool_choice(tool_choice)).unwrap();
+                serde_json::to_value(request).unwrap()["tool_choice"].clone()
+            })
+            .to_vec();
+
+        let expected = vec![
+            serde_json::json!({"type": "auto"}),
+            serde_json::json!({"type": "none"}),
+            serde_json::json!({"type": "any"}),
+            serde_json::json!({"type": "tool", "name": "math"}),
+        ];
+        assert_eq!(actual, expected);
+    }
+
     #[tokio::test]
     async fn test_fetch_models_success() -> anyhow::Result<()> {
         let mut fixture = MockServer::new().await;
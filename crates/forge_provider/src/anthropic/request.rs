@@ -280,6 +280,7 @@ pub enum ToolChoice {
         #[serde(skip_serializing_if = "Option::is_none")]
         disable_parallel_tool_use: Option<bool>,
     },
+    None,
     Tool {
         name: String,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -300,9 +301,7 @@ impl From<forge_app::domain::ToolChoice> for ToolChoice {
             forge_app::domain::ToolChoice::Required => {
                 ToolChoice::Any { disable_parallel_tool_use: None }
             }
-            forge_app::domain::ToolChoice::None => {
-                ToolChoice::Auto { disable_parallel_tool_use: None }
-            }
+            forge_app::domain::ToolChoice::None => ToolChoice::None,
         }
     }
 }
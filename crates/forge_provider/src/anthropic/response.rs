@@ -28,6 +28,7 @@ impl From<Model> for forge_app::domain::Model {
             tools_supported: Some(true),
             supports_parallel_tool_calls: None,
             supports_reasoning: None,
+            supports_vision: Some(true),
         }
     }
 }
@@ -44,6 +45,25 @@ pub struct MessageStart {
     pub usage: Usage,
 }
 
+impl MessageStart {
+    /// Replays a complete, non-streamed message as the parts it would have
+    /// been streamed in
+    pub fn into_messages(self) -> anyhow::Result<Vec<ChatCompletionMessage>> {
+        let mut messages = self
+            .content
+            .into_iter()
+            .map(ChatCompletionMessage::try_from)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let mut last = ChatCompletionMessage::assistant(Content::part(""));
+        if let Some(stop_reason) = self.stop_reason {
+            last = last.finish_reason(stop_reason);
+        }
+        last.usage = Some(self.usage.into());
+        messages.push(last);
+        Ok(messages)
+    }
+}
+
 #[derive(Deserialize, PartialEq, Clone, Debug)]
 pub struct Usage {
     pub input_tokens: Option<usize>,
@@ -289,6 +309,29 @@ impl TryFrom<ContentBlock> for ChatCompletionMessage {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_complete_message_is_replayed_as_parts() {
+        let fixture: MessageStart = serde_json::from_str(
+            r#"{"id":"msg_1","type":"message","role":"assistant","content":[{"type":"text","text":"Reading the file"},{"type":"tool_use","id":"toolu_1","name":"forge_tool_fs_read","input":{"path":"src/main.rs"}}],"model":"claude-3-opus-20240229","stop_reason":"tool_use","stop_sequence":null,"usage":{"input_tokens":10,"output_tokens":20}}"#,
+        )
+        .unwrap();
+
+        let actual = fixture.clone().into_messages().unwrap();
+
+        let expected = vec![
+            ChatCompletionMessage::assistant(Content::part("Reading the file")),
+            ChatCompletionMessage::assistant(Content::part("")).add_tool_call(ToolCallPart {
+                call_id: Some(ToolCallId::new("toolu_1")),
+                name: Some(ToolName::new("forge_tool_fs_read")),
+                arguments_part: r#"{"path":"src/main.rs"}"#.to_string(),
+            }),
+            ChatCompletionMessage::assistant(Content::part(""))
+                .finish_reason(StopReason::ToolUse)
+                .usage(fixture.usage),
+        ];
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_unknow_event() {
         let event = r#"{"type": "random_error", "error": {"type": "overloaded_error", "message": "Overloaded"}}"#;
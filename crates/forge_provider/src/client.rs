@@ -2,17 +2,22 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context as _, Result};
 use derive_setters::Setters;
 use forge_app::domain::{
-    ChatCompletionMessage, Context, HttpConfig, Model, ModelId, Provider, ResultStream, RetryConfig,
+    BoxStream, ChatCompletionMessage, Context, FinishReason, HttpConfig, Model, ModelId, Provider,
+    ResultStream, RetryConfig,
 };
 use reqwest::redirect::Policy;
 use tokio::sync::RwLock;
 use tokio_stream::StreamExt;
 
 use crate::anthropic::Anthropic;
+use crate::error::Error;
+use crate::gemini::Gemini;
+use crate::ollama::Ollama;
 use crate::openai::ForgeProvider;
 use crate::retry::into_retry;
 
@@ -70,22 +75,49 @@ impl ClientBuilder {
                     .with_context(|| format!("Failed to initialize: {url}"))?,
             ),
 
-            Provider::Anthropic { url, key } => InnerClient::Anthropic(
+            Provider::Anthropic { url, key, version } => InnerClient::Anthropic(
                 Anthropic::builder()
                     .client(client)
                     .api_key(key.to_string())
                     .base_url(url.clone())
-                    .anthropic_version("2023-06-01".to_string())
+                    .anthropic_version(
+                        version
+                            .clone()
+                            .unwrap_or_else(|| Provider::ANTHROPIC_VERSION.to_string()),
+                    )
                     .build()
                     .with_context(|| {
                         format!("Failed to initialize Anthropic client with URL: {url}")
                     })?,
             ),
+
+            Provider::Gemini { url, key } => InnerClient::Gemini(
+                Gemini::builder()
+                    .client(client)
+                    .api_key(key.to_string())
+                    .base_url(url.clone())
+                    .build()
+                    .with_context(|| {
+                        format!("Failed to initialize Gemini client with URL: {url}")
+                    })?,
+            ),
+
+            Provider::Ollama { url } => InnerClient::Ollama(
+                Ollama::builder()
+                    .client(client)
+                    .base_url(url.clone())
+                    .build()
+                    .with_context(|| {
+                        format!("Failed to initialize Ollama client with URL: {url}")
+                    })?,
+            ),
         };
 
         Ok(Client {
             inner: Arc::new(inner),
             retry_config,
+            stream_idle_timeout: Duration::from_secs(timeout_config.stream_idle_timeout),
+            request_timeout: timeout_config.request_timeout.map(Duration::from_secs),
             models_cache: Arc::new(RwLock::new(HashMap::new())),
         })
     }
@@ -94,6 +126,8 @@ impl ClientBuilder {
 #[derive(Clone)]
 pub struct Client {
     retry_config: Arc<RetryConfig>,
+    stream_idle_timeout: Duration,
+    request_timeout: Option<Duration>,
     inner: Arc<InnerClient>,
     models_cache: Arc<RwLock<HashMap<ModelId, Model>>>,
 }
@@ -101,6 +135,8 @@ pub struct Client {
 enum InnerClient {
     OpenAICompat(ForgeProvider),
     Anthropic(Anthropic),
+    Gemini(Gemini),
+    Ollama(Ollama),
 }
 
 impl Client {
@@ -113,6 +149,8 @@ impl Client {
         let models = self.clone().retry(match self.inner.as_ref() {
             InnerClient::OpenAICompat(provider) => provider.models().await,
             InnerClient::Anthropic(provider) => provider.models().await,
+            InnerClient::Gemini(provider) => provider.models().await,
+            InnerClient::Ollama(provider) => provider.models().await,
         })?;
 
         // Update the cache with all fetched models
@@ -134,15 +172,48 @@ impl Client {
         model: &ModelId,
         context: Context,
     ) -> ResultStream<ChatCompletionMessage, anyhow::Error> {
-        let chat_stream = self.clone().retry(match self.inner.as_ref() {
-            InnerClient::OpenAICompat(provider) => provider.chat(model, context).await,
-            InnerClient::Anthropic(provider) => provider.chat(model, context).await,
+        // The deadline covers both sending the request and streaming the response
+        let deadline = self
+            .request_timeout
+            .map(|timeout| (tokio::time::Instant::now() + timeout, timeout.as_secs()));
+        let request = async {
+            match self.inner.as_ref() {
+                InnerClient::OpenAICompat(provider) => provider.chat(model, context).await,
+                InnerClient::Anthropic(provider) => provider.chat(model, context).await,
+                InnerClient::Gemini(provider) => provider.chat(model, context).await,
+                InnerClient::Ollama(provider) => provider.chat(model, context).await,
+            }
+        };
+        let chat_stream = self.clone().retry(match deadline {
+            Some((deadline, secs)) => tokio::time::timeout_at(deadline, request)
+                .await
+                .unwrap_or_else(|_| Err(Error::RequestTimeout(secs).into())),
+            None => request.await,
         })?;
 
+        // A stalled stream is aborted once no chunk arrives within the idle timeout
+        let idle_timeout = self.stream_idle_timeout;
+        let mut is_stalled = false;
+        let chat_stream = chat_stream.timeout(idle_timeout).map_while(move |item| {
+            if is_stalled {
+                return None;
+            }
+            Some(item.unwrap_or_else(|_| {
+                is_stalled = true;
+                Err(Error::StreamIdleTimeout(idle_timeout.as_secs()).into())
+            }))
+        });
+
+        let chat_stream: BoxStream<ChatCompletionMessage, anyhow::Error> = match deadline {
+            Some((deadline, secs)) => with_deadline(Box::pin(chat_stream), deadline, secs),
+            None => Box::pin(chat_stream),
+        };
+
         let this = self.clone();
-        Ok(Box::pin(
-            chat_stream.map(move |item| this.clone().retry(item)),
-        ))
+        Ok(Box::pin(chat_stream.map(move |item| {
+            let item = item.and_then(ensure_not_filtered);
+            this.clone().retry(item)
+        })))
     }
 
     pub async fn models(&self) -> anyhow::Result<Vec<Model>> {
@@ -165,12 +236,47 @@ impl Client {
     }
 }
 
+/// Fails a response the provider stopped because of its content filter, so
+/// that the refusal isn't taken for a complete answer. The finish reason is
+/// part of the protocol, unlike the error codes in the retry config
+fn ensure_not_filtered(message: ChatCompletionMessage) -> Result<ChatCompletionMessage> {
+    if message.finish_reason == Some(FinishReason::ContentFilter) {
+        Err(Error::ContentFiltered { category: None }.into())
+    } else {
+        Ok(message)
+    }
+}
+
+/// Ends the stream with a [`Error::RequestTimeout`] once the deadline passes,
+/// however steadily the chunks keep arriving
+fn with_deadline<S>(
+    stream: S,
+    deadline: tokio::time::Instant,
+    secs: u64,
+) -> BoxStream<ChatCompletionMessage, anyhow::Error>
+where
+    S: futures::Stream<Item = Result<ChatCompletionMessage>> + Send + Unpin + 'static,
+{
+    Box::pin(futures::stream::unfold(
+        Some(stream),
+        move |stream| async move {
+            let mut stream = stream?;
+            match tokio::time::timeout_at(deadline, stream.next()).await {
+                Ok(Some(item)) => Some((item, Some(stream))),
+                Ok(None) => None,
+                Err(_) => Some((Err(Error::RequestTimeout(secs).into()), None)),
+            }
+        },
+    ))
+}
+
 #[cfg(test)]
 mod tests {
-    use forge_app::domain::Provider;
+    use forge_app::domain::{Content, Error as DomainError, Provider};
     use reqwest::Url;
 
     use super::*;
+    use crate::mock_server::MockServer;
 
     #[tokio::test]
     async fn test_cache_initialization() {
@@ -234,4 +340,218 @@ mod tests {
         let cache = client.models_cache.read().await;
         assert!(cache.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_stalled_stream_is_retried_after_idle_timeout() {
+        let mut fixture = MockServer::new().await;
+        let chunk = serde_json::json!({
+            "id": "chunk-1",
+            "model": "test-model",
+            "object": "chat.completion.chunk",
+            "created": 0,
+            "choices": [{"finish_reason": null, "delta": {"content": "Hello"}}]
+        });
+        fixture
+            .mock_stalled_chat(vec![chunk], Duration::from_secs(3))
+            .await;
+        let provider = Provider::OpenAI { url: Url::parse(&fixture.url()).unwrap(), key: None };
+        let client = ClientBuilder::new(provider, "dev")
+            .timeout_config(HttpConfig { stream_idle_timeout: 1, ..HttpConfig::default() })
+            .build()
+            .unwrap();
+
+        // Requests again while the stream fails with a retryable error, as the
+        // orchestrator does
+        let mut responses = Vec::new();
+        loop {
+            let response = client
+                .chat(&ModelId::new("test-model"), Context::default())
+                .await
+                .unwrap()
+                .collect::<Vec<_>>()
+                .await;
+            let is_retryable = response.iter().any(|message| {
+                message.as_ref().is_err_and(|error| {
+                    matches!(
+                        error.downcast_ref::<DomainError>(),
+                        Some(DomainError::Retryable(_))
+                    )
+                })
+            });
+            responses.push(response);
+            if !is_retryable || responses.len() == 3 {
+                break;
+            }
+        }
+
+        // The first attempt stalls after its first chunk, the second one completes
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].len(), 2);
+        assert!(responses[1].iter().all(|message| message.is_ok()));
+        assert_eq!(
+            responses[1][0].as_ref().unwrap().content,
+            Some(Content::part("Hello"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_long_generation_is_aborted_at_request_timeout() {
+        let mut fixture = MockServer::new().await;
+        let chunk = serde_json::json!({
+            "id": "chunk-1",
+            "model": "test-model",
+            "object": "chat.completion.chunk",
+            "created": 0,
+            "choices": [{"finish_reason": null, "delta": {"content": "Hello"}}]
+        });
+        // Chunks keep arriving well within the idle timeout for 4 seconds
+        fixture
+            .mock_slow_chat(vec![chunk; 10], Duration::from_millis(400))
+            .await;
+        let provider = Provider::OpenAI { url: Url::parse(&fixture.url()).unwrap(), key: None };
+        let client = ClientBuilder::new(provider, "dev")
+            .timeout_config(HttpConfig { request_timeout: Some(1), ..HttpConfig::default() })
+            .build()
+            .unwrap();
+
+        let started = tokio::time::Instant::now();
+        let actual = client
+            .chat(&ModelId::new("test-model"), Context::default())
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert!(started.elapsed() < Duration::from_secs(2));
+        let (error, chunks) = actual.split_last().unwrap();
+        assert!(chunks.len() < 10);
+        assert!(chunks.iter().all(|chunk| chunk.is_ok()));
+        let error = error.as_ref().unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::RequestTimeout(1))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_content_filter_refusal_is_classified_and_not_retried() {
+        let mut fixture = MockServer::new().await;
+        let mock = fixture
+            .mock_chat_error(
+                400,
+                serde_json::json!({
+                    "error": {
+                        "code": "content_filter",
+                        "message": "The response was filtered due to the prompt triggering the content management policy.",
+                        "innererror": {
+                            "code": "ResponsibleAIPolicyViolation",
+                            "content_filter_result": {
+                                "hate": {"filtered": true, "severity": "high"},
+                                "violence": {"filtered": false, "severity": "safe"}
+                            }
+                        }
+                    }
+                }),
+            )
+            .await;
+        let provider = Provider::OpenAI { url: Url::parse(&fixture.url()).unwrap(), key: None };
+        // Even a status that's otherwise retried isn't retried for a refusal
+        let client = ClientBuilder::new(provider, "dev")
+            .retry_config(Arc::new(
+                RetryConfig::default().retry_status_codes(vec![400]),
+            ))
+            .build()
+            .unwrap();
+
+        let actual = client
+            .chat(&ModelId::new("test-model"), Context::default())
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        mock.assert_async().await;
+        let error = actual[0].as_ref().unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::ContentFiltered { category: Some(category) }) if category == "hate"
+        ));
+        assert!(!matches!(
+            error.downcast_ref::<DomainError>(),
+            Some(DomainError::Retryable(_))
+        ));
+        assert_eq!(
+            error.to_string(),
+            "The provider's content filter refused the request as hate, rephrase it or leave out the flagged content as it would be refused again unchanged"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_response_stopped_by_content_filter_is_classified() {
+        let mut fixture = MockServer::new().await;
+        let chunk = serde_json::json!({
+            "id": "chunk-1",
+            "model": "test-model",
+            "object": "chat.completion.chunk",
+            "created": 0,
+            "choices": [{"finish_reason": "content_filter", "delta": {"content": ""}}]
+        });
+        fixture.mock_slow_chat(vec![chunk], Duration::ZERO).await;
+        let provider = Provider::OpenAI { url: Url::parse(&fixture.url()).unwrap(), key: None };
+        // The finish reason is a refusal whatever codes are configured
+        let client = ClientBuilder::new(provider, "dev")
+            .retry_config(Arc::new(
+                RetryConfig::default().content_filter_codes(Vec::<String>::new()),
+            ))
+            .build()
+            .unwrap();
+
+        let actual = client
+            .chat(&ModelId::new("test-model"), Context::default())
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        let error = actual.last().unwrap().as_ref().unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::ContentFiltered { category: None })
+        ));
+        assert!(!matches!(
+            error.downcast_ref::<DomainError>(),
+            Some(DomainError::Retryable(_))
+        ));
+    }
+
+    async fn assert_anthropic_version_header(version: Option<&str>, expected: &str) {
+        let mut fixture = MockServer::new().await;
+        let mock = fixture
+            .mock_models_with_header(
+                serde_json::json!({"data": [], "has_more": false}),
+                "anthropic-version",
+                expected,
+            )
+            .await;
+        let provider = Provider::Anthropic {
+            url: Url::parse(&format!("{}/", fixture.url())).unwrap(),
+            key: "sk-test-key".to_string(),
+            version: version.map(str::to_string),
+        };
+        let client = ClientBuilder::new(provider, "dev").build().unwrap();
+
+        client.models().await.unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_configured_anthropic_version_header_is_sent() {
+        assert_anthropic_version_header(Some("2025-01-01"), "2025-01-01").await;
+    }
+
+    #[tokio::test]
+    async fn test_default_anthropic_version_header_is_sent_when_unset() {
+        assert_anthropic_version_header(None, "2023-06-01").await;
+    }
 }
@@ -13,6 +13,10 @@ pub enum Error {
     #[error("{0}")]
     Anthropic(AnthropicErrorResponse),
 
+    #[error("{0}")]
+    #[from(skip)]
+    Ollama(String),
+
     #[error("Missing tool name")]
     ToolCallMissingName,
 
@@ -24,6 +28,19 @@ pub enum Error {
 
     #[error("Invalid Status Code: {0}")]
     InvalidStatusCode(u16),
+
+    #[error("No response received from the provider for {0} seconds")]
+    StreamIdleTimeout(u64),
+
+    #[error("The request took longer than the configured limit of {0} seconds")]
+    RequestTimeout(u64),
+
+    #[error(
+        "The provider's content filter refused the request{}, rephrase it or leave out the flagged content as it would be refused again unchanged",
+        category.as_ref().map(|category| format!(" as {category}")).unwrap_or_default()
+    )]
+    #[from(skip)]
+    ContentFiltered { category: Option<String> },
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -0,0 +1,4 @@
+mod provider;
+mod request;
+mod response;
+pub use provider::Gemini;
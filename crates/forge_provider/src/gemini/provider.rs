@@ -0,0 +1,366 @@
+use anyhow::Context as _;
+use derive_builder::Builder;
+use forge_app::domain::{ChatCompletionMessage, Context, Model, ModelId, ResultStream};
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::{Client, Url};
+use reqwest_eventsource::{Event, RequestBuilderExt};
+use tokio_stream::StreamExt;
+use tracing::debug;
+
+use super::request::Request;
+use super::response::{ListModelResponse, Response};
+use crate::error::Error;
+use crate::utils::format_http_context;
+
+#[derive(Clone, Builder)]
+pub struct Gemini {
+    client: Client,
+    api_key: String,
+    base_url: Url,
+}
+
+impl Gemini {
+    pub fn builder() -> GeminiBuilder {
+        GeminiBuilder::default()
+    }
+
+    fn url(&self, path: &str) -> anyhow::Result<Url> {
+        // Validate the path doesn't contain certain patterns
+        if path.contains("://") || path.contains("..") {
+            anyhow::bail!("Invalid path: Contains forbidden patterns");
+        }
+
+        // Remove leading slash to avoid double slashes
+        let path = path.trim_start_matches('/');
+
+        self.base_url
+            .join(path)
+            .with_context(|| format!("Failed to append {} to base URL: {}", path, self.base_url))
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+
+        // note: gemini api takes the api key in the `x-goog-api-key` header.
+        headers.insert(
+            "x-goog-api-key",
+            HeaderValue::from_str(self.api_key.as_str()).unwrap(),
+        );
+        headers
+    }
+}
+
+impl Gemini {
+    pub async fn chat(
+        &self,
+        model: &ModelId,
+        context: Context,
+    ) -> ResultStream<ChatCompletionMessage, anyhow::Error> {
+        let stream = context.stream.unwrap_or(true);
+        let request = Request::try_from(context)?;
+
+        if !stream {
+            let url = self.url(&format!("models/{model}:generateContent"))?;
+            debug!(url = %url, model = %model, "Connecting Upstream");
+            return self.complete(url, request).await;
+        }
+
+        let mut url = self.url(&format!("models/{model}:streamGenerateContent"))?;
+        url.set_query(Some("alt=sse"));
+        debug!(url = %url, model = %model, "Connecting Upstream");
+
+        let es = self
+            .client
+            .post(url.clone())
+            .headers(self.headers())
+            .json(&request)
+            .eventsource()
+            .with_context(|| format_http_context(None, "POST", &url))?;
+
+        let stream = es
+            .take_while(|message| !matches!(message, Err(reqwest_eventsource::Error::StreamEnded)))
+            .then(|event| async {
+                match event {
+                    Ok(Event::Open) => vec![],
+                    Ok(Event::Message(message)) if message.data.is_empty() => vec![],
+                    Ok(Event::Message(message)) => {
+                        match serde_json::from_str::<Response>(&message.data).with_context(|| {
+                            format!("Failed to parse Gemini response: {}", message.data)
+                        }) {
+                            Ok(response) => response.into_messages().into_iter().map(Ok).collect(),
+                            Err(error) => vec![Err(error)],
+                        }
+                    }
+                    Err(reqwest_eventsource::Error::StreamEnded) => vec![],
+                    Err(reqwest_eventsource::Error::InvalidStatusCode(_, response)) => {
+                        let status = response.status();
+                        let body = response.text().await.ok();
+                        vec![
+                            Err(Error::InvalidStatusCode(status.as_u16())).with_context(|| {
+                                match body {
+                                    Some(body) => {
+                                        format!("Invalid status code: {status} Reason: {body}")
+                                    }
+                                    None => {
+                                        format!("Invalid status code: {status} Reason: [Unknown]")
+                                    }
+                                }
+                            }),
+                        ]
+                    }
+                    Err(error @ reqwest_eventsource::Error::InvalidContentType(..)) => {
+                        debug!(error = ?error, "Invalid content type");
+                        vec![Err(error.into())]
+                    }
+                    Err(error) => {
+                        tracing::error!(error = ?error, "Failed to receive chat completion event");
+                        vec![Err(error.into())]
+                    }
+                }
+            })
+            .flat_map(tokio_stream::iter)
+            .map(move |response| response.with_context(|| format_http_context(None, "POST", &url)));
+
+        Ok(Box::pin(stream))
+    }
+
+    /// Requests the whole response at once and replays it as the parts it
+    /// would have been streamed in
+    async fn complete(
+        &self,
+        url: Url,
+        request: Request,
+    ) -> ResultStream<ChatCompletionMessage, anyhow::Error> {
+        let response = self
+            .client
+            .post(url.clone())
+            .headers(self.headers())
+            .json(&request)
+            .send()
+            .await
+            .with_context(|| format_http_context(None, "POST", &url))?;
+        let status = response.status();
+        let ctx_msg = format_http_context(Some(status), "POST", &url);
+        let text = response
+            .text()
+            .await
+            .with_context(|| ctx_msg.clone())
+            .with_context(|| "Failed to decode response into text")?;
+        if !status.is_success() {
+            return Err(Error::InvalidStatusCode(status.as_u16()))
+                .with_context(|| format!("Invalid status code: {status} Reason: {text}"))
+                .with_context(|| ctx_msg);
+        }
+
+        let messages = serde_json::from_str::<Response>(&text)
+            .with_context(|| format!("Failed to parse Gemini response: {text}"))
+            .with_context(|| ctx_msg)?
+            .into_messages();
+        debug!("Received completion from Upstream");
+
+        Ok(Box::pin(tokio_stream::iter(messages.into_iter().map(Ok))))
+    }
+
+    pub async fn models(&self) -> anyhow::Result<Vec<Model>> {
+        let url = self.url("models")?;
+        debug!(url = %url, "Fetching models");
+
+        let response = self
+            .client
+            .get(url.clone())
+            .headers(self.headers())
+            .send()
+            .await
+            .with_context(|| format_http_context(None, "GET", &url))
+            .with_context(|| "Failed to fetch models")?;
+        let status = response.status();
+        let ctx_msg = format_http_context(Some(status), "GET", &url);
+        let text = response
+            .text()
+            .await
+            .with_context(|| ctx_msg.clone())
+            .with_context(|| "Failed to decode response into text")?;
+
+        if !status.is_success() {
+            // treat non 200 response as error.
+            return Err(anyhow::anyhow!(text))
+                .with_context(|| ctx_msg)
+                .with_context(|| "Failed to fetch the models");
+        }
+
+        let response: ListModelResponse = serde_json::from_str(&text)
+            .with_context(|| ctx_msg)
+            .with_context(|| "Failed to deserialize models response")?;
+        Ok(response
+            .models
+            .into_iter()
+            .filter(|model| model.can_generate_content())
+            .map(Into::into)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use forge_app::domain::{
+        Content, ContextMessage, FinishReason, Reasoning, ReasoningPart, ToolCallFull, ToolName,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::mock_server::MockServer;
+
+    fn create_gemini(base_url: &str) -> Gemini {
+        Gemini::builder()
+            .client(Client::new())
+            .base_url(Url::parse(&format!("{base_url}/")).unwrap())
+            .api_key("test-key".to_string())
+            .build()
+            .unwrap()
+    }
+
+    fn fixture_context() -> Context {
+        Context::default().add_message(ContextMessage::user("Read the main file", None))
+    }
+
+    async fn chat(gemini: &Gemini, context: Context) -> Vec<ChatCompletionMessage> {
+        gemini
+            .chat(&ModelId::new("gemini-2.5-pro"), context)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .map(Result::unwrap)
+            .collect()
+    }
+
+    #[test]
+    fn test_url_for_stream() {
+        let gemini = create_gemini("https://generativelanguage.googleapis.com/v1beta");
+
+        let actual = gemini
+            .url("models/gemini-2.5-pro:streamGenerateContent")
+            .unwrap();
+
+        assert_eq!(
+            actual.as_str(),
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-pro:streamGenerateContent"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streamed_candidates_are_parsed() {
+        let mut fixture = MockServer::new().await;
+        let mock = fixture
+            .mock_gemini_stream(
+                "gemini-2.5-pro",
+                vec![
+                    serde_json::json!({"candidates": [{"content": {"role": "model", "parts": [
+                        {"text": "Looking for it", "thought": true}
+                    ]}}]}),
+                    serde_json::json!({"candidates": [{"content": {"role": "model", "parts": [
+                        {"text": "Reading it"}
+                    ]}}]}),
+                    serde_json::json!({"candidates": [{"content": {"role": "model", "parts": [
+                        {"functionCall": {"name": "forge_tool_fs_read", "args": {"path": "src/main.rs"}}, "thoughtSignature": "sig-1"}
+                    ]}, "finishReason": "STOP"}]}),
+                ],
+            )
+            .await;
+
+        let actual = chat(&create_gemini(&fixture.url()), fixture_context()).await;
+
+        mock.assert_async().await;
+        let call = actual[2].tool_calls[0].as_full().cloned().unwrap();
+        let expected = vec![
+            ChatCompletionMessage::assistant(Content::part(""))
+                .reasoning(Content::part("Looking for it"))
+                .add_reasoning_detail(Reasoning::Part(vec![ReasoningPart {
+                    text: Some("Looking for it".to_string()),
+                    signature: None,
+                }])),
+            ChatCompletionMessage::assistant(Content::part("Reading it")),
+            ChatCompletionMessage::assistant(Content::part(""))
+                .add_tool_call(
+                    ToolCallFull::new(ToolName::new("forge_tool_fs_read"))
+                        .call_id(call.call_id.clone().unwrap())
+                        .arguments(serde_json::json!({"path": "src/main.rs"})),
+                )
+                .add_reasoning_detail(Reasoning::Part(vec![ReasoningPart {
+                    text: None,
+                    signature: Some("sig-1".to_string()),
+                }])),
+            ChatCompletionMessage::assistant(Content::part(""))
+                .finish_reason(FinishReason::ToolCalls),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_non_streamed_response_is_replayed() {
+        let mut fixture = MockServer::new().await;
+        let mock = fixture
+            .mock_gemini_complete(
+                "gemini-2.5-pro",
+                serde_json::json!({"candidates": [{"content": {"role": "model", "parts": [
+                    {"text": "Done"}
+                ]}, "finishReason": "STOP"}]}),
+            )
+            .await;
+
+        let actual = chat(
+            &create_gemini(&fixture.url()),
+            fixture_context().stream(false),
+        )
+        .await;
+
+        mock.assert_async().await;
+        let expected = vec![
+            ChatCompletionMessage::assistant(Content::part("Done")),
+            ChatCompletionMessage::assistant(Content::part("")).finish_reason(FinishReason::Stop),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_stream_error_status_is_reported() {
+        let mut fixture = MockServer::new().await;
+        let mock = fixture.mock_gemini_error("gemini-2.5-pro", 429).await;
+
+        let actual = create_gemini(&fixture.url())
+            .chat(&ModelId::new("gemini-2.5-pro"), fixture_context())
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        mock.assert_async().await;
+        let error = actual[0].as_ref().unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::InvalidStatusCode(429))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_models_lists_content_models() -> anyhow::Result<()> {
+        let mut fixture = MockServer::new().await;
+        let mock = fixture
+            .mock_models(
+                serde_json::json!({"models": [
+                    {"name": "models/gemini-2.5-flash", "displayName": "Gemini 2.5 Flash", "supportedGenerationMethods": ["generateContent"]},
+                    {"name": "models/text-embedding-004", "supportedGenerationMethods": ["embedContent"]}
+                ]}),
+                200,
+            )
+            .await;
+
+        let actual = create_gemini(&fixture.url()).models().await?;
+
+        mock.assert_async().await;
+        let actual = actual.into_iter().map(|model| model.id).collect::<Vec<_>>();
+        assert_eq!(actual, vec![ModelId::new("gemini-2.5-flash")]);
+        Ok(())
+    }
+}
@@ -0,0 +1,560 @@
+use derive_setters::Setters;
+use forge_app::domain::{ContextMessage, Image, ReasoningConfig, Role};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Body of a `generateContent` request
+/// ref: https://ai.google.dev/api/generate-content#request-body
+#[derive(Serialize, Default, Setters)]
+#[serde(rename_all = "camelCase")]
+#[setters(into, strip_option)]
+pub struct Request {
+    contents: Vec<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_instruction: Option<SystemInstruction>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<Tool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_config: Option<ToolConfig>,
+    generation_config: GenerationConfig,
+}
+
+impl TryFrom<forge_app::domain::Context> for Request {
+    type Error = anyhow::Error;
+    fn try_from(request: forge_app::domain::Context) -> std::result::Result<Self, Self::Error> {
+        // note: Gemini takes the system prompt apart from the conversation, the
+        // system messages are joined into a single instruction.
+        let system = request
+            .messages
+            .iter()
+            .filter_map(|message| match message {
+                ContextMessage::Text(message) if message.role == Role::System => {
+                    Some(Part::text(message.content.clone()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let declarations = request
+            .tools
+            .into_iter()
+            .map(FunctionDeclaration::try_from)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            contents: contents(request.messages),
+            system_instruction: (!system.is_empty()).then_some(SystemInstruction { parts: system }),
+            tools: if declarations.is_empty() {
+                vec![]
+            } else {
+                vec![Tool { function_declarations: declarations }]
+            },
+            tool_config: request.tool_choice.map(ToolConfig::from),
+            generation_config: GenerationConfig {
+                max_output_tokens: request.max_tokens,
+                temperature: request.temperature.map(|t| t.value()),
+                top_p: request.top_p.map(|t| t.value()),
+                top_k: request.top_k.map(|t| t.value()),
+                thinking_config: request.reasoning.and_then(ThinkingConfig::from_reasoning),
+            },
+        })
+    }
+}
+
+/// Converts the conversation apart from the system messages. The results of
+/// calls made in parallel go in a single message, as Gemini expects one
+/// response part per call of the turn before it
+fn contents(messages: Vec<ContextMessage>) -> Vec<Message> {
+    let mut contents: Vec<Message> = Vec::new();
+    let mut is_after_tool_result = false;
+    for message in messages
+        .into_iter()
+        .filter(|message| !message.has_role(Role::System))
+    {
+        let is_tool_result = matches!(message, ContextMessage::Tool(_));
+        let message = Message::from(message);
+        match contents.last_mut() {
+            Some(last) if is_tool_result && is_after_tool_result => {
+                last.parts.extend(message.parts)
+            }
+            _ => contents.push(message),
+        }
+        is_after_tool_result = is_tool_result;
+    }
+    contents
+}
+
+#[derive(Serialize)]
+pub struct SystemInstruction {
+    parts: Vec<Part>,
+}
+
+#[derive(Serialize)]
+pub struct Message {
+    role: MessageRole,
+    parts: Vec<Part>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageRole {
+    User,
+    Model,
+}
+
+impl From<ContextMessage> for Message {
+    fn from(value: ContextMessage) -> Self {
+        match value {
+            ContextMessage::Text(message) => {
+                let mut parts = Vec::new();
+
+                // Thoughts are only sent back for their signature, which lets the model
+                // pick up its reasoning where it left off. It belongs to the first
+                // function call when there's one
+                let (text, mut signature) = message
+                    .reasoning_details
+                    .into_iter()
+                    .flatten()
+                    .find_map(|reasoning| Some((reasoning.text, reasoning.signature?)))
+                    .unzip();
+                let has_tool_calls = message
+                    .tool_calls
+                    .as_ref()
+                    .is_some_and(|calls| !calls.is_empty());
+                if !has_tool_calls && let Some(signature) = signature.take() {
+                    parts.push(Part {
+                        text: text.flatten(),
+                        thought: Some(true),
+                        thought_signature: Some(signature),
+                        ..Default::default()
+                    });
+                }
+
+                // note: Gemini rejects parts without any data, so empty text is left out
+                if !message.content.is_empty() {
+                    parts.push(Part::text(message.content));
+                }
+
+                for tool_call in message.tool_calls.into_iter().flatten() {
+                    parts.push(Part {
+                        thought_signature: signature.take(),
+                        function_call: Some(FunctionCall {
+                            id: tool_call.call_id.map(|id| id.as_str().to_string()),
+                            name: tool_call.name.to_string(),
+                            args: tool_call.arguments,
+                        }),
+                        ..Default::default()
+                    });
+                }
+
+                let role = match message.role {
+                    Role::Assistant => MessageRole::Model,
+                    Role::User | Role::System => MessageRole::User,
+                };
+                Message { role, parts }
+            }
+            ContextMessage::Tool(result) => {
+                let output = result
+                    .output
+                    .values
+                    .iter()
+                    .filter_map(|value| value.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                // note: the response has to be an object, errors are reported under
+                // `error` as Gemini suggests
+                let key = if result.is_error() { "error" } else { "output" };
+                Message {
+                    role: MessageRole::User,
+                    parts: vec![Part {
+                        function_response: Some(FunctionResponse {
+                            id: result.call_id.map(|id| id.as_str().to_string()),
+                            name: result.name.to_string(),
+                            response: serde_json::json!({ key: output }),
+                        }),
+                        ..Default::default()
+                    }],
+                }
+            }
+            ContextMessage::Image(image) => {
+                Message { role: MessageRole::User, parts: vec![Part::from(image)] }
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Default, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Part {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thought: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thought_signature: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inline_data: Option<InlineData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    function_call: Option<FunctionCall>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    function_response: Option<FunctionResponse>,
+}
+
+impl Part {
+    fn text(text: impl Into<String>) -> Self {
+        Part { text: Some(text.into()), ..Default::default() }
+    }
+}
+
+impl From<Image> for Part {
+    fn from(value: Image) -> Self {
+        // Images are kept as data URLs, Gemini takes the base64 data alone
+        let data = value
+            .url()
+            .split_once(";base64,")
+            .map(|(_, data)| data.to_string())
+            .unwrap_or_else(|| value.url().clone());
+        Part {
+            inline_data: Some(InlineData { mime_type: value.mime_type().clone(), data }),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineData {
+    mime_type: String,
+    data: String,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct FunctionCall {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    name: String,
+    args: Value,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct FunctionResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    name: String,
+    response: Value,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tool {
+    function_declarations: Vec<FunctionDeclaration>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionDeclaration {
+    name: String,
+    description: String,
+    /// Takes the schema as JSON Schema, unlike `parameters` which only
+    /// accepts a subset of OpenAPI
+    parameters_json_schema: Value,
+}
+
+impl TryFrom<forge_app::domain::ToolDefinition> for FunctionDeclaration {
+    type Error = anyhow::Error;
+    fn try_from(
+        value: forge_app::domain::ToolDefinition,
+    ) -> std::result::Result<Self, Self::Error> {
+        Ok(FunctionDeclaration {
+            name: value.name.to_string(),
+            description: value.description,
+            parameters_json_schema: serde_json::to_value(value.input_schema)?,
+        })
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolConfig {
+    function_calling_config: FunctionCallingConfig,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionCallingConfig {
+    mode: FunctionCallingMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allowed_function_names: Option<Vec<String>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FunctionCallingMode {
+    Auto,
+    Any,
+    None,
+}
+
+// ref: https://ai.google.dev/gemini-api/docs/function-calling#function_calling_modes
+impl From<forge_app::domain::ToolChoice> for ToolConfig {
+    fn from(value: forge_app::domain::ToolChoice) -> Self {
+        let (mode, allowed_function_names) = match value {
+            forge_app::domain::ToolChoice::Auto => (FunctionCallingMode::Auto, None),
+            forge_app::domain::ToolChoice::None => (FunctionCallingMode::None, None),
+            forge_app::domain::ToolChoice::Required => (FunctionCallingMode::Any, None),
+            forge_app::domain::ToolChoice::Call(name) => {
+                (FunctionCallingMode::Any, Some(vec![name.to_string()]))
+            }
+        };
+        ToolConfig {
+            function_calling_config: FunctionCallingConfig { mode, allowed_function_names },
+        }
+    }
+}
+
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_output_tokens: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking_config: Option<ThinkingConfig>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ThinkingConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking_budget: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    include_thoughts: Option<bool>,
+}
+
+impl ThinkingConfig {
+    /// Maps the reasoning config, a zero budget turns thinking off and the
+    /// thoughts are returned unless they're excluded
+    fn from_reasoning(reasoning: ReasoningConfig) -> Option<Self> {
+        match reasoning.enabled {
+            Some(false) => {
+                Some(ThinkingConfig { thinking_budget: Some(0), include_thoughts: None })
+            }
+            Some(true) => Some(ThinkingConfig {
+                thinking_budget: reasoning.max_tokens,
+                include_thoughts: Some(!reasoning.exclude.unwrap_or(false)),
+            }),
+            None => reasoning.max_tokens.map(|max_tokens| ThinkingConfig {
+                thinking_budget: Some(max_tokens),
+                include_thoughts: Some(!reasoning.exclude.unwrap_or(false)),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use forge_app::domain::{
+        Context, ReasoningFull, ToolCallFull, ToolCallId, ToolChoice, ToolName, ToolOutput,
+        ToolResult,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture_reasoning(enabled: Option<bool>, max_tokens: Option<usize>) -> ReasoningConfig {
+        ReasoningConfig { enabled, max_tokens, effort: None, exclude: None }
+    }
+
+    #[test]
+    fn test_request_conversion() {
+        let fixture = Context::default()
+            .add_message(ContextMessage::system("You're an expert at math."))
+            .add_message(ContextMessage::user("what's 2 + 2 ?", None))
+            .add_message(ContextMessage::assistant(
+                "here is the tool call.",
+                None,
+                Some(vec![ToolCallFull {
+                    name: ToolName::new("math"),
+                    call_id: Some(ToolCallId::new("math-1")),
+                    arguments: serde_json::json!({"expression": "2 + 2"}),
+                }]),
+            ))
+            .add_tool_results(vec![ToolResult {
+                name: ToolName::new("math"),
+                call_id: Some(ToolCallId::new("math-1")),
+                output: ToolOutput::text("4"),
+                pinned: false,
+            }])
+            .tool_choice(ToolChoice::Call(ToolName::new("math")));
+
+        let actual = serde_json::to_value(Request::try_from(fixture).unwrap()).unwrap();
+
+        let expected = serde_json::json!({
+            "contents": [
+                {"role": "user", "parts": [{"text": "what's 2 + 2 ?"}]},
+                {"role": "model", "parts": [
+                    {"text": "here is the tool call."},
+                    {"functionCall": {"id": "math-1", "name": "math", "args": {"expression": "2 + 2"}}}
+                ]},
+                {"role": "user", "parts": [
+                    {"functionResponse": {"id": "math-1", "name": "math", "response": {"output": "4"}}}
+                ]}
+            ],
+            "systemInstruction": {"parts": [{"text": "You're an expert at math."}]},
+            "toolConfig": {"functionCallingConfig": {"mode": "ANY", "allowedFunctionNames": ["math"]}},
+            "generationConfig": {}
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parallel_tool_results_are_sent_in_one_message() {
+        let fixture = Context::default()
+            .add_message(ContextMessage::user("Read both files", None))
+            .add_message(ContextMessage::assistant(
+                "",
+                None,
+                Some(vec![
+                    ToolCallFull {
+                        name: ToolName::new("read"),
+                        call_id: Some(ToolCallId::new("read-1")),
+                        arguments: serde_json::json!({"path": "a.rs"}),
+                    },
+                    ToolCallFull {
+                        name: ToolName::new("read"),
+                        call_id: Some(ToolCallId::new("read-2")),
+                        arguments: serde_json::json!({"path": "b.rs"}),
+                    },
+                ]),
+            ))
+            .add_tool_results(vec![
+                ToolResult {
+                    name: ToolName::new("read"),
+                    call_id: Some(ToolCallId::new("read-1")),
+                    output: ToolOutput::text("fn a() {}"),
+                    pinned: false,
+                },
+                ToolResult {
+                    name: ToolName::new("read"),
+                    call_id: Some(ToolCallId::new("read-2")),
+                    output: ToolOutput::text("fn b() {}"),
+                    pinned: false,
+                },
+            ])
+            .add_message(ContextMessage::user("Now compare them", None));
+
+        let actual =
+            serde_json::to_value(Request::try_from(fixture).unwrap()).unwrap()["contents"].clone();
+
+        let expected = serde_json::json!([
+            {"role": "user", "parts": [{"text": "Read both files"}]},
+            {"role": "model", "parts": [
+                {"functionCall": {"id": "read-1", "name": "read", "args": {"path": "a.rs"}}},
+                {"functionCall": {"id": "read-2", "name": "read", "args": {"path": "b.rs"}}}
+            ]},
+            {"role": "user", "parts": [
+                {"functionResponse": {"id": "read-1", "name": "read", "response": {"output": "fn a() {}"}}},
+                {"functionResponse": {"id": "read-2", "name": "read", "response": {"output": "fn b() {}"}}}
+            ]},
+            {"role": "user", "parts": [{"text": "Now compare them"}]}
+        ]);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_thought_signatures_are_sent_back() {
+        let fixture = ContextMessage::assistant(
+            "Done",
+            Some(vec![
+                ReasoningFull {
+                    text: Some("Adding the numbers".to_string()),
+                    signature: Some("sig-1".to_string()),
+                },
+                ReasoningFull { text: Some("Unsigned".to_string()), signature: None },
+            ]),
+            None,
+        );
+
+        let actual = Message::from(fixture).parts;
+
+        let expected = vec![
+            Part {
+                text: Some("Adding the numbers".to_string()),
+                thought: Some(true),
+                thought_signature: Some("sig-1".to_string()),
+                ..Default::default()
+            },
+            Part::text("Done"),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_thought_signature_goes_with_the_first_function_call() {
+        let fixture = ContextMessage::assistant(
+            "",
+            Some(vec![ReasoningFull {
+                text: None,
+                signature: Some("sig-2".to_string()),
+            }]),
+            Some(vec![
+                ToolCallFull::new(ToolName::new("forge_tool_fs_read")),
+                ToolCallFull::new(ToolName::new("forge_tool_fs_search")),
+            ]),
+        );
+
+        let actual = Message::from(fixture)
+            .parts
+            .into_iter()
+            .map(|part| part.thought_signature)
+            .collect::<Vec<_>>();
+
+        let expected = vec![Some("sig-2".to_string()), None];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_reasoning_maps_to_thinking_config() {
+        let fixture = [
+            fixture_reasoning(Some(true), Some(2048)),
+            fixture_reasoning(Some(true), None),
+            fixture_reasoning(Some(false), Some(2048)),
+            fixture_reasoning(None, Some(1024)),
+            fixture_reasoning(None, None),
+            ReasoningConfig { exclude: Some(true), ..fixture_reasoning(Some(true), None) },
+        ];
+
+        let actual = fixture.map(ThinkingConfig::from_reasoning).to_vec();
+
+        let expected = vec![
+            Some(ThinkingConfig { thinking_budget: Some(2048), include_thoughts: Some(true) }),
+            Some(ThinkingConfig { thinking_budget: None, include_thoughts: Some(true) }),
+            Some(ThinkingConfig { thinking_budget: Some(0), include_thoughts: None }),
+            Some(ThinkingConfig { thinking_budget: Some(1024), include_thoughts: Some(true) }),
+            None,
+            Some(ThinkingConfig { thinking_budget: None, include_thoughts: Some(false) }),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_image_is_sent_as_inline_data() {
+        let fixture = Image::new_base64("aGVsbG8=".to_string(), "image/png");
+
+        let actual = Part::from(fixture);
+
+        let expected = Part {
+            inline_data: Some(InlineData {
+                mime_type: "image/png".to_string(),
+                data: "aGVsbG8=".to_string(),
+            }),
+            ..Default::default()
+        };
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,356 @@
+use forge_app::domain::{
+    ChatCompletionMessage, Content, FinishReason, ModelId, Reasoning, ReasoningPart, TokenCount,
+    ToolCallFull, ToolCallId, ToolName,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Deserialize)]
+pub struct ListModelResponse {
+    #[serde(default)]
+    pub models: Vec<Model>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Model {
+    name: String,
+    display_name: Option<String>,
+    description: Option<String>,
+    input_token_limit: Option<u64>,
+    #[serde(default)]
+    supported_generation_methods: Vec<String>,
+    thinking: Option<bool>,
+}
+
+impl Model {
+    /// Whether the model can be chatted with, embedding models can't
+    pub fn can_generate_content(&self) -> bool {
+        self.supported_generation_methods
+            .iter()
+            .any(|method| method == "generateContent")
+    }
+}
+
+impl From<Model> for forge_app::domain::Model {
+    fn from(value: Model) -> Self {
+        // note: models are listed as `models/{id}`
+        let id = value
+            .name
+            .strip_prefix("models/")
+            .unwrap_or(&value.name)
+            .to_string();
+        Self {
+            id: ModelId::new(id),
+            name: value.display_name,
+            description: value.description,
+            context_length: value.input_token_limit,
+            tools_supported: Some(true),
+            supports_parallel_tool_calls: Some(true),
+            supports_reasoning: value.thinking,
+            supports_vision: Some(true),
+        }
+    }
+}
+
+/// A complete response, or a chunk of it when streamed
+/// ref: https://ai.google.dev/api/generate-content#generatecontentresponse
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    #[serde(default)]
+    pub candidates: Vec<Candidate>,
+    pub usage_metadata: Option<UsageMetadata>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Candidate {
+    pub content: Option<CandidateContent>,
+    pub finish_reason: Option<StopReason>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct CandidateContent {
+    #[serde(default)]
+    pub parts: Vec<Part>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Part {
+    pub text: Option<String>,
+    #[serde(default)]
+    pub thought: bool,
+    pub thought_signature: Option<String>,
+    pub function_call: Option<FunctionCall>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct FunctionCall {
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub args: Value,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageMetadata {
+    pub prompt_token_count: Option<usize>,
+    pub candidates_token_count: Option<usize>,
+    pub thoughts_token_count: Option<usize>,
+    pub cached_content_token_count: Option<usize>,
+    pub total_token_count: Option<usize>,
+}
+
+impl From<UsageMetadata> for forge_app::domain::Usage {
+    fn from(usage: UsageMetadata) -> Self {
+        let prompt_tokens = usage.prompt_token_count.unwrap_or_default();
+        // Thoughts are billed as output but not counted among the candidates
+        let completion_tokens = usage.candidates_token_count.unwrap_or_default()
+            + usage.thoughts_token_count.unwrap_or_default();
+        forge_app::domain::Usage {
+            prompt_tokens: TokenCount::Actual(prompt_tokens),
+            completion_tokens: TokenCount::Actual(completion_tokens),
+            total_tokens: TokenCount::Actual(
+                usage
+                    .total_token_count
+                    .unwrap_or(prompt_tokens + completion_tokens),
+            ),
+            cached_tokens: usage
+                .cached_content_token_count
+                .map(TokenCount::Actual)
+                .unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+}
+
+/// ref: https://ai.google.dev/api/generate-content#FinishReason
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StopReason {
+    Stop,
+    MaxTokens,
+    Safety,
+    Recitation,
+    Blocklist,
+    ProhibitedContent,
+    Spii,
+    ImageSafety,
+    #[serde(other)]
+    Other,
+}
+
+impl From<StopReason> for FinishReason {
+    fn from(value: StopReason) -> Self {
+        match value {
+            StopReason::MaxTokens => FinishReason::Length,
+            StopReason::Safety
+            | StopReason::Recitation
+            | StopReason::Blocklist
+            | StopReason::ProhibitedContent
+            | StopReason::Spii
+            | StopReason::ImageSafety => FinishReason::ContentFilter,
+            StopReason::Stop | StopReason::Other => FinishReason::Stop,
+        }
+    }
+}
+
+impl From<Part> for ChatCompletionMessage {
+    fn from(part: Part) -> Self {
+        let text = part.text.unwrap_or_default();
+        let (message, thought) = if let Some(call) = part.function_call {
+            // note: Gemini only sends complete calls, and the ID is optional so
+            // one is generated to pair the call with its result
+            let call_id = call
+                .id
+                .map(ToolCallId::new)
+                .unwrap_or_else(ToolCallId::generate);
+            let call = ToolCallFull::new(ToolName::new(call.name))
+                .call_id(call_id)
+                .arguments(call.args);
+            (
+                ChatCompletionMessage::assistant(Content::part("")).add_tool_call(call),
+                None,
+            )
+        } else if part.thought {
+            let message =
+                ChatCompletionMessage::assistant(Content::part("")).reasoning(Content::part(&text));
+            (message, Some(text))
+        } else {
+            (ChatCompletionMessage::assistant(Content::part(text)), None)
+        };
+
+        // The signature of a thought can come with any part, and has to be sent
+        // back with it
+        if thought.is_none() && part.thought_signature.is_none() {
+            return message;
+        }
+        message.add_reasoning_detail(Reasoning::Part(vec![ReasoningPart {
+            text: thought,
+            signature: part.thought_signature,
+        }]))
+    }
+}
+
+impl Response {
+    /// Splits the response into a message per part, the last one carrying
+    /// the finish reason and the usage
+    pub fn into_messages(self) -> Vec<ChatCompletionMessage> {
+        let candidate = self.candidates.into_iter().next();
+        let finish_reason = candidate
+            .as_ref()
+            .and_then(|candidate| candidate.finish_reason.clone());
+        let mut messages = candidate
+            .and_then(|candidate| candidate.content)
+            .map(|content| content.parts)
+            .unwrap_or_default()
+            .into_iter()
+            .map(ChatCompletionMessage::from)
+            .collect::<Vec<_>>();
+
+        let has_tool_calls = messages
+            .iter()
+            .any(|message| !message.tool_calls.is_empty());
+        if finish_reason.is_some() || self.usage_metadata.is_some() {
+            let mut last = ChatCompletionMessage::assistant(Content::part(""));
+            // note: Gemini stops with STOP after calling tools
+            last.finish_reason = finish_reason.map(|reason| match reason {
+                StopReason::Stop if has_tool_calls => FinishReason::ToolCalls,
+                reason => reason.into(),
+            });
+            last.usage = self.usage_metadata.map(Into::into);
+            messages.push(last);
+        }
+        messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture_response(json: &str) -> Response {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_text_chunk_is_a_content_part() {
+        let fixture = fixture_response(
+            r#"{"candidates":[{"content":{"role":"model","parts":[{"text":"Hello"}]},"index":0}]}"#,
+        );
+
+        let actual = fixture.into_messages();
+
+        let expected = vec![ChatCompletionMessage::assistant(Content::part("Hello"))];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_thought_is_mapped_to_reasoning() {
+        let fixture = fixture_response(
+            r#"{"candidates":[{"content":{"role":"model","parts":[{"text":"Adding the numbers","thought":true,"thoughtSignature":"sig-1"}]}}]}"#,
+        );
+
+        let actual = fixture.into_messages();
+
+        let expected = vec![
+            ChatCompletionMessage::assistant(Content::part(""))
+                .reasoning(Content::part("Adding the numbers"))
+                .add_reasoning_detail(Reasoning::Part(vec![ReasoningPart {
+                    text: Some("Adding the numbers".to_string()),
+                    signature: Some("sig-1".to_string()),
+                }])),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_function_call_finishes_with_tool_calls() {
+        let fixture = fixture_response(
+            r#"{"candidates":[{"content":{"role":"model","parts":[{"functionCall":{"id":"call-1","name":"forge_tool_fs_read","args":{"path":"src/main.rs"}},"thoughtSignature":"sig-2"}]},"finishReason":"STOP"}],"usageMetadata":{"promptTokenCount":10,"candidatesTokenCount":5,"thoughtsTokenCount":3,"totalTokenCount":18}}"#,
+        );
+
+        let actual = fixture.into_messages();
+
+        let mut expected_last = ChatCompletionMessage::assistant(Content::part(""))
+            .finish_reason(FinishReason::ToolCalls);
+        expected_last.usage = Some(forge_app::domain::Usage {
+            prompt_tokens: TokenCount::Actual(10),
+            completion_tokens: TokenCount::Actual(8),
+            total_tokens: TokenCount::Actual(18),
+            ..Default::default()
+        });
+        let expected = vec![
+            ChatCompletionMessage::assistant(Content::part(""))
+                .add_tool_call(
+                    ToolCallFull::new(ToolName::new("forge_tool_fs_read"))
+                        .call_id(ToolCallId::new("call-1"))
+                        .arguments(serde_json::json!({"path": "src/main.rs"})),
+                )
+                .add_reasoning_detail(Reasoning::Part(vec![ReasoningPart {
+                    text: None,
+                    signature: Some("sig-2".to_string()),
+                }])),
+            expected_last,
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_function_call_without_id_gets_one() {
+        let fixture = fixture_response(
+            r#"{"candidates":[{"content":{"parts":[{"functionCall":{"name":"forge_tool_fs_read","args":{}}}]}}]}"#,
+        );
+
+        let actual = fixture.into_messages();
+
+        let call = actual[0].tool_calls[0].as_full().unwrap();
+        assert!(call.call_id.is_some());
+    }
+
+    #[test]
+    fn test_safety_stop_is_a_content_filter() {
+        let fixture =
+            fixture_response(r#"{"candidates":[{"finishReason":"SAFETY"}],"modelVersion":"x"}"#);
+
+        let actual = fixture
+            .into_messages()
+            .pop()
+            .and_then(|message| message.finish_reason);
+
+        assert_eq!(actual, Some(FinishReason::ContentFilter));
+    }
+
+    #[test]
+    fn test_only_content_models_are_listed() {
+        let fixture: ListModelResponse = serde_json::from_str(
+            r#"{"models":[{"name":"models/gemini-2.5-pro","displayName":"Gemini 2.5 Pro","inputTokenLimit":1048576,"supportedGenerationMethods":["generateContent","countTokens"],"thinking":true},{"name":"models/text-embedding-004","supportedGenerationMethods":["embedContent"]}]}"#,
+        )
+        .unwrap();
+
+        let actual = fixture
+            .models
+            .into_iter()
+            .filter(Model::can_generate_content)
+            .map(forge_app::domain::Model::from)
+            .collect::<Vec<_>>();
+
+        let expected = serde_json::json!([{
+            "id": "gemini-2.5-pro",
+            "name": "Gemini 2.5 Pro",
+            "description": null,
+            "context_length": 1048576,
+            "tools_supported": true,
+            "supports_parallel_tool_calls": true,
+            "supports_reasoning": true,
+            "supports_vision": true
+        }]);
+        let actual = serde_json::to_value(actual).unwrap();
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,8 +1,10 @@
 mod anthropic;
 mod client;
 mod error;
+mod gemini;
 #[cfg(test)]
 mod mock_server;
+mod ollama;
 mod openai;
 mod retry;
 
@@ -1,4 +1,7 @@
-use mockito::{Mock, Server, ServerGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use mockito::{Matcher, Mock, Server, ServerGuard};
 
 pub struct MockServer {
     server: ServerGuard,
@@ -19,6 +22,199 @@ impl MockServer {
             .await
     }
 
+    /// Serves the models only when the request carries the expected header
+    pub async fn mock_models_with_header(
+        &mut self,
+        body: serde_json::Value,
+        header: &str,
+        value: &str,
+    ) -> Mock {
+        self.server
+            .mock("GET", "/models")
+            .match_header(header, value)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body.to_string())
+            .create_async()
+            .await
+    }
+
+    /// Answers a chat request that asks not to be streamed with the whole
+    /// completion at once
+    pub async fn mock_chat_completion(&mut self, body: serde_json::Value) -> Mock {
+        self.server
+            .mock("POST", "/chat/completions")
+            .match_body(Matcher::PartialJson(serde_json::json!({ "stream": false })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body.to_string())
+            .create_async()
+            .await
+    }
+
+    /// Fails the chat request with the status and the error body
+    pub async fn mock_chat_error(&mut self, status: usize, body: serde_json::Value) -> Mock {
+        self.server
+            .mock("POST", "/chat/completions")
+            .with_status(status)
+            .with_header("content-type", "application/json")
+            .with_body(body.to_string())
+            .create_async()
+            .await
+    }
+
+    /// Streams the events and then, for the first request only, keeps the
+    /// connection open without sending anything else for the given duration.
+    /// Later requests get the events followed by the end of the stream
+    pub async fn mock_stalled_chat(
+        &mut self,
+        events: Vec<serde_json::Value>,
+        stall: Duration,
+    ) -> Mock {
+        let stalled = AtomicBool::new(false);
+        self.server
+            .mock("POST", "/chat/completions")
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_chunked_body(move |writer| {
+                for event in &events {
+                    writer.write_all(format!("data: {event}\n\n").as_bytes())?;
+                }
+                writer.flush()?;
+                if stalled.swap(true, Ordering::SeqCst) {
+                    writer.write_all(b"data: [DONE]\n\n")?;
+                } else {
+                    std::thread::sleep(stall);
+                }
+                Ok(())
+            })
+            .create_async()
+            .await
+    }
+
+    /// Streams the events one at a time, waiting for the given interval before
+    /// each of them, like a long generation that never stalls
+    pub async fn mock_slow_chat(
+        &mut self,
+        events: Vec<serde_json::Value>,
+        interval: Duration,
+    ) -> Mock {
+        self.server
+            .mock("POST", "/chat/completions")
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_chunked_body(move |writer| {
+                for event in &events {
+                    std::thread::sleep(interval);
+                    writer.write_all(format!("data: {event}\n\n").as_bytes())?;
+                    writer.flush()?;
+                }
+                Ok(())
+            })
+            .create_async()
+            .await
+    }
+
+    /// Streams the events as Gemini does for `streamGenerateContent`
+    pub async fn mock_gemini_stream(
+        &mut self,
+        model: &str,
+        events: Vec<serde_json::Value>,
+    ) -> Mock {
+        let body = events
+            .iter()
+            .map(|event| format!("data: {event}\r\n\r\n"))
+            .collect::<String>();
+        self.server
+            .mock(
+                "POST",
+                format!("/models/{model}:streamGenerateContent").as_str(),
+            )
+            .match_query(Matcher::UrlEncoded("alt".into(), "sse".into()))
+            .match_header("x-goog-api-key", "test-key")
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body(body)
+            .create_async()
+            .await
+    }
+
+    /// Answers Gemini's `generateContent` with the whole response at once
+    pub async fn mock_gemini_complete(&mut self, model: &str, body: serde_json::Value) -> Mock {
+        self.server
+            .mock("POST", format!("/models/{model}:generateContent").as_str())
+            .match_header("x-goog-api-key", "test-key")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body.to_string())
+            .create_async()
+            .await
+    }
+
+    /// Fails Gemini's `streamGenerateContent` with the status
+    pub async fn mock_gemini_error(&mut self, model: &str, status: usize) -> Mock {
+        self.server
+            .mock(
+                "POST",
+                format!("/models/{model}:streamGenerateContent").as_str(),
+            )
+            .match_query(Matcher::Any)
+            .with_status(status)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"error":{"code":429,"status":"RESOURCE_EXHAUSTED"}}"#)
+            .create_async()
+            .await
+    }
+
+    /// Streams the lines as Ollama does for `/api/chat`
+    pub async fn mock_ollama_chat(&mut self, lines: Vec<serde_json::Value>) -> Mock {
+        let body = lines
+            .iter()
+            .map(|line| format!("{line}\n"))
+            .collect::<String>();
+        self.server
+            .mock("POST", "/api/chat")
+            .with_status(200)
+            .with_header("content-type", "application/x-ndjson")
+            .with_body(body)
+            .create_async()
+            .await
+    }
+
+    /// Fails Ollama's `/api/chat` with the status and the error body
+    pub async fn mock_ollama_error(&mut self, status: usize, body: serde_json::Value) -> Mock {
+        self.server
+            .mock("POST", "/api/chat")
+            .with_status(status)
+            .with_header("content-type", "application/json")
+            .with_body(body.to_string())
+            .create_async()
+            .await
+    }
+
+    /// Lists the models pulled on the Ollama server
+    pub async fn mock_ollama_tags(&mut self, body: serde_json::Value) -> Mock {
+        self.server
+            .mock("GET", "/api/tags")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body.to_string())
+            .create_async()
+            .await
+    }
+
+    /// Answers Ollama's `/api/show` for the model with its details
+    pub async fn mock_ollama_show(&mut self, model: &str, body: serde_json::Value) -> Mock {
+        self.server
+            .mock("POST", "/api/show")
+            .match_body(Matcher::PartialJson(serde_json::json!({ "model": model })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body.to_string())
+            .create_async()
+            .await
+    }
+
     pub fn url(&self) -> String {
         self.server.url()
     }
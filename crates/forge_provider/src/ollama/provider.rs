@@ -0,0 +1,304 @@
+use anyhow::Context as _;
+use derive_builder::Builder;
+use forge_app::domain::{ChatCompletionMessage, Context, Model, ModelId, ResultStream};
+use futures::{Stream, StreamExt};
+use reqwest::{Client, Url};
+use tracing::debug;
+
+use super::request::Request;
+use super::response::{ListModelResponse, ModelDetails, Response};
+use crate::error::Error;
+use crate::utils::format_http_context;
+
+/// Talks to a local Ollama server, which needs no API key
+#[derive(Clone, Builder)]
+pub struct Ollama {
+    client: Client,
+    base_url: Url,
+}
+
+impl Ollama {
+    pub fn builder() -> OllamaBuilder {
+        OllamaBuilder::default()
+    }
+
+    fn url(&self, path: &str) -> anyhow::Result<Url> {
+        // Validate the path doesn't contain certain patterns
+        if path.contains("://") || path.contains("..") {
+            anyhow::bail!("Invalid path: Contains forbidden patterns");
+        }
+
+        // Remove leading slash to avoid double slashes
+        let path = path.trim_start_matches('/');
+
+        self.base_url
+            .join(path)
+            .with_context(|| format!("Failed to append {} to base URL: {}", path, self.base_url))
+    }
+}
+
+impl Ollama {
+    pub async fn chat(
+        &self,
+        model: &ModelId,
+        context: Context,
+    ) -> ResultStream<ChatCompletionMessage, anyhow::Error> {
+        let request = Request::from(context).model(model);
+        let url = self.url("api/chat")?;
+        debug!(url = %url, model = %model, "Connecting Upstream");
+
+        let response = self
+            .client
+            .post(url.clone())
+            .json(&request)
+            .send()
+            .await
+            .with_context(|| format_http_context(None, "POST", &url))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.ok();
+            return Err(Error::InvalidStatusCode(status.as_u16()))
+                .with_context(|| match body {
+                    Some(body) => format!("Invalid status code: {status} Reason: {body}"),
+                    None => format!("Invalid status code: {status} Reason: [Unknown]"),
+                })
+                .with_context(|| format_http_context(Some(status), "POST", &url));
+        }
+
+        // note: the response is streamed as a JSON object per line, when it isn't
+        // streamed the whole response is a single line
+        let stream = lines(response.bytes_stream())
+            .filter(|line| {
+                let blank = matches!(line, Ok(line) if line.trim().is_empty());
+                async move { !blank }
+            })
+            .map(move |line| {
+                line.and_then(|line| {
+                    serde_json::from_str::<Response>(&line)
+                        .with_context(|| format!("Failed to parse Ollama response: {line}"))
+                })
+                .and_then(ChatCompletionMessage::try_from)
+                .with_context(|| format_http_context(Some(status), "POST", &url))
+            });
+
+        Ok(Box::pin(stream))
+    }
+
+    pub async fn models(&self) -> anyhow::Result<Vec<Model>> {
+        let url = self.url("api/tags")?;
+        debug!(url = %url, "Fetching models");
+
+        let response = self
+            .client
+            .get(url.clone())
+            .send()
+            .await
+            .with_context(|| format_http_context(None, "GET", &url))
+            .with_context(|| "Failed to fetch models")?;
+        let status = response.status();
+        let ctx_msg = format_http_context(Some(status), "GET", &url);
+        let text = response
+            .text()
+            .await
+            .with_context(|| ctx_msg.clone())
+            .with_context(|| "Failed to decode response into text")?;
+
+        if !status.is_success() {
+            // treat non 200 response as error.
+            return Err(anyhow::anyhow!(text))
+                .with_context(|| ctx_msg)
+                .with_context(|| "Failed to fetch the models");
+        }
+
+        let response: ListModelResponse = serde_json::from_str(&text)
+            .with_context(|| ctx_msg)
+            .with_context(|| "Failed to deserialize models response")?;
+
+        // The listing doesn't tell what the models can do, their details are
+        // read one by one
+        let models = response.models.into_iter().map(|model| async move {
+            let details = self.details(&model.name).await.unwrap_or_else(|error| {
+                debug!(model = %model.name, error = ?error, "Failed to read model details");
+                ModelDetails::default()
+            });
+            details.into_model(model.name)
+        });
+        Ok(futures::future::join_all(models).await)
+    }
+
+    async fn details(&self, model: &str) -> anyhow::Result<ModelDetails> {
+        let url = self.url("api/show")?;
+        let response = self
+            .client
+            .post(url.clone())
+            .json(&serde_json::json!({ "model": model }))
+            .send()
+            .await
+            .with_context(|| format_http_context(None, "POST", &url))?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+}
+
+/// Splits the streamed body into lines, the last one doesn't need to end
+/// with a line break
+fn lines<S, B>(body: S) -> impl Stream<Item = anyhow::Result<String>>
+where
+    S: Stream<Item = reqwest::Result<B>>,
+    B: AsRef<[u8]>,
+{
+    futures::stream::unfold(
+        (Box::pin(body), Vec::<u8>::new()),
+        |(mut body, mut buffer)| async move {
+            loop {
+                if let Some(end) = buffer.iter().position(|byte| *byte == b'\n') {
+                    let line = buffer.drain(..=end).collect::<Vec<_>>();
+                    let line = String::from_utf8(line).map_err(anyhow::Error::from);
+                    return Some((line, (body, buffer)));
+                }
+                match body.next().await {
+                    Some(Ok(chunk)) => buffer.extend_from_slice(chunk.as_ref()),
+                    Some(Err(error)) => return Some((Err(error.into()), (body, buffer))),
+                    None if buffer.is_empty() => return None,
+                    None => {
+                        let line = String::from_utf8(std::mem::take(&mut buffer))
+                            .map_err(anyhow::Error::from);
+                        return Some((line, (body, buffer)));
+                    }
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use forge_app::domain::{Content, ContextMessage, FinishReason, TokenCount, Usage};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::mock_server::MockServer;
+
+    fn create_ollama(base_url: &str) -> Ollama {
+        Ollama::builder()
+            .client(Client::new())
+            .base_url(Url::parse(&format!("{base_url}/")).unwrap())
+            .build()
+            .unwrap()
+    }
+
+    fn fixture_context() -> Context {
+        Context::default().add_message(ContextMessage::user("Say hello", None))
+    }
+
+    #[tokio::test]
+    async fn test_streamed_lines_are_parsed() {
+        let mut fixture = MockServer::new().await;
+        let mock = fixture
+            .mock_ollama_chat(vec![
+                serde_json::json!({"message": {"role": "assistant", "content": "Hel"}, "done": false}),
+                serde_json::json!({"message": {"role": "assistant", "content": "lo"}, "done": false}),
+                serde_json::json!({"message": {"role": "assistant", "content": ""}, "done": true, "done_reason": "stop", "prompt_eval_count": 5, "eval_count": 2}),
+            ])
+            .await;
+
+        let actual = create_ollama(&fixture.url())
+            .chat(&ModelId::new("llama3.2"), fixture_context())
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+
+        mock.assert_async().await;
+        let mut done =
+            ChatCompletionMessage::assistant(Content::part("")).finish_reason(FinishReason::Stop);
+        done.usage = Some(Usage {
+            prompt_tokens: TokenCount::Actual(5),
+            completion_tokens: TokenCount::Actual(2),
+            total_tokens: TokenCount::Actual(7),
+            ..Default::default()
+        });
+        let expected = vec![
+            ChatCompletionMessage::assistant(Content::part("Hel")),
+            ChatCompletionMessage::assistant(Content::part("lo")),
+            done,
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_error_status_is_reported() {
+        let mut fixture = MockServer::new().await;
+        let mock = fixture
+            .mock_ollama_error(
+                404,
+                serde_json::json!({"error": "model \"llama3.2\" not found, try pulling it first"}),
+            )
+            .await;
+
+        let actual = create_ollama(&fixture.url())
+            .chat(&ModelId::new("llama3.2"), fixture_context())
+            .await;
+
+        mock.assert_async().await;
+        let error = actual.err().unwrap();
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::InvalidStatusCode(404))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_models_are_described_by_their_details() -> anyhow::Result<()> {
+        let mut fixture = MockServer::new().await;
+        let tags = fixture
+            .mock_ollama_tags(serde_json::json!({"models": [
+                {"name": "llama3.2:latest"},
+                {"name": "qwen3:8b"}
+            ]}))
+            .await;
+        let llama = fixture
+            .mock_ollama_show(
+                "llama3.2:latest",
+                serde_json::json!({"capabilities": ["completion", "tools"]}),
+            )
+            .await;
+        // Older versions of Ollama don't report the capabilities
+        let qwen = fixture
+            .mock_ollama_show("qwen3:8b", serde_json::json!({"model_info": {}}))
+            .await;
+
+        let actual = create_ollama(&fixture.url()).models().await?;
+
+        tags.assert_async().await;
+        llama.assert_async().await;
+        qwen.assert_async().await;
+        let actual = actual
+            .into_iter()
+            .map(|model| (model.id, model.tools_supported))
+            .collect::<Vec<_>>();
+        let expected = vec![
+            (ModelId::new("llama3.2:latest"), Some(true)),
+            (ModelId::new("qwen3:8b"), None),
+        ];
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_lines_are_split_across_chunks() {
+        let fixture = futures::stream::iter(vec![
+            Ok::<_, reqwest::Error>("{\"a\":".as_bytes()),
+            Ok("1}\n{\"b\"".as_bytes()),
+            Ok(":2}".as_bytes()),
+        ]);
+
+        let actual = lines(fixture).map(Result::unwrap).collect::<Vec<_>>().await;
+
+        let expected = vec!["{\"a\":1}\n".to_string(), "{\"b\":2}".to_string()];
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,255 @@
+use forge_app::domain::{ContextMessage, Role};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Body of a `/api/chat` request
+/// ref: https://github.com/ollama/ollama/blob/main/docs/api.md#generate-a-chat-completion
+#[derive(Serialize, Default)]
+pub struct Request {
+    model: String,
+    messages: Vec<Message>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<Tool>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    think: Option<bool>,
+    options: Options,
+}
+
+impl Request {
+    pub fn model(mut self, model: impl ToString) -> Self {
+        self.model = model.to_string();
+        self
+    }
+}
+
+impl From<forge_app::domain::Context> for Request {
+    fn from(request: forge_app::domain::Context) -> Self {
+        Self {
+            model: String::new(),
+            messages: request.messages.into_iter().map(Message::from).collect(),
+            tools: request.tools.into_iter().map(Tool::from).collect(),
+            stream: request.stream.unwrap_or(true),
+            // note: tool choice isn't supported, the model always picks the tools itself
+            think: request.reasoning.and_then(|reasoning| reasoning.enabled),
+            options: Options {
+                num_predict: request.max_tokens,
+                temperature: request.temperature.map(|t| t.value()),
+                top_p: request.top_p.map(|t| t.value()),
+                top_k: request.top_k.map(|t| t.value()),
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Message {
+    role: MessageRole,
+    content: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    images: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tool_calls: Vec<ToolCall>,
+    /// Name of the tool the message is the result of
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_name: Option<String>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageRole {
+    System,
+    User,
+    Assistant,
+    Tool,
+}
+
+impl From<ContextMessage> for Message {
+    fn from(value: ContextMessage) -> Self {
+        match value {
+            ContextMessage::Text(message) => Message {
+                role: match message.role {
+                    Role::System => MessageRole::System,
+                    Role::User => MessageRole::User,
+                    Role::Assistant => MessageRole::Assistant,
+                },
+                content: message.content,
+                images: vec![],
+                tool_calls: message
+                    .tool_calls
+                    .into_iter()
+                    .flatten()
+                    .map(|tool_call| ToolCall {
+                        function: Function {
+                            name: tool_call.name.to_string(),
+                            arguments: tool_call.arguments,
+                        },
+                    })
+                    .collect(),
+                tool_name: None,
+            },
+            // note: results are paired with their call by the name of the tool, Ollama
+            // doesn't use call IDs
+            ContextMessage::Tool(result) => Message {
+                role: MessageRole::Tool,
+                content: result
+                    .output
+                    .values
+                    .iter()
+                    .filter_map(|value| value.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                images: vec![],
+                tool_calls: vec![],
+                tool_name: Some(result.name.to_string()),
+            },
+            // Images are kept as data URLs, Ollama takes the base64 data alone
+            ContextMessage::Image(image) => Message {
+                role: MessageRole::User,
+                content: String::new(),
+                images: vec![
+                    image
+                        .url()
+                        .split_once(";base64,")
+                        .map(|(_, data)| data.to_string())
+                        .unwrap_or_else(|| image.url().clone()),
+                ],
+                tool_calls: vec![],
+                tool_name: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ToolCall {
+    function: Function,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Function {
+    name: String,
+    arguments: Value,
+}
+
+#[derive(Serialize)]
+pub struct Tool {
+    r#type: &'static str,
+    function: FunctionDefinition,
+}
+
+#[derive(Serialize)]
+pub struct FunctionDefinition {
+    name: String,
+    description: String,
+    parameters: Value,
+}
+
+impl From<forge_app::domain::ToolDefinition> for Tool {
+    fn from(value: forge_app::domain::ToolDefinition) -> Self {
+        Tool {
+            r#type: "function",
+            function: FunctionDefinition {
+                name: value.name.to_string(),
+                description: value.description,
+                parameters: serde_json::to_value(value.input_schema).unwrap_or_default(),
+            },
+        }
+    }
+}
+
+/// Sampling options, `num_predict` limits the tokens generated
+#[derive(Serialize, Default)]
+pub struct Options {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_predict: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_k: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use forge_app::domain::{
+        Context, Image, ReasoningConfig, ToolCallFull, ToolCallId, ToolName, ToolOutput, ToolResult,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_request_conversion() {
+        let fixture = Context::default()
+            .add_message(ContextMessage::system("You're an expert at math."))
+            .add_message(ContextMessage::user("what's 2 + 2 ?", None))
+            .add_message(ContextMessage::assistant(
+                "here is the tool call.",
+                None,
+                Some(vec![ToolCallFull {
+                    name: ToolName::new("math"),
+                    call_id: Some(ToolCallId::new("math-1")),
+                    arguments: serde_json::json!({"expression": "2 + 2"}),
+                }]),
+            ))
+            .add_tool_results(vec![ToolResult {
+                name: ToolName::new("math"),
+                call_id: Some(ToolCallId::new("math-1")),
+                output: ToolOutput::text("4"),
+                pinned: false,
+            }])
+            .max_tokens(100usize);
+
+        let actual = serde_json::to_value(Request::from(fixture).model("llama3.2")).unwrap();
+
+        let expected = serde_json::json!({
+            "model": "llama3.2",
+            "messages": [
+                {"role": "system", "content": "You're an expert at math."},
+                {"role": "user", "content": "what's 2 + 2 ?"},
+                {"role": "assistant", "content": "here is the tool call.", "tool_calls": [
+                    {"function": {"name": "math", "arguments": {"expression": "2 + 2"}}}
+                ]},
+                {"role": "tool", "content": "4", "tool_name": "math"}
+            ],
+            "stream": true,
+            "options": {"num_predict": 100}
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_image_is_sent_as_base64() {
+        let fixture = ContextMessage::Image(Image::new_base64("aW1hZ2U=".to_string(), "image/png"));
+
+        let actual = Message::from(fixture);
+
+        let expected = Message {
+            role: MessageRole::User,
+            content: String::new(),
+            images: vec!["aW1hZ2U=".to_string()],
+            tool_calls: vec![],
+            tool_name: None,
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_reasoning_turns_thinking_on_and_off() {
+        let fixture = |enabled| {
+            Context::default().reasoning(ReasoningConfig {
+                enabled,
+                max_tokens: None,
+                effort: None,
+                exclude: None,
+            })
+        };
+
+        let actual =
+            [Some(true), Some(false), None].map(|enabled| Request::from(fixture(enabled)).think);
+
+        assert_eq!(actual, [Some(true), Some(false), None]);
+    }
+}
@@ -0,0 +1,244 @@
+use forge_app::domain::{
+    ChatCompletionMessage, Content, FinishReason, ModelId, TokenCount, ToolCallFull, ToolCallId,
+    ToolName, Usage,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::Error;
+
+/// Models pulled on the Ollama server, listed by `/api/tags`
+#[derive(Deserialize)]
+pub struct ListModelResponse {
+    #[serde(default)]
+    pub models: Vec<Model>,
+}
+
+#[derive(Deserialize)]
+pub struct Model {
+    pub name: String,
+}
+
+/// Details of a model, returned by `/api/show`
+#[derive(Deserialize, Default)]
+pub struct ModelDetails {
+    /// Features of the model, e.g. `tools`, `vision` and `thinking`. Only
+    /// reported by recent versions of Ollama.
+    pub capabilities: Option<Vec<String>>,
+    #[serde(default)]
+    pub model_info: serde_json::Map<String, Value>,
+}
+
+impl ModelDetails {
+    fn supports(&self, capability: &str) -> Option<bool> {
+        self.capabilities
+            .as_ref()
+            .map(|capabilities| capabilities.iter().any(|name| name == capability))
+    }
+
+    /// The context length is keyed by the architecture, e.g.
+    /// `llama.context_length`
+    fn context_length(&self) -> Option<u64> {
+        self.model_info
+            .iter()
+            .find(|(key, _)| key.ends_with(".context_length"))
+            .and_then(|(_, value)| value.as_u64())
+    }
+
+    /// Describes the model, capabilities that aren't reported are left
+    /// unknown so that tools fall back to being described in the prompt
+    pub fn into_model(self, name: String) -> forge_app::domain::Model {
+        forge_app::domain::Model {
+            id: ModelId::new(&name),
+            name: Some(name),
+            description: None,
+            context_length: self.context_length(),
+            tools_supported: self.supports("tools"),
+            supports_parallel_tool_calls: None,
+            supports_reasoning: self.supports("thinking"),
+            supports_vision: self.supports("vision"),
+        }
+    }
+}
+
+/// A line of the streamed response, or the whole response when it isn't
+/// streamed
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Response {
+    pub message: Option<ResponseMessage>,
+    #[serde(default)]
+    pub done: bool,
+    pub done_reason: Option<String>,
+    pub prompt_eval_count: Option<usize>,
+    pub eval_count: Option<usize>,
+    /// Set instead of the message when generation fails midway
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct ResponseMessage {
+    #[serde(default)]
+    pub content: String,
+    pub thinking: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCall>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct ToolCall {
+    pub function: Function,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+impl TryFrom<Response> for ChatCompletionMessage {
+    type Error = anyhow::Error;
+
+    fn try_from(response: Response) -> Result<Self, Self::Error> {
+        if let Some(error) = response.error {
+            return Err(Error::Ollama(error).into());
+        }
+
+        let mut message = ChatCompletionMessage::assistant(Content::part(""));
+        if let Some(content) = response.message {
+            message = message.content_part(content.content);
+            if let Some(thinking) = content.thinking.filter(|thinking| !thinking.is_empty()) {
+                message = message.reasoning(Content::part(thinking));
+            }
+            // note: Ollama only sends complete calls and without an ID, one is
+            // generated to pair the call with its result
+            for tool_call in content.tool_calls {
+                message = message.add_tool_call(
+                    ToolCallFull::new(ToolName::new(tool_call.function.name))
+                        .call_id(ToolCallId::generate())
+                        .arguments(tool_call.function.arguments),
+                );
+            }
+        }
+
+        if response.done {
+            message.finish_reason = Some(match response.done_reason.as_deref() {
+                Some("length") => FinishReason::Length,
+                _ => FinishReason::Stop,
+            });
+            let prompt_tokens = response.prompt_eval_count.unwrap_or_default();
+            let completion_tokens = response.eval_count.unwrap_or_default();
+            message.usage = Some(Usage {
+                prompt_tokens: TokenCount::Actual(prompt_tokens),
+                completion_tokens: TokenCount::Actual(completion_tokens),
+                total_tokens: TokenCount::Actual(prompt_tokens + completion_tokens),
+                ..Default::default()
+            });
+        }
+
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture_response(json: &str) -> Response {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_content_chunk() {
+        let fixture = fixture_response(
+            r#"{"model":"llama3.2","created_at":"2025-01-01T00:00:00Z","message":{"role":"assistant","content":"Hello"},"done":false}"#,
+        );
+
+        let actual = ChatCompletionMessage::try_from(fixture).unwrap();
+
+        let expected = ChatCompletionMessage::assistant(Content::part("Hello"));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_thinking_is_mapped_to_reasoning() {
+        let fixture = fixture_response(
+            r#"{"message":{"role":"assistant","content":"","thinking":"Adding the numbers"},"done":false}"#,
+        );
+
+        let actual = ChatCompletionMessage::try_from(fixture).unwrap();
+
+        let expected = ChatCompletionMessage::assistant(Content::part(""))
+            .reasoning(Content::part("Adding the numbers"));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_tool_call_gets_an_id() {
+        let fixture = fixture_response(
+            r#"{"message":{"role":"assistant","content":"","tool_calls":[{"function":{"name":"forge_tool_fs_read","arguments":{"path":"src/main.rs"}}}]},"done":false}"#,
+        );
+
+        let actual = ChatCompletionMessage::try_from(fixture).unwrap();
+
+        let call = actual.tool_calls[0].as_full().unwrap();
+        assert_eq!(call.name, ToolName::new("forge_tool_fs_read"));
+        assert_eq!(call.arguments, serde_json::json!({"path": "src/main.rs"}));
+        assert!(call.call_id.is_some());
+    }
+
+    #[test]
+    fn test_done_chunk_carries_finish_reason_and_usage() {
+        let fixture = fixture_response(
+            r#"{"message":{"role":"assistant","content":""},"done":true,"done_reason":"length","prompt_eval_count":26,"eval_count":259}"#,
+        );
+
+        let actual = ChatCompletionMessage::try_from(fixture).unwrap();
+
+        let mut expected =
+            ChatCompletionMessage::assistant(Content::part("")).finish_reason(FinishReason::Length);
+        expected.usage = Some(Usage {
+            prompt_tokens: TokenCount::Actual(26),
+            completion_tokens: TokenCount::Actual(259),
+            total_tokens: TokenCount::Actual(285),
+            ..Default::default()
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_error_line_is_an_error() {
+        let fixture = fixture_response(r#"{"error":"model runner has unexpectedly stopped"}"#);
+
+        let actual = ChatCompletionMessage::try_from(fixture).unwrap_err();
+
+        assert!(matches!(
+            actual.downcast_ref::<Error>(),
+            Some(Error::Ollama(message)) if message == "model runner has unexpectedly stopped"
+        ));
+    }
+
+    #[test]
+    fn test_capabilities_describe_the_model() {
+        let fixture: ModelDetails = serde_json::from_str(
+            r#"{"capabilities":["completion","tools"],"model_info":{"general.architecture":"llama","llama.context_length":131072}}"#,
+        )
+        .unwrap();
+
+        let actual = fixture.into_model("llama3.2:latest".to_string());
+
+        let expected = serde_json::json!({
+            "id": "llama3.2:latest",
+            "name": "llama3.2:latest",
+            "description": null,
+            "context_length": 131072,
+            "tools_supported": true,
+            "supports_parallel_tool_calls": null,
+            "supports_reasoning": false,
+            "supports_vision": false
+        });
+        assert_eq!(serde_json::to_value(actual).unwrap(), expected);
+    }
+}
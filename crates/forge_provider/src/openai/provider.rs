@@ -80,7 +80,8 @@ impl ForgeProvider {
         model: &ModelId,
         context: ChatContext,
     ) -> ResultStream<ChatCompletionMessage, anyhow::Error> {
-        let mut request = Request::from(context).model(model.clone()).stream(true);
+        let stream = context.stream.unwrap_or(true);
+        let mut request = Request::from(context).model(model.clone()).stream(stream);
         let mut pipeline = ProviderPipeline::new(&self.provider);
         request = pipeline.transform(request);
 
@@ -96,6 +97,10 @@ impl ForgeProvider {
             "Connecting Upstream"
         );
 
+        if !stream {
+            return self.inner_complete(url, headers, request).await;
+        }
+
         let es = self
             .client
             .post(url.clone())
@@ -170,6 +175,47 @@ impl ForgeProvider {
         Ok(Box::pin(stream))
     }
 
+    /// Requests the whole completion at once, for proxies that mangle streamed
+    /// responses, and replays it as a single message
+    async fn inner_complete(
+        &self,
+        url: Url,
+        headers: HeaderMap,
+        request: Request,
+    ) -> ResultStream<ChatCompletionMessage, anyhow::Error> {
+        let response = self
+            .client
+            .post(url.clone())
+            .headers(headers)
+            .json(&request)
+            .send()
+            .await
+            .with_context(|| format_http_context(None, "POST", &url))?;
+        let status = response.status();
+        let ctx_message = format_http_context(Some(status), "POST", &url);
+        let body = response
+            .text()
+            .await
+            .with_context(|| ctx_message.clone())
+            .with_context(|| "Failed to decode response into text")?;
+        if !status.is_success() {
+            return Err(Error::InvalidStatusCode(status.as_u16()))
+                .with_context(|| format!("{status} Reason: {body}"))
+                .with_context(|| ctx_message);
+        }
+
+        let message = serde_json::from_str::<Response>(&body)
+            .with_context(|| format!("Failed to parse Forge Provider response: {body}"))
+            .and_then(|response| {
+                ChatCompletionMessage::try_from(response)
+                    .with_context(|| format!("Failed to create completion message: {body}"))
+            })
+            .with_context(|| ctx_message)?;
+        debug!("Received completion from Upstream");
+
+        Ok(Box::pin(tokio_stream::once(Ok(message))))
+    }
+
     async fn inner_models(&self) -> Result<Vec<forge_app::domain::Model>> {
         let url = self.url("models")?;
         debug!(url = %url, "Fetching models");
@@ -249,6 +295,14 @@ impl From<Model> for forge_app::domain::Model {
             .iter()
             .flatten()
             .any(|param| param == "reasoning");
+        // note: the modality reads as `{input}->{output}`, e.g. `text+image->text`
+        let supports_vision = value.architecture.as_ref().map(|architecture| {
+            architecture
+                .modality
+                .split("->")
+                .next()
+                .is_some_and(|input| input.split('+').any(|modality| modality == "image"))
+        });
 
         forge_app::domain::Model {
             id: value.id,
@@ -258,6 +312,7 @@ impl From<Model> for forge_app::domain::Model {
             tools_supported: Some(tools_supported),
             supports_parallel_tool_calls: Some(supports_parallel_tool_calls),
             supports_reasoning: Some(is_reasoning_supported),
+            supports_vision,
         }
     }
 }
@@ -265,6 +320,8 @@ impl From<Model> for forge_app::domain::Model {
 #[cfg(test)]
 mod tests {
     use anyhow::Context;
+    use forge_app::domain::{Content, FinishReason, ToolCallFull, ToolCallId, ToolName};
+    use pretty_assertions::assert_eq;
     use reqwest::Client;
 
     use super::*;
@@ -292,6 +349,7 @@ mod tests {
                     "name": "Test Model 1",
                     "description": "A test model",
                     "context_length": 4096,
+                    "architecture": {"modality": "text+image->text", "tokenizer": "GPT"},
                     "supported_parameters": ["tools", "supports_parallel_tool_calls"]
                 },
                 {
@@ -318,6 +376,61 @@ mod tests {
         serde_json::json!({ "data": [] })
     }
 
+    #[tokio::test]
+    async fn test_chat_without_streaming_parses_the_whole_completion() -> anyhow::Result<()> {
+        let mut fixture = MockServer::new().await;
+        let mock = fixture
+            .mock_chat_completion(serde_json::json!({
+                "id": "completion-1",
+                "model": "test-model",
+                "object": "chat.completion",
+                "created": 0,
+                "choices": [{
+                    "index": 0,
+                    "logprobs": null,
+                    "finish_reason": "tool_calls",
+                    "message": {
+                        "role": "assistant",
+                        "content": "Reading the file",
+                        "tool_calls": [{
+                            "id": "call-1",
+                            "type": "function",
+                            "function": {
+                                "name": "forge_tool_fs_read",
+                                "arguments": "{\"path\":\"src/main.rs\"}"
+                            }
+                        }]
+                    }
+                }]
+            }))
+            .await;
+        let provider = create_provider(&fixture.url())?;
+
+        let actual = provider
+            .chat(
+                &ModelId::new("test-model"),
+                ChatContext::default().stream(false),
+            )
+            .await?
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        mock.assert_async().await;
+        let expected = vec![
+            ChatCompletionMessage::assistant(Content::full("Reading the file"))
+                .finish_reason(FinishReason::ToolCalls)
+                .add_tool_call(ToolCallFull {
+                    call_id: Some(ToolCallId::new("call-1")),
+                    name: ToolName::new("forge_tool_fs_read"),
+                    arguments: serde_json::json!({ "path": "src/main.rs" }),
+                }),
+        ];
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_fetch_models_success() -> anyhow::Result<()> {
         let mut fixture = MockServer::new().await;
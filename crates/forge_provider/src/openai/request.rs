@@ -108,6 +108,32 @@ pub struct Tool {
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ResponseFormat {
     pub r#type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_schema: Option<JsonSchemaFormat>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonSchemaFormat {
+    pub name: String,
+    pub schema: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict: Option<bool>,
+}
+
+impl From<forge_app::domain::ResponseFormat> for ResponseFormat {
+    fn from(value: forge_app::domain::ResponseFormat) -> Self {
+        use forge_app::domain::ResponseFormat as Format;
+        match value {
+            Format::Text => ResponseFormat { r#type: "text".to_string(), json_schema: None },
+            Format::JsonObject => {
+                ResponseFormat { r#type: "json_object".to_string(), json_schema: None }
+            }
+            Format::JsonSchema { name, schema, strict } => ResponseFormat {
+                r#type: "json_schema".to_string(),
+                json_schema: Some(JsonSchemaFormat { name, schema, strict }),
+            },
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -254,7 +280,7 @@ impl From<Context> for Request {
             },
             model: None,
             prompt: Default::default(),
-            response_format: Default::default(),
+            response_format: context.response_format.map(ResponseFormat::from),
             stop: Default::default(),
             stream: Default::default(),
             max_tokens: context.max_tokens.map(|t| t as u32),
@@ -276,7 +302,10 @@ impl From<Context> for Request {
             route: Default::default(),
             provider: Default::default(),
             parallel_tool_calls: Some(false),
-            stream_options: Some(StreamOptions { include_usage: Some(true) }),
+            stream_options: context
+                .stream
+                .unwrap_or(true)
+                .then_some(StreamOptions { include_usage: Some(true) }),
             session_id: context.conversation_id.map(|id| id.to_string()),
             reasoning: context.reasoning,
             max_completion_tokens: Default::default(),
@@ -519,4 +548,52 @@ mod tests {
             "\"middle-out\""
         );
     }
+
+    #[test]
+    fn test_request_carries_json_schema_response_format() {
+        let fixture =
+            Context::default().response_format(forge_app::domain::ResponseFormat::JsonSchema {
+                name: "summary".to_string(),
+                schema: json!({"type": "object", "required": ["title"]}),
+                strict: Some(true),
+            });
+
+        let actual = serde_json::to_value(Request::from(fixture).response_format).unwrap();
+
+        let expected = json!({
+            "type": "json_schema",
+            "json_schema": {
+                "name": "summary",
+                "schema": {"type": "object", "required": ["title"]},
+                "strict": true
+            }
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_request_carries_tool_choice() {
+        let fixture = [
+            forge_app::domain::ToolChoice::Auto,
+            forge_app::domain::ToolChoice::None,
+            forge_app::domain::ToolChoice::Required,
+            forge_app::domain::ToolChoice::Call(ToolName::new("forge_tool_fs_read")),
+        ];
+
+        let actual = fixture
+            .map(|tool_choice| {
+                serde_json::to_value(Request::from(Context::default().tool_choice(tool_choice)))
+                    .unwrap()["tool_choice"]
+                    .clone()
+            })
+            .to_vec();
+
+        let expected = vec![
+            json!("auto"),
+            json!("none"),
+            json!("required"),
+            json!({"type": "function", "function": {"name": "forge_tool_fs_read"}}),
+        ];
+        assert_eq!(actual, expected);
+    }
 }
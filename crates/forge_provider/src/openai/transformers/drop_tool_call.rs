@@ -74,7 +74,10 @@ mod tests {
             top_p: None,
             top_k: None,
             reasoning: None,
+            response_format: None,
+            stream: None,
             usage: None,
+            last_compaction: None,
         };
 
         let request = Request::from(context);
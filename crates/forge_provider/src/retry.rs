@@ -5,6 +5,11 @@ use crate::error::{Error, ErrorResponse};
 const TRANSPORT_ERROR_CODES: [&str; 3] = ["ERR_STREAM_PREMATURE_CLOSE", "ECONNRESET", "ETIMEDOUT"];
 
 pub fn into_retry(error: anyhow::Error, retry_config: &RetryConfig) -> anyhow::Error {
+    // Refusals are never retried, whatever their status code
+    if let Some(category) = content_filter(&error, &retry_config.content_filter_codes) {
+        return error.context(Error::ContentFiltered { category });
+    }
+
     if let Some(code) = get_req_status_code(&error)
         .or(get_event_req_status_code(&error))
         .or(get_api_status_code(&error))
@@ -16,6 +21,7 @@ pub fn into_retry(error: anyhow::Error, retry_config: &RetryConfig) -> anyhow::E
     if is_api_transport_error(&error)
         || is_req_transport_error(&error)
         || is_event_transport_error(&error)
+        || is_stream_idle_timeout(&error)
         || is_empty_error(&error)
     {
         return DomainError::Retryable(error).into();
@@ -24,6 +30,75 @@ pub fn into_retry(error: anyhow::Error, retry_config: &RetryConfig) -> anyhow::E
     error
 }
 
+/// Looks for a content filter refusal in the error, either in the error
+/// response of the provider or in the body of a failed request. Returns the
+/// category of the flagged content, when the provider reports it
+fn content_filter(error: &anyhow::Error, codes: &[String]) -> Option<Option<String>> {
+    if matches!(
+        error.downcast_ref::<Error>(),
+        Some(Error::ContentFiltered { .. })
+    ) {
+        return None;
+    }
+    error.chain().find_map(|cause| {
+        let body = match cause.downcast_ref::<Error>() {
+            Some(Error::Response(response)) => serde_json::to_value(response).ok()?,
+            _ => parse_json_body(&cause.to_string())?,
+        };
+        has_content_filter_code(&body, codes).then(|| filtered_category(&body))
+    })
+}
+
+/// Parses the JSON object the message ends with, such as the body of a
+/// response appended to its status
+fn parse_json_body(message: &str) -> Option<serde_json::Value> {
+    let start = message.find('{')?;
+    serde_json::from_str(&message[start..]).ok()
+}
+
+fn has_content_filter_code(value: &serde_json::Value, codes: &[String]) -> bool {
+    match value {
+        serde_json::Value::Object(object) => object.iter().any(|(key, value)| {
+            let is_code = [
+                "code",
+                "type",
+                "finish_reason",
+                "block_reason",
+                "blockReason",
+            ]
+            .contains(&key.as_str())
+                && value
+                    .as_str()
+                    .is_some_and(|value| codes.iter().any(|code| code == value));
+            is_code || has_content_filter_code(value, codes)
+        }),
+        serde_json::Value::Array(values) => values
+            .iter()
+            .any(|value| has_content_filter_code(value, codes)),
+        _ => false,
+    }
+}
+
+/// Category of the flagged content, from the filter results some providers
+/// report along with the refusal, e.g. `{"hate": {"filtered": true}}`
+fn filtered_category(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Object(object) => object.iter().find_map(|(key, value)| {
+            let is_filtered = value
+                .get("filtered")
+                .and_then(serde_json::Value::as_bool)
+                .unwrap_or_default();
+            if is_filtered {
+                Some(key.clone())
+            } else {
+                filtered_category(value)
+            }
+        }),
+        serde_json::Value::Array(values) => values.iter().find_map(filtered_category),
+        _ => None,
+    }
+}
+
 fn get_api_status_code(error: &anyhow::Error) -> Option<u16> {
     error.downcast_ref::<Error>().and_then(|error| match error {
         Error::Response(error) => error
@@ -85,6 +160,12 @@ fn is_api_transport_error(error: &anyhow::Error) -> bool {
         })
 }
 
+fn is_stream_idle_timeout(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<Error>()
+        .is_some_and(|error| matches!(error, Error::StreamIdleTimeout(_)))
+}
+
 fn is_empty_error(error: &anyhow::Error) -> bool {
     error.downcast_ref::<Error>().is_some_and(|e| match e {
         Error::Response(error) => {
@@ -314,6 +395,39 @@ mod tests {
         assert!(is_retryable(actual));
     }
 
+    #[test]
+    fn test_into_retry_with_stream_idle_timeout() {
+        // Setup
+        let retry_config = RetryConfig::default().retry_status_codes(vec![]);
+        let error = anyhow::Error::from(Error::StreamIdleTimeout(300));
+
+        // Execute
+        let actual = into_retry(error, &retry_config);
+
+        // Verify
+        assert!(is_retryable(actual));
+    }
+
+    #[test]
+    fn test_into_retry_with_content_policy_violation() {
+        // Setup
+        let retry_config = RetryConfig::default().retry_status_codes(vec![400]);
+        let inner_error = ErrorResponse::default()
+            .code(ErrorCode::String("content_policy_violation".to_string()))
+            .message("Your request was rejected by the safety system".to_string());
+        let error = anyhow::Error::from(Error::Response(inner_error));
+
+        // Execute
+        let actual = into_retry(error, &retry_config);
+
+        // Verify - refusals aren't retried even with a retryable status code
+        assert!(matches!(
+            actual.downcast_ref::<Error>(),
+            Some(Error::ContentFiltered { category: None })
+        ));
+        assert!(!is_retryable(actual));
+    }
+
     #[test]
     fn test_is_empty_error_with_default_error_response() {
         // Setup
@@ -113,6 +113,7 @@ pub mod tests {
         fn get_environment(&self) -> Environment {
             Environment {
                 os: "test".to_string(),
+                arch: "test".to_string(),
                 pid: 12345,
                 cwd: PathBuf::from("/test"),
                 home: Some(PathBuf::from("/home/test")),
@@ -121,12 +122,24 @@ pub mod tests {
                 retry_config: Default::default(),
                 max_search_lines: 25,
                 fetch_truncation_limit: 0,
+                fetch_cache_ttl: 0,
                 stdout_max_prefix_length: 0,
                 stdout_max_suffix_length: 0,
+                tool_limits: Default::default(),
                 max_read_size: 2000,
                 http: Default::default(),
                 max_file_size: 10_000_000,
                 forge_api_url: Url::parse("http://forgecode.dev/api").unwrap(),
+                shell_env: Default::default(),
+                truncation_template: None,
+                otlp_endpoint: None,
+                debug_raw_output: false,
+                sandbox_root: None,
+                max_concurrent_requests: None,
+                redact_home: false,
+                diff_ignore_whitespace: false,
+                diff_ignore_trailing_newline: false,
+                offload_dir: None,
             }
         }
 
@@ -472,6 +485,15 @@ pub mod tests {
         ) -> anyhow::Result<std::process::ExitStatus> {
             unimplemented!()
         }
+
+        async fn execute_command_with_input(
+            &self,
+            _: String,
+            _: PathBuf,
+            _: String,
+        ) -> anyhow::Result<CommandOutput> {
+            unimplemented!()
+        }
     }
 
     #[async_trait::async_trait]
@@ -507,6 +529,20 @@ pub mod tests {
             }
             Ok(Some(options))
         }
+
+        /// Prompts the user to select multiple options from a list, returning
+        /// their positions
+        async fn select_many_positions(
+            &self,
+            _: &str,
+            options: Vec<String>,
+        ) -> anyhow::Result<Option<Vec<usize>>> {
+            // For testing, we can just return all positions
+            if options.is_empty() {
+                return Err(anyhow::anyhow!("No options provided"));
+            }
+            Ok(Some((0..options.len()).collect()))
+        }
     }
 
     // Create a composite mock service that implements the required traits
@@ -21,7 +21,12 @@ impl<F: EnvironmentInfra + WalkerInfra> ForgeDiscoveryService<F> {
         let files = self.service.walk(config).await?;
         Ok(files
             .into_iter()
-            .map(|file| File { path: file.path.clone(), is_dir: file.is_dir() })
+            .map(|file| File {
+                path: file.path.clone(),
+                is_dir: file.is_dir(),
+                size: file.size,
+                modified: file.modified,
+            })
             .collect())
     }
 }
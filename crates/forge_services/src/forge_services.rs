@@ -1,6 +1,7 @@
 use std::sync::Arc;
+use std::time::Duration;
 
-use forge_app::Services;
+use forge_app::{RequestLimit, Services, ToolchainCache};
 
 use crate::app_config::ForgeConfigService;
 use crate::attachment::ForgeChatRequest;
@@ -55,6 +56,8 @@ pub struct ForgeServices<F: McpServerInfra + WalkerInfra> {
     config_service: Arc<ForgeConfigService<F>>,
     auth_service: Arc<AuthService<F>>,
     provider_service: Arc<ForgeProviderRegistry<F>>,
+    request_limit: RequestLimit,
+    toolchain_cache: ToolchainCache,
 }
 
 impl<
@@ -86,9 +89,12 @@ impl<
         let file_patch_service = Arc::new(ForgeFsPatch::new(infra.clone()));
         let file_undo_service = Arc::new(ForgeFsUndo::new(infra.clone()));
         let shell_service = Arc::new(ForgeShell::new(infra.clone()));
-        let fetch_service = Arc::new(ForgeFetch::new());
+        let fetch_service = Arc::new(ForgeFetch::new(Duration::from_secs(
+            infra.get_environment().fetch_cache_ttl,
+        )));
         let followup_service = Arc::new(ForgeFollowup::new(infra.clone()));
         let provider_service = Arc::new(ForgeProviderRegistry::new(infra.clone()));
+        let request_limit = RequestLimit::new(infra.get_environment().max_concurrent_requests);
         let env_service = Arc::new(ForgeEnvironmentService::new(infra));
         Self {
             conversation_service,
@@ -112,6 +118,8 @@ impl<
             auth_service,
             chat_service,
             provider_service,
+            request_limit,
+            toolchain_cache: Default::default(),
         }
     }
 }
@@ -237,4 +245,12 @@ impl<
     fn provider_registry(&self) -> &Self::ProviderRegistry {
         &self.provider_service
     }
+
+    fn request_limit(&self) -> &RequestLimit {
+        &self.request_limit
+    }
+
+    fn toolchain_cache(&self) -> &ToolchainCache {
+        &self.toolchain_cache
+    }
 }
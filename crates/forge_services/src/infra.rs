@@ -1,4 +1,5 @@
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use anyhow::Result;
 use bytes::Bytes;
@@ -119,6 +120,16 @@ pub trait CommandInfra: Send + Sync {
         command: &str,
         working_dir: PathBuf,
     ) -> anyhow::Result<std::process::ExitStatus>;
+
+    /// Executes a shell command with the input written to its stdin, the
+    /// output is captured without being shown on the console. Stdout is passed
+    /// on as it was printed, so output that isn't valid UTF-8 is an error
+    async fn execute_command_with_input(
+        &self,
+        command: String,
+        working_dir: PathBuf,
+        input: String,
+    ) -> anyhow::Result<CommandOutput>;
 }
 
 #[async_trait::async_trait]
@@ -142,6 +153,15 @@ pub trait UserInfra: Send + Sync {
         message: &str,
         options: Vec<String>,
     ) -> anyhow::Result<Option<Vec<String>>>;
+
+    /// Prompts the user to select multiple options from a list, returning the
+    /// positions of the selected options
+    /// Returns None if the user interrupts the selection
+    async fn select_many_positions(
+        &self,
+        message: &str,
+        options: Vec<String>,
+    ) -> anyhow::Result<Option<Vec<usize>>>;
 }
 
 #[async_trait::async_trait]
@@ -167,6 +187,25 @@ pub trait WalkerInfra: Send + Sync {
     async fn walk(&self, config: Walker) -> anyhow::Result<Vec<WalkedFile>>;
 }
 
+#[async_trait::async_trait]
+pub trait FileWatcherInfra: Send + Sync {
+    /// Waits until files in the working directory matching the glob pattern
+    /// change and returns their paths, once no further change has been seen
+    /// for the debounce period
+    async fn wait_for_changes(
+        &self,
+        pattern: &str,
+        debounce: Duration,
+    ) -> anyhow::Result<Vec<PathBuf>>;
+}
+
+#[async_trait::async_trait]
+pub trait FileLockInfra: Send + Sync {
+    /// Takes an advisory lock through the given lock file that's held until
+    /// the returned lock is dropped
+    async fn lock_file(&self, path: &Path) -> anyhow::Result<forge_fs::FileLock>;
+}
+
 // TODO: rename me, add Infra suffix
 #[async_trait::async_trait]
 pub trait HttpInfra: Send + Sync + 'static {
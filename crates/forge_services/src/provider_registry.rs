@@ -30,14 +30,31 @@ impl<F: EnvironmentInfra> ForgeProviderRegistry<F> {
         if let Some(url) = self.infra.get_env_var("ANTHROPIC_URL") {
             return Some(ProviderUrl::Anthropic(url));
         }
+
+        if let Some(url) = self.infra.get_env_var("GEMINI_URL") {
+            return Some(ProviderUrl::Gemini(url));
+        }
+
+        if let Some(url) = self.infra.get_env_var("OLLAMA_URL") {
+            return Some(ProviderUrl::Ollama(url));
+        }
         None
     }
     fn get_provider(&self, forge_config: AppConfig) -> Option<Provider> {
+        if let Some(profile) = forge_config.profile() {
+            return Some(profile.to_provider());
+        }
         if let Some(forge_key) = &forge_config.key_info {
             let provider = Provider::forge(forge_key.api_key.as_str());
             return Some(override_url(provider, self.provider_url()));
         }
-        resolve_env_provider(self.provider_url(), self.infra.as_ref())
+        resolve_env_provider(self.provider_url(), self.infra.as_ref()).map(|mut provider| {
+            // Allows pinning or upgrading the API version without a new release
+            if let Some(version) = self.infra.get_env_var("ANTHROPIC_VERSION") {
+                provider.anthropic_version(version);
+            }
+            provider
+        })
     }
 }
 
@@ -60,21 +77,34 @@ fn resolve_env_provider<F: EnvironmentInfra>(
     url: Option<ProviderUrl>,
     env: &F,
 ) -> Option<Provider> {
-    let keys: [ProviderSearch; 6] = [
+    // Setting only the Ollama URL asks for the local server, even when a key of
+    // another provider is around
+    if let Some(ProviderUrl::Ollama(_)) = url {
+        return Some(override_url(Provider::ollama(), url));
+    }
+
+    let keys: [ProviderSearch; 7] = [
         ("FORGE_KEY", Box::new(Provider::forge)),
         ("OPENROUTER_API_KEY", Box::new(Provider::open_router)),
         ("REQUESTY_API_KEY", Box::new(Provider::requesty)),
         ("XAI_API_KEY", Box::new(Provider::xai)),
         ("OPENAI_API_KEY", Box::new(Provider::openai)),
         ("ANTHROPIC_API_KEY", Box::new(Provider::anthropic)),
+        ("GEMINI_API_KEY", Box::new(Provider::gemini)),
     ];
 
-    keys.into_iter().find_map(|(key, fun)| {
-        env.get_env_var(key).map(|key| {
-            let provider = fun(&key);
-            override_url(provider, url.clone())
+    keys.into_iter()
+        .find_map(|(key, fun)| {
+            env.get_env_var(key).map(|key| {
+                let provider = fun(&key);
+                override_url(provider, url.clone())
+            })
+        })
+        // A local Ollama server takes no key, it's used when its URL is set
+        .or_else(|| {
+            env.get_env_var("OLLAMA_URL")
+                .map(|url| override_url(Provider::ollama(), Some(ProviderUrl::Ollama(url))))
         })
-    })
 }
 
 fn override_url(mut provider: Provider, url: Option<ProviderUrl>) -> Provider {
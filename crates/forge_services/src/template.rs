@@ -133,6 +133,7 @@ impl<F: EnvironmentInfra + FileReaderInfra> TemplateService for ForgeTemplateSer
 
 #[cfg(test)]
 mod tests {
+    use forge_app::domain::{SystemContext, SystemContextSection};
     use pretty_assertions::assert_eq;
     use serde_json::json;
 
@@ -186,6 +187,54 @@ mod tests {
         assert!(actual.contains("<operating_system>test-os</operating_system>"));
     }
 
+    #[tokio::test]
+    async fn test_render_partial_scratchpad() {
+        let service = ForgeTemplateService::new(Arc::new(MockCompositeService::new()));
+        let fixture = SystemContext { scratchpad: "- [ ] emit".to_string(), ..Default::default() };
+
+        let actual = service
+            .render_template("{{> forge-partial-scratchpad.hbs }}", &fixture)
+            .await
+            .unwrap();
+
+        assert!(actual.contains("<scratchpad>\n- [ ] emit\n</scratchpad>"));
+    }
+
+    #[tokio::test]
+    async fn test_render_system_sections_in_configured_order() {
+        let service = ForgeTemplateService::new(Arc::new(MockCompositeService::new()));
+        let fixture = SystemContext {
+            current_time: "2024-01-01 12:00:00 UTC".to_string(),
+            files: vec!["/file1.txt".to_string()],
+            custom_rules: "Always write tests".to_string(),
+            tool_supported: true,
+            ..Default::default()
+        }
+        .with_sections(vec![
+            SystemContextSection::CustomRules,
+            SystemContextSection::Time,
+            SystemContextSection::Files,
+        ]);
+
+        let rendered = service
+            .render_template("{{> forge-partial-system-sections.hbs }}", &fixture)
+            .await
+            .unwrap();
+        let actual = rendered
+            .lines()
+            .filter(|line| line.starts_with('<') && !line.starts_with("</"))
+            .map(|line| {
+                line.trim_start_matches('<')
+                    .split('>')
+                    .next()
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+
+        let expected = vec!["custom_rules", "system_time", "file_list"];
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_compile_template_hbs_file() {
         // Fixture: Create a handlebars template content and test data
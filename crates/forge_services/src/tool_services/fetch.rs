@@ -1,29 +1,82 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
 use anyhow::{Context, anyhow};
-use forge_app::{HttpResponse, NetFetchService, ResponseContext};
-use reqwest::{Client, Url};
+use forge_app::{FetchRequest, HttpResponse, NetFetchService, ResponseContext};
+use reqwest::{Client, Method, Url};
+
+/// Identifies a GET, the only fetches that are reused, by its URL and
+/// headers along with whether the content is converted
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct FetchKey {
+    url: String,
+    headers: BTreeMap<String, String>,
+    raw: bool,
+}
+
+/// Responses fetched during the session, reused for repeated fetches until
+/// they expire
+#[derive(Debug)]
+struct FetchCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<FetchKey, (Instant, HttpResponse)>>,
+}
+
+impl FetchCache {
+    fn new(ttl: Duration) -> Self {
+        Self { ttl, entries: Mutex::default() }
+    }
+
+    fn get(&self, key: &FetchKey) -> Option<HttpResponse> {
+        let entries = self.entries.lock().unwrap();
+        let (fetched_at, response) = entries.get(key)?;
+        (fetched_at.elapsed() < self.ttl).then(|| HttpResponse { cached: true, ..response.clone() })
+    }
+
+    /// Returns the cached response for the key, or awaits `fetch` and caches
+    /// its response. With `no_cache` the response is always fetched, and
+    /// replaces the cached one.
+    async fn get_or_fetch(
+        &self,
+        key: FetchKey,
+        no_cache: bool,
+        fetch: impl Future<Output = anyhow::Result<HttpResponse>>,
+    ) -> anyhow::Result<HttpResponse> {
+        if !no_cache && let Some(response) = self.get(&key) {
+            return Ok(response);
+        }
+        let response = fetch.await?;
+        if !self.ttl.is_zero() {
+            self.entries
+                .lock()
+                .unwrap()
+                .insert(key, (Instant::now(), response.clone()));
+        }
+        Ok(response)
+    }
+}
 
 /// Retrieves content from URLs as markdown or raw text. Enables access to
 /// current online information including websites, APIs and documentation. Use
 /// for obtaining up-to-date information beyond training data, verifying facts,
 /// or retrieving specific online content. Handles HTTP/HTTPS and converts HTML
-/// to readable markdown by default. Cannot access private/restricted resources
-/// requiring authentication. Respects robots.txt and may be blocked by
+/// to readable markdown by default. Requests can use another method and carry
+/// headers and a body, e.g. to call an authenticated JSON API, in which case
+/// the response is returned untouched. Respects robots.txt and may be blocked by
 /// anti-scraping measures. For large pages, returns the first 40,000 characters
 /// and stores the complete content in a temporary file for subsequent access.
+///
+/// Responses are reused for `cache_ttl`, a zero duration disables the cache.
 #[derive(Debug)]
 pub struct ForgeFetch {
     client: Client,
-}
-
-impl Default for ForgeFetch {
-    fn default() -> Self {
-        Self::new()
-    }
+    cache: FetchCache,
 }
 
 impl ForgeFetch {
-    pub fn new() -> Self {
-        Self { client: Client::new() }
+    pub fn new(cache_ttl: Duration) -> Self {
+        Self { client: Client::new(), cache: FetchCache::new(cache_ttl) }
     }
 }
 
@@ -62,12 +115,23 @@ impl ForgeFetch {
         Ok(())
     }
 
-    async fn fetch_url(&self, url: &Url, force_raw: bool) -> anyhow::Result<HttpResponse> {
+    async fn fetch_url(
+        &self,
+        url: &Url,
+        method: Method,
+        request: &FetchRequest,
+        force_raw: bool,
+    ) -> anyhow::Result<HttpResponse> {
         self.check_robots_txt(url).await?;
 
-        let response = self
-            .client
-            .get(url.as_str())
+        let mut builder = self.client.request(method, url.as_str());
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = &request.body {
+            builder = builder.body(body.clone());
+        }
+        let response = builder
             .send()
             .await
             .map_err(|e| anyhow!("Failed to fetch URL {}: {}", url, e))?;
@@ -99,13 +163,20 @@ impl ForgeFetch {
 
         if is_page_html && !force_raw {
             let content = html2md::parse_html(&page_raw);
-            Ok(HttpResponse { content, context: ResponseContext::Raw, code, content_type })
+            Ok(HttpResponse {
+                content,
+                context: ResponseContext::Raw,
+                code,
+                content_type,
+                cached: false,
+            })
         } else {
             Ok(HttpResponse {
                 content: page_raw,
                 context: ResponseContext::Parsed,
                 code,
                 content_type,
+                cached: false,
             })
         }
     }
@@ -113,9 +184,194 @@ impl ForgeFetch {
 
 #[async_trait::async_trait]
 impl NetFetchService for ForgeFetch {
-    async fn fetch(&self, url: String, raw: Option<bool>) -> anyhow::Result<HttpResponse> {
-        let url = Url::parse(&url).with_context(|| format!("Failed to parse URL: {url}"))?;
+    async fn fetch(
+        &self,
+        request: FetchRequest,
+        raw: Option<bool>,
+        no_cache: Option<bool>,
+    ) -> anyhow::Result<HttpResponse> {
+        let url = Url::parse(&request.url)
+            .with_context(|| format!("Failed to parse URL: {}", request.url))?;
+        let method = parse_method(request.method.as_deref())?;
+        check_headers(&request.headers)?;
+
+        // The response of any other method is left untouched and never reused
+        if method != Method::GET {
+            return self.fetch_url(&url, method, &request, true).await;
+        }
+
+        let raw = raw.unwrap_or(false);
+        let key = FetchKey { url: url.to_string(), headers: request.headers.clone(), raw };
+        self.cache
+            .get_or_fetch(
+                key,
+                no_cache.unwrap_or(false),
+                self.fetch_url(&url, method, &request, raw),
+            )
+            .await
+    }
+}
+
+fn parse_method(method: Option<&str>) -> anyhow::Result<Method> {
+    match method {
+        Some(method) => Method::from_bytes(method.to_uppercase().as_bytes())
+            .with_context(|| format!("Invalid HTTP method: {method}")),
+        None => Ok(Method::GET),
+    }
+}
+
+/// Rejects headers with line breaks, which would let a value inject further
+/// headers into the request
+fn check_headers(headers: &BTreeMap<String, String>) -> anyhow::Result<()> {
+    let line_break = |text: &str| text.contains(['\r', '\n']);
+    match headers
+        .iter()
+        .find(|(name, value)| line_break(name) || line_break(value))
+    {
+        Some((name, _)) => Err(anyhow!(
+            "Header {name:?} can't contain line breaks, it would inject further headers"
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture_key() -> FetchKey {
+        FetchKey {
+            url: "https://docs.rs/tokio".to_string(),
+            headers: BTreeMap::new(),
+            raw: false,
+        }
+    }
+
+    /// Fetches the key through the cache, counting the actual fetches
+    async fn fetch(cache: &FetchCache, fetches: &AtomicUsize, no_cache: bool) -> HttpResponse {
+        cache
+            .get_or_fetch(fixture_key(), no_cache, async {
+                fetches.fetch_add(1, Ordering::SeqCst);
+                Ok(HttpResponse {
+                    content: "# tokio".to_string(),
+                    code: 200,
+                    context: ResponseContext::Parsed,
+                    content_type: "text/html".to_string(),
+                    cached: false,
+                })
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_repeated_fetch_is_served_from_the_cache() {
+        let fixture = FetchCache::new(Duration::from_secs(60));
+        let fetches = AtomicUsize::new(0);
+
+        fetch(&fixture, &fetches, false).await;
+        let actual = fetch(&fixture, &fetches, false).await;
+
+        assert_eq!(actual.content, "# tokio");
+        assert!(actual.cached);
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_no_cache_fetches_again() {
+        let fixture = FetchCache::new(Duration::from_secs(60));
+        let fetches = AtomicUsize::new(0);
+
+        fetch(&fixture, &fetches, false).await;
+        let actual = fetch(&fixture, &fetches, true).await;
+
+        assert!(!actual.cached);
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+
+    fn fixture_headers(name: &str, value: &str) -> BTreeMap<String, String> {
+        BTreeMap::from([(name.to_string(), value.to_string())])
+    }
+
+    #[tokio::test]
+    async fn test_get_sends_headers_and_converts_html() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/docs")
+            .match_header("x-api-key", "secret")
+            .with_header("content-type", "text/html")
+            .with_body("<html><h1>Docs</h1></html>")
+            .create_async()
+            .await;
+        let fixture = FetchRequest {
+            url: format!("{}/docs", server.url()),
+            headers: fixture_headers("X-Api-Key", "secret"),
+            ..Default::default()
+        };
+
+        let actual = ForgeFetch::new(Duration::ZERO)
+            .fetch(fixture, None, None)
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(
+            actual.content,
+            html2md::parse_html("<html><h1>Docs</h1></html>")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post_sends_body_and_leaves_response_untouched() {
+        let fixture_body = r#"{"title":"Crash on start"}"#;
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/issues")
+            .match_header("authorization", "Bearer token")
+            .match_body(fixture_body)
+            .with_status(201)
+            .with_header("content-type", "text/html")
+            .with_body("<html><h1>Created</h1></html>")
+            .expect(2)
+            .create_async()
+            .await;
+        let fixture = FetchRequest {
+            url: format!("{}/issues", server.url()),
+            method: Some("post".to_string()),
+            headers: fixture_headers("Authorization", "Bearer token"),
+            body: Some(fixture_body.to_string()),
+        };
+        let fetch = ForgeFetch::new(Duration::from_secs(60));
+
+        fetch.fetch(fixture.clone(), None, None).await.unwrap();
+        let actual = fetch.fetch(fixture, None, None).await.unwrap();
+
+        // Sent again, a POST is never served from the cache
+        mock.assert_async().await;
+        assert_eq!(actual.code, 201);
+        assert_eq!(actual.content, "<html><h1>Created</h1></html>");
+        assert!(!actual.cached);
+    }
+
+    #[tokio::test]
+    async fn test_headers_with_line_breaks_are_rejected() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("GET", "/").expect(0).create_async().await;
+        let fixture = FetchRequest {
+            url: server.url(),
+            headers: fixture_headers("X-Trace", "1\r\nX-Admin: true"),
+            ..Default::default()
+        };
+
+        let actual = ForgeFetch::new(Duration::ZERO)
+            .fetch(fixture, None, None)
+            .await;
 
-        self.fetch_url(&url, raw.unwrap_or(false)).await
+        mock.assert_async().await;
+        assert!(actual.is_err());
     }
 }
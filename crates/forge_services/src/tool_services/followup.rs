@@ -48,4 +48,12 @@ impl<F: UserInfra> FollowUpService for ForgeFollowup<F> {
 
         Ok(result)
     }
+
+    async fn select_positions(
+        &self,
+        question: String,
+        options: Vec<String>,
+    ) -> anyhow::Result<Option<Vec<usize>>> {
+        self.infra.select_many_positions(&question, options).await
+    }
 }
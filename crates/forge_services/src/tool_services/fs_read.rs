@@ -2,11 +2,13 @@ use std::path::Path;
 use std::sync::Arc;
 
 use anyhow::Context;
+use forge_app::domain::shell_quote;
 use forge_app::{Content, FsReadService, ReadOutput};
+use forge_fs::ForgeFS;
 
 use crate::range::resolve_range;
 use crate::utils::assert_absolute_path;
-use crate::{EnvironmentInfra, FileInfoInfra, FileReaderInfra as InfraFsReadService};
+use crate::{CommandInfra, EnvironmentInfra, FileInfoInfra, FileReaderInfra as InfraFsReadService};
 
 /// Validates that file size does not exceed the maximum allowed file size.
 ///
@@ -37,12 +39,13 @@ async fn assert_file_size<F: FileInfoInfra>(
 /// Reads file contents from the specified absolute path. Ideal for analyzing
 /// code, configuration files, documentation, or textual data. Automatically
 /// extracts text from PDF and DOCX files, preserving the original formatting.
-/// Returns the content as a string. For files larger than 2,000 lines,
-/// the tool automatically returns only the first 2,000 lines. You should
-/// always rely on this default behavior and avoid specifying custom ranges
-/// unless absolutely necessary. If needed, specify a range with the start_line
-/// and end_line parameters, ensuring the total range does not exceed 2,000
-/// lines. Specifying a range exceeding this limit will result in an error.
+/// Returns the content as a string. For files larger than the configured
+/// `max_read_size`, the tool automatically returns only the first
+/// `max_read_size` lines. You should always rely on this default behavior and
+/// avoid specifying custom ranges unless absolutely necessary. If needed,
+/// specify a range with the start_line and end_line parameters, ensuring the
+/// total range does not exceed `max_read_size` lines. Specifying a range
+/// exceeding this limit will result in an error.
 /// Binary files are automatically detected and rejected.
 pub struct ForgeFsRead<F>(Arc<F>);
 
@@ -53,7 +56,9 @@ impl<F> ForgeFsRead<F> {
 }
 
 #[async_trait::async_trait]
-impl<F: FileInfoInfra + EnvironmentInfra + InfraFsReadService> FsReadService for ForgeFsRead<F> {
+impl<F: FileInfoInfra + EnvironmentInfra + InfraFsReadService + CommandInfra> FsReadService
+    for ForgeFsRead<F>
+{
     async fn read(
         &self,
         path: String,
@@ -64,8 +69,11 @@ impl<F: FileInfoInfra + EnvironmentInfra + InfraFsReadService> FsReadService for
         assert_absolute_path(path)?;
         let env = self.0.get_environment();
 
-        // Validate file size before reading content
-        assert_file_size(&*self.0, path, env.max_file_size).await?;
+        // Validate file size before reading content, offloaded tool outputs are
+        // meant to be read in ranges however large they are
+        if !env.is_offloaded(path) {
+            assert_file_size(&*self.0, path, env.max_file_size).await?;
+        }
 
         let (start_line, end_line) = resolve_range(start_line, end_line, env.max_read_size);
 
@@ -82,16 +90,85 @@ impl<F: FileInfoInfra + EnvironmentInfra + InfraFsReadService> FsReadService for
             total_lines: file_info.total_lines,
         })
     }
+
+    async fn read_all(&self, path: String) -> anyhow::Result<String> {
+        let path = Path::new(&path);
+        assert_absolute_path(path)?;
+        self.0
+            .read_utf8(path)
+            .await
+            .with_context(|| format!("Failed to read file content from {}", path.display()))
+    }
+
+    async fn read_revision(
+        &self,
+        path: String,
+        revision: String,
+        start_line: Option<u64>,
+        end_line: Option<u64>,
+    ) -> anyhow::Result<ReadOutput> {
+        let path = Path::new(&path);
+        assert_absolute_path(path)?;
+        // Would otherwise be taken as an option of `git show`
+        if revision.is_empty() || revision.starts_with('-') {
+            anyhow::bail!("Invalid git revision '{revision}'");
+        }
+        let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+            anyhow::bail!("Expected a file path, got {}", path.display());
+        };
+        let env = self.0.get_environment();
+
+        // The path is given relative to the file's directory, so the file is
+        // found wherever the repository root is
+        let object = format!("{revision}:./{}", name.to_string_lossy());
+        let output = self
+            .0
+            .execute_command_with_input(
+                format!("git show {}", shell_quote(&object)),
+                dir.to_path_buf(),
+                String::new(),
+            )
+            .await
+            .with_context(|| format!("Failed to read {} at revision {revision}", path.display()))?;
+        if output.exit_code != Some(0) {
+            anyhow::bail!(
+                "Failed to read {} at revision {revision}: {}",
+                path.display(),
+                output.stderr.trim()
+            );
+        }
+        if output.stdout.contains('\0') {
+            anyhow::bail!("Binary files are not supported: {}", path.display());
+        }
+        if output.stdout.len() as u64 > env.max_file_size {
+            anyhow::bail!(
+                "File size ({} bytes) exceeds the maximum allowed size of {} bytes",
+                output.stdout.len(),
+                env.max_file_size
+            );
+        }
+
+        let (start_line, end_line) = resolve_range(start_line, end_line, env.max_read_size);
+        let (content, file_info) = ForgeFS::select_lines(output.stdout, start_line, end_line)?;
+        Ok(ReadOutput {
+            content: Content::File(content),
+            start_line: file_info.start_line,
+            end_line: file_info.end_line,
+            total_lines: file_info.total_lines,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::path::PathBuf;
+
     use pretty_assertions::assert_eq;
     use tempfile::NamedTempFile;
     use tokio::fs;
 
     use super::*;
-    use crate::attachment::tests::MockFileService;
+    use crate::attachment::tests::{MockEnvironmentInfra, MockFileService};
 
     // Helper to create a temporary file with specific content size
     async fn create_test_file_with_size(size: usize) -> anyhow::Result<NamedTempFile> {
@@ -196,4 +273,150 @@ mod tests {
         assert!(actual.is_err());
         assert_eq!(actual.unwrap_err().to_string(), expected);
     }
+
+    /// Reads the files from disk, with a file size limit of 64 bytes and a read
+    /// limit of 50 lines
+    struct DiskInfra {
+        offload_dir: PathBuf,
+    }
+
+    impl EnvironmentInfra for DiskInfra {
+        fn get_environment(&self) -> forge_app::domain::Environment {
+            MockEnvironmentInfra {}
+                .get_environment()
+                .max_file_size(64u64)
+                .max_read_size(50u64)
+                .offload_dir(self.offload_dir.clone())
+        }
+
+        fn get_env_var(&self, _key: &str) -> Option<String> {
+            None
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FileInfoInfra for DiskInfra {
+        async fn is_binary(&self, path: &Path) -> anyhow::Result<bool> {
+            forge_fs::ForgeFS::is_binary_file(path).await
+        }
+
+        async fn is_file(&self, path: &Path) -> anyhow::Result<bool> {
+            Ok(forge_fs::ForgeFS::is_file(path))
+        }
+
+        async fn exists(&self, path: &Path) -> anyhow::Result<bool> {
+            Ok(forge_fs::ForgeFS::exists(path))
+        }
+
+        async fn file_size(&self, path: &Path) -> anyhow::Result<u64> {
+            forge_fs::ForgeFS::file_size(path).await
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl InfraFsReadService for DiskInfra {
+        async fn read_utf8(&self, path: &Path) -> anyhow::Result<String> {
+            forge_fs::ForgeFS::read_utf8(path).await
+        }
+
+        async fn read(&self, path: &Path) -> anyhow::Result<Vec<u8>> {
+            forge_fs::ForgeFS::read(path).await
+        }
+
+        async fn range_read_utf8(
+            &self,
+            path: &Path,
+            start_line: u64,
+            end_line: u64,
+        ) -> anyhow::Result<(String, forge_fs::FileInfo)> {
+            forge_fs::ForgeFS::read_range_utf8(path, start_line, end_line).await
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl CommandInfra for DiskInfra {
+        async fn execute_command(
+            &self,
+            _command: String,
+            _working_dir: PathBuf,
+        ) -> anyhow::Result<forge_app::domain::CommandOutput> {
+            unimplemented!()
+        }
+
+        async fn execute_command_raw(
+            &self,
+            _command: &str,
+            _working_dir: PathBuf,
+        ) -> anyhow::Result<std::process::ExitStatus> {
+            unimplemented!()
+        }
+
+        async fn execute_command_with_input(
+            &self,
+            _command: String,
+            _working_dir: PathBuf,
+            _input: String,
+        ) -> anyhow::Result<forge_app::domain::CommandOutput> {
+            unimplemented!()
+        }
+    }
+
+    fn fixture_output() -> String {
+        (1..=100)
+            .map(|line| format!("output line {line}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[tokio::test]
+    async fn test_offloaded_output_is_read_by_range_whatever_its_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = dir.path().join("forge_shell_stdout_a1.txt");
+        fs::write(&fixture, fixture_output()).await.unwrap();
+        let service = ForgeFsRead::new(Arc::new(DiskInfra {
+            offload_dir: dir.path().to_path_buf(),
+        }));
+
+        let actual = service
+            .read(fixture.display().to_string(), Some(50), Some(52))
+            .await
+            .unwrap();
+
+        let Content::File(content) = actual.content;
+        let expected = "output line 50\noutput line 51\noutput line 52";
+        assert_eq!(content, expected);
+        assert_eq!(actual.total_lines, 100);
+    }
+
+    #[tokio::test]
+    async fn test_large_file_outside_the_offload_dir_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = dir.path().join("forge_shell_stdout_a1.txt");
+        fs::write(&fixture, fixture_output()).await.unwrap();
+        let offload_dir = dir.path().join("offload");
+        let service = ForgeFsRead::new(Arc::new(DiskInfra { offload_dir }));
+
+        let actual = service
+            .read(fixture.display().to_string(), Some(50), Some(52))
+            .await;
+
+        assert!(actual.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_whole_file_is_read_past_the_read_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = dir.path().join("AGENTS.md");
+        fs::write(&fixture, fixture_output()).await.unwrap();
+        let offload_dir = dir.path().join("offload");
+        let service = ForgeFsRead::new(Arc::new(DiskInfra { offload_dir }));
+
+        let actual = service
+            .read_all(fixture.display().to_string())
+            .await
+            .unwrap();
+
+        let expected = fixture_output();
+        assert_eq!(actual, expected);
+    }
 }
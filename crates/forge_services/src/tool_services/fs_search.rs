@@ -288,9 +288,11 @@ mod test {
                         .to_string_lossy()
                         .to_string();
                     let file_name = path.file_name().map(|n| n.to_string_lossy().to_string());
-                    let size = entry.metadata().await?.len();
+                    let metadata = entry.metadata().await?;
+                    let size = metadata.len();
+                    let modified = metadata.modified().ok();
 
-                    files.push(WalkedFile { path: relative_path, file_name, size });
+                    files.push(WalkedFile { path: relative_path, file_name, size, modified });
                 }
             }
             Ok(files)
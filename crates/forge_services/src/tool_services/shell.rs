@@ -59,4 +59,25 @@ impl<I: CommandInfra + EnvironmentInfra> ShellService for ForgeShell<I> {
 
         Ok(ShellOutput { output, shell: self.env.shell.clone() })
     }
+
+    async fn pipe(
+        &self,
+        command: String,
+        cwd: PathBuf,
+        input: String,
+        keep_ansi: bool,
+    ) -> anyhow::Result<ShellOutput> {
+        Self::validate_command(&command)?;
+
+        let mut output = self
+            .infra
+            .execute_command_with_input(command, cwd, input)
+            .await?;
+        if !keep_ansi {
+            output.stdout = strip_ansi(output.stdout);
+            output.stderr = strip_ansi(output.stderr);
+        }
+
+        Ok(ShellOutput { output, shell: self.env.shell.clone() })
+    }
 }
@@ -1,18 +1,28 @@
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 use anyhow::Result;
 use colored::Colorize;
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use rand::seq::SliceRandom;
 use tokio::task::JoinHandle;
 
 /// Manages spinner functionality for the UI
 #[derive(Default)]
 pub struct SpinnerManager {
+    /// Draws the spinner and the footer as separate lines
+    bars: MultiProgress,
     spinner: Option<ProgressBar>,
     start_time: Option<Instant>,
     message: Option<String>,
+    /// Message shown by the tracker, shared so it can change while the
+    /// spinner runs
+    label: Arc<Mutex<String>>,
     tracker: Option<JoinHandle<()>>,
+    /// Line shown below the spinner, kept across restarts
+    footer: Option<String>,
+    /// Status line drawing the footer while the spinner runs
+    footer_bar: Option<ProgressBar>,
 }
 
 impl SpinnerManager {
@@ -43,12 +53,13 @@ impl SpinnerManager {
 
         // Store the base message without styling for later use with the timer
         self.message = Some(word.to_string());
+        self.label = Arc::new(Mutex::new(word.to_string()));
 
         // Initialize the start time for the timer
         self.start_time = Some(Instant::now());
 
         // Create the spinner with a better style that respects terminal width
-        let pb = ProgressBar::new_spinner();
+        let pb = self.bars.add(ProgressBar::new_spinner());
 
         // This style includes {msg} which will be replaced with our formatted message
         // The {spinner} will show a visual spinner animation
@@ -72,11 +83,15 @@ impl SpinnerManager {
         pb.set_message(message);
 
         self.spinner = Some(pb);
+        self.footer_bar = self
+            .footer
+            .as_deref()
+            .map(|footer| self.add_footer_bar(footer));
 
         // Clone the necessary components for the tracker task
         let spinner_clone = self.spinner.clone();
         let start_time_clone = self.start_time;
-        let message_clone = self.message.clone();
+        let label_clone = self.label.clone();
 
         // Spwan tracker to keep the track of time in sec.
         self.tracker = Some(tokio::spawn(async move {
@@ -84,8 +99,9 @@ impl SpinnerManager {
             loop {
                 interval.tick().await;
                 // Update the spinner with the current elapsed time
+                let label = label_clone.lock().ok().map(|label| label.clone());
                 if let (Some(spinner), Some(start_time), Some(message)) =
-                    (&spinner_clone, start_time_clone, &message_clone)
+                    (&spinner_clone, start_time_clone, label)
                 {
                     let elapsed = start_time.elapsed();
                     let seconds = elapsed.as_secs();
@@ -112,6 +128,9 @@ impl SpinnerManager {
         if let Some(spinner) = self.spinner.take() {
             // Always finish the spinner first
             spinner.finish_and_clear();
+            if let Some(footer_bar) = self.footer_bar.take() {
+                footer_bar.finish_and_clear();
+            }
 
             // Then print the message if provided
             if let Some(msg) = message {
@@ -132,6 +151,53 @@ impl SpinnerManager {
         Ok(())
     }
 
+    /// Changes the message of the running spinner without resetting its
+    /// timer, or starts the spinner with it
+    pub fn set_message(&mut self, message: &str) -> Result<()> {
+        let (Some(spinner), Some(start_time)) = (&self.spinner, self.start_time) else {
+            return self.start(Some(message));
+        };
+
+        self.message = Some(message.to_string());
+        if let Ok(mut label) = self.label.lock() {
+            *label = message.to_string();
+        }
+        spinner.set_message(format!(
+            "{} {}s · {}",
+            message.green().bold(),
+            start_time.elapsed().as_secs(),
+            "Ctrl+C to interrupt".white().dimmed()
+        ));
+        Ok(())
+    }
+
+    /// Sets the line shown below the spinner while it runs, or removes it.
+    /// The footer is a status line of its own that goes away with the
+    /// spinner, so it never ends up in the scrolling output
+    pub fn set_footer(&mut self, footer: Option<String>) {
+        match (self.footer_bar.as_ref(), footer.as_deref()) {
+            (Some(footer_bar), Some(footer)) => footer_bar.set_message(footer.dimmed().to_string()),
+            (Some(_), None) => {
+                if let Some(footer_bar) = self.footer_bar.take() {
+                    footer_bar.finish_and_clear();
+                }
+            }
+            (None, Some(footer)) if self.spinner.is_some() => {
+                self.footer_bar = Some(self.add_footer_bar(footer));
+            }
+            (None, _) => {}
+        }
+        self.footer = footer;
+    }
+
+    /// Adds the status line showing the footer below the spinner
+    fn add_footer_bar(&self, footer: &str) -> ProgressBar {
+        let footer_bar = self.bars.add(ProgressBar::new_spinner());
+        footer_bar.set_style(ProgressStyle::default_spinner().template("{msg}").unwrap());
+        footer_bar.set_message(footer.dimmed().to_string());
+        footer_bar
+    }
+
     pub fn write_ln(&mut self, message: impl ToString) -> Result<()> {
         let is_running = self.spinner.is_some();
         let prev_message = self.message.clone();
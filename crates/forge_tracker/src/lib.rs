@@ -4,8 +4,10 @@ mod dispatch;
 mod error;
 mod event;
 mod log;
+mod rolling;
 pub use can_track::VERSION;
 pub use dispatch::Tracker;
 use error::Result;
 pub use event::{Event, EventKind, ToolCallPayload};
-pub use log::{Guard, init_tracing};
+pub use log::{Guard, init_tracing, otel_layer};
+pub use rolling::log_file;
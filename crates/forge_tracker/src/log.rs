@@ -1,42 +1,89 @@
 use std::path::PathBuf;
 
-use tracing::debug;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use tracing::{Subscriber, debug};
 use tracing_appender::non_blocking::{self, WorkerGuard};
-use tracing_subscriber::{self};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
 
 use crate::Tracker;
 use crate::can_track::can_track;
+use crate::rolling::{MAX_LOG_FILES, MAX_LOG_SIZE, SizeRollingWriter};
 
-pub fn init_tracing(log_path: PathBuf, tracker: Tracker) -> anyhow::Result<Guard> {
+/// Initializes logging, spans are additionally exported over OTLP/HTTP when
+/// an `otlp_endpoint` is given.
+pub fn init_tracing(
+    log_path: PathBuf,
+    otlp_endpoint: Option<String>,
+    tracker: Tracker,
+) -> anyhow::Result<Guard> {
     debug!(path = %log_path.display(), "Initializing logging system in JSON format");
 
-    // If tracking is enabled, use PostHog for logging; otherwise, use a rolling
-    // file appender.
-    let (writer, guard, level) = prepare_writer(log_path, tracker);
+    // If tracking is enabled, use PostHog for logging; otherwise, use a file
+    // appender rolled over by size.
+    let (writer, guard, level) = prepare_writer(log_path, tracker)?;
 
-    tracing_subscriber::fmt()
+    let fmt = tracing_subscriber::fmt::layer()
         .json()
-        .with_env_filter(tracing_subscriber::EnvFilter::try_from_env("FORGE_LOG").unwrap_or(level))
         .with_timer(tracing_subscriber::fmt::time::uptime())
         .with_thread_ids(false)
         .with_target(false)
         .with_file(true)
         .with_line_number(true)
         .with_writer(writer)
+        .with_filter(tracing_subscriber::EnvFilter::try_from_env("FORGE_LOG").unwrap_or(level));
+
+    let provider = otlp_endpoint.map(otlp_provider).transpose()?;
+    tracing_subscriber::registry()
+        .with(fmt)
+        .with(provider.as_ref().map(otel_layer))
         .init();
 
     debug!("JSON logging system initialized successfully");
-    Ok(Guard(guard))
+    Ok(Guard(guard, provider))
+}
+
+/// Layer that turns the spans created by forge into OpenTelemetry spans,
+/// span fields are exported as attributes. It can be stacked on any
+/// subscriber that keeps track of spans, such as the registry along with the
+/// log layer.
+pub fn otel_layer<S>(provider: &SdkTracerProvider) -> impl Layer<S> + Send + Sync + 'static
+where
+    S: Subscriber + for<'span> LookupSpan<'span> + 'static,
+{
+    tracing_opentelemetry::layer()
+        .with_tracer(provider.tracer("forge"))
+        .with_filter(tracing_subscriber::filter::filter_fn(|metadata| {
+            metadata.is_span() && metadata.target().starts_with("forge")
+        }))
+}
+
+fn otlp_provider(endpoint: String) -> anyhow::Result<SdkTracerProvider> {
+    debug!(endpoint = %endpoint, "Exporting spans over OTLP");
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()?;
+
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name("forge").build())
+        .build())
 }
 
 fn prepare_writer(
     log_path: PathBuf,
     tracker: Tracker,
-) -> (
+) -> anyhow::Result<(
     non_blocking::NonBlocking,
     WorkerGuard,
     tracing_subscriber::EnvFilter,
-) {
+)> {
     let ((non_blocking, guard), env) = if can_track() {
         let append = PostHogWriter::new(tracker);
         (
@@ -44,16 +91,25 @@ fn prepare_writer(
             tracing_subscriber::EnvFilter::new("forge=info"),
         )
     } else {
-        let append = tracing_appender::rolling::daily(log_path, "forge.log");
+        let append = SizeRollingWriter::new(log_path, MAX_LOG_SIZE, MAX_LOG_FILES)?;
         (
             tracing_appender::non_blocking(append),
             tracing_subscriber::EnvFilter::new("forge=debug"),
         )
     };
-    (non_blocking, guard, env)
+    Ok((non_blocking, guard, env))
 }
 
-pub struct Guard(#[allow(dead_code)] WorkerGuard);
+pub struct Guard(#[allow(dead_code)] WorkerGuard, Option<SdkTracerProvider>);
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        // Flushes the spans that are still batched
+        if let Some(provider) = self.1.take() {
+            let _ = provider.shutdown();
+        }
+    }
+}
 
 struct PostHogWriter {
     tracker: Tracker,
@@ -0,0 +1,123 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Name of the log file being written to, rolled files get a numeric suffix
+const LOG_FILE_NAME: &str = "forge.log";
+/// Size the log file is rolled over at
+pub const MAX_LOG_SIZE: u64 = 10 << 20; // 10 MiB
+/// Number of rolled files kept besides the current one
+pub const MAX_LOG_FILES: usize = 5;
+
+/// Returns the log file currently written to in the logs directory
+pub fn log_file(dir: &Path) -> PathBuf {
+    dir.join(LOG_FILE_NAME)
+}
+
+/// Appends to `forge.log`, rolling it over to `forge.log.1` once it reaches
+/// the size limit. Older files shift to the next suffix and those beyond the
+/// number of files kept are removed.
+pub struct SizeRollingWriter {
+    dir: PathBuf,
+    max_size: u64,
+    max_files: usize,
+    file: File,
+    size: u64,
+}
+
+impl SizeRollingWriter {
+    pub fn new(dir: PathBuf, max_size: u64, max_files: usize) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let file = Self::open(&dir)?;
+        let size = file.metadata()?.len();
+        Ok(Self { dir, max_size, max_files, file, size })
+    }
+
+    fn open(dir: &Path) -> io::Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_file(dir))
+    }
+
+    fn rolled_file(&self, index: usize) -> PathBuf {
+        self.dir.join(format!("{LOG_FILE_NAME}.{index}"))
+    }
+
+    fn roll(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let oldest = self.rolled_file(self.max_files);
+        if oldest.exists() {
+            fs::remove_file(oldest)?;
+        }
+        for index in (1..self.max_files).rev() {
+            let from = self.rolled_file(index);
+            if from.exists() {
+                fs::rename(from, self.rolled_file(index + 1))?;
+            }
+        }
+        if self.max_files > 0 {
+            fs::rename(log_file(&self.dir), self.rolled_file(1))?;
+        } else {
+            fs::remove_file(log_file(&self.dir))?;
+        }
+        self.file = Self::open(&self.dir)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for SizeRollingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // A line larger than the limit still goes to a file of its own
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_size {
+            self.roll()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn files(dir: &Path) -> Vec<(String, String)> {
+        let mut files = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().path();
+                let name = path.file_name().unwrap().to_string_lossy().to_string();
+                (name, fs::read_to_string(&path).unwrap())
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_rolls_over_at_size_and_prunes_oldest_files() {
+        let fixture = tempfile::tempdir().unwrap();
+        let mut writer = SizeRollingWriter::new(fixture.path().to_path_buf(), 10, 2).unwrap();
+
+        for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+            writer.write_all(line.as_bytes()).unwrap();
+        }
+        writer.flush().unwrap();
+
+        let actual = files(fixture.path());
+        let expected = vec![
+            ("forge.log".to_string(), "fourth\n".to_string()),
+            ("forge.log.1".to_string(), "third\n".to_string()),
+            ("forge.log.2".to_string(), "second\n".to_string()),
+        ];
+        assert_eq!(actual, expected);
+    }
+}
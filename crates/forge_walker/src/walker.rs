@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::SystemTime;
 
 use anyhow::{Context, Result};
 use derive_setters::Setters;
@@ -11,6 +12,7 @@ pub struct File {
     pub path: String,
     pub file_name: Option<String>,
     pub size: u64,
+    pub modified: Option<SystemTime>,
 }
 
 impl File {
@@ -88,7 +90,8 @@ impl Walker {
             .context("Failed to spawn blocking task")?
     }
 
-    fn is_likely_binary(path: &std::path::Path) -> bool {
+    /// Whether the extension of the file is one of binary files
+    pub fn is_likely_binary(path: &std::path::Path) -> bool {
         if let Some(extension) = path.extension() {
             let ext = extension.to_string_lossy().to_lowercase();
             // List of common binary file extensions loaded from file
@@ -191,7 +194,12 @@ impl Walker {
                 path_string
             };
 
-            files.push(File { path: path_string, file_name, size: file_size });
+            files.push(File {
+                path: path_string,
+                file_name,
+                size: file_size,
+                modified: metadata.modified().ok(),
+            });
 
             if !is_dir {
                 total_size += file_size;